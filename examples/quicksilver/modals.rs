@@ -45,6 +45,7 @@ impl MainApp {
         let screen_size = screen.clone();
 
         delegate.add_stage_builder(move || StageBuilder::load_modals_scene(screen_size));
+        delegate.add_stage_builder(move || StageBuilder::load_dialogs_scene(screen_size));
         // delegate.add_stage_builder(move || StageBuilder::load_themes_demo(screen_size));
         // delegate.add_stage_builder(move || StageBuilder::build_dots_demo(screen_size));
 
@@ -192,6 +193,36 @@ impl StageBuilder {
         stage
     }
 
+    fn load_dialogs_scene(screen: Vector) -> Stage {
+        let mut stage = Stage::new(Rectangle::new_sized(screen));
+        stage.title = "Alert and confirm dialogs".to_string();
+
+        let dialog = Dialog::alert("Saved", "The document was saved.");
+        let alert_id = stage.add_dialog(dialog);
+
+        let dialog = Dialog::confirm("Delete file?", "The file will be moved to the trash.").on_choice(|index, _| {
+            log::debug!("Confirm dialog choice={}", index);
+        });
+        let confirm_id = stage.add_dialog(dialog);
+
+        let frame = Rectangle::new_sized(screen);
+        let mut scene = Scene::new(frame).with_id(BG_SCENE, "Dialogs");
+        let buttons = vec![("Show alert", alert_id), ("Show confirm", confirm_id)];
+        let mut ypos = 80.0;
+        for (title, modal_id) in buttons {
+            let frame = Rectangle::new(((screen.x - 160.0) / 2.0, ypos), (160.0, 40.0));
+            let mut button = Button::new(frame).with_text(title);
+            button.layer.corner_radius = 5.0;
+            button.set_onclick(move |state| {
+                state.event_bus.dispatch_event(NavEvent::Modal(modal_id), NodeID::default(), None);
+            });
+            scene.add_control(Box::new(button));
+            ypos += 60.0;
+        }
+        stage.add_scene(scene);
+        stage
+    }
+
     fn load_themes_demo(screen: Vector) -> Stage {
         let mut stage = Stage::new(Rectangle::new_sized(screen));
         stage.title = "Change themes".to_string();
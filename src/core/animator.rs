@@ -124,6 +124,34 @@ impl Animator {
                 let out = m1.lerp(*m2, scale as f32);
                 Prop::Tint(out)
             }
+            Prop::StopColor(index, m1) => {
+                let m2 = match target {
+                    Prop::StopColor(_, m2) => m2,
+                    _ => return initial.clone(),
+                };
+                let out = m1.lerp(*m2, scale as f32);
+                Prop::StopColor(*index, out)
+            }
+            Prop::ShadowColor(m1) => {
+                let m2 = unwrap_to!(target => Prop::ShadowColor);
+                let out = m1.lerp(*m2, scale as f32);
+                Prop::ShadowColor(out)
+            }
+            Prop::ShadowOffset(m1) => {
+                let m2 = unwrap_to!(target => Prop::ShadowOffset);
+                let out = m1.lerp(*m2, scale);
+                Prop::ShadowOffset(out)
+            }
+            Prop::ShadowSize(v1) => {
+                let v2 = unwrap_to!(target => Prop::ShadowSize);
+                let out = v1.lerp(*v2, scale);
+                Prop::ShadowSize(out)
+            }
+            Prop::CornerRadius(v1) => {
+                let v2 = unwrap_to!(target => Prop::CornerRadius);
+                let out = v1.lerp(*v2, scale);
+                Prop::CornerRadius(out)
+            }
             Prop::Position(m1) => {
                 let m2 = unwrap_to!(target => Prop::Position);
                 let out = m1.lerp(*m2, scale);
@@ -48,6 +48,66 @@ pub fn rgb_from_hex(hex: &str) -> (f32, f32, f32, f32) {
     }
 }
 
+/// Helper function like rgb_from_hex that returns None instead of panicking if the hex string is malformed.
+/// Allows the formats "#FFFFFF" and "#FFFFFFFF", with or without the "#".
+pub fn parse_hex(hex: &str) -> Option<(f32, f32, f32, f32)> {
+    let trimmed_hex = hex.trim().trim_start_matches('#');
+    if (trimmed_hex.len() != 6 && trimmed_hex.len() != 8) || !trimmed_hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    Some(rgb_from_hex(trimmed_hex))
+}
+
+/// Helper function to convert rgba values, each in range 0.0 to 255.0, to a hex color string.
+/// The alpha is only included if it is less than 255.0.
+pub fn hex_from_rgb(r: f32, g: f32, b: f32, a: f32) -> String {
+    let byte = |v: f32| v.round().max(0.0).min(255.0) as u8;
+    if byte(a) < 255 {
+        format!("#{:02X}{:02X}{:02X}{:02X}", byte(r), byte(g), byte(b), byte(a))
+    } else {
+        format!("#{:02X}{:02X}{:02X}", byte(r), byte(g), byte(b))
+    }
+}
+
+/// Helper function to convert hue in degrees (0.0 to 360.0) and saturation and value (0.0 to 1.0) to rgb values
+/// in range 0.0 to 1.0
+pub fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (f32, f32, f32) {
+    let h = (h % 360.0 + 360.0) % 360.0 / 60.0;
+    let c = v * s;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    (r + m, g + m, b + m)
+}
+
+/// Helper function to convert rgb values in range 0.0 to 1.0 to hue in degrees (0.0 to 360.0) and saturation and
+/// value (0.0 to 1.0). The hue of a gray is 0.0.
+pub fn rgb_to_hsv(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let h = {
+        if delta <= std::f32::EPSILON {
+            0.0
+        } else if (max - r).abs() <= std::f32::EPSILON {
+            60.0 * ((g - b) / delta).rem_euclid(6.0)
+        } else if (max - g).abs() <= std::f32::EPSILON {
+            60.0 * ((b - r) / delta + 2.0)
+        } else {
+            60.0 * ((r - g) / delta + 4.0)
+        }
+    };
+    let s = if max <= std::f32::EPSILON { 0.0 } else { delta / max };
+    (h, s, max)
+}
+
 /// Helper method to convert color in u32 format (like 0xFFFFFF) to rgb u8 values
 /// Currently unused
 pub fn hex_to_rgb(c: u32) -> (u8, u8, u8) {
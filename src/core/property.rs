@@ -19,9 +19,14 @@ pub type ColorRGBA = Vector4<f32>;
 pub type Point2D = Vector2<f32>;
 /// Float values for width and height
 pub type Frame2D = Vector2<f32>;
+/// Float values for the corner radii in the order: top left, top right, bottom right, bottom left
+pub type Corners4D = Vector4<f32>;
 /// Unused. Intended for bezier curve paths. TODO?
 pub type Bezier = Vector4<f32>;
 
+/// The number of gradient stops that can be animated with Prop::StopColor
+pub const MAX_ANIMATED_STOPS: usize = 8;
+
 /// The Prop enum contains a cgmath::Vector instance that is interpolated in the Animator update() method
 /// based on the initial and target Prop values of the same type.
 #[derive(Copy, Clone, PartialEq)]
@@ -48,6 +53,16 @@ pub enum Prop {
     Border(Option<ColorRGBA>, FloatProp),
     /// Tint color refers to the foreground color of some nested objects (ie, Text)
     Tint(ColorRGBA),
+    /// The color of the gradient stop at the index, with RGBA values in range 0 to 255
+    StopColor(u32, ColorRGBA),
+    /// The color of the shadow, with RGBA values in range 0 to 255
+    ShadowColor(ColorRGBA),
+    /// The x-y offset of the shadow from the object
+    ShadowOffset(Point2D),
+    /// The spread and softness of the shadow
+    ShadowSize(Frame2D),
+    /// The radius of each corner of an object
+    CornerRadius(Corners4D),
 }
 
 impl fmt::Debug for Prop {
@@ -57,6 +72,17 @@ impl fmt::Debug for Prop {
             Prop::Alpha(val) => write!(f, "Alpha({:.2})", val[0]),
             Prop::Color(rgba) => write!(f, "Color({}, {}, {}) Alpha({})", rgba[0], rgba[1], rgba[2], rgba[3]),
             Prop::Tint(rgba) => write!(f, "Color({}, {}, {}) Alpha({})", rgba[0], rgba[1], rgba[2], rgba[3]),
+            Prop::StopColor(index, rgba) => {
+                write!(f, "StopColor[{}]({}, {}, {}) Alpha({})", index, rgba[0], rgba[1], rgba[2], rgba[3])
+            }
+            Prop::ShadowColor(rgba) => {
+                write!(f, "ShadowColor({}, {}, {}) Alpha({})", rgba[0], rgba[1], rgba[2], rgba[3])
+            }
+            Prop::ShadowOffset(pos) => write!(f, "ShadowOffset({:.2}, {:.2})", pos[0], pos[1]),
+            Prop::ShadowSize(size) => write!(f, "ShadowSize(spread({:.2}), softness({:.2}))", size[0], size[1]),
+            Prop::CornerRadius(radii) => {
+                write!(f, "CornerRadius({:.2}, {:.2}, {:.2}, {:.2})", radii[0], radii[1], radii[2], radii[3])
+            }
             Prop::Rotate(val) => write!(f, "Rotate({:.2})", val[0]),
             Prop::Position(pos) => write!(f, "Position({:.2}, {:.2})", pos[0], pos[1]),
             Prop::Size(size) => write!(f, "Size({:.2}, {:.2})", size[0], size[1]),
@@ -94,6 +120,12 @@ impl Prop {
             Prop::Resize(_) => 7,
             Prop::Border(_, _) => 8,
             Prop::Tint(_) => 9,
+            // Each stop needs its own id so that the stops are animated separately
+            Prop::StopColor(index, _) => 10 + index,
+            Prop::ShadowColor(_) => 30,
+            Prop::ShadowOffset(_) => 31,
+            Prop::ShadowSize(_) => 32,
+            Prop::CornerRadius(_) => 33,
         }
     }
 
@@ -112,6 +144,11 @@ impl Prop {
             7 => Prop::Resize(Frame2D::zero()),
             8 => Prop::Border(None, FloatProp::zero()),
             9 => Prop::Tint(ColorRGBA::zero()),
+            id if id >= 10 && id < 10 + MAX_ANIMATED_STOPS as u32 => Prop::StopColor(id - 10, ColorRGBA::zero()),
+            30 => Prop::ShadowColor(ColorRGBA::zero()),
+            31 => Prop::ShadowOffset(Point2D::zero()),
+            32 => Prop::ShadowSize(Frame2D::zero()),
+            33 => Prop::CornerRadius(Corners4D::zero()),
             _ => Prop::None,
         }
     }
@@ -120,7 +157,7 @@ impl Prop {
     /// All of the internal values are zero vectors so this is only useful for iterating
     /// the list of possible Props that need to be inspected.
     pub fn get_prop_list() -> Vec<Prop> {
-        let list = vec![
+        let mut list = vec![
             Prop::Alpha(FloatProp::zero()),
             Prop::Color(ColorRGBA::zero()),
             Prop::Position(Point2D::zero()),
@@ -129,6 +166,13 @@ impl Prop {
             Prop::Border(None, FloatProp::zero()),
             Prop::Tint(ColorRGBA::zero()),
         ];
+        for index in 0..MAX_ANIMATED_STOPS as u32 {
+            list.push(Prop::StopColor(index, ColorRGBA::zero()));
+        }
+        list.push(Prop::ShadowColor(ColorRGBA::zero()));
+        list.push(Prop::ShadowOffset(Point2D::zero()));
+        list.push(Prop::ShadowSize(Frame2D::zero()));
+        list.push(Prop::CornerRadius(Corners4D::zero()));
         list
     }
 
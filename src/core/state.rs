@@ -4,7 +4,10 @@ use super::clock::*;
 use crate::events::*;
 use crate::gui::{gui_print_type, Stage, GUI_NAMES_MAP};
 
-use quicksilver::geom::Vector;
+use quicksilver::{
+    geom::Vector,
+    input::{Key, Keyboard},
+};
 
 use std::{
     any::TypeId,
@@ -33,6 +36,49 @@ pub trait Playable {
     fn set_state(&mut self, _state: PlayState) {}
 }
 
+/// The state of the keyboard modifier keys. AppDelegate updates the copy in AppState so that mouse
+/// handlers can support Ctrl/Cmd-click and Shift-click.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct KeyModifiers {
+    /// Either shift key
+    pub shift: bool,
+    /// Either control key
+    pub ctrl: bool,
+    /// Either alt/option key
+    pub alt: bool,
+    /// The Windows or Command key
+    pub logo: bool,
+}
+
+impl KeyModifiers {
+    /// Read the modifier keys from the current keyboard state
+    pub fn from_keyboard(keyboard: &Keyboard) -> Self {
+        KeyModifiers {
+            shift: keyboard[Key::LShift].is_down() || keyboard[Key::RShift].is_down(),
+            ctrl: keyboard[Key::LControl].is_down() || keyboard[Key::RControl].is_down(),
+            alt: keyboard[Key::LAlt].is_down() || keyboard[Key::RAlt].is_down(),
+            logo: keyboard[Key::LWin].is_down() || keyboard[Key::RWin].is_down(),
+        }
+    }
+
+    /// True if Ctrl or Cmd is down, which is used to toggle a selection
+    pub fn command(&self) -> bool {
+        self.ctrl || self.logo
+    }
+
+    /// Update the state for a key press or release. Returns true if the key is a modifier.
+    pub fn update(&mut self, key: &Key, is_down: bool) -> bool {
+        match key {
+            Key::LShift | Key::RShift => self.shift = is_down,
+            Key::LControl | Key::RControl => self.ctrl = is_down,
+            Key::LAlt | Key::RAlt => self.alt = is_down,
+            Key::LWin | Key::RWin => self.logo = is_down,
+            _ => return false,
+        }
+        true
+    }
+}
+
 /// Mutable state object passed through Responder methods for capturing and handling
 /// user events from keyboard and mouse
 pub struct AppState {
@@ -51,6 +97,10 @@ pub struct AppState {
     pub transformers: HashMap<u32, Transformer>,
     /// The event queue
     pub event_bus: EventBus,
+    /// The modifier keys that are currently down
+    pub modifiers: KeyModifiers,
+    /// Set by a handler to tell AppDelegate not to apply its default action for the current event
+    pub(crate) event_consumed: bool,
     /// The observers that have been declared
     pub(crate) observers_map: HashMap<String, Vec<NodePath>>,
     /// Storage where key=Tag and value = NodePath
@@ -77,6 +127,8 @@ impl AppState {
             total_time: 0.0,
             transformers: HashMap::new(),
             event_bus: EventBus::default(),
+            modifiers: KeyModifiers::default(),
+            event_consumed: false,
             node_tags: HashMap::new(),
             observers_map: HashMap::new(),
             send_notifications: HashMap::new(),
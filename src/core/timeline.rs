@@ -78,7 +78,7 @@ impl Timeline {
     }
 
     pub fn add_sprite(&mut self, mut view: Box<dyn Displayable>, start: f64) {
-        if !view.get_layer_mut().has_animation() {
+        if !view.get_layer_mut().has_animation() && view.get_playable().is_none() {
             log::error!("No Tween has been set for this view");
             return;
         }
@@ -133,6 +133,22 @@ impl Timeline {
         }
     }
 
+    /// Move the queued sprites into the Timeline without registering them with the AppState. This is for a
+    /// Timeline that is not part of a Scene, such as the toast animations of a Stage. Call play() first so that
+    /// the total time includes the queued sprites.
+    pub(crate) fn load_sprites(&mut self) {
+        for mut sprite in self.sprites_queue.drain(..) {
+            let id = self.sprites.keys().last().map_or(1, |id| id + 1);
+            sprite.view.set_id(id);
+            self.sprites.insert(id, sprite);
+        }
+    }
+
+    /// True when all of the animations have finished
+    pub fn is_completed(&self) -> bool {
+        self.state == PlayState::Completed
+    }
+
     /// Calculate the total time for all animations in the Timeline
     pub fn calc_total_time(&self) -> f64 {
         if let Some(max) =
@@ -226,6 +242,9 @@ impl Displayable for Timeline {
                 for sprite in &mut self.sprites.values_mut() {
                     if sprite.start <= elapsed && sprite.end > elapsed {
                         sprite.view.get_layer_mut().play();
+                        if let Some(playable) = sprite.view.get_playable() {
+                            playable.play();
+                        }
                     }
                 }
                 state.event_bus.dispatch_event(TimelineEvent::Starting, self.layer.node_id(), None);
@@ -237,6 +256,9 @@ impl Displayable for Timeline {
                         if sprite.start <= elapsed && sprite.end > elapsed {
                             // If not playing, start. Tween.play method checks play state first
                             sprite.view.get_layer_mut().play();
+                            if let Some(playable) = sprite.view.get_playable() {
+                                playable.play();
+                            }
                         }
                         sprite.view.update(window, state);
                     }
@@ -350,6 +372,9 @@ impl Playable for Timeline {
         // self.state = PlayState::Waiting;
         for sprite in &mut self.sprites.values_mut() {
             sprite.view.get_layer_mut().reset();
+            if let Some(playable) = sprite.view.get_playable() {
+                playable.reset();
+            }
             // A Scene in a timeline needs to inform its subviews about a Reset event to force them
             // back to their original positions
             if sprite.view.get_type_id() == TypeId::of::<Scene>() {
@@ -375,3 +400,73 @@ pub enum SpriteAlign {
     /// Stagger start time by specified seconds
     Stagger(f32),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{position, PropSet};
+
+    struct Dot {
+        layer: Layer,
+    }
+
+    impl Displayable for Dot {
+        fn get_type_id(&self) -> TypeId {
+            TypeId::of::<Dot>()
+        }
+
+        fn get_layer(&self) -> &Layer {
+            &self.layer
+        }
+
+        fn get_layer_mut(&mut self) -> &mut Layer {
+            &mut self.layer
+        }
+
+        fn get_frame(&self) -> Rectangle {
+            self.layer.frame
+        }
+
+        fn move_to(&mut self, pos: (f32, f32)) {
+            self.layer.frame.pos = Vector::new(pos.0, pos.1);
+        }
+
+        fn set_theme(&mut self, _theme: &mut Theme) {}
+
+        fn notify(&mut self, _event: &DisplayEvent) {}
+
+        fn update(&mut self, _window: &mut Window, _state: &mut AppState) {}
+    }
+
+    fn dot(animated: bool) -> Box<dyn Displayable> {
+        let mut dot = Dot { layer: Layer::new(Rectangle::new((0.0, 0.0), (10.0, 10.0))) };
+        if animated {
+            dot.layer.animate_with_props(PropSet::new(vec![position(50.0, 0.0)], 0.5), false);
+        }
+        Box::new(dot)
+    }
+
+    #[test]
+    fn test_load_sprites() {
+        let mut timeline = Timeline::new(Rectangle::new((0.0, 0.0), (100.0, 100.0)));
+        timeline.add_sprite(dot(true), 0.0);
+        timeline.add_sprite(dot(true), 0.25);
+        // Views without an animation are rejected
+        timeline.add_sprite(dot(false), 0.0);
+        assert_eq!(timeline.sprites_queue.len(), 2);
+
+        timeline.play();
+        assert_eq!(timeline.total_time, 0.75);
+        timeline.load_sprites();
+        assert!(timeline.sprites_queue.is_empty());
+        assert_eq!(timeline.sprites.keys().cloned().collect::<Vec<u32>>(), vec![1, 2]);
+        assert_eq!(timeline.sprites[&2].view.get_id(), 2);
+        assert_eq!(timeline.sprites[&2].start, 0.25);
+        assert!(!timeline.is_completed());
+
+        // Sprites loaded later get new ids
+        timeline.add_sprite(dot(true), 1.0);
+        timeline.load_sprites();
+        assert_eq!(timeline.sprites.keys().cloned().collect::<Vec<u32>>(), vec![1, 2, 3]);
+    }
+}
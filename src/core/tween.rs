@@ -66,6 +66,39 @@ pub fn tint(hex: &str) -> Prop {
     Prop::Tint(ColorRGBA::new(rgb.0, rgb.1, rgb.2, rgb.3))
 }
 
+/// Change the color of the gradient stop at the index to the specified hex color (e.g 0xFFFFFF)
+pub fn stop_color(index: u32, hex: &str) -> Prop {
+    let rgb = rgb_from_hex(hex);
+    Prop::StopColor(index, ColorRGBA::new(rgb.0, rgb.1, rgb.2, rgb.3))
+}
+
+/// Change the shadow color to the specified hex color (e.g 0xFFFFFF). Include the alpha in the hex value to
+/// fade the shadow, like "#00000066".
+pub fn shadow_color(hex: &str) -> Prop {
+    let rgb = rgb_from_hex(hex);
+    Prop::ShadowColor(ColorRGBA::new(rgb.0, rgb.1, rgb.2, rgb.3))
+}
+
+/// Move the shadow to the specified x-y offset from the object
+pub fn shadow_offset(x: f32, y: f32) -> Prop {
+    Prop::ShadowOffset(Point2D::new(x, y))
+}
+
+/// Change the spread and softness of the shadow
+pub fn shadow_size(spread: f32, softness: f32) -> Prop {
+    Prop::ShadowSize(Frame2D::new(spread, softness))
+}
+
+/// Change the radius of all corners
+pub fn corner_radius(radius: f32) -> Prop {
+    Prop::CornerRadius(Corners4D::new(radius, radius, radius, radius))
+}
+
+/// Change the radius of each corner
+pub fn corner_radii(top_left: f32, top_right: f32, bottom_right: f32, bottom_left: f32) -> Prop {
+    Prop::CornerRadius(Corners4D::new(top_left, top_right, bottom_right, bottom_left))
+}
+
 /// Rotate the object to the specified degrees (range 0.0..360.0)
 pub fn rotate(degrees: f32) -> Prop {
     Prop::Rotate(FloatProp::new(degrees))
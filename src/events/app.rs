@@ -1,6 +1,6 @@
 /// Events related to navigation requests and actions
 ///
-use super::AnyEvent;
+use super::{AnyEvent, ValidationError};
 use crate::core::NodeID;
 
 use std::any::TypeId;
@@ -56,5 +56,123 @@ pub enum SceneEvent {
     Transition,
     /// Theme changed to the number that identifies it
     ChangeTheme(u32),
+    /// The Scene is about to be displayed by a NavController
+    WillAppear(NodeID),
+    /// The Scene is displayed by a NavController after the transition
+    DidAppear(NodeID),
+    /// The Scene is about to be hidden or removed by a NavController
+    WillDisappear(NodeID),
+    /// The Scene was hidden or removed by a NavController after the transition
+    DidDisappear(NodeID),
 }
 impl AnyEvent for SceneEvent {}
+
+/// Events dispatched by input controls when the user changes the value of a field. The sender
+/// NodeID and tag are provided through EventBus::dispatch_event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldEvent {
+    /// The field content was edited
+    Changed,
+    /// The field value passed validation
+    Valid,
+    /// The field value failed validation
+    Invalid(ValidationError),
+}
+impl AnyEvent for FieldEvent {}
+
+/// Events emitted by list controls when the selection changes. The selection itself can be read from
+/// the control with get_field_value().
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SelectionEvent {
+    /// The selection changed. Params: the number of selected rows and the focused row index
+    Changed(usize, Option<usize>),
+    /// The user pressed Return on the focused row. Param: the row index
+    Activated(usize),
+}
+impl AnyEvent for SelectionEvent {}
+
+/// Events emitted by a Table for changes that are not a selection change
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TableEvent {
+    /// The rows were sorted. Params: the column index and true if ascending
+    Sorted(usize, bool),
+    /// A column was resized by dragging the header divider. Param: the column index
+    ColumnResized(usize),
+    /// A cell value was committed to the datasource. Params: the row and column index
+    CellEdited(usize, usize),
+}
+impl AnyEvent for TableEvent {}
+
+/// Events emitted by a TreeView. Params: the id of the TreeNode
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TreeEvent {
+    /// The node was selected
+    Selected(u32),
+    /// The user pressed Return on the selected node
+    Activated(u32),
+    /// The node was expanded
+    Expanded(u32),
+    /// The node was collapsed
+    Collapsed(u32),
+}
+impl AnyEvent for TreeEvent {}
+
+/// Events emitted by a TabBar. The first param is the tab id
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TabEvent {
+    /// The tab was selected
+    Selected(u32),
+    /// The tab was closed
+    Closed(u32),
+    /// The tab was dragged to a new position. Params: the tab id and new index
+    Moved(u32, usize),
+}
+impl AnyEvent for TabEvent {}
+
+/// Events for the modal scenes of a Stage. The first param is the modal id returned by Stage::add_modal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ModalEvent {
+    /// The present animation finished
+    Presented(u32),
+    /// The dismiss animation finished
+    Dismissed(u32),
+    /// A Dialog button was chosen. Params: the modal id and the button index
+    Choice(u32, usize),
+}
+impl AnyEvent for ModalEvent {}
+
+/// Events for the menus of a Stage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MenuEvent {
+    /// The menu was opened. Param: the menu id returned by Stage::add_menu
+    Opened(u32),
+    /// The menu was closed. Param: the menu id
+    Closed(u32),
+    /// A menu item was chosen. Param: the MenuItem id
+    Selected(u32),
+}
+impl AnyEvent for MenuEvent {}
+
+/// Events for the toasts of a Stage. Param: the id of the Toast
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToastEvent {
+    /// The toast was shown
+    Shown(u32),
+    /// The toast was dismissed by its timeout or a click
+    Dismissed(u32),
+    /// The action button of the toast was clicked
+    Action(u32),
+}
+impl AnyEvent for ToastEvent {}
+
+/// Events emitted by a SplitView
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SplitEvent {
+    /// A divider was dragged to resize the panes on either side. Param: the divider index
+    Resized(usize),
+    /// The pane was collapsed. Param: the pane index
+    Collapsed(usize),
+    /// The pane was expanded from its collapsed state. Param: the pane index
+    Expanded(usize),
+}
+impl AnyEvent for SplitEvent {}
@@ -65,6 +65,12 @@ impl EventBox {
         EventBox { event: Box::new(event), event_type: TypeId::of::<E>(), sender: NodeID::default(), tag: None }
     }
 
+    /// Constructor for an event that can't implement AnyEvent because it is not Copy, such as a Toast with
+    /// its message text. Use downcast_ref() to read it.
+    pub fn from_message<M: Any>(message: M) -> Self {
+        EventBox { event: Box::new(message), event_type: TypeId::of::<M>(), sender: NodeID::default(), tag: None }
+    }
+
     /// Determine if the event type matches
     pub fn is_type<E: AnyEvent>(&self) -> bool {
         self.event_type == TypeId::of::<E>()
@@ -133,6 +139,13 @@ impl EventBus {
         self.event_queue.push(event);
     }
 
+    /// Add an event that is not Copy to the queue, such as a Toast
+    pub fn dispatch_message<M: Any>(&mut self, message: M, sender: NodeID) {
+        let mut event = EventBox::from_message(message);
+        event.sender = sender;
+        self.event_queue.push(event);
+    }
+
     /// Pop an event from the queue
     pub fn dequeue(&mut self) -> Option<EventBox> {
         if !self.event_queue.is_empty() {
@@ -40,3 +40,70 @@ pub enum TweenType {
     /// A rotation animation
     Rotation,
 }
+
+/// Describes why the value of an input field failed validation. Used by FieldEvent::Invalid and
+/// FieldValue::Invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationError {
+    /// A value is required but the field is empty
+    Required,
+    /// The value has fewer chars than the specified minimum
+    TooShort(usize),
+    /// The value has more chars than the specified maximum
+    TooLong(usize),
+    /// The input mask has not been completely filled
+    Incomplete,
+    /// Not a valid decimal number
+    InvalidNumber,
+    /// Not a valid integer
+    InvalidInteger,
+    /// Not a valid email address
+    InvalidEmail,
+    /// Not a valid http or https URL
+    InvalidUrl,
+    /// Not a valid date in YYYY-MM-DD format
+    InvalidDate,
+    /// The value did not match the regex pattern
+    PatternMismatch,
+    /// A custom validator closure rejected the value
+    Custom,
+}
+
+impl ValidationError {
+    /// A short description that can be displayed to the user
+    pub fn description(&self) -> String {
+        match self {
+            ValidationError::Required => "This field is required".to_string(),
+            ValidationError::TooShort(min) => format!("Must be at least {} characters", min),
+            ValidationError::TooLong(max) => format!("Must be at most {} characters", max),
+            ValidationError::Incomplete => "Input is incomplete".to_string(),
+            ValidationError::InvalidNumber => "Not a valid number".to_string(),
+            ValidationError::InvalidInteger => "Not a valid whole number".to_string(),
+            ValidationError::InvalidEmail => "Not a valid email address".to_string(),
+            ValidationError::InvalidUrl => "Not a valid URL".to_string(),
+            ValidationError::InvalidDate => "Not a valid date".to_string(),
+            ValidationError::PatternMismatch => "Invalid format".to_string(),
+            ValidationError::Custom => "Invalid value".to_string(),
+        }
+    }
+}
+
+/// Text input events from a platform IME or dead-key sequence. Backends feed these to Responders
+/// with handle_text_input(). The text is not final until CompositionCommit.
+/// These are not AnyEvents because they contain a String.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextInputEvent {
+    /// A composition session has started
+    CompositionStart,
+    /// The preedit text has changed. The cursor is a char index within the text.
+    CompositionUpdate {
+        /// The preedit text
+        text: String,
+        /// Cursor position within the preedit text
+        cursor: usize,
+    },
+    /// The composition is finished and the text should be inserted
+    CompositionCommit(String),
+    /// The composition was cancelled and the preedit text should be discarded
+    CompositionCancel,
+}
@@ -0,0 +1,335 @@
+/// AnimatedImageView plays a sequence of frames from a sprite sheet
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Vector},
+    graphics::Color,
+    lifecycle::{Asset, Window},
+};
+
+use std::any::TypeId;
+
+use super::*;
+
+//-- Main -----------------------------------------------------------------------
+
+/// A view that plays frames of a SpriteSheet at a fixed frame rate. It implements Playable, so it starts when play()
+/// is called or when its scheduled time arrives in a Timeline.
+pub struct AnimatedImageView {
+    /// The base layer
+    pub layer: Layer,
+    /// The bytes of the sprite sheet image
+    loader: Option<Asset<Vec<u8>>>,
+    /// The uploaded sprite sheet image
+    texture: Option<GPUTexture>,
+    /// The frames in the texture
+    sheet: SpriteSheet,
+    /// The indexes of the sheet frames to play
+    sequence: Vec<usize>,
+    /// Frames per second
+    fps: f32,
+    /// Restart after the last frame
+    looping: bool,
+    /// Play the frames forward and then backward
+    ping_pong: bool,
+    /// The playback state
+    state: PlayState,
+    /// The clock time when the sequence started, adjusted for pauses
+    started_at: f64,
+    /// The seconds played so far, saved when paused
+    elapsed: f64,
+    /// The position in the sequence
+    current: usize,
+}
+
+impl AnimatedImageView {
+    /// Constructor with the sprite sheet image as an asset. All frames of the sheet are played in order.
+    pub fn new(frame: Rectangle, asset: Option<Asset<Vec<u8>>>, sheet: SpriteSheet) -> Self {
+        let layer = Layer::new(frame);
+        let sequence = (0..sheet.len()).collect();
+        AnimatedImageView {
+            layer,
+            loader: asset,
+            texture: None,
+            sheet,
+            sequence,
+            fps: 12.0,
+            looping: true,
+            ping_pong: false,
+            state: PlayState::Waiting,
+            started_at: 0.0,
+            elapsed: 0.0,
+            current: 0,
+        }
+    }
+
+    /// Constructor with a sprite sheet image that has already been uploaded
+    pub fn with_texture(frame: Rectangle, texture: GPUTexture, sheet: SpriteSheet) -> Self {
+        let mut view = AnimatedImageView::new(frame, None, sheet);
+        view.texture = Some(texture);
+        view
+    }
+
+    /// Builder method to set the frames per second. The default is 12.
+    pub fn with_fps(mut self, fps: f32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Builder method to set whether the sequence repeats forever. The default is true.
+    pub fn with_loop(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    /// Builder method to play the frames forward and then backward
+    pub fn with_ping_pong(mut self, ping_pong: bool) -> Self {
+        self.ping_pong = ping_pong;
+        self
+    }
+
+    /// Builder method to play the frames whose names start with the prefix. See SpriteSheet::sequence
+    pub fn with_sequence(mut self, prefix: &str) -> Self {
+        self.set_sequence(self.sheet.sequence(prefix));
+        self
+    }
+
+    /// Set the indexes of the sheet frames to play and go back to the first one
+    pub fn set_sequence(&mut self, sequence: Vec<usize>) {
+        self.sequence = sequence.into_iter().filter(|index| *index < self.sheet.len()).collect();
+        self.current = 0;
+        self.elapsed = 0.0;
+    }
+
+    /// The number of frames in one cycle, which includes the way back for ping-pong
+    fn cycle_length(&self) -> usize {
+        let count = self.sequence.len();
+        if self.ping_pong && count > 1 {
+            count * 2 - 2
+        } else {
+            count
+        }
+    }
+
+    /// The seconds to play one cycle
+    pub fn duration(&self) -> f64 {
+        if self.fps <= 0.0 {
+            return 0.0;
+        }
+        self.cycle_length() as f64 / self.fps as f64
+    }
+
+    /// True while the frames are advancing
+    pub fn is_playing(&self) -> bool {
+        self.state == PlayState::Running
+    }
+
+    /// The name of the frame that is displayed
+    pub fn current_frame(&self) -> Option<&str> {
+        let index = self.sequence.get(self.current)?;
+        self.sheet.get(*index).map(|frame| frame.name.as_str())
+    }
+
+    /// Calculate the position in the sequence for the seconds played
+    fn position_at(&self, elapsed: f64) -> usize {
+        let cycle = self.cycle_length();
+        if cycle == 0 {
+            return 0;
+        }
+        let tick = (elapsed * self.fps as f64).floor().max(0.0) as usize;
+        let step = tick % cycle;
+        if step < self.sequence.len() {
+            step
+        } else {
+            cycle - step
+        }
+    }
+
+    /// Advance the playhead with the clock
+    fn advance(&mut self, state: &mut AppState) {
+        let current = state.clock.current_time();
+        match self.state {
+            PlayState::Pending => {
+                self.started_at = current - self.elapsed;
+                self.state = PlayState::Running;
+            }
+            PlayState::Running => {
+                self.elapsed = current - self.started_at;
+                if !self.looping && self.elapsed >= self.duration() {
+                    // Hold the frame at the end of the cycle, which is the first frame for ping-pong
+                    self.current = if self.ping_pong { 0 } else { self.sequence.len().saturating_sub(1) };
+                    self.state = PlayState::Completed;
+                } else {
+                    self.current = self.position_at(self.elapsed);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Load the sprite sheet image into a texture when the asset is ready
+    fn load_texture(&mut self) {
+        if self.texture.is_some() {
+            return;
+        }
+        let name = self.node_key();
+        let mut texture = None;
+        if let Some(loader) = &mut self.loader {
+            let result = loader.execute(|bytes| {
+                texture = DrawImage::upload_image_bytes(&name, bytes.as_slice());
+                Ok(())
+            });
+            if let Err(err) = result {
+                log::error!("{} asset loading failed: {}", name, err);
+                return;
+            }
+        }
+        self.texture = texture;
+    }
+}
+
+// ************************************************************************************
+// Displayable trait
+// ************************************************************************************
+
+impl Displayable for AnimatedImageView {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<AnimatedImageView>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn get_content_size(&self) -> Vector {
+        match self.sequence.first().and_then(|index| self.sheet.get(*index)) {
+            Some(frame) => frame.source_size,
+            None => self.get_frame().size,
+        }
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        let ok = self.layer.apply_theme(theme);
+        if !ok {
+            return;
+        }
+    }
+
+    /// The Timeline uses this to schedule the playback
+    fn get_tween_duration(&mut self) -> f64 {
+        let tween_time = self.layer.animation.as_ref().map_or(0.0, |tween| tween.total_time());
+        tween_time.max(self.duration())
+    }
+
+    fn get_playable(&mut self) -> Option<&mut dyn Playable> {
+        Some(self)
+    }
+
+    fn handle_event(&mut self, event: &EventBox, _app_state: &mut AppState) {
+        if let Ok(evt) = event.downcast_ref::<PlayerEvent>() {
+            match evt {
+                PlayerEvent::Play => self.play(),
+                PlayerEvent::Pause => self.pause(),
+                PlayerEvent::Reset => self.reset(),
+                PlayerEvent::Replay => {
+                    self.reset();
+                    self.play();
+                }
+                _ => (),
+            }
+        }
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        self.advance(state);
+    }
+
+    fn render(&mut self, _theme: &mut Theme, window: &mut Window) {
+        if self.layer.debug {
+            self.layer.draw_border(window);
+        }
+        self.load_texture();
+        let texture = match self.texture {
+            Some(texture) => texture,
+            None => return,
+        };
+        let frame = match self.sequence.get(self.current).and_then(|index| self.sheet.get(*index)) {
+            Some(frame) => frame,
+            None => return,
+        };
+        let bounds = if self.layer.is_transitioning() { self.layer.transition.frame } else { self.layer.frame };
+        let quad = frame.tex_quad(texture.size());
+        if let Some(task) = DrawImage::sub_texture(texture.idx, frame.draw_rect(&bounds), Some(quad), Color::WHITE) {
+            window.add_task(task);
+        }
+    }
+}
+
+// ************************************************************************************
+// Playable trait
+// ************************************************************************************
+
+impl Playable for AnimatedImageView {
+    /// Start from the Waiting state or resume after a pause. A completed sequence must be reset first.
+    fn play(&mut self) {
+        match self.state {
+            PlayState::Waiting | PlayState::Idle => {
+                self.state = PlayState::Pending;
+            }
+            _ => (),
+        }
+    }
+
+    /// Stop and hold the current frame
+    fn stop(&mut self) {
+        self.state = PlayState::Completed;
+    }
+
+    /// Hold the current frame until play() is called
+    fn pause(&mut self) {
+        if self.state == PlayState::Running || self.state == PlayState::Pending {
+            self.state = PlayState::Idle;
+        }
+    }
+
+    /// Go back to the first frame and wait for play()
+    fn reset(&mut self) {
+        self.state = PlayState::Waiting;
+        self.elapsed = 0.0;
+        self.current = 0;
+        self.layer.reset();
+    }
+
+    fn set_state(&mut self, state: PlayState) {
+        self.state = state;
+    }
+}
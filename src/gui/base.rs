@@ -5,10 +5,17 @@ use crate::events::*;
 
 use std::any::{Any, TypeId};
 
-use super::{gui_print_type, layer::Layer, stage::StageContext, theme::Theme};
+use super::{
+    date_picker::CalendarDate,
+    gui_print_type,
+    layer::{Layer, Visibility},
+    stage::StageContext,
+    theme::Theme,
+    time_picker::TimeOfDay,
+};
 
 use quicksilver::{
-    geom::{Rectangle, Vector},
+    geom::{Rectangle, Shape, Vector},
     graphics::Color,
     input::Key,
     lifecycle::Window,
@@ -27,6 +34,16 @@ pub enum FieldValue {
     Selections(Vec<usize>),
     /// Checkbox value
     Checkbox(bool),
+    /// Numeric value of a field, such as the progress of a ProgressBar from 0.0 to 1.0
+    Number(f32),
+    /// Color value of a field, such as a ColorPicker
+    Color(Color),
+    /// Date value of a field, such as a DatePicker
+    Date(CalendarDate),
+    /// Time value of a field, such as a TimePicker
+    Time(TimeOfDay),
+    /// Text value of an input field that failed validation, with the reason
+    Invalid(String, ValidationError),
 }
 
 /// This trait lives in quicksilver_ui because it is heavily tied into quicksilver.
@@ -139,6 +156,16 @@ pub trait Displayable: Any {
         false
     }
 
+    /// Find the topmost visible Layer at the point that passes the filter, such as a Layer with a tooltip.
+    /// Containers override this to search their child objects first.
+    fn layer_at(&self, pt: &Vector, filter: &dyn Fn(&Layer) -> bool) -> Option<&Layer> {
+        let layer = self.get_layer();
+        if layer.visibility != Visibility::Visible || !pt.overlaps_rectangle(&layer.frame) || !filter(layer) {
+            return None;
+        }
+        Some(layer)
+    }
+
     /// Allow a GUI component to define the perimeter area that it needs outside of its
     /// base frame. This is currently used for masking UI overflow in ListBox and other
     /// components
@@ -160,6 +187,11 @@ pub trait Displayable: Any {
         0.0
     }
 
+    /// Views with their own playback, like AnimatedImageView, return themselves so a Timeline can play them
+    fn get_playable(&mut self) -> Option<&mut dyn Playable> {
+        None
+    }
+
     /// Standard format for printing out view information. In general, traits implementors do not need to override it. However, if an object contains
     /// nested views, it may be useful to print out those details. OptionGroup is one example.
     fn debug_out(&self) -> String {
@@ -225,4 +257,9 @@ pub trait Responder: Displayable {
     fn handle_key_command(&mut self, _key: &Key, _window: &mut Window) -> bool {
         false
     }
+
+    /// Handle IME composition and dead-key input. Returns true if the event was consumed.
+    fn handle_text_input(&mut self, _event: &TextInputEvent, _window: &mut Window) -> bool {
+        false
+    }
 }
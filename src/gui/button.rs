@@ -158,7 +158,9 @@ impl Displayable for Button {
         if !ok {
             return;
         }
-        self.layer.bg_style = BackgroundStyle::Solid(theme.button_bg_color);
+        if !self.layer.bg_style.is_image() {
+            self.layer.bg_style = BackgroundStyle::Solid(theme.button_bg_color);
+        }
     }
 
     fn handle_event(&mut self, event: &EventBox, _app_state: &mut AppState) {
@@ -235,7 +237,19 @@ impl Displayable for Button {
             view.render(theme, window);
         }
         if let Some(view) = &mut self.image {
-            view.render(theme, window);
+            if self.layer.get_corner_radii().iter().any(|radius| *radius > 0.0) {
+                // Draw the image mesh directly so that it can be clipped to the rounded corners
+                let mut meshes = view.get_layer_mut().prepare_render(window);
+                if meshes.is_empty() {
+                    meshes.extend(view.content_mesh());
+                }
+                for mut task in meshes {
+                    self.layer.clip_to_corners(&mut task);
+                    window.add_task(task);
+                }
+            } else {
+                view.render(theme, window);
+            }
         }
     }
 
@@ -290,7 +304,7 @@ impl Displayable for Button {
 
 impl Responder for Button {
     fn handle_mouse_up(&mut self, pt: &Vector, state: &mut AppState) -> bool {
-        if pt.overlaps_rectangle(&self.layer.frame) {
+        if self.layer.contains_point(pt) {
             self.layer.handle_click_animation();
             if let Some(cb) = &mut self.layer.on_click {
                 (&mut *cb)(state);
@@ -156,8 +156,9 @@ impl Displayable for Checkbox {
         let box_frame = UITools::position_left_middle(&frame, &box_frame, 5.0);
         match self.check_style {
             CheckStyle::X => {
+                let radii = self.layer.get_corner_radii();
                 let mut task = MeshTask::new(0);
-                let mut mesh = DrawShape::rectangle(&box_frame, None, Some(stroke_color), stroke, 0.0);
+                let mut mesh = DrawShape::rounded_rectangle(&box_frame, None, Some(stroke_color), stroke, radii);
                 task.append(&mut mesh);
 
                 if self.is_checked {
@@ -178,6 +179,20 @@ impl Displayable for Checkbox {
                     ];
                     let mut line = DrawShape::line(&pts, stroke_color, stroke);
                     task.append(&mut line);
+
+                    // Clip the X to the outside edge of the border so that it stays inside rounded corners
+                    if radii.iter().any(|radius| *radius > 0.0) {
+                        let half = stroke / 2.0;
+                        let outer = Rectangle::new(
+                            box_frame.pos - Vector::new(half, half),
+                            box_frame.size + Vector::new(stroke, stroke),
+                        );
+                        let mut outer_radii = radii;
+                        for radius in outer_radii.iter_mut().filter(|radius| **radius > 0.0) {
+                            *radius += half;
+                        }
+                        UITools::clip_rounded(&mut task, &outer, outer_radii);
+                    }
                 }
                 self.layer.meshes.push(task.clone());
                 window.add_task(task);
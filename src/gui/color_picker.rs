@@ -0,0 +1,599 @@
+/// ColorPicker
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Vector},
+    graphics::{Background::Col, Color, GpuTriangle, MeshTask, Vertex},
+    input::{Key, MouseCursor},
+    lifecycle::Window,
+};
+use std::any::TypeId;
+
+use super::*;
+
+/// The padding inside the frame
+const PICKER_PADDING: f32 = 8.0;
+/// The space between the parts of the picker
+const PICKER_GAP: f32 = 8.0;
+/// The width of the hue and alpha sliders
+const SLIDER_WIDTH: f32 = 16.0;
+/// The height of the hex/RGB text field and the color preview
+const FIELD_HEIGHT: f32 = 28.0;
+/// The width and height of a swatch in the history
+const SWATCH_SIZE: f32 = 18.0;
+/// The maximum number of colors in the swatch history
+const MAX_HISTORY: usize = 8;
+/// The size of the squares in the checkerboard behind transparent colors
+const CHECKER_SIZE: f32 = 6.0;
+
+/// Add a quad with a color at each corner to the task. The GPU blends the colors between the corners.
+/// The corners are in order: top left, top right, bottom right, bottom left.
+fn gradient_quad(task: &mut MeshTask, rect: &Rectangle, colors: [Color; 4]) {
+    let offset = task.vertices.len() as u32;
+    let (x1, y1) = (rect.x(), rect.y());
+    let (x2, y2) = (rect.x() + rect.width(), rect.y() + rect.height());
+    task.vertices.push(Vertex::new(Vector::new(x1, y1), None, Col(colors[0])));
+    task.vertices.push(Vertex::new(Vector::new(x2, y1), None, Col(colors[1])));
+    task.vertices.push(Vertex::new(Vector::new(x2, y2), None, Col(colors[2])));
+    task.vertices.push(Vertex::new(Vector::new(x1, y2), None, Col(colors[3])));
+    task.triangles.push(GpuTriangle::new(offset, [0, 1, 2], 9, Col(colors[0])));
+    task.triangles.push(GpuTriangle::new(offset, [2, 3, 0], 9, Col(colors[0])));
+}
+
+/// Add a checkerboard to the task, which shows through transparent colors drawn over it
+fn checkerboard(task: &mut MeshTask, rect: &Rectangle) {
+    let light = Color::from_hex("#FFFFFF");
+    let dark = Color::from_hex("#CCCCCC");
+    let mut mesh = DrawShape::rectangle(rect, Some(light), None, 0.0, 0.0);
+    task.append(&mut mesh);
+    let cols = (rect.width() / CHECKER_SIZE).ceil() as usize;
+    let rows = (rect.height() / CHECKER_SIZE).ceil() as usize;
+    for row in 0..rows {
+        for col in (row % 2..cols).step_by(2) {
+            let x = rect.x() + col as f32 * CHECKER_SIZE;
+            let y = rect.y() + row as f32 * CHECKER_SIZE;
+            let w = CHECKER_SIZE.min(rect.x() + rect.width() - x);
+            let h = CHECKER_SIZE.min(rect.y() + rect.height() - y);
+            let mut mesh = DrawShape::rectangle(&Rectangle::new((x, y), (w, h)), Some(dark), None, 0.0, 0.0);
+            task.append(&mut mesh);
+        }
+    }
+}
+
+/// The part of the picker that is being dragged
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PickerDrag {
+    SaturationValue,
+    Hue,
+    Alpha,
+}
+
+//-- ColorPicker -----------------------------------------------------------------------
+
+/// A color picker with a saturation/value area, hue and alpha sliders, a text field for hex or RGB entry
+/// and a history of recently picked colors.
+///
+/// The text field accepts "#RRGGBB", "#RRGGBBAA" or comma separated RGB values like "255, 128, 0" with an
+/// optional alpha, which can be wrapped in "rgb()" or "rgba()". Press Return to apply the text.
+/// Changes made by the user dispatch FieldEvent::Changed and the value is a FieldValue::Color.
+pub struct ColorPicker {
+    /// The base layer
+    pub layer: Layer,
+    /// Hue in degrees from 0.0 to 360.0
+    hue: f32,
+    /// Saturation from 0.0 to 1.0
+    saturation: f32,
+    /// Value (brightness) from 0.0 to 1.0
+    value: f32,
+    /// Alpha from 0.0 to 1.0
+    alpha: f32,
+    /// Recently picked colors, newest first
+    history: Vec<Color>,
+    /// The text field for hex or RGB entry
+    text_field: TextField,
+    /// Is the text field being edited?
+    is_editing: bool,
+    /// The text field is replaced when the picker moves and needs the theme in the next render()
+    field_needs_theme: bool,
+    /// The part of the picker that is being dragged
+    drag: Option<PickerDrag>,
+    /// Theme colors
+    border_color: Color,
+    /// FieldEvents waiting to be dispatched in the next update()
+    pending_events: Vec<FieldEvent>,
+}
+
+impl ColorPicker {
+    /// Constructor
+    pub fn new(frame: Rectangle) -> Self {
+        let layer = Layer::new(frame);
+        let text_field = TextField::new(frame, true);
+        let mut picker = ColorPicker {
+            layer,
+            hue: 0.0,
+            saturation: 0.0,
+            value: 1.0,
+            alpha: 1.0,
+            history: Vec::new(),
+            text_field,
+            is_editing: false,
+            field_needs_theme: true,
+            drag: None,
+            border_color: Color::from_hex("#AAAAAA"),
+            pending_events: Vec::new(),
+        };
+        picker.reset_field();
+        picker
+    }
+
+    /// Builder method to set the initial color
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.set_color(color);
+        self
+    }
+
+    /// Builder method to set the swatch history, newest first
+    pub fn with_history(mut self, colors: &[Color]) -> Self {
+        self.history = colors.iter().take(MAX_HISTORY).cloned().collect();
+        self
+    }
+
+    /// The selected color
+    pub fn color(&self) -> Color {
+        let (r, g, b) = hsv_to_rgb(self.hue, self.saturation, self.value);
+        Color { r, g, b, a: self.alpha }
+    }
+
+    /// Set the selected color. This does not change the history.
+    pub fn set_color(&mut self, color: Color) {
+        let (h, s, v) = rgb_to_hsv(color.r, color.g, color.b);
+        // Keep the hue of grays so that the hue slider does not jump
+        if s > 0.0 && v > 0.0 {
+            self.hue = h;
+        }
+        self.saturation = s;
+        self.value = v;
+        self.alpha = color.a;
+        self.update_text();
+    }
+
+    /// The selected color as a hex string. The alpha is only included if the color is transparent.
+    pub fn hex(&self) -> String {
+        let color = self.color();
+        hex_from_rgb(color.r * 255.0, color.g * 255.0, color.b * 255.0, color.a * 255.0)
+    }
+
+    /// Recently picked colors, newest first
+    pub fn history(&self) -> &[Color] {
+        &self.history
+    }
+
+    /// Add the color to the front of the history. If it is already in the history, it is moved to the front.
+    pub fn add_to_history(&mut self, color: Color) {
+        let hex = |c: &Color| hex_from_rgb(c.r * 255.0, c.g * 255.0, c.b * 255.0, c.a * 255.0);
+        let key = hex(&color);
+        self.history.retain(|c| hex(c) != key);
+        self.history.insert(0, color);
+        self.history.truncate(MAX_HISTORY);
+    }
+
+    /// Remove all colors from the history
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+
+    /// Parse "#RRGGBB", "#RRGGBBAA" or comma separated RGB values from 0 to 255 with an optional alpha,
+    /// which can be wrapped in "rgb()" or "rgba()". The alpha can be from 0.0 to 1.0 or from 0 to 255.
+    pub fn parse_color(text: &str) -> Option<Color> {
+        let text = text.trim();
+        if let Some(rgba) = parse_hex(text) {
+            return Some(Color { r: rgba.0 / 255.0, g: rgba.1 / 255.0, b: rgba.2 / 255.0, a: rgba.3 / 255.0 });
+        }
+        let inner =
+            text.trim_start_matches("rgba").trim_start_matches("rgb").trim_start_matches('(').trim_end_matches(')');
+        let values: Vec<f32> = inner.split(',').filter_map(|part| part.trim().parse::<f32>().ok()).collect();
+        if values.len() != inner.split(',').count() || values.len() < 3 || values.len() > 4 {
+            return None;
+        }
+        if values[..3].iter().any(|v| *v < 0.0 || *v > 255.0) {
+            return None;
+        }
+        let a = match values.get(3) {
+            Some(a) if *a <= 1.0 => *a,
+            Some(a) => *a / 255.0,
+            None => 1.0,
+        };
+        Some(Color { r: values[0] / 255.0, g: values[1] / 255.0, b: values[2] / 255.0, a: a.max(0.0).min(1.0) })
+    }
+
+    /// The frame inside the padding
+    fn content_frame(&self) -> Rectangle {
+        UITools::padded_rect(&self.layer.frame, -PICKER_PADDING, -PICKER_PADDING)
+    }
+
+    /// The y position of the text field row
+    fn field_y(&self) -> f32 {
+        let content = self.content_frame();
+        content.y() + content.height() - SWATCH_SIZE - PICKER_GAP - FIELD_HEIGHT
+    }
+
+    /// The saturation/value area in the top left
+    fn sv_frame(&self) -> Rectangle {
+        let content = self.content_frame();
+        let width = content.width() - (SLIDER_WIDTH + PICKER_GAP) * 2.0;
+        let height = self.field_y() - PICKER_GAP - content.y();
+        Rectangle::new(content.pos, (width.max(0.0), height.max(0.0)))
+    }
+
+    fn hue_frame(&self) -> Rectangle {
+        let sv = self.sv_frame();
+        Rectangle::new((sv.x() + sv.width() + PICKER_GAP, sv.y()), (SLIDER_WIDTH, sv.height()))
+    }
+
+    fn alpha_frame(&self) -> Rectangle {
+        let hue = self.hue_frame();
+        Rectangle::new((hue.x() + hue.width() + PICKER_GAP, hue.y()), (SLIDER_WIDTH, hue.height()))
+    }
+
+    /// The preview of the selected color, left of the text field
+    fn preview_frame(&self) -> Rectangle {
+        Rectangle::new((self.content_frame().x(), self.field_y()), (FIELD_HEIGHT, FIELD_HEIGHT))
+    }
+
+    fn field_frame(&self) -> Rectangle {
+        let content = self.content_frame();
+        let x = content.x() + FIELD_HEIGHT + PICKER_GAP;
+        Rectangle::new((x, self.field_y()), ((content.x() + content.width() - x).max(0.0), FIELD_HEIGHT))
+    }
+
+    fn swatch_frame(&self, index: usize) -> Rectangle {
+        let content = self.content_frame();
+        let x = content.x() + index as f32 * (SWATCH_SIZE + PICKER_GAP / 2.0);
+        Rectangle::new((x, content.y() + content.height() - SWATCH_SIZE), (SWATCH_SIZE, SWATCH_SIZE))
+    }
+
+    /// Replace the text field with one at the current field frame
+    fn reset_field(&mut self) {
+        let mut field = TextField::new(self.field_frame(), true).with_padding(6.0, 2.0).with_max_length(24);
+        field.set_text(&self.hex());
+        self.text_field = field;
+        self.field_needs_theme = true;
+        self.is_editing = false;
+    }
+
+    /// Replace the text field if the picker has moved
+    fn relayout(&mut self) {
+        if self.text_field.get_frame() != self.field_frame() {
+            self.reset_field();
+        }
+    }
+
+    /// Show the hex of the selected color in the text field, unless the user is typing in it
+    fn update_text(&mut self) {
+        if !self.is_editing {
+            let hex = self.hex();
+            self.text_field.set_text(&hex);
+        }
+    }
+
+    /// Apply the text in the text field if it is a valid color
+    fn commit_text(&mut self) {
+        self.text_field.notify(&DisplayEvent::Deactivate);
+        self.is_editing = false;
+        let text = self.text_field.get_text().to_owned();
+        match ColorPicker::parse_color(&text) {
+            Some(color) => {
+                self.set_color(color);
+                self.add_to_history(color);
+                self.pending_events.push(FieldEvent::Changed);
+            }
+            None => self.update_text(),
+        }
+    }
+
+    /// Change the value of the dragged part for the mouse position
+    fn drag_to(&mut self, pt: &Vector) {
+        let drag = match self.drag {
+            Some(drag) => drag,
+            None => return,
+        };
+        let fraction = |value: f32, start: f32, length: f32| {
+            if length > 0.0 {
+                ((value - start) / length).max(0.0).min(1.0)
+            } else {
+                0.0
+            }
+        };
+        match drag {
+            PickerDrag::SaturationValue => {
+                let sv = self.sv_frame();
+                self.saturation = fraction(pt.x, sv.x(), sv.width());
+                self.value = 1.0 - fraction(pt.y, sv.y(), sv.height());
+            }
+            PickerDrag::Hue => {
+                let hue = self.hue_frame();
+                self.hue = fraction(pt.y, hue.y(), hue.height()) * 360.0;
+            }
+            PickerDrag::Alpha => {
+                let alpha = self.alpha_frame();
+                self.alpha = 1.0 - fraction(pt.y, alpha.y(), alpha.height());
+            }
+        }
+        self.update_text();
+        self.pending_events.push(FieldEvent::Changed);
+    }
+
+    /// Draw a marker around the point, which is visible on light and dark colors
+    fn draw_marker(task: &mut MeshTask, center: &Vector) {
+        let mut mesh = DrawShape::circle(center, 6.0, None, Some(Color::BLACK), 1.0);
+        task.append(&mut mesh);
+        let mut mesh = DrawShape::circle(center, 5.0, None, Some(Color::WHITE), 2.0);
+        task.append(&mut mesh);
+    }
+
+    /// Draw a marker across the slider at the fraction of its height
+    fn draw_slider_marker(task: &mut MeshTask, rect: &Rectangle, fraction: f32) {
+        let y = rect.y() + rect.height() * fraction;
+        let marker = Rectangle::new((rect.x() - 2.0, y - 3.0), (rect.width() + 4.0, 6.0));
+        let mut mesh = DrawShape::rectangle(&marker, None, Some(Color::BLACK), 1.0, 2.0);
+        task.append(&mut mesh);
+        let marker = UITools::padded_rect(&marker, -1.0, -1.0);
+        let mut mesh = DrawShape::rectangle(&marker, None, Some(Color::WHITE), 1.0, 2.0);
+        task.append(&mut mesh);
+    }
+}
+
+// *****************************************************************************************************
+// ColorPicker :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for ColorPicker {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<ColorPicker>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+        self.relayout();
+    }
+
+    fn align_view(&mut self, origin: Vector) {
+        self.layer.frame.pos = self.layer.anchor_pt + origin;
+        self.relayout();
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        let ok = self.layer.apply_theme(theme);
+        if !ok {
+            return;
+        }
+        self.border_color = theme.border_color;
+        self.text_field.set_theme(theme);
+        self.field_needs_theme = false;
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Deactivate => {
+                if self.is_editing {
+                    self.commit_text();
+                }
+            }
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+                self.relayout();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        self.text_field.update(window, state);
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        self.layer.draw_background(window);
+        let color = self.color();
+        let opaque = Color { a: 1.0, ..color };
+        let clear = Color { a: 0.0, ..color };
+        let black = Color::BLACK;
+        let transparent = Color { a: 0.0, ..Color::BLACK };
+
+        let mut task = MeshTask::new(0);
+
+        // Saturation increases to the right and value decreases downward
+        let sv = self.sv_frame();
+        let (r, g, b) = hsv_to_rgb(self.hue, 1.0, 1.0);
+        let pure = Color { r, g, b, a: 1.0 };
+        gradient_quad(&mut task, &sv, [Color::WHITE, pure, pure, Color::WHITE]);
+        gradient_quad(&mut task, &sv, [transparent, transparent, black, black]);
+        let pt = Vector::new(sv.x() + sv.width() * self.saturation, sv.y() + sv.height() * (1.0 - self.value));
+        ColorPicker::draw_marker(&mut task, &pt);
+
+        // The hue slider has one gradient for each 60 degrees
+        let hue = self.hue_frame();
+        let step = hue.height() / 6.0;
+        for i in 0..6 {
+            let (r, g, b) = hsv_to_rgb(i as f32 * 60.0, 1.0, 1.0);
+            let top = Color { r, g, b, a: 1.0 };
+            let (r, g, b) = hsv_to_rgb((i + 1) as f32 * 60.0, 1.0, 1.0);
+            let bottom = Color { r, g, b, a: 1.0 };
+            let rect = Rectangle::new((hue.x(), hue.y() + step * i as f32), (hue.width(), step));
+            gradient_quad(&mut task, &rect, [top, top, bottom, bottom]);
+        }
+        ColorPicker::draw_slider_marker(&mut task, &hue, self.hue / 360.0);
+
+        // The alpha slider goes from opaque at the top to transparent at the bottom
+        let alpha = self.alpha_frame();
+        checkerboard(&mut task, &alpha);
+        gradient_quad(&mut task, &alpha, [opaque, opaque, clear, clear]);
+        ColorPicker::draw_slider_marker(&mut task, &alpha, 1.0 - self.alpha);
+
+        let preview = self.preview_frame();
+        checkerboard(&mut task, &preview);
+        let mut mesh = DrawShape::rectangle(&preview, Some(color), Some(self.border_color), 1.0, 0.0);
+        task.append(&mut mesh);
+
+        for (index, swatch) in self.history.iter().enumerate() {
+            let rect = self.swatch_frame(index);
+            if rect.x() + rect.width() > self.content_frame().x() + self.content_frame().width() {
+                break;
+            }
+            checkerboard(&mut task, &rect);
+            let mut mesh = DrawShape::rectangle(&rect, Some(*swatch), Some(self.border_color), 1.0, 0.0);
+            task.append(&mut mesh);
+        }
+        window.add_task(task);
+
+        if self.field_needs_theme {
+            self.text_field.set_theme(theme);
+            self.field_needs_theme = false;
+        }
+        self.text_field.render(theme, window);
+        self.layer.draw_border(window);
+    }
+
+    fn set_hover_animation(&mut self, props: PropSet) {
+        self.layer.hover_effect = Some(props);
+    }
+
+    /// While a part of the picker is dragged, its value follows the mouse
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        if self.drag.is_some() {
+            self.drag_to(pt);
+            window.set_cursor(MouseCursor::Crosshair);
+            return true;
+        }
+        if pt.overlaps_rectangle(&self.layer.frame) {
+            if pt.overlaps_rectangle(&self.field_frame()) {
+                window.set_cursor(MouseCursor::Text);
+            } else if pt.overlaps_rectangle(&self.sv_frame()) {
+                window.set_cursor(MouseCursor::Crosshair);
+            } else {
+                window.set_cursor(MouseCursor::Hand);
+            }
+            self.layer.mouse_state = MouseState::Hover;
+            return true;
+        }
+        self.layer.mouse_state = MouseState::None;
+        false
+    }
+}
+
+// *****************************************************************************************************
+// ColorPicker :: Responder
+// *****************************************************************************************************
+
+impl Responder for ColorPicker {
+    fn get_field_value(&self) -> FieldValue {
+        FieldValue::Color(self.color())
+    }
+
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if type_id == self.get_type_id() && layer_id == self.layer.get_id() {
+            if let FieldValue::Color(color) = value {
+                self.set_color(*color);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn handle_mouse_down(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        if pt.overlaps_rectangle(&self.field_frame()) {
+            if self.text_field.handle_mouse_down(pt, state) {
+                self.is_editing = true;
+            }
+            return true;
+        }
+        if self.is_editing {
+            self.commit_text();
+        }
+        if !pt.overlaps_rectangle(&self.layer.frame) {
+            return false;
+        }
+        if pt.overlaps_rectangle(&self.sv_frame()) {
+            self.drag = Some(PickerDrag::SaturationValue);
+        } else if pt.overlaps_rectangle(&self.hue_frame()) {
+            self.drag = Some(PickerDrag::Hue);
+        } else if pt.overlaps_rectangle(&self.alpha_frame()) {
+            self.drag = Some(PickerDrag::Alpha);
+        } else if let Some(index) = (0..self.history.len()).find(|i| pt.overlaps_rectangle(&self.swatch_frame(*i))) {
+            let color = self.history[index];
+            self.set_color(color);
+            self.pending_events.push(FieldEvent::Changed);
+        }
+        self.drag_to(pt);
+        true
+    }
+
+    /// The color that was dragged to is added to the history
+    fn handle_mouse_up(&mut self, _pt: &Vector, _state: &mut AppState) -> bool {
+        if self.drag.take().is_some() {
+            let color = self.color();
+            self.add_to_history(color);
+            return true;
+        }
+        false
+    }
+
+    fn handle_key_press(&mut self, c: char, window: &mut Window) {
+        if self.is_editing {
+            self.text_field.handle_key_press(c, window);
+        }
+    }
+
+    fn handle_text_input(&mut self, event: &TextInputEvent, window: &mut Window) -> bool {
+        if self.is_editing {
+            return self.text_field.handle_text_input(event, window);
+        }
+        false
+    }
+
+    /// Return applies the text, Escape discards it and Tab applies it and lets the Scene move the focus
+    fn handle_key_command(&mut self, key: &Key, window: &mut Window) -> bool {
+        if !self.is_editing {
+            return *key == Key::Tab;
+        }
+        match key {
+            Key::Return => {
+                self.commit_text();
+                true
+            }
+            Key::Tab => {
+                self.commit_text();
+                true
+            }
+            Key::Escape => {
+                self.text_field.notify(&DisplayEvent::Deactivate);
+                self.is_editing = false;
+                self.update_text();
+                true
+            }
+            _ => self.text_field.handle_key_command(key, window),
+        }
+    }
+}
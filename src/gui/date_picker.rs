@@ -0,0 +1,904 @@
+/// DatePicker
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Transform, Vector},
+    graphics::{Color, MeshTask},
+    input::{Key, MouseCursor},
+    lifecycle::Window,
+};
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::fmt;
+
+use super::*;
+
+/// The duration in seconds of the month slide and the popup fade
+const PICKER_DURATION: f64 = 0.25;
+/// The padding inside the calendar
+const CALENDAR_PADDING: f32 = 8.0;
+/// The height of the header with the month title and navigation arrows
+const HEADER_HEIGHT: f32 = 28.0;
+/// The height of the row of weekday names
+const WEEKDAY_HEIGHT: f32 = 20.0;
+/// The width of each navigation arrow button in the header
+const ARROW_WIDTH: f32 = 24.0;
+/// The size of the calendar popup in Dropdown mode
+const POPUP_SIZE: (f32, f32) = (252.0, 248.0);
+/// The space between the field and the popup in Dropdown mode
+pub(super) const POPUP_MARGIN: f32 = 4.0;
+
+const MONTH_NAMES: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+const WEEKDAY_NAMES: [&str; 7] = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
+
+//-- Dates -----------------------------------------------------------------------
+
+/// A day of the week
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    #[allow(missing_docs)]
+    Sunday,
+    #[allow(missing_docs)]
+    Monday,
+    #[allow(missing_docs)]
+    Tuesday,
+    #[allow(missing_docs)]
+    Wednesday,
+    #[allow(missing_docs)]
+    Thursday,
+    #[allow(missing_docs)]
+    Friday,
+    #[allow(missing_docs)]
+    Saturday,
+}
+
+impl Weekday {
+    /// The weekday for the number of days since Sunday
+    pub fn from_index(index: u32) -> Weekday {
+        match index % 7 {
+            0 => Weekday::Sunday,
+            1 => Weekday::Monday,
+            2 => Weekday::Tuesday,
+            3 => Weekday::Wednesday,
+            4 => Weekday::Thursday,
+            5 => Weekday::Friday,
+            _ => Weekday::Saturday,
+        }
+    }
+
+    /// The number of days since Sunday
+    pub fn index(&self) -> u32 {
+        *self as u32
+    }
+
+    /// The first day of the week for a locale such as "en-US" or "de_DE", based on CLDR week data.
+    /// Most of the world starts on Monday, which is also the result for unknown locales.
+    pub fn first_for_locale(locale: &str) -> Weekday {
+        let locale = locale.replace('_', "-");
+        let mut parts = locale.split('-');
+        let language = parts.next().unwrap_or_default().to_lowercase();
+        let region = parts.find(|part| part.len() == 2).map(|part| part.to_uppercase());
+        let sunday =
+            ["US", "CA", "MX", "BR", "JP", "KR", "TW", "HK", "IL", "IN", "PH", "ZA", "AU", "SA", "PE", "CO", "VE"];
+        let saturday = ["AE", "AF", "BH", "DZ", "EG", "IQ", "IR", "JO", "KW", "LY", "OM", "QA", "SD", "SY"];
+        match region {
+            Some(region) if sunday.contains(&region.as_str()) => Weekday::Sunday,
+            Some(region) if saturday.contains(&region.as_str()) => Weekday::Saturday,
+            Some(_) => Weekday::Monday,
+            None => match language.as_str() {
+                "en" | "ja" | "ko" | "he" | "hi" | "pt" => Weekday::Sunday,
+                "fa" => Weekday::Saturday,
+                _ => Weekday::Monday,
+            },
+        }
+    }
+}
+
+/// A date in the proleptic Gregorian calendar without a time zone. Dates are ordered chronologically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CalendarDate {
+    /// The year, such as 2020
+    pub year: i32,
+    /// The month from 1 to 12
+    pub month: u32,
+    /// The day of the month from 1
+    pub day: u32,
+}
+
+impl CalendarDate {
+    /// Constructor. Returns None if the month or day is out of range.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if month < 1 || month > 12 || day < 1 || day > Validators::days_in_month(year, month) {
+            return None;
+        }
+        Some(CalendarDate { year, month, day })
+    }
+
+    /// Parse a date in YYYY-MM-DD format
+    pub fn parse(text: &str) -> Option<Self> {
+        let (year, month, day) = Validators::parse_date(text)?;
+        Some(CalendarDate { year, month, day })
+    }
+
+    /// The number of days in the month of this date
+    pub fn days_in_month(&self) -> u32 {
+        Validators::days_in_month(self.year, self.month)
+    }
+
+    /// The day of the week
+    pub fn weekday(&self) -> Weekday {
+        // 1970-01-01 was a Thursday
+        Weekday::from_index((self.to_days() + 4).rem_euclid(7) as u32)
+    }
+
+    /// The date that is the number of days later, or earlier if negative
+    pub fn add_days(&self, days: i64) -> Self {
+        CalendarDate::from_days(self.to_days() + days)
+    }
+
+    /// The date that is the number of months later, or earlier if negative. The day is limited to the
+    /// number of days in the new month.
+    pub fn add_months(&self, months: i32) -> Self {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let year = index.div_euclid(12);
+        let month = index.rem_euclid(12) as u32 + 1;
+        let day = self.day.min(Validators::days_in_month(year, month));
+        CalendarDate { year, month, day }
+    }
+
+    /// The first day of the month of this date
+    pub fn first_of_month(&self) -> Self {
+        CalendarDate { day: 1, ..*self }
+    }
+
+    /// The number of days since 1970-01-01, using the days_from_civil algorithm by Howard Hinnant
+    fn to_days(&self) -> i64 {
+        let year = if self.month <= 2 { self.year - 1 } else { self.year } as i64;
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let month = self.month as i64;
+        let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// The inverse of to_days()
+    fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        CalendarDate { year, month, day }
+    }
+}
+
+impl fmt::Display for CalendarDate {
+    /// Format as YYYY-MM-DD
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+//-- Helpers -----------------------------------------------------------------------
+
+/// Whether a picker is shown in place or opens a popup from a field
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PickerMode {
+    /// The frame is the picker itself
+    Inline,
+    /// The frame is a field that shows the value and opens the picker below it when clicked
+    Dropdown,
+}
+
+/// Text meshes for the labels of the pickers, created at origin 0, 0 and keyed by the text and the size
+/// of the box that they are centered in
+pub(super) struct LabelCache {
+    tasks: HashMap<(String, u32, u32), MeshTask>,
+}
+
+impl LabelCache {
+    pub(super) fn new() -> Self {
+        LabelCache { tasks: HashMap::new() }
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.tasks.clear();
+    }
+
+    /// The text centered in the rect, with the color replaced if specified and the alpha multiplied
+    pub(super) fn draw(
+        &mut self,
+        theme: &mut Theme,
+        style: FontStyle,
+        text: &str,
+        rect: &Rectangle,
+        color: Option<Color>,
+        alpha: f32,
+    ) -> Option<MeshTask> {
+        let key = (text.to_owned(), rect.width() as u32, rect.height() as u32);
+        if !self.tasks.contains_key(&key) {
+            let frame = Rectangle::new((0.0, 0.0), rect.size);
+            let params = TextParams::new(style)
+                .frame(frame)
+                .text(text)
+                .align(TextAlign::Center, VertAlign::Middle)
+                .multiline(false);
+            let task = theme.default_font.draw(params)?;
+            self.tasks.insert(key.clone(), task);
+        }
+        let mut task = self.tasks.get(&key)?.clone();
+        for vertex in task.vertices.iter_mut() {
+            vertex.pos = Transform::translate(rect.pos) * vertex.pos;
+            if let Some(color) = color {
+                vertex.col = color;
+            }
+            vertex.col.a *= alpha;
+        }
+        Some(task)
+    }
+}
+
+/// The fraction of the rect inside the bounds, which is used to fade out content that slides past the edges
+pub(super) fn visible_fraction(rect: &Rectangle, bounds: &Rectangle) -> f32 {
+    let left = rect.x().max(bounds.x());
+    let right = (rect.x() + rect.width()).min(bounds.x() + bounds.width());
+    let top = rect.y().max(bounds.y());
+    let bottom = (rect.y() + rect.height()).min(bounds.y() + bounds.height());
+    if right <= left || bottom <= top || rect.width() <= 0.0 || rect.height() <= 0.0 {
+        return 0.0;
+    }
+    ((right - left) * (bottom - top)) / (rect.width() * rect.height())
+}
+
+/// Draw a small triangle that points left (-1.0), right (1.0), up (-2.0) or down (2.0)
+pub(super) fn draw_arrow(task: &mut MeshTask, center: &Vector, direction: f32, color: Color) {
+    let s = 4.0;
+    let (a, b, c) = {
+        if direction.abs() > 1.5 {
+            let d = direction.signum();
+            (
+                Vector::new(center.x - s, center.y - s * d / 2.0),
+                Vector::new(center.x + s, center.y - s * d / 2.0),
+                Vector::new(center.x, center.y + s * d / 2.0),
+            )
+        } else {
+            let d = direction.signum();
+            (
+                Vector::new(center.x - s * d / 2.0, center.y - s),
+                Vector::new(center.x - s * d / 2.0, center.y + s),
+                Vector::new(center.x + s * d / 2.0, center.y),
+            )
+        }
+    };
+    let mut mesh = DrawShape::triangle(&[&a, &b, &c], Some(color), None, 0.0);
+    task.append(&mut mesh);
+}
+
+/// Draw the field of a picker in Dropdown mode, which shows the value and a down arrow
+pub(super) fn draw_dropdown_field(
+    labels: &mut LabelCache,
+    theme: &mut Theme,
+    style: FontStyle,
+    frame: &Rectangle,
+    text: &str,
+    colors: &PickerColors,
+    has_focus: bool,
+    window: &mut Window,
+) {
+    let border = if has_focus { colors.focus } else { colors.border };
+    let mut task = MeshTask::new(0);
+    let mut mesh = DrawShape::rectangle(frame, Some(colors.bg), Some(border), 1.0, 4.0);
+    task.append(&mut mesh);
+    let arrow = Vector::new(frame.x() + frame.width() - 14.0, frame.y() + frame.height() / 2.0);
+    draw_arrow(&mut task, &arrow, 2.0, colors.fg);
+    window.add_task(task);
+
+    let rect = UITools::inset_rect(frame, 8.0, 0.0, 28.0, 0.0);
+    if let Some(task) = labels.draw(theme, style, text, &rect, Some(colors.fg), 1.0) {
+        window.add_task(task);
+    }
+}
+
+/// The theme colors shared by the pickers
+#[derive(Clone, Copy, Debug)]
+pub(super) struct PickerColors {
+    pub(super) bg: Color,
+    pub(super) fg: Color,
+    pub(super) border: Color,
+    pub(super) select_bg: Color,
+    pub(super) select_fg: Color,
+    pub(super) focus: Color,
+}
+
+impl PickerColors {
+    pub(super) fn new() -> Self {
+        PickerColors {
+            bg: Color::WHITE,
+            fg: Color::BLACK,
+            border: Color::from_hex("#AAAAAA"),
+            select_bg: Color::from_hex("#4373c2"),
+            select_fg: Color::WHITE,
+            focus: Color::from_hex("#80A4C2"),
+        }
+    }
+
+    pub(super) fn from_theme(theme: &Theme) -> Self {
+        PickerColors {
+            bg: theme.input_bg_color,
+            fg: theme.input_fg_color,
+            border: theme.border_color,
+            select_bg: theme.button_bg_color,
+            select_fg: theme.button_fg_color,
+            focus: theme.cursor_color,
+        }
+    }
+}
+
+/// The driver layer for a fade or slide, where the frame height goes from 0.0 to 1.0
+pub(super) fn start_progress(layer: &mut Layer) {
+    layer.frame.size.y = 0.0;
+    let propset = PropSet::new(vec![size(1.0, 1.0)], PICKER_DURATION).ease(Ease::SineOut);
+    layer.animate_with_props(propset, true);
+}
+
+//-- DatePicker -----------------------------------------------------------------------
+
+/// A month calendar for picking a date. The header has arrows for the previous and next month and year,
+/// and the days slide in when the month changes. Dates before min_date, after max_date, in the list of
+/// disabled dates or on a disabled weekday cannot be picked.
+///
+/// In Dropdown mode, the frame is a field that shows the date and opens the calendar below it.
+/// When the picker has focus, the arrow keys move the focused day, PageUp and PageDown change the month
+/// (with Shift, the year) and Space or Return picks the focused day.
+///
+/// The value is a FieldValue::Date and changes made by the user dispatch FieldEvent::Changed.
+pub struct DatePicker {
+    /// The base layer
+    pub layer: Layer,
+    /// Inline or Dropdown
+    pub mode: PickerMode,
+    /// The first column of the calendar
+    pub first_weekday: Weekday,
+    /// The earliest date that can be picked
+    pub min_date: Option<CalendarDate>,
+    /// The latest date that can be picked
+    pub max_date: Option<CalendarDate>,
+    /// Dates that cannot be picked
+    pub disabled_dates: Vec<CalendarDate>,
+    /// Weekdays that cannot be picked, such as weekends
+    pub disabled_weekdays: Vec<Weekday>,
+    /// The picked date
+    selected: Option<CalendarDate>,
+    /// The day with the keyboard focus. Its month is the month that is displayed.
+    focus_date: CalendarDate,
+    /// Has the picker been activated by its Scene?
+    has_focus: bool,
+    /// Is the popup open in Dropdown mode?
+    is_open: bool,
+    /// Drives the fade of the popup
+    popup: Layer,
+    /// Drives the month slide
+    slide: Layer,
+    /// The month that slides out and the direction, where 1.0 is toward the next month
+    slide_from: Option<(CalendarDate, f32)>,
+    labels: LabelCache,
+    colors: PickerColors,
+    /// FieldEvents waiting to be dispatched in the next update()
+    pending_events: Vec<FieldEvent>,
+}
+
+impl DatePicker {
+    /// Constructor. The calendar shows the month of the initial date.
+    pub fn new(frame: Rectangle, initial: CalendarDate) -> Self {
+        DatePicker {
+            layer: Layer::new(frame),
+            mode: PickerMode::Inline,
+            first_weekday: Weekday::Monday,
+            min_date: None,
+            max_date: None,
+            disabled_dates: Vec::new(),
+            disabled_weekdays: Vec::new(),
+            selected: None,
+            focus_date: initial,
+            has_focus: false,
+            is_open: false,
+            popup: Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0))),
+            slide: Layer::new(Rectangle::new((0.0, 0.0), (1.0, 1.0))),
+            slide_from: None,
+            labels: LabelCache::new(),
+            colors: PickerColors::new(),
+            pending_events: Vec::new(),
+        }
+    }
+
+    /// Builder method to set the mode
+    pub fn with_mode(mut self, mode: PickerMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Builder method to select the date
+    pub fn with_selected(mut self, date: CalendarDate) -> Self {
+        self.selected = Some(date);
+        self.focus_date = date;
+        self
+    }
+
+    /// Builder method to set the earliest and latest dates that can be picked
+    pub fn with_range(mut self, min_date: Option<CalendarDate>, max_date: Option<CalendarDate>) -> Self {
+        self.min_date = min_date;
+        self.max_date = max_date;
+        self
+    }
+
+    /// Builder method to set the dates that cannot be picked
+    pub fn with_disabled_dates(mut self, dates: &[CalendarDate]) -> Self {
+        self.disabled_dates = dates.to_vec();
+        self
+    }
+
+    /// Builder method to set the weekdays that cannot be picked
+    pub fn with_disabled_weekdays(mut self, weekdays: &[Weekday]) -> Self {
+        self.disabled_weekdays = weekdays.to_vec();
+        self
+    }
+
+    /// Builder method to set the first column of the calendar
+    pub fn with_first_weekday(mut self, weekday: Weekday) -> Self {
+        self.first_weekday = weekday;
+        self
+    }
+
+    /// Builder method to set the first column of the calendar from a locale such as "en-US"
+    pub fn with_locale(mut self, locale: &str) -> Self {
+        self.first_weekday = Weekday::first_for_locale(locale);
+        self
+    }
+
+    /// The picked date
+    pub fn selected(&self) -> Option<CalendarDate> {
+        self.selected
+    }
+
+    /// Pick the date and show its month. Returns false if the date is disabled.
+    pub fn select(&mut self, date: CalendarDate) -> bool {
+        if !self.is_enabled(&date) {
+            return false;
+        }
+        self.selected = Some(date);
+        self.show_date(date);
+        true
+    }
+
+    /// Clear the picked date
+    pub fn clear_selection(&mut self) {
+        self.selected = None;
+    }
+
+    /// Can the date be picked?
+    pub fn is_enabled(&self, date: &CalendarDate) -> bool {
+        if self.min_date.map_or(false, |min| *date < min) || self.max_date.map_or(false, |max| *date > max) {
+            return false;
+        }
+        !self.disabled_dates.contains(date) && !self.disabled_weekdays.contains(&date.weekday())
+    }
+
+    /// Is the popup open in Dropdown mode?
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Open or close the popup in Dropdown mode
+    pub fn set_open(&mut self, open: bool) {
+        if self.mode != PickerMode::Dropdown || open == self.is_open {
+            return;
+        }
+        self.is_open = open;
+        if open {
+            start_progress(&mut self.popup);
+        } else {
+            self.popup.animation = None;
+            self.popup.frame.size.y = 0.0;
+        }
+    }
+
+    /// Move the focus to the date. If it is in another month, the new month slides in.
+    fn show_date(&mut self, date: CalendarDate) {
+        let old = self.focus_date.first_of_month();
+        self.focus_date = date;
+        let new = date.first_of_month();
+        if new != old {
+            let direction = if new > old { 1.0 } else { -1.0 };
+            self.slide_from = Some((old, direction));
+            start_progress(&mut self.slide);
+        }
+    }
+
+    /// Pick the date as if the user clicked it
+    fn choose(&mut self, date: CalendarDate) {
+        if self.select(date) {
+            self.pending_events.push(FieldEvent::Changed);
+            self.set_open(false);
+        }
+    }
+
+    /// The frame of the calendar, which is below the field in Dropdown mode
+    fn calendar_frame(&self) -> Rectangle {
+        let frame = self.layer.frame;
+        match self.mode {
+            PickerMode::Inline => frame,
+            PickerMode::Dropdown => Rectangle::new((frame.x(), frame.y() + frame.height() + POPUP_MARGIN), POPUP_SIZE),
+        }
+    }
+
+    /// Is the calendar shown?
+    fn calendar_visible(&self) -> bool {
+        self.mode == PickerMode::Inline || self.is_open
+    }
+
+    fn header_frame(&self) -> Rectangle {
+        let rect = UITools::padded_rect(&self.calendar_frame(), -CALENDAR_PADDING, -CALENDAR_PADDING);
+        Rectangle::new(rect.pos, (rect.width(), HEADER_HEIGHT))
+    }
+
+    /// The navigation arrows in the header in order: previous year, previous month, next month, next year
+    fn arrow_frames(&self) -> [Rectangle; 4] {
+        let header = self.header_frame();
+        let right = header.x() + header.width();
+        let arrow = |x: f32| Rectangle::new((x, header.y()), (ARROW_WIDTH, header.height()));
+        [
+            arrow(header.x()),
+            arrow(header.x() + ARROW_WIDTH),
+            arrow(right - ARROW_WIDTH * 2.0),
+            arrow(right - ARROW_WIDTH),
+        ]
+    }
+
+    /// The area of the day cells
+    fn grid_frame(&self) -> Rectangle {
+        let rect = UITools::padded_rect(&self.calendar_frame(), -CALENDAR_PADDING, -CALENDAR_PADDING);
+        let top = HEADER_HEIGHT + WEEKDAY_HEIGHT;
+        Rectangle::new((rect.x(), rect.y() + top), (rect.width(), (rect.height() - top).max(0.0)))
+    }
+
+    /// The column of the date in the calendar
+    fn column_of(&self, date: &CalendarDate) -> u32 {
+        (date.weekday().index() + 7 - self.first_weekday.index()) % 7
+    }
+
+    /// The frame of the day cell for the date in the month of the first date, offset horizontally
+    fn cell_frame(&self, first: &CalendarDate, day: u32, offset: f32) -> Rectangle {
+        let grid = self.grid_frame();
+        let (width, height) = (grid.width() / 7.0, grid.height() / 6.0);
+        let index = self.column_of(first) + day - 1;
+        let (col, row) = (index % 7, index / 7);
+        Rectangle::new((grid.x() + width * col as f32 + offset, grid.y() + height * row as f32), (width, height))
+    }
+
+    /// The date of the day cell at the point in the displayed month
+    fn date_at_point(&self, pt: &Vector) -> Option<CalendarDate> {
+        let first = self.focus_date.first_of_month();
+        (1..=first.days_in_month())
+            .find(|day| pt.overlaps_rectangle(&self.cell_frame(&first, *day, 0.0)))
+            .map(|day| CalendarDate { day, ..first })
+    }
+
+    /// Draw the days of the month of the first date
+    fn draw_month(&mut self, theme: &mut Theme, first: CalendarDate, offset: f32, alpha: f32, window: &mut Window) {
+        let grid = self.grid_frame();
+        let colors = self.colors;
+        let style = self.layer.font_style;
+        let mut shapes = MeshTask::new(0);
+        let mut text: Vec<MeshTask> = Vec::new();
+        for day in 1..=first.days_in_month() {
+            let date = CalendarDate { day, ..first };
+            let rect = self.cell_frame(&first, day, offset);
+            let alpha = alpha * visible_fraction(&rect, &grid);
+            if alpha <= 0.0 {
+                continue;
+            }
+            let radius = rect.width().min(rect.height()) / 2.0 - 2.0;
+            let selected = self.selected == Some(date);
+            if selected {
+                let color = Color { a: colors.select_bg.a * alpha, ..colors.select_bg };
+                let mut mesh = DrawShape::circle(&rect.center(), radius, Some(color), None, 0.0);
+                shapes.append(&mut mesh);
+            }
+            if self.has_focus && self.focus_date == date && offset == 0.0 {
+                let color = Color { a: colors.focus.a * alpha, ..colors.focus };
+                let mut mesh = DrawShape::circle(&rect.center(), radius, None, Some(color), 2.0);
+                shapes.append(&mut mesh);
+            }
+            let (color, alpha) = {
+                if selected {
+                    (colors.select_fg, alpha)
+                } else if self.is_enabled(&date) {
+                    (colors.fg, alpha)
+                } else {
+                    (colors.fg, alpha * 0.35)
+                }
+            };
+            if let Some(task) = self.labels.draw(theme, style, &day.to_string(), &rect, Some(color), alpha) {
+                text.push(task);
+            }
+        }
+        window.add_task(shapes);
+        for task in text {
+            window.add_task(task);
+        }
+    }
+
+    /// Draw the calendar with the alpha of the popup
+    fn draw_calendar(&mut self, theme: &mut Theme, alpha: f32, window: &mut Window) {
+        let colors = self.colors;
+        let style = self.layer.font_style;
+        let dim = |color: Color| Color { a: color.a * alpha, ..color };
+
+        let mut task = MeshTask::new(0);
+        if self.mode == PickerMode::Dropdown {
+            if let Some(shadow) = theme.popup_shadow {
+                let shadow = ShadowStyle { color: dim(shadow.color), ..shadow };
+                task.append(&mut shadow.draw(&self.calendar_frame(), [4.0; 4]));
+            }
+            let mut mesh =
+                DrawShape::rectangle(&self.calendar_frame(), Some(dim(colors.bg)), Some(dim(colors.border)), 1.0, 4.0);
+            task.append(&mut mesh);
+        }
+        let arrows = self.arrow_frames();
+        let directions = [-1.0, -1.0, 1.0, 1.0];
+        for (index, rect) in arrows.iter().enumerate() {
+            let center = rect.center();
+            // The year arrows are doubled
+            if index == 0 || index == 3 {
+                let shift = Vector::new(3.0 * directions[index], 0.0);
+                draw_arrow(&mut task, &(center - shift), directions[index], dim(colors.fg));
+                draw_arrow(&mut task, &(center + shift), directions[index], dim(colors.fg));
+            } else {
+                draw_arrow(&mut task, &center, directions[index], dim(colors.fg));
+            }
+        }
+        window.add_task(task);
+
+        let header = self.header_frame();
+        let title = format!("{} {}", MONTH_NAMES[self.focus_date.month as usize - 1], self.focus_date.year);
+        if let Some(task) = self.labels.draw(theme, style, &title, &header, Some(colors.fg), alpha) {
+            window.add_task(task);
+        }
+        let grid = self.grid_frame();
+        let width = grid.width() / 7.0;
+        for col in 0..7 {
+            let name = WEEKDAY_NAMES[((self.first_weekday.index() + col) % 7) as usize];
+            let rect =
+                Rectangle::new((grid.x() + width * col as f32, grid.y() - WEEKDAY_HEIGHT), (width, WEEKDAY_HEIGHT));
+            if let Some(task) = self.labels.draw(theme, style, name, &rect, Some(colors.fg), alpha * 0.6) {
+                window.add_task(task);
+            }
+        }
+
+        let first = self.focus_date.first_of_month();
+        match self.slide_from {
+            Some((from, direction)) => {
+                let progress = self.slide.frame.size.y;
+                self.draw_month(theme, from, -direction * progress * grid.width(), alpha, window);
+                self.draw_month(theme, first, direction * (1.0 - progress) * grid.width(), alpha, window);
+            }
+            None => self.draw_month(theme, first, 0.0, alpha, window),
+        }
+    }
+}
+
+// *****************************************************************************************************
+// DatePicker :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for DatePicker {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<DatePicker>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        let ok = self.layer.apply_theme(theme);
+        if !ok {
+            return;
+        }
+        self.layer.font_style = FontStyle::new(theme.font_size * 0.8, theme.input_fg_color);
+        self.colors = PickerColors::from_theme(theme);
+        self.labels.clear();
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Activate => {
+                self.has_focus = true;
+            }
+            DisplayEvent::Deactivate => {
+                self.has_focus = false;
+                self.set_open(false);
+            }
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        self.popup.tween_update(state);
+        if self.slide_from.is_some() {
+            self.slide.tween_update(state);
+            if self.slide.frame.size.y >= 1.0 {
+                self.slide_from = None;
+            }
+        }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        match self.mode {
+            PickerMode::Inline => {
+                self.layer.draw_background(window);
+                self.draw_calendar(theme, 1.0, window);
+                self.layer.draw_border(window);
+            }
+            PickerMode::Dropdown => {
+                let text = self.selected.map_or(String::default(), |date| date.to_string());
+                let (frame, style, colors) = (self.layer.frame, self.layer.font_style, self.colors);
+                draw_dropdown_field(&mut self.labels, theme, style, &frame, &text, &colors, self.has_focus, window);
+                if self.is_open {
+                    let alpha = self.popup.frame.size.y.max(0.0).min(1.0);
+                    self.draw_calendar(theme, alpha, window);
+                }
+            }
+        }
+    }
+
+    fn set_hover_animation(&mut self, props: PropSet) {
+        self.layer.hover_effect = Some(props);
+    }
+
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        let calendar = self.calendar_visible() && pt.overlaps_rectangle(&self.calendar_frame());
+        if calendar || pt.overlaps_rectangle(&self.layer.frame) {
+            let clickable = self.mode == PickerMode::Dropdown && pt.overlaps_rectangle(&self.layer.frame)
+                || self.arrow_frames().iter().any(|rect| pt.overlaps_rectangle(rect))
+                || self.date_at_point(pt).map_or(false, |date| self.is_enabled(&date));
+            window.set_cursor(if clickable { MouseCursor::Hand } else { MouseCursor::Default });
+            self.layer.mouse_state = MouseState::Hover;
+            return true;
+        }
+        self.layer.mouse_state = MouseState::None;
+        false
+    }
+}
+
+// *****************************************************************************************************
+// DatePicker :: Responder
+// *****************************************************************************************************
+
+impl Responder for DatePicker {
+    fn get_field_value(&self) -> FieldValue {
+        match self.selected {
+            Some(date) => FieldValue::Date(date),
+            None => FieldValue::None,
+        }
+    }
+
+    /// The Date value selects the date and None clears the selection
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if type_id == self.get_type_id() && layer_id == self.layer.get_id() {
+            match value {
+                FieldValue::Date(date) => {
+                    self.select(*date);
+                    return true;
+                }
+                FieldValue::None => {
+                    self.clear_selection();
+                    return true;
+                }
+                _ => (),
+            }
+        }
+        false
+    }
+
+    fn handle_mouse_down(&mut self, pt: &Vector, _state: &mut AppState) -> bool {
+        if self.mode == PickerMode::Dropdown && pt.overlaps_rectangle(&self.layer.frame) {
+            let open = !self.is_open;
+            self.set_open(open);
+            return true;
+        }
+        if !self.calendar_visible() || !pt.overlaps_rectangle(&self.calendar_frame()) {
+            self.set_open(false);
+            return false;
+        }
+        let months = [-12, -1, 1, 12];
+        if let Some(index) = self.arrow_frames().iter().position(|rect| pt.overlaps_rectangle(rect)) {
+            let date = self.focus_date.add_months(months[index]);
+            self.show_date(date);
+        } else if let Some(date) = self.date_at_point(pt) {
+            self.choose(date);
+        }
+        true
+    }
+
+    /// The arrow keys move the focused day, PageUp and PageDown change the month (with Shift, the year), and
+    /// Space or Return picks the focused day. In Dropdown mode, they open the popup first and Escape closes it.
+    fn handle_key_command(&mut self, key: &Key, window: &mut Window) -> bool {
+        if let Key::Tab = key {
+            self.set_open(false);
+            return true;
+        }
+        if !self.calendar_visible() {
+            if let Key::Space | Key::Return | Key::Down = key {
+                self.set_open(true);
+                return true;
+            }
+            return false;
+        }
+        let shift = KeyModifiers::from_keyboard(window.keyboard()).shift;
+        let date = self.focus_date;
+        let target = match key {
+            Key::Left => date.add_days(-1),
+            Key::Right => date.add_days(1),
+            Key::Up => date.add_days(-7),
+            Key::Down => date.add_days(7),
+            Key::PageUp => date.add_months(if shift { -12 } else { -1 }),
+            Key::PageDown => date.add_months(if shift { 12 } else { 1 }),
+            Key::Space | Key::Return => {
+                self.choose(date);
+                return true;
+            }
+            Key::Escape if self.is_open => {
+                self.set_open(false);
+                return true;
+            }
+            _ => return false,
+        };
+        self.show_date(target);
+        true
+    }
+}
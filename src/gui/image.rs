@@ -2,10 +2,11 @@
 ///
 use crate::core::*;
 use crate::events::*;
+use crate::tools::*;
 
 use quicksilver::{
     geom::{Rectangle, Shape, Transform, Vector},
-    graphics::{Background::Img, GpuTriangle, Image, MeshTask},
+    graphics::{Background::Img, Color, GpuTriangle, Image, MeshTask},
     lifecycle::{Asset, Window},
 };
 
@@ -30,6 +31,9 @@ pub enum ImageScaleMode {
     /// Leave original size. If larger than the given frame size, you may need to adjust
     /// the frame origin.
     Original,
+    /// Keep the corners at their original size and stretch or tile the edges and center. Useful for skinned
+    /// buttons and panels that must resize without distortion.
+    NineSlice(NineSlice),
 }
 
 //-- Image -----------------------------------------------------------------------
@@ -46,13 +50,34 @@ pub struct ImageView {
     image_size: Vector,
     /// How to scale the image to fit in the Layer frame
     scale_mode: ImageScaleMode,
+    /// The uploaded image, only used by ImageScaleMode::NineSlice
+    texture: Option<GPUTexture>,
+    /// The name of an image registered in the Theme image_atlas, used instead of the asset
+    atlas_name: Option<String>,
+    /// The last error from the image_atlas, so that a miss is only logged once
+    atlas_error: Option<AtlasError>,
 }
 
 impl ImageView {
     /// Constructor
     pub fn new(frame: Rectangle, asset: Option<Asset<Vec<u8>>>) -> Self {
         let layer = Layer::new(frame);
-        ImageView { layer, loader: asset, image_size: Vector::ZERO, scale_mode: ImageScaleMode::ScaleToFill }
+        ImageView {
+            layer,
+            loader: asset,
+            image_size: Vector::ZERO,
+            scale_mode: ImageScaleMode::ScaleToFill,
+            texture: None,
+            atlas_name: None,
+            atlas_error: None,
+        }
+    }
+
+    /// Constructor with the name of an image registered in the Theme image_atlas
+    pub fn from_atlas(frame: Rectangle, name: &str) -> Self {
+        let mut view = ImageView::new(frame, None);
+        view.atlas_name = Some(name.to_string());
+        view
     }
 
     pub fn with_scale_mode(mut self, scale_mode: ImageScaleMode) -> Self {
@@ -62,9 +87,23 @@ impl ImageView {
 
     pub fn set_asset(&mut self, asset: Option<Asset<Vec<u8>>>) {
         self.loader = asset;
+        self.texture = None;
+    }
+
+    /// Get the image mesh for the current frame, which is cached in the layer after the asset has loaded.
+    /// Parent views can translate copies of the mesh to draw the same image in many places.
+    pub fn content_mesh(&mut self) -> Option<MeshTask> {
+        if self.layer.meshes.is_empty() {
+            let task = self.draw_content()?;
+            self.layer.meshes.push(task);
+        }
+        self.layer.meshes.first().cloned()
     }
 
     fn draw_content(&mut self) -> Option<MeshTask> {
+        if let ImageScaleMode::NineSlice(slice) = self.scale_mode {
+            return self.draw_nine_slice(&slice);
+        }
         let mut mesh = MeshTask::new(0);
         let scale_mode = self.scale_mode;
         let frame = self.layer.frame;
@@ -95,6 +134,43 @@ impl ImageView {
         None
     }
 
+    /// Nine-slice scaling needs texture coordinates for each patch, so the image is uploaded as a texture once
+    fn draw_nine_slice(&mut self, slice: &NineSlice) -> Option<MeshTask> {
+        if self.texture.is_none() {
+            let name = self.node_key();
+            let mut texture = None;
+            if let Some(loader) = &mut self.loader {
+                loader
+                    .execute(|bytes| {
+                        texture = DrawImage::upload_image_bytes(&name, bytes.as_slice());
+                        Ok(())
+                    })
+                    .expect("Asset loading failed");
+            }
+            self.texture = texture;
+        }
+        let texture = self.texture?;
+        self.image_size = texture.size();
+        let mesh = DrawImage::nine_slice(&texture, &self.layer.frame, slice, Color::WHITE);
+        if mesh.vertices.len() > 0 {
+            return Some(mesh);
+        }
+        None
+    }
+
+    /// Draw a region of an atlas page. The mesh is not cached because the atlas can move images.
+    fn draw_atlas_image(&mut self, texture: &GPUTexture) -> Option<MeshTask> {
+        self.image_size = texture.size();
+        let frame = self.layer.frame;
+        if let ImageScaleMode::NineSlice(slice) = self.scale_mode {
+            return Some(DrawImage::nine_slice(texture, &frame, &slice, Color::WHITE));
+        }
+        let trans = ImageView::scale_image(texture.size(), self.scale_mode, &frame);
+        let rect = Rectangle::new(trans * Vector::ZERO, trans * Vector::ONE - trans * Vector::ZERO);
+        let quad = texture.tex_quad(Rectangle::new_sized(texture.size()));
+        DrawImage::sub_texture(texture.idx, rect, Some(quad), Color::WHITE)
+    }
+
     /// Create Transform for image scaling
     fn scale_image(img_size: Vector, scale_mode: ImageScaleMode, frame: &Rectangle) -> Transform {
         let img_aspect = img_size.x / img_size.y;
@@ -202,10 +278,27 @@ impl Displayable for ImageView {
         self.layer.tween_update(state);
     }
 
-    fn render(&mut self, _theme: &mut Theme, window: &mut Window) {
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
         if self.layer.debug {
             self.layer.draw_border(window);
         }
+        if let Some(name) = self.atlas_name.clone() {
+            match theme.image_atlas.get(&name) {
+                Ok(texture) => {
+                    self.atlas_error = None;
+                    if let Some(task) = self.draw_atlas_image(&texture) {
+                        window.add_task(task);
+                    }
+                }
+                Err(err) => {
+                    if self.atlas_error.as_ref() != Some(&err) {
+                        log::warn!("{} {}", self.debug_id(), err);
+                        self.atlas_error = Some(err);
+                    }
+                }
+            }
+            return;
+        }
         let meshes = self.layer.prepare_render(window);
         if meshes.len() > 0 {
             for task in meshes.into_iter() {
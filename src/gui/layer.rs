@@ -55,12 +55,17 @@ pub struct Layer {
     pub rotation: f32,
     /// Corner radius for the layer.
     pub corner_radius: f32,
+    /// Optional radius for each corner in the order: top left, top right, bottom right, bottom left. This
+    /// overrides corner_radius.
+    pub corner_radii: Option<[f32; 4]>,
     /// Experimental point outside of the frame for rotation
     pub anchor_pt: Vector,
     /// Enum to describe background of object.
     pub bg_style: BackgroundStyle,
     /// Background color for the scene. Otherwise it's transparent
     pub border_style: BorderStyle,
+    /// Optional drop shadow or inset shadow
+    pub shadow: Option<ShadowStyle>,
     /// Defines font size and color
     pub font_style: FontStyle,
     /// Current MouseState of the layer
@@ -69,6 +74,10 @@ pub struct Layer {
     pub hover_effect: Option<PropSet>,
     /// The animation Props for the hover event
     pub click_effect: Option<PropSet>,
+    /// Optional tooltip that the Stage shows when the mouse hovers over the layer
+    pub tooltip: Option<Tooltip>,
+    /// Optional id of a Menu added with Stage::add_menu that opens when the layer is right-clicked
+    pub context_menu: Option<u32>,
     /// Callback method for handling click action
     pub(super) on_click: Option<Box<dyn FnMut(&mut AppState) + 'static>>,
     /// Temporary storage for observers to be aggregated into AppState
@@ -107,13 +116,17 @@ impl Clone for Layer {
             transition: self.transition.clone(),
             rotation: self.rotation,
             corner_radius: self.corner_radius,
+            corner_radii: self.corner_radii,
             anchor_pt: self.anchor_pt,
-            bg_style: self.bg_style,
+            bg_style: self.bg_style.clone(),
             border_style: self.border_style,
+            shadow: self.shadow,
             font_style: self.font_style,
             mouse_state: MouseState::None,
             hover_effect: self.hover_effect.clone(),
             click_effect: self.click_effect.clone(),
+            tooltip: self.tooltip.clone(),
+            context_menu: self.context_menu,
             on_click: None,
             queued_observers: Vec::new(),
             event_listeners: HashMap::new(),
@@ -145,13 +158,17 @@ impl Layer {
             transition: Transition::new(frame, Color::WHITE, 0.0),
             rotation: 0.0,
             corner_radius: 0.0,
+            corner_radii: None,
             anchor_pt: frame.top_left(),
             bg_style: BackgroundStyle::None,
             border_style: BorderStyle::None,
+            shadow: None,
             font_style: FontStyle::new(14.0, Color::BLACK),
             mouse_state: MouseState::None,
             hover_effect: None,
             click_effect: None,
+            tooltip: None,
+            context_menu: None,
             on_click: None,
             queued_observers: Vec::new(),
             event_listeners: HashMap::new(),
@@ -255,7 +272,10 @@ impl Layer {
             if self.click_effect.is_none() {
                 self.click_effect = Some(theme.on_button_click.clone());
             }
-            self.bg_style = BackgroundStyle::Solid(theme.button_bg_color);
+            // Skinned buttons keep their image
+            if !self.bg_style.is_image() {
+                self.bg_style = BackgroundStyle::Solid(theme.button_bg_color);
+            }
             self.font_style = FontStyle::new(theme.font_size, theme.button_fg_color);
         }
         true
@@ -378,7 +398,7 @@ impl Layer {
 
     /// Standard method called by components when mouseover occurs
     pub(super) fn handle_mouse_over(&mut self, pt: &Vector) -> bool {
-        if pt.overlaps_rectangle(&self.frame) {
+        if self.contains_point(pt) {
             // if self.debug {
             //     log::trace!("Hover over {}", self.debug_out());
             // }
@@ -477,34 +497,101 @@ impl Layer {
                         Some(color)
                     }
                 }
-                BackgroundStyle::None => None,
+                _ => None,
             }
         };
-        let mut mesh = {
-            // FIXME: transitioning is defined poorly.
-            if self.is_transitioning() {
-                DrawShape::rectangle(&self.transition.frame, bg_color, border.0, border.1, self.corner_radius)
-            } else {
-                DrawShape::rectangle(&self.frame, bg_color, border.0, border.1, self.corner_radius)
+        // FIXME: transitioning is defined poorly.
+        let frame = if self.is_transitioning() { self.transition.frame } else { self.frame };
+        let shadow =
+            if self.is_transitioning() && self.shadow.is_some() { Some(self.transition.shadow) } else { self.shadow };
+        let radii = self.current_radii();
+        let mut task = MeshTask::new(0);
+        if let Some(shadow) = shadow.filter(|shadow| !shadow.inset) {
+            task.append(&mut shadow.draw(&frame, radii));
+        }
+        let mut gradient = match &self.bg_style {
+            BackgroundStyle::LinearGradient(stops, angle) => {
+                DrawShape::linear_gradient(&frame, &self.current_stops(stops), *angle, radii)
+            }
+            BackgroundStyle::RadialGradient(stops, center) => {
+                DrawShape::radial_gradient(&frame, &self.current_stops(stops), *center, radii)
             }
+            _ => Mesh::new(),
         };
-        if mesh.vertices.len() > 0 {
-            let mut task = MeshTask::new(0);
-            task.vertices.append(&mut mesh.vertices);
-            task.triangles.append(&mut mesh.triangles);
+        task.append(&mut gradient);
+        task.append(&mut DrawShape::rounded_rectangle(&frame, bg_color, None, 0.0, radii));
+        if let BackgroundStyle::NineSlice(texture, slice) = &self.bg_style {
+            // The image has its own texture, so it goes in a separate task between the shapes under and over it
+            if task.vertices.len() > 0 {
+                window.add_task(task);
+            }
+            let tint = if self.is_transitioning() { self.transition.color } else { Color::WHITE };
+            let mut image = DrawImage::nine_slice(texture, &frame, slice, tint);
+            self.clip_to_corners(&mut image);
+            if image.vertices.len() > 0 {
+                window.add_task(image);
+            }
+            task = MeshTask::new(0);
+        }
+        // The inset shadow is drawn over the background and under the border
+        if let Some(shadow) = shadow.filter(|shadow| shadow.inset) {
+            task.append(&mut shadow.draw(&frame, radii));
+        }
+        task.append(&mut DrawShape::rounded_rectangle(&frame, None, border.0, border.1, radii));
+        if task.vertices.len() > 0 {
             window.add_task(task);
         }
     }
 
+    /// Fill areas outside of the frame with the background, as if it continued past the frame with the colors at
+    /// its edges. This masks content that overflows the frame. Returns false if there is no solid or gradient
+    /// background to draw.
+    pub(super) fn draw_background_pad(&self, areas: &[Rectangle], window: &mut Window) -> bool {
+        let frame = if self.is_transitioning() { self.transition.frame } else { self.frame };
+        let mut task = MeshTask::new(0);
+        for area in areas {
+            let mut mesh = match &self.bg_style {
+                BackgroundStyle::Solid(color) => {
+                    let color = if self.is_transitioning() { self.transition.color } else { *color };
+                    DrawShape::rectangle(area, Some(color), None, 0.0, 0.0)
+                }
+                BackgroundStyle::LinearGradient(stops, angle) => {
+                    DrawShape::linear_gradient_pad(&frame, area, &self.current_stops(stops), *angle)
+                }
+                BackgroundStyle::RadialGradient(stops, center) => {
+                    DrawShape::radial_gradient_pad(&frame, area, &self.current_stops(stops), *center)
+                }
+                _ => return false,
+            };
+            task.append(&mut mesh);
+        }
+        if task.vertices.len() > 0 {
+            window.add_task(task);
+        }
+        true
+    }
+
+    /// Get the gradient stops with the colors of the transition while animating
+    fn current_stops(&self, stops: &[GradientStop]) -> Vec<GradientStop> {
+        if !self.is_transitioning() {
+            return stops.to_vec();
+        }
+        let mut stops = stops.to_vec();
+        for (stop, color) in stops.iter_mut().zip(self.transition.stop_colors.iter()) {
+            stop.color = *color;
+        }
+        stops
+    }
+
     /// Method to draw a border based on self.BorderStyle
     pub(super) fn draw_border(&self, window: &mut Window) {
         let mut mesh = match self.border_style {
             BorderStyle::SolidLine(color, width) => {
-                // TODO: allow rounded corners?
+                let radii = self.current_radii();
                 if self.is_transitioning() {
-                    DrawShape::rectangle(&self.transition.frame, None, Some(color), width, self.corner_radius)
+                    DrawShape::rounded_rectangle(&self.transition.frame, None, Some(color), width, radii)
                 } else {
-                    DrawShape::rectangle(&self.frame, None, Some(color), width, self.corner_radius)
+                    DrawShape::rounded_rectangle(&self.frame, None, Some(color), width, radii)
                 }
             }
             _ => Mesh::new(),
@@ -578,6 +665,41 @@ impl Layer {
         }
     }
 
+    /// The radius of each corner in the order: top left, top right, bottom right, bottom left
+    pub fn get_corner_radii(&self) -> [f32; 4] {
+        self.corner_radii.unwrap_or([self.corner_radius; 4])
+    }
+
+    /// Set a different radius for each corner in the order: top left, top right, bottom right, bottom left
+    pub fn set_corner_radii(&mut self, radii: [f32; 4]) {
+        self.corner_radii = Some(radii);
+    }
+
+    /// The corner radii to draw with. During animation, these come from the transition.
+    pub(super) fn current_radii(&self) -> [f32; 4] {
+        // A hover without an animation does not initialise the transition
+        if self.is_transitioning() && self.has_animation() {
+            self.transition.corner_radii
+        } else {
+            self.get_corner_radii()
+        }
+    }
+
+    /// Is the point inside the frame, excluding the area outside of rounded corners?
+    pub fn contains_point(&self, pt: &Vector) -> bool {
+        rounded_rect_contains(&self.frame, self.get_corner_radii(), pt)
+    }
+
+    /// Clip content to the rounded corners of the background. Nothing changes if the corners are square.
+    pub(super) fn clip_to_corners(&self, task: &mut MeshTask) {
+        let radii = self.current_radii();
+        if radii.iter().all(|radius| *radius <= 0.0) {
+            return;
+        }
+        let frame = if self.is_transitioning() { self.transition.frame } else { self.frame };
+        UITools::clip_rounded(task, &frame, radii);
+    }
+
     pub fn is_transitioning(&self) -> bool {
         if self.is_animating() {
             true
@@ -725,6 +847,32 @@ impl Tweenable for Layer {
                 self.transition.tint.b * 255.0 as f32,
                 self.transition.tint.a * 255.0 as f32,
             )),
+            Prop::StopColor(index, _) => match self.bg_style.get_stops() {
+                Some(stops) if (*index as usize) < stops.len().min(MAX_ANIMATED_STOPS) => {
+                    let color = self.transition.stop_colors[*index as usize];
+                    Prop::StopColor(
+                        *index,
+                        ColorRGBA::new(color.r * 255.0, color.g * 255.0, color.b * 255.0, color.a * 255.0),
+                    )
+                }
+                _ => Prop::None,
+            },
+            // Shadow props read the transition, so that a layer without a shadow can animate one in
+            Prop::ShadowColor(_) => {
+                let color = self.transition.shadow.color;
+                Prop::ShadowColor(ColorRGBA::new(color.r * 255.0, color.g * 255.0, color.b * 255.0, color.a * 255.0))
+            }
+            Prop::ShadowOffset(_) => {
+                let offset = self.transition.shadow.offset;
+                Prop::ShadowOffset(Point2D::new(offset.x, offset.y))
+            }
+            Prop::ShadowSize(_) => {
+                Prop::ShadowSize(Frame2D::new(self.transition.shadow.spread, self.transition.shadow.softness))
+            }
+            Prop::CornerRadius(_) => {
+                let radii = self.get_corner_radii();
+                Prop::CornerRadius(Corners4D::new(radii[0], radii[1], radii[2], radii[3]))
+            }
             Prop::Rotate(_) => Prop::Rotate(FloatProp::new(self.rotation)),
             Prop::Position(_) => Prop::Position(Point2D::new(self.frame.pos.x, self.frame.pos.y)),
             Prop::Size(_) => Prop::Size(Frame2D::new(self.frame.size.x, self.frame.size.y)),
@@ -761,6 +909,21 @@ impl Tweenable for Layer {
                 self.transition.tint.b = rgba[2] / 255.0;
                 self.transition.tint.a = rgba[3] / 255.0;
             }
+            Prop::StopColor(index, rgba) => {
+                if let Some(color) = self.transition.stop_colors.get_mut(*index as usize) {
+                    *color = Color { r: rgba[0] / 255.0, g: rgba[1] / 255.0, b: rgba[2] / 255.0, a: rgba[3] / 255.0 };
+                }
+            }
+            Prop::ShadowColor(rgba) => {
+                self.transition.shadow.color =
+                    Color { r: rgba[0] / 255.0, g: rgba[1] / 255.0, b: rgba[2] / 255.0, a: rgba[3] / 255.0 };
+            }
+            Prop::ShadowOffset(pos) => self.transition.shadow.offset = Vector::new(pos[0], pos[1]),
+            Prop::ShadowSize(size) => {
+                self.transition.shadow.spread = size[0];
+                self.transition.shadow.softness = size[1];
+            }
+            Prop::CornerRadius(radii) => self.transition.corner_radii = [radii[0], radii[1], radii[2], radii[3]],
             Prop::Rotate(val) => self.transition.rotation = val[0] as f32,
             Prop::Position(pos) => {
                 self.frame.pos.x = pos[0] as f32;
@@ -796,18 +959,30 @@ impl Tweenable for Layer {
         // FIXME: With ShapeView, this is wrong because the initial mesh has a fill color. However, with animation,
         // that color is not set properly
         self.transition.color = self.bg_style.get_color();
+        if let Some(stops) = self.bg_style.get_stops() {
+            for (color, stop) in self.transition.stop_colors.iter_mut().zip(stops.iter()) {
+                *color = stop.color;
+            }
+        }
         let border = self.border_style.get_border();
         self.transition.border_color = border.0;
         self.transition.border_width = border.1;
+        self.transition.shadow = self.shadow.unwrap_or_default();
+        self.transition.corner_radii = self.get_corner_radii();
         self.transition.rotation = self.rotation;
     }
 
     /// After animation is complete, save the temporary props to the corresponding layer fields
     fn save_props(&mut self) {
         self.rotation = self.transition.rotation;
-        match self.bg_style {
-            BackgroundStyle::Solid(_) => {
-                self.bg_style = BackgroundStyle::Solid(self.transition.color);
+        match &mut self.bg_style {
+            BackgroundStyle::Solid(color) => {
+                *color = self.transition.color;
+            }
+            BackgroundStyle::LinearGradient(stops, _) | BackgroundStyle::RadialGradient(stops, _) => {
+                for (stop, color) in stops.iter_mut().zip(self.transition.stop_colors.iter()) {
+                    stop.color = *color;
+                }
             }
             _ => (),
         }
@@ -817,12 +992,19 @@ impl Tweenable for Layer {
             }
             _ => (),
         }
+        if self.shadow.is_some() || self.transition.shadow != ShadowStyle::default() {
+            self.shadow = Some(self.transition.shadow);
+        }
+        if self.transition.corner_radii != self.get_corner_radii() {
+            self.set_corner_radii(self.transition.corner_radii);
+        }
         self.initial = self.frame;
     }
 
     fn apply(&mut self, prop: &Prop) {
         match prop {
             Prop::Alpha(val) => self.transition.color.a = val[0] as f32,
+            Prop::Color(_) if self.bg_style.is_image() => (),
             Prop::Color(rgba) => {
                 if rgba[3] == 0.0 {
                     self.bg_style = BackgroundStyle::None;
@@ -835,6 +1017,28 @@ impl Tweenable for Layer {
             Prop::Tint(rgba) => {
                 let _color = Color { r: rgba[0] / 255.0, g: rgba[1] / 255.0, b: rgba[2] / 255.0, a: rgba[3] / 255.0 };
             }
+            Prop::StopColor(index, rgba) => match &mut self.bg_style {
+                BackgroundStyle::LinearGradient(stops, _) | BackgroundStyle::RadialGradient(stops, _) => {
+                    if let Some(stop) = stops.get_mut(*index as usize) {
+                        stop.color =
+                            Color { r: rgba[0] / 255.0, g: rgba[1] / 255.0, b: rgba[2] / 255.0, a: rgba[3] / 255.0 };
+                    }
+                }
+                _ => (),
+            },
+            Prop::ShadowColor(rgba) => {
+                let color = Color { r: rgba[0] / 255.0, g: rgba[1] / 255.0, b: rgba[2] / 255.0, a: rgba[3] / 255.0 };
+                self.shadow.get_or_insert_with(ShadowStyle::default).color = color;
+            }
+            Prop::ShadowOffset(pos) => {
+                self.shadow.get_or_insert_with(ShadowStyle::default).offset = Vector::new(pos[0], pos[1]);
+            }
+            Prop::ShadowSize(size) => {
+                let shadow = self.shadow.get_or_insert_with(ShadowStyle::default);
+                shadow.spread = size[0];
+                shadow.softness = size[1];
+            }
+            Prop::CornerRadius(radii) => self.set_corner_radii([radii[0], radii[1], radii[2], radii[3]]),
             Prop::Rotate(val) => self.rotation = val[0] as f32,
             Prop::Position(pos) => {
                 self.frame.pos.x = pos[0] as f32;
@@ -922,16 +1126,22 @@ pub enum MouseState {
 }
 
 /// Enum in Layer to describe background
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum BackgroundStyle {
     /// No background
     None,
     /// Params: background color, corner radius. Use 0.0 for no corner
     Solid(Color),
+    /// Params: color stops, angle in degrees where 0.0 is left to right and 90.0 is top to bottom
+    LinearGradient(Vec<GradientStop>, f32),
+    /// Params: color stops, center as a fraction of the frame size. The gradient reaches the farthest corner.
+    RadialGradient(Vec<GradientStop>, Vector),
+    /// Params: uploaded texture, insets. The image is tinted by the animated background color.
+    NineSlice(GPUTexture, NineSlice),
 }
 
 impl BackgroundStyle {
-    /// Enum method to get the output color for the style
+    /// Enum method to get the output color for the style. Gradients return the color of the first stop.
     pub fn get_color(&self) -> Color {
         match self {
             BackgroundStyle::None => {
@@ -940,6 +1150,28 @@ impl BackgroundStyle {
                 color
             }
             BackgroundStyle::Solid(color) => *color,
+            BackgroundStyle::LinearGradient(stops, _) | BackgroundStyle::RadialGradient(stops, _) => {
+                stops.first().map_or(Color::WHITE, |stop| stop.color)
+            }
+            BackgroundStyle::NineSlice(_, _) => Color::WHITE,
+        }
+    }
+
+    /// Check if the style draws a texture. The animated background color only tints it.
+    pub fn is_image(&self) -> bool {
+        match self {
+            BackgroundStyle::NineSlice(_, _) => true,
+            _ => false,
+        }
+    }
+
+    /// Get the color stops if the style is a gradient
+    pub fn get_stops(&self) -> Option<&[GradientStop]> {
+        match self {
+            BackgroundStyle::LinearGradient(stops, _) | BackgroundStyle::RadialGradient(stops, _) => {
+                Some(stops.as_slice())
+            }
+            _ => None,
         }
     }
 }
@@ -966,6 +1198,80 @@ impl BorderStyle {
     }
 }
 
+/// Define a drop shadow, glow or inset shadow for UI object
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShadowStyle {
+    /// The shadow color. The alpha sets the darkness of the shadow.
+    pub color: Color,
+    /// The x-y offset from the frame
+    pub offset: Vector,
+    /// The distance that the shadow grows past the frame on each side, or shrinks if inset
+    pub spread: f32,
+    /// The width of the fade from the shadow color to transparent
+    pub softness: f32,
+    /// Draw the shadow inside the frame, over the background
+    pub inset: bool,
+}
+
+impl Default for ShadowStyle {
+    /// A transparent shadow, which is the starting point for animating a shadow in
+    fn default() -> Self {
+        ShadowStyle {
+            color: Color { a: 0.0, ..Color::BLACK },
+            offset: Vector::ZERO,
+            spread: 0.0,
+            softness: 0.0,
+            inset: false,
+        }
+    }
+}
+
+impl ShadowStyle {
+    /// Constructor for a drop shadow
+    pub fn new(color: Color, offset: Vector, softness: f32) -> Self {
+        ShadowStyle { color, offset, softness, ..ShadowStyle::default() }
+    }
+
+    /// Constructor for a glow around the frame, with no offset
+    pub fn glow(color: Color, spread: f32, softness: f32) -> Self {
+        ShadowStyle { color, spread, softness, ..ShadowStyle::default() }
+    }
+
+    /// Builder method to set the spread
+    pub fn with_spread(mut self, spread: f32) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    /// Builder method to draw the shadow inside the frame
+    pub fn with_inset(mut self, inset: bool) -> Self {
+        self.inset = inset;
+        self
+    }
+
+    /// Draw the mesh of the shadow for the frame and corner radii
+    pub fn draw(&self, frame: &Rectangle, radii: [f32; 4]) -> Mesh {
+        if self.color.a <= 0.0 {
+            return Mesh::new();
+        }
+        let spread = if self.inset { -self.spread } else { self.spread };
+        let pos = frame.pos + self.offset - Vector::new(spread, spread);
+        let size = frame.size + Vector::new(spread, spread) * 2.0;
+        let rect = Rectangle::new(pos, Vector::new(size.x.max(0.0), size.y.max(0.0)));
+        let mut shadow_radii = [0.0; 4];
+        for (radius, corner_radius) in shadow_radii.iter_mut().zip(radii.iter()) {
+            if *corner_radius > 0.0 {
+                *radius = (corner_radius + spread).max(0.0);
+            }
+        }
+        if self.inset {
+            DrawShape::inner_shadow(frame, radii, &rect, shadow_radii, self.color, self.softness)
+        } else {
+            DrawShape::shadow(&rect, shadow_radii, self.color, self.softness)
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 // #[non_exhaustive]
 pub enum Visibility {
@@ -988,10 +1294,16 @@ pub struct Transition {
     pub color: Color,
     /// The foreground color. Only relevant to some nested GUI objects like Text
     pub tint: Color,
+    /// The colors of the gradient stops of the background
+    pub stop_colors: [Color; MAX_ANIMATED_STOPS],
     /// The current border width
     pub border_width: f32,
     /// The current border color
     pub border_color: Color,
+    /// The current shadow
+    pub shadow: ShadowStyle,
+    /// The current radius of each corner
+    pub corner_radii: [f32; 4],
     /// The current rotation
     pub rotation: f32,
     /// The x-y scale factor if size is changing
@@ -1004,6 +1316,20 @@ impl Transition {
         let border_width = 0.0;
         let border_color = Color::BLACK;
         let scale = Vector::ONE;
-        Transition { frame, color, tint: color, border_width, border_color, rotation, scale }
+        let stop_colors = [color; MAX_ANIMATED_STOPS];
+        let shadow = ShadowStyle::default();
+        let corner_radii = [0.0; 4];
+        Transition {
+            frame,
+            color,
+            tint: color,
+            stop_colors,
+            border_width,
+            border_color,
+            shadow,
+            corner_radii,
+            rotation,
+            scale,
+        }
     }
 }
@@ -8,7 +8,7 @@ use crate::tools::*;
 use quicksilver::{
     geom::{Rectangle, Shape, Transform, Vector},
     graphics::{Background::Col, Background::Img, Color, Image, MeshTask},
-    input::MouseCursor,
+    input::{Key, MouseCursor},
     lifecycle::Window,
 };
 
@@ -16,6 +16,8 @@ use quicksilver::{
 use image_rs::{imageops, DynamicImage, Pixel, RgbaImage};
 
 use std::any::TypeId;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use std::f32;
 use std::ops::Range;
 
@@ -47,7 +49,8 @@ pub enum RowState {
     Selected,
 }
 
-/// A wrapper for holding data and rendering information for each row
+/// A wrapper for holding data and rendering information for each row. Only rows that are visible are
+/// kept in memory.
 pub struct RowData {
     /// The string value
     pub text: String,
@@ -65,6 +68,165 @@ impl RowData {
     }
 }
 
+//-- Datasource -----------------------------------------------------------------------
+
+/// Trait for providing rows to a ListBox on demand, so that large lists do not need to be loaded into
+/// memory. The ListBox only asks for rows in the visible range.
+pub trait ListDataSource {
+    /// The total number of rows
+    fn row_count(&self) -> usize;
+
+    /// The text for the row at the index. This is used for text rows and for identifying rows.
+    fn row_at(&self, index: usize) -> String;
+
+    /// Override to provide a custom height for the row at the index. If None, the ListBox row_height
+    /// is used.
+    fn row_height(&self, _index: usize) -> Option<f32> {
+        None
+    }
+}
+
+impl ListDataSource for Vec<String> {
+    fn row_count(&self) -> usize {
+        self.len()
+    }
+
+    fn row_at(&self, index: usize) -> String {
+        self[index].clone()
+    }
+}
+
+/// Object-safe interface for a set of custom row views. ListBox calls these methods with the frames of
+/// the visible rows on each pass of the run loop. See RowRecycler.
+pub trait RowViews {
+    /// Assign a view to each visible row, where each row is (datasource index, frame, is selected).
+    /// Views for rows that are no longer visible are recycled.
+    fn layout_rows(&mut self, rows: &[(usize, Rectangle, bool)], theme: &mut Theme);
+
+    /// Apply a theme to all views, including recycled views
+    fn set_theme(&mut self, theme: &mut Theme);
+
+    /// Call update() on the visible views
+    fn update(&mut self, window: &mut Window, state: &mut AppState);
+
+    /// Call render() on the visible views
+    fn render(&mut self, theme: &mut Theme, window: &mut Window);
+
+    /// The number of views that have been created, including recycled views
+    fn materialized_count(&self) -> usize;
+}
+
+/// Creates custom row views for a ListBox and reuses them while scrolling. The build closure creates a
+/// new view and the configure closure populates a new or recycled view with the content for a row index
+/// and its selection state.
+pub struct RowRecycler<T: Displayable> {
+    build: Box<dyn Fn(Rectangle) -> T>,
+    configure: Box<dyn Fn(&mut T, usize, bool)>,
+    /// The views currently assigned to a row index, with the selection state they were configured with
+    active: Vec<(usize, bool, T)>,
+    /// Views that are not visible and can be reused
+    pool: Vec<T>,
+}
+
+impl<T: Displayable> RowRecycler<T> {
+    /// Constructor
+    pub fn new<B, C>(build: B, configure: C) -> Self
+    where
+        B: Fn(Rectangle) -> T + 'static,
+        C: Fn(&mut T, usize, bool) + 'static,
+    {
+        RowRecycler { build: Box::new(build), configure: Box::new(configure), active: Vec::new(), pool: Vec::new() }
+    }
+
+    /// Move a view to the row frame and resize it if the row height or width has changed
+    fn fit_view(view: &mut T, frame: &Rectangle) {
+        if view.get_frame().size != frame.size {
+            view.get_layer_mut().frame.size = frame.size;
+        }
+        view.align_view(frame.pos);
+    }
+}
+
+impl<T: Displayable> RowViews for RowRecycler<T> {
+    fn layout_rows(&mut self, rows: &[(usize, Rectangle, bool)], theme: &mut Theme) {
+        // Move views for rows that scrolled out of view to the pool
+        let mut i = 0;
+        while i < self.active.len() {
+            if rows.iter().any(|(index, _, _)| *index == self.active[i].0) {
+                i += 1;
+            } else {
+                let (_, _, view) = self.active.swap_remove(i);
+                self.pool.push(view);
+            }
+        }
+        for (index, frame, selected) in rows {
+            if let Some((_, was_selected, view)) = self.active.iter_mut().find(|(i, _, _)| *i == *index) {
+                Self::fit_view(view, frame);
+                if *was_selected != *selected {
+                    (self.configure)(view, *index, *selected);
+                    *was_selected = *selected;
+                }
+                continue;
+            }
+            let mut view = {
+                // Prefer a recycled view that already has the size of the row
+                let reuse = self.pool.iter().position(|view| view.get_frame().size == frame.size);
+                if let Some(view) = reuse.map(|i| self.pool.swap_remove(i)).or_else(|| self.pool.pop()) {
+                    view
+                } else {
+                    // Child views are positioned relative to the row origin so that align_view can move them
+                    let mut view = (self.build)(*frame);
+                    view.set_origin(frame.pos);
+                    view.set_theme(theme);
+                    view
+                }
+            };
+            Self::fit_view(&mut view, frame);
+            (self.configure)(&mut view, *index, *selected);
+            self.active.push((*index, *selected, view));
+        }
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        for (_, _, view) in &mut self.active {
+            view.set_theme(theme);
+        }
+        for view in &mut self.pool {
+            view.set_theme(theme);
+        }
+    }
+
+    fn update(&mut self, window: &mut Window, state: &mut AppState) {
+        for (_, _, view) in &mut self.active {
+            view.update(window, state);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        for (_, _, view) in &mut self.active {
+            view.render(theme, window);
+        }
+    }
+
+    fn materialized_count(&self) -> usize {
+        self.active.len() + self.pool.len()
+    }
+}
+
+/// Defines how rows can be selected in a ListBox
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SelectionMode {
+    /// Rows cannot be selected
+    None,
+    /// Only one row can be selected
+    Single,
+    /// Ctrl/Cmd toggles a row and Shift selects a range
+    Multiple,
+}
+
+/// The time in seconds after which type-ahead search starts over
+const TYPEAHEAD_TIMEOUT: f64 = 1.0;
+
 // *****************************************************************************************************
 // ListBox
 // *****************************************************************************************************
@@ -75,31 +237,62 @@ impl RowData {
 /// Scene that will paint over the overflow with the default background color. This is a temporary
 /// solution until there is a convenient means of clipping the rendered content.
 ///
-/// A datasource will feed data to the ListBox as needed to populate the rows with content.
+/// A ListDataSource feeds data to the ListBox as needed to populate the visible rows with content.
+/// Rows can have variable heights and can be custom Displayable views created by a RowRecycler.
+///
+/// Row indexes come in two forms. The datasource index identifies the data and is used in selections
+/// and events. The display index is the position of the row after sorting and filtering.
 ///
 pub struct ListBox {
     /// The base layer
     pub layer: Layer,
-    /// The index of the currently hovered row
+    /// The datasource index of the currently hovered row
     pub hover_row: Option<usize>,
-    /// The index of the currently selected row
+    /// The datasource index of the most recently selected row
     pub select_row: Option<usize>,
-    /// The row height
+    /// The default row height
     pub row_height: f32,
     /// The line separator between each row, including first and last
     pub row_border_style: BorderStyle,
-    /// Is it single select or multiple?
-    pub multiselect: bool,
-    /// The datasource as an array of strings
-    datasource: Vec<RowData>,
-    /// The array of visible ListBoxRow objects
+    /// Single or multiple selection
+    pub selection_mode: SelectionMode,
+    /// The datasource which provides rows on demand
+    datasource: Box<dyn ListDataSource>,
+    /// The datasource index for each display index, after sorting and filtering
+    row_map: Vec<usize>,
+    /// Optional sort order for the rows
+    sort_fn: Option<Box<dyn Fn(&str, &str) -> Ordering>>,
+    /// Optional filter where rows that return false are hidden
+    filter_fn: Option<Box<dyn Fn(&str) -> bool>>,
+    /// The y offset of each row relative to the top of the content. Has row_count + 1 values, where the
+    /// last value is the content height.
+    row_offsets: Vec<f32>,
+    /// The selected rows as datasource indexes
+    selections: BTreeSet<usize>,
+    /// The display index where a Shift range selection starts
+    anchor_row: Option<usize>,
+    /// The display index of the row with keyboard focus
+    focus_row: Option<usize>,
+    /// RowData for the visible rows, keyed by datasource index
+    row_cache: BTreeMap<usize, RowData>,
+    /// Reusable ListBoxRow objects for drawing text rows
     rows: Vec<ListBoxRow>,
+    /// Optional custom row views
+    row_views: Option<Box<dyn RowViews>>,
     /// Hover animation for the ListBoxRow. Unused
     pub on_row_hover: Option<PropSet>,
     /// Animation for row select
     pub on_row_select: Option<PropSet>,
     /// Stores the scroll offset
     scroll_offset: f32,
+    /// The chars typed for type-ahead search
+    typeahead: String,
+    /// The time of the last type-ahead char
+    typeahead_time: f64,
+    /// The clock time from the last update()
+    current_time: f64,
+    /// SelectionEvents waiting to be dispatched in the next update()
+    pending_events: Vec<SelectionEvent>,
 }
 
 impl ListBox {
@@ -116,31 +309,423 @@ impl ListBox {
             select_row: None,
             row_height: 20.0,
             row_border_style: BorderStyle::None,
-            multiselect: false,
-            datasource: Vec::new(),
+            selection_mode: SelectionMode::Single,
+            datasource: Box::new(Vec::<String>::new()),
+            row_map: Vec::new(),
+            sort_fn: None,
+            filter_fn: None,
+            row_offsets: vec![0.0],
+            selections: BTreeSet::new(),
+            anchor_row: None,
+            focus_row: None,
+            row_cache: BTreeMap::new(),
             rows: Vec::new(),
+            row_views: None,
             on_row_hover: Some(row_hover),
             on_row_select: Some(row_select),
             scroll_offset: 0.0,
+            typeahead: String::new(),
+            typeahead_time: 0.0,
+            current_time: 0.0,
+            pending_events: Vec::new(),
         }
     }
 
-    /// Load datasource with RowData objects
+    /// Builder method to display custom row views. The build closure creates a view and the configure
+    /// closure populates it for a datasource index and selection state. Only the views needed for the
+    /// visible rows are created, and they are reused as rows scroll in and out of view.
+    pub fn with_row_builder<T, B, C>(mut self, build: B, configure: C) -> Self
+    where
+        T: Displayable,
+        B: Fn(Rectangle) -> T + 'static,
+        C: Fn(&mut T, usize, bool) + 'static,
+    {
+        self.row_views = Some(Box::new(RowRecycler::new(build, configure)));
+        self
+    }
+
+    /// Builder method to set the SelectionMode
+    pub fn with_selection_mode(mut self, mode: SelectionMode) -> Self {
+        self.selection_mode = mode;
+        self
+    }
+
+    /// Load datasource with an array of strings
     pub fn set_datasource(&mut self, values: Vec<String>) {
-        for string in values {
-            let row_data = RowData::new(string);
-            self.datasource.push(row_data);
+        self.set_source(values);
+    }
+
+    /// Set a datasource that provides rows on demand
+    pub fn set_source<D>(&mut self, source: D)
+    where
+        D: ListDataSource + 'static,
+    {
+        self.datasource = Box::new(source);
+        self.select_row = None;
+        self.hover_row = None;
+        self.scroll_offset = 0.0;
+        self.row_cache.clear();
+        self.clear_selection();
+        self.reload_data();
+    }
+
+    /// Sort the rows using a comparison of the row text. The datasource is not changed.
+    pub fn set_sort<C>(&mut self, cb: C)
+    where
+        C: Fn(&str, &str) -> Ordering + 'static,
+    {
+        self.sort_fn = Some(Box::new(cb));
+        self.reload_data();
+    }
+
+    /// Remove the sort order
+    pub fn clear_sort(&mut self) {
+        self.sort_fn = None;
+        self.reload_data();
+    }
+
+    /// Only show rows where the closure returns true for the row text. Hidden rows are deselected.
+    pub fn set_filter<C>(&mut self, cb: C)
+    where
+        C: Fn(&str) -> bool + 'static,
+    {
+        self.filter_fn = Some(Box::new(cb));
+        self.reload_data();
+    }
+
+    /// Remove the filter
+    pub fn clear_filter(&mut self) {
+        self.filter_fn = None;
+        self.reload_data();
+    }
+
+    /// Apply the sort and filter, then recalculate the row offsets and discard cached text. Call this if
+    /// the datasource row count or row heights have changed.
+    pub fn reload_data(&mut self) {
+        let focus_index = self.focus_row.and_then(|row| self.row_map.get(row).cloned());
+        let anchor_index = self.anchor_row.and_then(|row| self.row_map.get(row).cloned());
+
+        let count = self.datasource.row_count();
+        let mut row_map: Vec<usize> = (0..count).collect();
+        if self.filter_fn.is_some() || self.sort_fn.is_some() {
+            let mut keyed: Vec<(usize, String)> =
+                row_map.iter().map(|index| (*index, self.datasource.row_at(*index))).collect();
+            if let Some(filter) = &self.filter_fn {
+                keyed.retain(|(_, text)| filter(text));
+            }
+            if let Some(sort) = &self.sort_fn {
+                keyed.sort_by(|a, b| sort(&a.1, &b.1));
+            }
+            row_map = keyed.into_iter().map(|(index, _)| index).collect();
+        }
+
+        let mut offsets: Vec<f32> = Vec::with_capacity(row_map.len() + 1);
+        let mut y = 0.0;
+        offsets.push(y);
+        for index in &row_map {
+            y += self.datasource.row_height(*index).unwrap_or(self.row_height);
+            offsets.push(y);
+        }
+        self.row_offsets = offsets;
+
+        // Keep selections and focus for rows that are still shown
+        let before = self.selections.len();
+        let shown: BTreeSet<usize> = row_map.iter().cloned().collect();
+        self.selections.retain(|index| shown.contains(index));
+        self.focus_row = focus_index.and_then(|index| row_map.iter().position(|i| *i == index));
+        self.anchor_row = anchor_index.and_then(|index| row_map.iter().position(|i| *i == index));
+        self.row_map = row_map;
+        if self.selections.len() != before {
+            self.selection_changed();
+        }
+
+        self.row_cache.clear();
+        let upper_limit = (self.content_height() - self.layer.frame.height()).max(0.0);
+        self.scroll_offset = self.scroll_offset.min(upper_limit);
+    }
+
+    /// The number of rows that are shown, after filtering
+    pub fn row_count(&self) -> usize {
+        self.row_map.len()
+    }
+
+    /// The total height of all rows
+    pub fn content_height(&self) -> f32 {
+        *self.row_offsets.last().unwrap_or(&0.0)
+    }
+
+    /// Get the text of the row at the specified display index
+    pub fn row_text(&self, row: usize) -> Option<String> {
+        self.row_map.get(row).map(|index| self.datasource.row_at(*index))
+    }
+
+    /// The number of custom row views that exist, including recycled views
+    pub fn materialized_rows(&self) -> usize {
+        if let Some(views) = &self.row_views {
+            views.materialized_count()
+        } else {
+            self.row_cache.len()
+        }
+    }
+
+    // *****************************************************************************************************
+    // Selection
+    // *****************************************************************************************************
+
+    /// The selected rows as datasource indexes in ascending order
+    pub fn selected_rows(&self) -> Vec<usize> {
+        self.selections.iter().cloned().collect()
+    }
+
+    /// Check if the row with the datasource index is selected
+    pub fn is_selected(&self, index: usize) -> bool {
+        self.selections.contains(&index)
+    }
+
+    /// Select all rows that are shown. Only works with SelectionMode::Multiple
+    pub fn select_all(&mut self) {
+        if self.selection_mode != SelectionMode::Multiple {
+            return;
+        }
+        self.selections = self.row_map.iter().cloned().collect();
+        self.selection_changed();
+    }
+
+    /// Deselect all rows
+    pub fn clear_selection(&mut self) {
+        if self.selections.is_empty() {
+            return;
+        }
+        self.selections.clear();
+        self.selection_changed();
+    }
+
+    /// Select the row at the display index as if it was clicked with the specified modifier keys
+    pub fn select_display_row(&mut self, row: usize, modifiers: KeyModifiers) {
+        self.select_at(row, modifiers, false);
+    }
+
+    /// Update the selection for a click or key press on the row at the display index. With the keyboard,
+    /// Ctrl/Cmd only moves the focus.
+    fn select_at(&mut self, row: usize, modifiers: KeyModifiers, from_keyboard: bool) {
+        if row >= self.row_count() {
+            return;
+        }
+        let index = self.row_map[row];
+        self.focus_row = Some(row);
+        match self.selection_mode {
+            SelectionMode::None => return,
+            SelectionMode::Single => {
+                self.selections.clear();
+                self.selections.insert(index);
+                self.anchor_row = Some(row);
+            }
+            SelectionMode::Multiple => {
+                if modifiers.shift {
+                    let anchor = self.anchor_row.unwrap_or(row);
+                    if !modifiers.command() {
+                        self.selections.clear();
+                    }
+                    for i in anchor.min(row)..=anchor.max(row) {
+                        self.selections.insert(self.row_map[i]);
+                    }
+                } else if modifiers.command() {
+                    if from_keyboard {
+                        return;
+                    }
+                    if !self.selections.remove(&index) {
+                        self.selections.insert(index);
+                    }
+                    self.anchor_row = Some(row);
+                } else {
+                    self.selections.clear();
+                    self.selections.insert(index);
+                    self.anchor_row = Some(row);
+                }
+            }
+        }
+        self.select_row = Some(index);
+        self.selection_changed();
+    }
+
+    /// Queue a SelectionEvent and discard the row highlights so they are rebuilt
+    fn selection_changed(&mut self) {
+        for (index, data) in self.row_cache.iter_mut() {
+            if !self.selections.contains(index) {
+                data.layer = None;
+                data.row_state = MouseState::None;
+            }
+        }
+        let focus = self.focus_row.and_then(|row| self.row_map.get(row).cloned());
+        self.pending_events.push(SelectionEvent::Changed(self.selections.len(), focus));
+    }
+
+    /// Scroll the minimum amount needed to show the row at the display index
+    pub fn scroll_to_row(&mut self, row: usize) {
+        if row >= self.row_count() {
+            return;
+        }
+        let (top, bottom) = (self.row_offsets[row], self.row_offsets[row + 1]);
+        self.scroll_offset = UITools::scroll_to_span(self.scroll_offset, top, bottom, self.layer.frame.height());
+    }
+
+    /// Find the next row where the text starts with the type-ahead string, starting at the focused row
+    fn find_typeahead_row(&self) -> Option<usize> {
+        let count = self.row_count();
+        if count == 0 {
+            return None;
         }
+        // A new search starts after the focused row. A longer search can match the focused row again.
+        let start = match self.focus_row {
+            Some(row) if self.typeahead.chars().count() > 1 => row,
+            Some(row) => row + 1,
+            None => 0,
+        };
+        (0..count).map(|i| (start + i) % count).find(|row| {
+            let text = self.datasource.row_at(self.row_map[*row]).to_lowercase();
+            text.starts_with(&self.typeahead)
+        })
+    }
+
+    // *****************************************************************************************************
+    // Layout
+    // *****************************************************************************************************
+
+    /// Find the display index at the y offset within the content
+    fn row_at_offset(&self, y: f32) -> Option<usize> {
+        if y < 0.0 || y >= self.content_height() {
+            return None;
+        }
+        match self.row_offsets.binary_search_by(|v| v.partial_cmp(&y).unwrap_or(Ordering::Less)) {
+            Ok(row) => Some(row),
+            Err(row) => Some(row - 1),
+        }
+    }
+
+    /// Find the display index at the window position
+    fn row_at_point(&self, pt: &Vector) -> Option<usize> {
+        if !self.layer.contains_point(pt) {
+            return None;
+        }
+        let local_y = pt.y - self.layer.frame.pos.y;
+        self.row_at_offset(local_y + self.scroll_offset)
     }
 
     /// Based on the scroll_offset, determine what rows are visible in the scrollable frame
     fn get_visible_range(&self) -> Range<usize> {
-        let shift = self.scroll_offset / self.row_height;
-        let start = shift.floor() as usize;
-        let row_count = (self.layer.frame.size.y / self.row_height + shift.fract()).ceil() as usize;
-        // log::debug!("offset={:?} shift={:?}", self.scroll_offset, shift);
-        // log::debug!("start={:?} count={:?}", start, row_count);
-        return start..(start + row_count);
+        let count = self.row_count();
+        let start = self.row_at_offset(self.scroll_offset).unwrap_or(count);
+        let max_y = self.scroll_offset + self.layer.frame.height();
+        let mut end = start;
+        while end < count && self.row_offsets[end] < max_y {
+            end += 1;
+        }
+        return start..end;
+    }
+
+    /// Get the datasource index, window frame and selection state of every visible row
+    fn get_visible_rows(&self) -> Vec<(usize, Rectangle, bool)> {
+        let frame = self.layer.frame;
+        self.get_visible_range()
+            .map(|row| {
+                let index = self.row_map[row];
+                let ypos = frame.y() + self.row_offsets[row] - self.scroll_offset;
+                let height = self.row_offsets[row + 1] - self.row_offsets[row];
+                let rect = Rectangle::new((frame.x(), ypos), (frame.width(), height));
+                (index, rect, self.selections.contains(&index))
+            })
+            .collect()
+    }
+
+    /// Custom row views cannot be clipped like text meshes, so the parts of the first and last rows
+    /// that overflow the top or bottom of the frame are painted over with the background of the ListBox,
+    /// continued past its edges. A ListBox without a solid or gradient background uses the theme background color.
+    fn mask_row_overflow(&self, visible: &[(usize, Rectangle, bool)], theme: &Theme, window: &mut Window) {
+        let frame = self.layer.frame;
+        let bottom = frame.y() + frame.height();
+        let mut blocks: Vec<Rectangle> = Vec::new();
+        for (_, rect, _) in visible {
+            if rect.y() < frame.y() {
+                blocks.push(Rectangle::new((frame.x(), rect.y()), (frame.width(), frame.y() - rect.y())));
+            }
+            let rect_bottom = rect.y() + rect.height();
+            if rect_bottom > bottom {
+                blocks.push(Rectangle::new((frame.x(), bottom), (frame.width(), rect_bottom - bottom)));
+            }
+        }
+        if !blocks.is_empty() && !self.layer.draw_background_pad(&blocks, window) {
+            window.add_task(UITools::draw_rectangles(blocks, theme.bg_color));
+        }
+    }
+
+    /// Draw the text rows using the cached text meshes. Text is rendered as needed for rows that
+    /// scroll into view, and discarded when the row is no longer visible.
+    fn render_text_rows(&mut self, visible: &[(usize, Rectangle, bool)], theme: &mut Theme, window: &mut Window) {
+        let frame = self.layer.frame;
+
+        // Discard RowData for rows that are out of view
+        self.row_cache.retain(|index, _| visible.iter().any(|(i, _, _)| i == index));
+
+        while self.rows.len() < visible.len() {
+            let mut row = ListBoxRow::new(Rectangle::new(frame.pos, (frame.width(), self.row_height)));
+            row.set_id(self.rows.len() as u32);
+            row.set_theme(theme);
+            self.rows.push(row);
+        }
+
+        for (i, (index, rect, selected)) in visible.iter().enumerate() {
+            let datasource = &self.datasource;
+            let data = self.row_cache.entry(*index).or_insert_with(|| RowData::new(datasource.row_at(*index)));
+            if data.render.is_none() {
+                let params = TextParams::new(self.layer.font_style)
+                    .frame(Rectangle::new_sized(rect.size))
+                    .text(&data.text)
+                    .align(TextAlign::Left, VertAlign::Middle)
+                    .multiline(false);
+                data.render = theme.default_font.draw(params);
+            }
+            // Selected rows that scroll into view are highlighted without animation
+            if *selected && data.layer.is_none() {
+                let mut layer = self.rows[0].layer.clone();
+                if let Some(transition) = &self.on_row_select {
+                    layer.apply_props(&transition.props.clone());
+                }
+                data.row_state = MouseState::Select;
+                data.layer = Some(layer);
+            }
+            if let Some(render) = &data.render {
+                let mut mesh = render.clone();
+                // All text meshes were created at origin 0, 0 and thus need to be translated to the actual
+                // row position.
+                for vertex in &mut mesh.vertices.iter_mut() {
+                    vertex.pos = Transform::translate(rect.pos) * vertex.pos;
+                }
+                // If first visible row, check if the row rect overflows the top of the listbox.
+                // If last visible row, check if the row rect overflows the bottom of the listbox.
+                // Clip the mesh top or bottom as needed.
+                let mut bounds = rect.clone();
+                let y_overflow = frame.y() - rect.y();
+                if y_overflow > 0.0 {
+                    bounds = Rectangle::new(frame.pos, (frame.width(), rect.height() - y_overflow));
+                    UITools::clip_mesh(&mut mesh, &bounds, RectSide::Top);
+                }
+                let y_overflow = (rect.y() + rect.height()) - (frame.y() + frame.height());
+                if y_overflow > 0.0 {
+                    bounds = Rectangle::new((frame.x(), bounds.y()), (frame.width(), bounds.height() - y_overflow));
+                    UITools::clip_mesh(&mut mesh, &bounds, RectSide::Bottom);
+                }
+                let transition = {
+                    if let Some(layer) = &data.layer {
+                        Some(layer.transition.clone())
+                    } else {
+                        None
+                    }
+                };
+                self.rows[i].render_row(mesh, bounds, transition, &self.layer, window);
+            } else {
+                log::debug!(">>> mesh_task is None!");
+            }
+        }
     }
 }
 
@@ -170,44 +755,17 @@ impl Displayable for ListBox {
         self.layer.frame.pos.y = pos.1;
     }
 
-    /// This function is responsible for building all of the ListBoxRows and pre-rendering text as
-    /// meshes. This is necessary to support theme changes that can affect the row height and font color.
+    /// Theme changes can affect the row height and font color, so the row templates and cached text
+    /// are discarded and created again during the render phase.
     fn set_theme(&mut self, theme: &mut Theme) {
         // Don't allow style to be locked
         let _ = self.layer.apply_theme(theme);
 
-        // Create only the rows needed to fill the visible range. Each row is a template
-        // that is populated during the render phase
-
-        self.rows.clear();
         // FIXME: row height should be themed.
-        let row_count = (self.layer.frame.size.y / self.row_height) as usize + 1;
-        log::debug!("Row count={}", row_count);
-        for i in 0..row_count {
-            let rect = Rectangle::new(
-                (self.layer.frame.pos.x, self.layer.frame.pos.y + self.row_height * i as f32),
-                (self.layer.frame.size.x, self.row_height),
-            );
-            let mut row = ListBoxRow::new(rect);
-            row.set_id(i as u32);
-            row.set_theme(theme);
-            self.rows.push(row);
-        }
-
-        // Render all string values in datasource as meshes to make performance better
-        let frame = Rectangle::new_sized((self.layer.frame.size.x, self.row_height));
-
-        for data in &mut self.datasource {
-            let mut params = TextParams::new(self.layer.font_style)
-                .frame(frame)
-                .align(TextAlign::Left, VertAlign::Middle)
-                .multiline(false);
-            params.text = data.text.clone();
-            if let Some(render) = theme.default_font.draw(params) {
-                data.render = Some(render);
-            } else {
-                log::debug!(">>> mesh_task is None!");
-            }
+        self.rows.clear();
+        self.reload_data();
+        if let Some(views) = &mut self.row_views {
+            views.set_theme(theme);
         }
     }
 
@@ -226,24 +784,26 @@ impl Displayable for ListBox {
     /// The ListBox should manage the UI state of its rows.
     /// The mouse state of a row should determine whether to reset or not.
     /// Otherwise, leave it alone?
-    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+    fn update(&mut self, window: &mut Window, state: &mut AppState) {
         self.layer.tween_update(state);
+        self.current_time = state.clock.current_time();
 
-        self.datasource.iter_mut().for_each(|x| {
+        self.row_cache.values_mut().for_each(|x| {
             if let Some(ref mut layer) = x.layer {
                 layer.tween_update(state);
             }
         });
+        if let Some(views) = &mut self.row_views {
+            views.update(window, state);
+        }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
     }
 
-    fn render(&mut self, _theme: &mut Theme, window: &mut Window) {
-        if self.rows.len() == 0 {
-            log::warn!("No rows found. Rows are created when set_theme is called.");
-            return;
-        }
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
         self.layer.draw_background(window);
         let frame = self.layer.frame;
-        // log::debug!("frame={:?}", frame);
         // Create a mesh to hold row borders
         let mut graphics = MeshTask::new(0);
         let border: (Color, f32) = {
@@ -253,65 +813,29 @@ impl Displayable for ListBox {
             }
         };
 
-        // Iterate through the rows that are in the visible range
-        let range = self.get_visible_range();
-        let shift_y = self.scroll_offset % self.row_height;
-
-        let range_start = range.start;
-        let last = self.rows.len() - 1;
-        for (i, row) in &mut self.rows.iter_mut().enumerate() {
-            // Calculate where row should render, using shift_y as the scroll offset
-            let ypos = frame.pos.y - shift_y + self.row_height * i as f32;
-            let rect = Rectangle::new((frame.pos.x, ypos), (frame.size.x, self.row_height));
-            // Draw borders as row separators. Future: cache these lines
-            if ypos > frame.y() {
-                if border.1 > 0.0 {
-                    let pts: [&Vector; 2] = [&Vector::new(rect.x(), ypos), &Vector::new(rect.x() + rect.width(), ypos)];
+        // Only the rows in the visible range are materialized
+        let visible = self.get_visible_rows();
+        if border.1 > 0.0 {
+            for (_, rect, _) in &visible {
+                // Draw borders as row separators. Future: cache these lines
+                if rect.y() > frame.y() {
+                    let pts: [&Vector; 2] =
+                        [&Vector::new(rect.x(), rect.y()), &Vector::new(rect.x() + rect.width(), rect.y())];
                     let mut line = DrawShape::line(&pts, border.0, border.1);
                     graphics.append(&mut line);
                 }
             }
+        }
 
-            let row_index = range_start + i;
-            let mut bounds = rect.clone();
-            if row_index < self.datasource.len() {
-                let row_data = &self.datasource[row_index];
-                if let Some(render) = &row_data.render {
-                    let mut mesh = render.clone();
-                    // All text meshes were created at origin 0, 0 and thus need to be translated to the actual
-                    // row position.
-                    for vertex in &mut mesh.vertices.iter_mut() {
-                        vertex.pos = Transform::translate(rect.pos) * vertex.pos;
-                    }
-                    // If first visible row, check if the row rect overflows the top of the listbox.
-                    // If last visible row, check if the row rect overflows the bottom of the listbox.
-                    // Clip the mesh top or bottom as needed.
-                    if i == 0 {
-                        let y_overflow = frame.y() - rect.y();
-                        if y_overflow > 0.0 {
-                            bounds = Rectangle::new(frame.pos, (frame.width(), rect.height() - y_overflow));
-                            UITools::clip_mesh(&mut mesh, &bounds, RectSide::Top);
-                        }
-                    } else if i == last {
-                        let y_overflow = (rect.y() + rect.height()) - (frame.y() + frame.height());
-                        if y_overflow > 0.0 {
-                            bounds = Rectangle::new((frame.x(), rect.y()), (frame.width(), rect.height() - y_overflow));
-                            UITools::clip_mesh(&mut mesh, &bounds, RectSide::Bottom);
-                        }
-                    }
-                    let transition = {
-                        if let Some(layer) = &row_data.layer {
-                            Some(layer.transition.clone())
-                        } else {
-                            None
-                        }
-                    };
-                    row.render_row(mesh, bounds, transition, window);
-                }
-            }
+        if let Some(views) = &mut self.row_views {
+            views.layout_rows(&visible, theme);
+            views.render(theme, window);
+            self.mask_row_overflow(&visible, theme, window);
+        } else {
+            self.render_text_rows(&visible, theme, window);
         }
 
-        let content_height = self.datasource.len() as f32 * self.row_height;
+        let content_height = self.content_height();
         if let Some(rect) = UITools::get_scrollbar_frame(content_height, &frame, self.scroll_offset) {
             // FIXME: use mesh
             window.draw(&rect, Col(Color::from_hex(UITools::SCROLLBAR_COLOR)));
@@ -326,32 +850,12 @@ impl Displayable for ListBox {
     }
 
     fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
-        let range = self.get_visible_range();
-        if pt.overlaps_rectangle(&self.layer.frame) {
+        if self.layer.contains_point(pt) {
             self.layer.mouse_state = MouseState::Hover;
             window.set_cursor(MouseCursor::Hand);
-            let local_y = pt.y - self.layer.frame.pos.y;
-            let mut index = (local_y / self.row_height).floor() as usize;
-            index += range.start;
-            if index < self.datasource.len() {
-                self.hover_row = Some(index);
+            if let Some(row) = self.row_at_point(pt) {
+                self.hover_row = Some(self.row_map[row]);
                 // TODO: Finish implementing hover effect
-                // log::debug!("hover index={:?} y={:?}", index, 0);
-                // let data = &mut self.datasource[index];
-                // data.row_state = MouseState::Hover;
-
-                // // show hover animation
-                // if let Some(transition) = &self.on_row_hover {
-                //     let layer = self.rows[0].get_layer().clone();
-                //     if let Some(layer) = &mut data.layer {
-                //         if transition.duration > 0.0 {
-                //             layer.animate_with_props(transition.clone());
-                //         } else {
-                //             layer.apply_props(&transition.props.clone());
-                //         }
-                //     }
-                // }
-
                 return true;
             }
         } else {
@@ -368,44 +872,63 @@ impl Displayable for ListBox {
 // *****************************************************************************************************
 
 impl Responder for ListBox {
-    /// Calculate whether x, y are in the rect bounds of any child row with maths.
-    /// Identify the row by getting x, y offset from listbox origin.
-    fn handle_mouse_down(&mut self, pt: &Vector, state: &mut AppState) -> bool {
-        let range = self.get_visible_range();
-
-        if pt.overlaps_rectangle(&self.layer.frame) {
-            let local_y = pt.y - self.layer.frame.pos.y;
-            let mut index = (local_y / self.row_height).floor() as usize;
-            index += range.start;
-            log::debug!("local_y={:?} clicked row={:?}", local_y, index);
-            if index < self.datasource.len() {
-                state.row_target = Some(index);
-                self.select_row = Some(index);
-                for (_, data) in &mut self.datasource.iter_mut().enumerate() {
-                    if !self.multiselect && data.layer.is_some() {
-                        data.layer = None;
-                        data.row_state = MouseState::None;
-                    }
+    fn get_field_value(&self) -> FieldValue {
+        if self.selections.is_empty() {
+            return FieldValue::None;
+        }
+        FieldValue::Selections(self.selected_rows())
+    }
+
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if type_id == self.get_type_id() && layer_id == self.layer.get_id() {
+            match value {
+                FieldValue::Selections(rows) => {
+                    let limit = match self.selection_mode {
+                        SelectionMode::None => 0,
+                        SelectionMode::Single => 1,
+                        SelectionMode::Multiple => rows.len(),
+                    };
+                    self.selections = rows.iter().filter(|i| self.row_map.contains(i)).take(limit).cloned().collect();
+                    self.selection_changed();
+                    return true;
                 }
+                FieldValue::None => {
+                    self.clear_selection();
+                    return true;
+                }
+                _ => (),
+            }
+        }
+        false
+    }
+
+    /// Find the row at the mouse position using the row offsets. The modifier keys in AppState
+    /// determine whether the click toggles or extends the selection.
+    fn handle_mouse_down(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        if let Some(row) = self.row_at_point(pt) {
+            let index = self.row_map[row];
+            log::debug!("clicked row={:?} index={:?}", row, index);
+            state.row_target = Some(index);
+            self.select_at(row, state.modifiers, false);
 
-                if self.rows.len() > 0 {
-                    let mut layer = self.rows[0].layer.clone();
-                    let data = &mut self.datasource[index];
-                    if data.row_state != MouseState::Select {
-                        data.row_state = MouseState::Select;
-                        if let Some(transition) = &self.on_row_select {
-                            if transition.duration > 0.0 {
-                                layer.animate_with_props(transition.clone(), true);
-                            } else {
-                                layer.apply_props(&transition.props.clone());
-                            }
+            // Animate the highlight for the clicked row
+            if self.selections.contains(&index) && self.rows.len() > 0 {
+                let mut layer = self.rows[0].layer.clone();
+                let datasource = &self.datasource;
+                let data = self.row_cache.entry(index).or_insert_with(|| RowData::new(datasource.row_at(index)));
+                if data.row_state != MouseState::Select || data.layer.is_none() {
+                    data.row_state = MouseState::Select;
+                    if let Some(transition) = &self.on_row_select {
+                        if transition.duration > 0.0 {
+                            layer.animate_with_props(transition.clone(), true);
+                        } else {
+                            layer.apply_props(&transition.props.clone());
                         }
-                        data.layer = Some(layer);
                     }
+                    data.layer = Some(layer);
                 }
-
-                return true;
             }
+            return true;
         }
         false
     }
@@ -415,14 +938,59 @@ impl Responder for ListBox {
         match self.layer.mouse_state {
             MouseState::Hover => {
                 // Calculate upper_limit as the content size outside of the frame.
-                let upper_limit = self.datasource.len() as f32 * self.row_height - self.layer.frame.height();
+                let upper_limit = self.content_height() - self.layer.frame.height();
                 let eval_y = ((self.scroll_offset + pt.y) * SCROLL_FACTOR).min(upper_limit);
-                // log::debug!("pt.y={:?} eval_y={:?}", pt.y, eval_y);
                 self.scroll_offset = eval_y.max(0.0);
             }
             _ => (),
         }
     }
+
+    /// Type-ahead search. Chars typed within TYPEAHEAD_TIMEOUT of each other are combined.
+    fn handle_key_press(&mut self, c: char, _window: &mut Window) {
+        if c.is_control() || self.row_count() == 0 {
+            return;
+        }
+        if self.current_time - self.typeahead_time > TYPEAHEAD_TIMEOUT {
+            self.typeahead.clear();
+        }
+        self.typeahead_time = self.current_time;
+        self.typeahead.extend(c.to_lowercase());
+        if let Some(row) = self.find_typeahead_row() {
+            self.select_at(row, KeyModifiers::default(), true);
+            self.scroll_to_row(row);
+        }
+    }
+
+    /// Arrow keys, Home, End, PageUp and PageDown move the selection. Shift extends the selection and
+    /// Ctrl/Cmd moves the focus without changing the selection. Ctrl/Cmd-A selects all.
+    fn handle_key_command(&mut self, key: &Key, window: &mut Window) -> bool {
+        let count = self.row_count();
+        if count == 0 {
+            return false;
+        }
+        let modifiers = KeyModifiers::from_keyboard(window.keyboard());
+        let page = UITools::page_size(self.layer.frame.height(), self.row_height);
+        if let Some(target) = UITools::row_for_key(key, self.focus_row, count, page) {
+            self.select_at(target, modifiers, true);
+            self.scroll_to_row(target);
+            return true;
+        }
+        match key {
+            Key::A if modifiers.command() => {
+                self.select_all();
+                true
+            }
+            Key::Return => {
+                if let Some(row) = self.focus_row {
+                    self.pending_events.push(SelectionEvent::Activated(self.row_map[row]));
+                    return true;
+                }
+                false
+            }
+            _ => false,
+        }
+    }
 }
 
 // *****************************************************************************************************
@@ -443,19 +1011,22 @@ impl ListBoxRow {
         ListBoxRow { layer }
     }
 
-    /// Method to render a row
+    /// Method to render a row. The background and text are clipped to the rounded corners of the parent layer.
     pub fn render_row(
         &mut self,
         mut mesh: MeshTask,
         bounds: Rectangle,
         transition: Option<Transition>,
+        parent: &Layer,
         window: &mut Window,
     ) {
+        parent.clip_to_corners(&mut mesh);
         if let Some(transition) = transition {
             // Draw a background rectangle with the transition.color
             let mut bg_mesh = DrawShape::rectangle(&bounds, Some(transition.color), None, 0.0, 0.0);
             let mut mesh_task = MeshTask::new(0);
             mesh_task.append(&mut bg_mesh);
+            parent.clip_to_corners(&mut mesh_task);
             window.add_task(mesh_task);
             // Change the font color
             mesh.vertices.iter_mut().for_each(|x| x.col = transition.tint);
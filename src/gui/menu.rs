@@ -0,0 +1,1018 @@
+/// Menus, context menus and the menu bar of a Stage
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Transform, Vector},
+    graphics::{Color, MeshTask},
+    input::Key,
+    lifecycle::{Asset, Window},
+};
+
+use std::collections::{BTreeMap, HashMap};
+
+use super::*;
+
+/// The height of a menu item row
+const MENU_ROW_HEIGHT: f32 = 24.0;
+/// The height of a separator row
+const MENU_SEPARATOR_HEIGHT: f32 = 9.0;
+/// The padding above and below the items of a menu
+const MENU_PADDING: f32 = 4.0;
+/// The minimum width of a menu
+const MENU_MIN_WIDTH: f32 = 160.0;
+/// The width of the column for checkmarks and icons on the left and submenu arrows on the right
+const MENU_GUTTER: f32 = 24.0;
+/// The space between the title and the shortcut hint
+const MENU_SHORTCUT_SPACING: f32 = 24.0;
+/// The width and height of icons
+const MENU_ICON_SIZE: f32 = 16.0;
+/// The horizontal padding around each title in a MenuBar
+const MENU_BAR_PADDING: f32 = 10.0;
+/// The duration in seconds of the fade in animation
+const MENU_DURATION: f64 = 0.12;
+/// The minimum distance between a menu and the edge of the window
+const WINDOW_MARGIN: f32 = 4.0;
+
+//-- Accelerator -----------------------------------------------------------------------
+
+/// A keyboard shortcut for a MenuItem. The command modifier is either Ctrl or the Windows/Command key.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Accelerator {
+    /// The key
+    pub key: Key,
+    /// Requires Ctrl or Cmd
+    pub command: bool,
+    /// Requires Shift
+    pub shift: bool,
+    /// Requires Alt/Option
+    pub alt: bool,
+}
+
+impl Accelerator {
+    /// Constructor for a key without modifiers, such as a function key
+    pub fn new(key: Key) -> Self {
+        Accelerator { key, command: false, shift: false, alt: false }
+    }
+
+    /// Constructor for Ctrl/Cmd and the key
+    pub fn command(key: Key) -> Self {
+        Accelerator { command: true, ..Accelerator::new(key) }
+    }
+
+    /// Builder method to require Shift
+    pub fn with_shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    /// Builder method to require Alt
+    pub fn with_alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Returns true if the key and modifiers match exactly
+    pub fn matches(&self, key: &Key, modifiers: &KeyModifiers) -> bool {
+        self.key == *key
+            && self.command == modifiers.command()
+            && self.shift == modifiers.shift
+            && self.alt == modifiers.alt
+    }
+
+    /// The hint shown in a menu, such as "Ctrl+Shift+S"
+    pub fn label(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if self.command {
+            parts.push("Ctrl".to_string());
+        }
+        if self.alt {
+            parts.push("Alt".to_string());
+        }
+        if self.shift {
+            parts.push("Shift".to_string());
+        }
+        // The number keys are named Key0 to Key9
+        let key = format!("{:?}", self.key);
+        if key.len() == 4 && key.starts_with("Key") {
+            parts.push(key[3..].to_string());
+        } else {
+            parts.push(key);
+        }
+        parts.join("+")
+    }
+}
+
+/// The location of a MenuItem, which is the menu id and the item index at each submenu level
+#[derive(Clone, Debug, PartialEq)]
+pub struct MenuItemPath {
+    /// The id returned by Stage::add_menu
+    pub menu_id: u32,
+    /// The index of the item in the menu, preceded by the indexes of its parent submenu items
+    pub indices: Vec<usize>,
+}
+
+/// Maps keyboard shortcuts to the menu items that they choose. A Stage keeps one registry for all of its
+/// menus, so an accelerator works whether or not its menu is open.
+#[derive(Default)]
+pub struct AcceleratorRegistry {
+    entries: Vec<(Accelerator, MenuItemPath)>,
+}
+
+impl AcceleratorRegistry {
+    /// Constructor
+    pub fn new() -> Self {
+        AcceleratorRegistry { entries: Vec::new() }
+    }
+
+    /// Register the accelerator for the menu item. Returns false if the accelerator is already registered.
+    pub fn register(&mut self, accelerator: Accelerator, path: MenuItemPath) -> bool {
+        if self.entries.iter().any(|(existing, _)| *existing == accelerator) {
+            log::warn!("Accelerator {} is already registered", accelerator.label());
+            return false;
+        }
+        self.entries.push((accelerator, path));
+        true
+    }
+
+    /// Remove the accelerator
+    pub fn unregister(&mut self, accelerator: &Accelerator) {
+        self.entries.retain(|(existing, _)| existing != accelerator);
+    }
+
+    /// Find the menu item for the key and modifiers
+    pub fn find(&self, key: &Key, modifiers: &KeyModifiers) -> Option<&MenuItemPath> {
+        self.entries.iter().find(|(accelerator, _)| accelerator.matches(key, modifiers)).map(|(_, path)| path)
+    }
+
+    /// The number of registered accelerators
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no accelerators
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+//-- Menu -----------------------------------------------------------------------
+
+/// An item in a Menu. Choosing it runs its Command, toggles the checkmark of a checkable item and
+/// dispatches MenuEvent::Selected with the item id.
+pub struct MenuItem {
+    /// The id dispatched in MenuEvent::Selected
+    pub id: u32,
+    /// The title text
+    pub title: String,
+    /// Optional name of an icon that was added with Stage::add_menu_icon
+    pub icon: Option<String>,
+    /// The checkmark state, or None if the item is not checkable
+    pub checked: Option<bool>,
+    /// Can the item be chosen?
+    pub enabled: bool,
+    /// Optional keyboard shortcut, which is shown as a hint
+    pub accelerator: Option<Accelerator>,
+    /// Optional submenu that opens beside the item
+    pub submenu: Option<Menu>,
+    /// Is the item a divider line?
+    pub is_separator: bool,
+    /// The Command to run when the item is chosen
+    command: Option<Command>,
+}
+
+impl MenuItem {
+    /// Constructor
+    pub fn new(id: u32, title: &str) -> Self {
+        MenuItem {
+            id,
+            title: title.to_owned(),
+            icon: None,
+            checked: None,
+            enabled: true,
+            accelerator: None,
+            submenu: None,
+            is_separator: false,
+            command: None,
+        }
+    }
+
+    /// A divider line between groups of items
+    pub fn separator() -> Self {
+        MenuItem { is_separator: true, enabled: false, ..MenuItem::new(0, "") }
+    }
+
+    /// An item that opens the submenu
+    pub fn submenu(title: &str, menu: Menu) -> Self {
+        MenuItem { submenu: Some(menu), ..MenuItem::new(0, title) }
+    }
+
+    /// Builder method to set the icon name
+    pub fn with_icon(mut self, name: &str) -> Self {
+        self.icon = Some(name.to_owned());
+        self
+    }
+
+    /// Builder method to make the item checkable with the initial state
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    /// Builder method to enable or disable the item
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Builder method to set the keyboard shortcut
+    pub fn with_accelerator(mut self, accelerator: Accelerator) -> Self {
+        self.accelerator = Some(accelerator);
+        self
+    }
+
+    /// Builder method to set the Command that runs when the item is chosen
+    pub fn with_command(mut self, command: Command) -> Self {
+        self.command = Some(command);
+        self
+    }
+
+    /// Builder method for a Command that dispatches the event when the item is chosen
+    pub fn with_event<E: AnyEvent>(self, event: E) -> Self {
+        let command = Command::new(Box::new(self.id)).event(event);
+        self.with_command(command)
+    }
+
+    /// Can the item be highlighted and chosen?
+    pub fn is_selectable(&self) -> bool {
+        self.enabled && !self.is_separator
+    }
+}
+
+/// A list of MenuItems. Add it to a Stage with Stage::add_menu() or to a MenuBar.
+#[derive(Default)]
+pub struct Menu {
+    /// The items from top to bottom
+    pub items: Vec<MenuItem>,
+}
+
+impl Menu {
+    /// Constructor
+    pub fn new() -> Self {
+        Menu { items: Vec::new() }
+    }
+
+    /// Builder method to add an item
+    pub fn with_item(mut self, item: MenuItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Add an item
+    pub fn add_item(&mut self, item: MenuItem) {
+        self.items.push(item);
+    }
+
+    /// Find the item with the id in this menu or its submenus
+    pub fn item_mut(&mut self, id: u32) -> Option<&mut MenuItem> {
+        for item in &mut self.items {
+            if item.is_separator {
+                continue;
+            }
+            if item.id == id && item.submenu.is_none() {
+                return Some(item);
+            }
+            if let Some(menu) = &mut item.submenu {
+                if let Some(found) = menu.item_mut(id) {
+                    return Some(found);
+                }
+            }
+        }
+        None
+    }
+
+    /// The submenu at the indices of its parent items. An empty path is this menu.
+    pub(super) fn submenu_at(&self, path: &[usize]) -> Option<&Menu> {
+        let mut menu = self;
+        for index in path {
+            menu = menu.items.get(*index)?.submenu.as_ref()?;
+        }
+        Some(menu)
+    }
+
+    pub(super) fn item_at(&self, path: &[usize]) -> Option<&MenuItem> {
+        let (last, parents) = path.split_last()?;
+        self.submenu_at(parents)?.items.get(*last)
+    }
+
+    pub(super) fn item_at_mut(&mut self, path: &[usize]) -> Option<&mut MenuItem> {
+        let (last, parents) = path.split_last()?;
+        let mut menu = self;
+        for index in parents {
+            menu = menu.items.get_mut(*index)?.submenu.as_mut()?;
+        }
+        menu.items.get_mut(*last)
+    }
+
+    /// The index of the next item that can be highlighted after the current one, wrapping around
+    pub(super) fn next_selectable(&self, current: Option<usize>, forward: bool) -> Option<usize> {
+        let count = self.items.len();
+        if count == 0 {
+            return None;
+        }
+        let mut index = match current {
+            Some(index) => index,
+            None if forward => count - 1,
+            None => 0,
+        };
+        for _ in 0..count {
+            index = if forward { (index + 1) % count } else { (index + count - 1) % count };
+            if self.items[index].is_selectable() {
+                return Some(index);
+            }
+        }
+        None
+    }
+}
+
+//-- MenuBar -----------------------------------------------------------------------
+
+/// A title in a MenuBar
+struct MenuBarEntry {
+    title: String,
+    menu_id: u32,
+    /// The position of the title, which is set when the bar is drawn
+    rect: Rectangle,
+    /// The text mesh at origin 0, 0
+    text: Option<MeshTask>,
+}
+
+/// A row of menu titles that open their menus below them. Set it with Stage::set_menu_bar(), which draws it
+/// above the scenes.
+pub struct MenuBar {
+    /// The position and size of the bar
+    pub frame: Rectangle,
+    /// Menus that are added to the Stage by Stage::set_menu_bar()
+    menus: Vec<(String, Menu)>,
+    entries: Vec<MenuBarEntry>,
+}
+
+impl MenuBar {
+    /// Constructor
+    pub fn new(frame: Rectangle) -> Self {
+        MenuBar { frame, menus: Vec::new(), entries: Vec::new() }
+    }
+
+    /// Builder method to add a menu with the title
+    pub fn with_menu(mut self, title: &str, menu: Menu) -> Self {
+        self.menus.push((title.to_owned(), menu));
+        self
+    }
+}
+
+// *****************************************************************************************************
+// MenuView
+// *****************************************************************************************************
+
+/// An open menu or submenu
+struct MenuPanel {
+    /// The indexes of the submenu items from the root menu. Empty for the root menu.
+    path: Vec<usize>,
+    /// The preferred top left position of the root menu. Submenus are placed beside their parent row.
+    anchor: Vector,
+    /// The position and size, which is set when the menu is drawn
+    frame: Option<Rectangle>,
+    /// The top and height of each row relative to the frame
+    rows: Vec<(f32, f32)>,
+    /// Text meshes at origin 0, 0 for the title and shortcut hint of each row
+    text: Vec<(Option<MeshTask>, Option<MeshTask>)>,
+    /// The highlighted row
+    highlight: Option<usize>,
+}
+
+impl MenuPanel {
+    fn new(path: Vec<usize>, anchor: Vector) -> Self {
+        MenuPanel { path, anchor, frame: None, rows: Vec::new(), text: Vec::new(), highlight: None }
+    }
+
+    fn contains(&self, pt: &Vector) -> bool {
+        self.frame.map_or(false, |frame| pt.overlaps_rectangle(&frame))
+    }
+
+    fn row_rect(&self, row: usize) -> Option<Rectangle> {
+        let frame = self.frame?;
+        let (top, height) = *self.rows.get(row)?;
+        Some(Rectangle::new((frame.x(), frame.y() + top), (frame.width(), height)))
+    }
+
+    fn row_at(&self, pt: &Vector) -> Option<usize> {
+        (0..self.rows.len()).find(|row| self.row_rect(*row).map_or(false, |rect| pt.overlaps_rectangle(&rect)))
+    }
+}
+
+/// Owns the menus of a Stage and displays the MenuBar and the open menu with its submenus above all scenes.
+/// The Layer is only used to run the fade in Tween, where the frame height goes from 0.0 to 1.0.
+pub(super) struct MenuView {
+    /// The menus, where the key is the menu id
+    pub(super) menus: BTreeMap<u32, Menu>,
+    /// The keyboard shortcuts of all menu items
+    pub(super) accelerators: AcceleratorRegistry,
+    next_id: u32,
+    bar: Option<MenuBar>,
+    icons: HashMap<String, ImageView>,
+    /// The id of the open menu
+    open_id: Option<u32>,
+    /// The MenuBar entry of the open menu
+    bar_index: Option<usize>,
+    /// The open menu followed by its open submenus
+    panels: Vec<MenuPanel>,
+    /// Items chosen with the mouse, keyboard or an accelerator, which run in the next update()
+    chosen: Vec<MenuItemPath>,
+    /// MenuEvents waiting to be dispatched in the next update()
+    pending_events: Vec<MenuEvent>,
+    layer: Layer,
+    font_size: f32,
+    bg_color: Color,
+    fg_color: Color,
+    select_bg_color: Color,
+    select_fg_color: Color,
+    border_color: Color,
+    bar_bg_color: Color,
+    bar_fg_color: Color,
+    shadow: Option<ShadowStyle>,
+}
+
+impl MenuView {
+    pub(super) fn new() -> Self {
+        MenuView {
+            menus: BTreeMap::new(),
+            accelerators: AcceleratorRegistry::new(),
+            next_id: 1,
+            bar: None,
+            icons: HashMap::new(),
+            open_id: None,
+            bar_index: None,
+            panels: Vec::new(),
+            chosen: Vec::new(),
+            pending_events: Vec::new(),
+            layer: Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0))),
+            font_size: 14.0,
+            bg_color: Color::WHITE,
+            fg_color: Color::BLACK,
+            select_bg_color: Color::from_hex("#4373c2"),
+            select_fg_color: Color::WHITE,
+            border_color: Color::from_hex("#AAAAAA"),
+            bar_bg_color: Color::from_hex("#F4F4F4"),
+            bar_fg_color: Color::BLACK,
+            shadow: None,
+        }
+    }
+
+    pub(super) fn set_theme(&mut self, theme: &Theme) {
+        self.font_size = theme.font_size * 0.8;
+        self.bg_color = theme.input_bg_color;
+        self.fg_color = theme.input_fg_color;
+        self.select_bg_color = theme.button_bg_color;
+        self.select_fg_color = theme.button_fg_color;
+        self.border_color = theme.border_color;
+        self.bar_bg_color = theme.stripe_bg_color;
+        self.bar_fg_color = theme.fg_color;
+        self.shadow = theme.popup_shadow;
+        // Discard the text and layout that used the previous theme
+        for panel in &mut self.panels {
+            panel.frame = None;
+        }
+        if let Some(bar) = &mut self.bar {
+            for entry in &mut bar.entries {
+                entry.text = None;
+            }
+        }
+    }
+
+    /// Add the menu and register its accelerators. Returns the menu id.
+    pub(super) fn add_menu(&mut self, menu: Menu) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        MenuView::register_accelerators(&mut self.accelerators, id, &menu, &mut Vec::new());
+        self.menus.insert(id, menu);
+        id
+    }
+
+    fn register_accelerators(registry: &mut AcceleratorRegistry, menu_id: u32, menu: &Menu, path: &mut Vec<usize>) {
+        for (index, item) in menu.items.iter().enumerate() {
+            path.push(index);
+            if let Some(accelerator) = item.accelerator {
+                registry.register(accelerator, MenuItemPath { menu_id, indices: path.clone() });
+            }
+            if let Some(submenu) = &item.submenu {
+                MenuView::register_accelerators(registry, menu_id, submenu, path);
+            }
+            path.pop();
+        }
+    }
+
+    pub(super) fn set_bar(&mut self, mut bar: MenuBar) {
+        self.close();
+        let menus: Vec<(String, Menu)> = bar.menus.drain(..).collect();
+        for (title, menu) in menus {
+            let menu_id = self.add_menu(menu);
+            bar.entries.push(MenuBarEntry { title, menu_id, rect: Rectangle::new_sized((0.0, 0.0)), text: None });
+        }
+        self.bar = Some(bar);
+    }
+
+    pub(super) fn add_icon(&mut self, name: &str, asset: Asset<Vec<u8>>) {
+        let frame = Rectangle::new_sized((MENU_ICON_SIZE, MENU_ICON_SIZE));
+        let view = ImageView::new(frame, Some(asset)).with_scale_mode(ImageScaleMode::AspectFit);
+        self.icons.insert(name.to_owned(), view);
+    }
+
+    pub(super) fn is_open(&self) -> bool {
+        self.open_id.is_some()
+    }
+
+    fn open_menu(&self) -> Option<&Menu> {
+        self.open_id.and_then(|id| self.menus.get(&id))
+    }
+
+    /// Open the menu with its top left corner at the anchor, or the nearest position inside the window.
+    pub(super) fn open(&mut self, menu_id: u32, anchor: Vector, bar_index: Option<usize>) -> bool {
+        if !self.menus.contains_key(&menu_id) {
+            return false;
+        }
+        match self.open_id {
+            Some(id) => self.pending_events.push(MenuEvent::Closed(id)),
+            None => {
+                // Only fade in when no menu was open, so that moving across the MenuBar does not flicker
+                self.layer.frame.size.y = 0.0;
+                let propset = PropSet::new(vec![size(1.0, 1.0)], MENU_DURATION).ease(Ease::SineOut);
+                self.layer.animate_with_props(propset, true);
+            }
+        }
+        self.open_id = Some(menu_id);
+        self.bar_index = bar_index;
+        self.panels = vec![MenuPanel::new(Vec::new(), anchor)];
+        self.pending_events.push(MenuEvent::Opened(menu_id));
+        true
+    }
+
+    /// Close the open menu and its submenus
+    pub(super) fn close(&mut self) {
+        if let Some(id) = self.open_id.take() {
+            self.pending_events.push(MenuEvent::Closed(id));
+        }
+        self.bar_index = None;
+        self.panels.clear();
+        self.layer.animation = None;
+        self.layer.frame.size.y = 0.0;
+    }
+
+    fn open_bar_menu(&mut self, index: usize) {
+        let (menu_id, rect) = match self.bar.as_ref().and_then(|bar| bar.entries.get(index)) {
+            Some(entry) => (entry.menu_id, entry.rect),
+            None => return,
+        };
+        if self.bar_index != Some(index) {
+            self.open(menu_id, Vector::new(rect.x(), rect.y() + rect.height()), Some(index));
+        }
+    }
+
+    /// Open the next or previous menu of the MenuBar with the first item highlighted
+    fn step_bar_menu(&mut self, forward: bool) {
+        let count = self.bar.as_ref().map_or(0, |bar| bar.entries.len());
+        let index = match self.bar_index {
+            Some(index) if count > 1 => index,
+            _ => return,
+        };
+        self.open_bar_menu(if forward { (index + 1) % count } else { (index + count - 1) % count });
+        let first = self.open_menu().and_then(|menu| menu.next_selectable(None, true));
+        if let Some(panel) = self.panels.first_mut() {
+            panel.highlight = first;
+        }
+    }
+
+    fn bar_entry_at(&self, pt: &Vector) -> Option<usize> {
+        let bar = self.bar.as_ref()?;
+        bar.entries.iter().position(|entry| pt.overlaps_rectangle(&entry.rect))
+    }
+
+    fn bar_contains(&self, pt: &Vector) -> bool {
+        self.bar.as_ref().map_or(false, |bar| pt.overlaps_rectangle(&bar.frame))
+    }
+
+    fn item_at(&self, index: usize, row: usize) -> Option<&MenuItem> {
+        let mut path = self.panels.get(index)?.path.clone();
+        path.push(row);
+        self.open_menu()?.item_at(&path)
+    }
+
+    /// Replace the submenus after the panel with the submenu of the row, if it has one
+    fn open_submenu(&mut self, index: usize, row: usize, highlight_first: bool) -> bool {
+        self.panels.truncate(index + 1);
+        let first = match self.item_at(index, row) {
+            Some(item) if item.is_selectable() => match &item.submenu {
+                Some(menu) => menu.next_selectable(None, true),
+                None => return false,
+            },
+            _ => return false,
+        };
+        let mut path = self.panels[index].path.clone();
+        path.push(row);
+        let mut panel = MenuPanel::new(path, Vector::ZERO);
+        if highlight_first {
+            panel.highlight = first;
+        }
+        self.panels.push(panel);
+        true
+    }
+
+    /// Choose the item in the row, or open its submenu
+    fn choose(&mut self, index: usize, row: usize) {
+        let menu_id = match self.open_id {
+            Some(id) => id,
+            None => return,
+        };
+        let has_submenu = match self.item_at(index, row) {
+            Some(item) if item.is_selectable() => item.submenu.is_some(),
+            _ => return,
+        };
+        if has_submenu {
+            self.open_submenu(index, row, true);
+            return;
+        }
+        let mut indices = self.panels[index].path.clone();
+        indices.push(row);
+        self.chosen.push(MenuItemPath { menu_id, indices });
+        self.close();
+    }
+
+    fn move_highlight(&mut self, forward: bool) {
+        let index = match self.panels.len() {
+            0 => return,
+            len => len - 1,
+        };
+        let panel = &self.panels[index];
+        let next = self
+            .open_menu()
+            .and_then(|menu| menu.submenu_at(&panel.path))
+            .and_then(|menu| menu.next_selectable(panel.highlight, forward));
+        self.panels[index].highlight = next;
+    }
+
+    /// Highlight the row under the mouse and open its submenu. Moving across the MenuBar while a menu from the
+    /// bar is open switches to the menu under the mouse. Returns true if the mouse is over the menus or bar.
+    pub(super) fn handle_mouse_at(&mut self, pt: &Vector) -> bool {
+        if self.bar_contains(pt) {
+            if let (Some(index), Some(_)) = (self.bar_entry_at(pt), self.bar_index) {
+                self.open_bar_menu(index);
+            }
+            return true;
+        }
+        if self.open_id.is_none() {
+            return false;
+        }
+        if let Some(index) = self.panels.iter().rposition(|panel| panel.contains(pt)) {
+            let row = self.panels[index]
+                .row_at(pt)
+                .filter(|row| self.item_at(index, *row).map_or(false, |item| item.is_selectable()));
+            if row != self.panels[index].highlight {
+                self.panels[index].highlight = row;
+                self.panels.truncate(index + 1);
+                if let Some(row) = row {
+                    self.open_submenu(index, row, false);
+                }
+            }
+        }
+        true
+    }
+
+    /// Choose the item under the mouse or open and close menus from the MenuBar. A click outside of the menus
+    /// closes them. Returns true if the click was handled.
+    pub(super) fn handle_mouse_down(&mut self, pt: &Vector) -> bool {
+        if self.bar_contains(pt) {
+            match self.bar_entry_at(pt) {
+                Some(index) if self.bar_index != Some(index) => self.open_bar_menu(index),
+                _ => self.close(),
+            }
+            return true;
+        }
+        if self.open_id.is_none() {
+            return false;
+        }
+        match self.panels.iter().rposition(|panel| panel.contains(pt)) {
+            Some(index) => {
+                if let Some(row) = self.panels[index].row_at(pt) {
+                    self.choose(index, row);
+                }
+            }
+            None => self.close(),
+        }
+        true
+    }
+
+    /// While a menu is open, the arrow keys move through the items and menus, Return chooses and Escape closes
+    /// the current menu. Otherwise the key is checked against the accelerators. Returns true if handled.
+    pub(super) fn handle_key(&mut self, key: &Key, modifiers: &KeyModifiers) -> bool {
+        if self.open_id.is_none() {
+            let path = match self.accelerators.find(key, modifiers) {
+                Some(path) => path.clone(),
+                None => return false,
+            };
+            self.chosen.push(path);
+            return true;
+        }
+        let index = self.panels.len() - 1;
+        let highlight = self.panels[index].highlight;
+        match key {
+            Key::Up => self.move_highlight(false),
+            Key::Down => self.move_highlight(true),
+            Key::Right => {
+                let opened = match highlight {
+                    Some(row) => self.open_submenu(index, row, true),
+                    None => false,
+                };
+                if !opened {
+                    self.step_bar_menu(true);
+                }
+            }
+            Key::Left if index > 0 => self.panels.truncate(index),
+            Key::Left => self.step_bar_menu(false),
+            Key::Return => {
+                if let Some(row) = highlight {
+                    self.choose(index, row);
+                }
+            }
+            Key::Escape if index > 0 => self.panels.truncate(index),
+            Key::Escape => self.close(),
+            _ => (),
+        }
+        true
+    }
+
+    /// Run the commands of the chosen items and dispatch the MenuEvents
+    pub(super) fn update(&mut self, state: &mut AppState, sender: NodeID) {
+        self.layer.tween_update(state);
+        for path in self.chosen.drain(..) {
+            let item = match self.menus.get_mut(&path.menu_id).and_then(|menu| menu.item_at_mut(&path.indices)) {
+                Some(item) if item.is_selectable() && item.submenu.is_none() => item,
+                _ => continue,
+            };
+            if let Some(checked) = item.checked {
+                item.checked = Some(!checked);
+            }
+            if let Some(command) = &mut item.command {
+                if let Some(action) = &mut command.action {
+                    (&mut *action)(state);
+                }
+            }
+            self.pending_events.push(MenuEvent::Selected(item.id));
+        }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, sender, None);
+        }
+    }
+
+    fn draw_text(theme: &mut Theme, text: &str, font_size: f32, color: Color, height: f32) -> Option<MeshTask> {
+        let rect = Rectangle::new((0.0, 0.0), (1000.0, height));
+        let style = FontStyle::new(font_size, color);
+        let params =
+            TextParams::new(style).frame(rect).text(text).align(TextAlign::Left, VertAlign::Middle).multiline(false);
+        theme.default_font.draw(params)
+    }
+
+    /// Copy the text mesh to the position with the color
+    fn place_text(task: &MeshTask, pos: Vector, color: Color) -> MeshTask {
+        let mut task = task.clone();
+        for vertex in &mut task.vertices.iter_mut() {
+            vertex.pos = Transform::translate(pos) * vertex.pos;
+            vertex.col = color;
+        }
+        task
+    }
+
+    /// Create the text meshes and the frame of the panel that fits inside the window
+    fn layout(&mut self, index: usize, theme: &mut Theme, bounds: &Rectangle) {
+        let menu = match self.open_menu().and_then(|menu| menu.submenu_at(&self.panels[index].path)) {
+            Some(menu) => menu,
+            None => return,
+        };
+        let mut rows: Vec<(f32, f32)> = Vec::new();
+        let mut text: Vec<(Option<MeshTask>, Option<MeshTask>)> = Vec::new();
+        let mut top = MENU_PADDING;
+        let mut width = MENU_MIN_WIDTH;
+        for item in &menu.items {
+            if item.is_separator {
+                rows.push((top, MENU_SEPARATOR_HEIGHT));
+                text.push((None, None));
+                top += MENU_SEPARATOR_HEIGHT;
+                continue;
+            }
+            let title = MenuView::draw_text(theme, &item.title, self.font_size, self.fg_color, MENU_ROW_HEIGHT);
+            let hint = item.accelerator.and_then(|accelerator| {
+                MenuView::draw_text(theme, &accelerator.label(), self.font_size, self.fg_color, MENU_ROW_HEIGHT)
+            });
+            let mut row_width = MENU_GUTTER * 2.0;
+            if let Some(task) = &title {
+                row_width += task.content_size.0;
+            }
+            if let Some(task) = &hint {
+                row_width += MENU_SHORTCUT_SPACING + task.content_size.0;
+            }
+            width = width.max(row_width);
+            rows.push((top, MENU_ROW_HEIGHT));
+            text.push((title, hint));
+            top += MENU_ROW_HEIGHT;
+        }
+        let size = Vector::new(width, top + MENU_PADDING);
+        let right = bounds.x() + bounds.width() - WINDOW_MARGIN;
+        let bottom = bounds.y() + bounds.height() - WINDOW_MARGIN;
+
+        let parent_row = match (index, self.panels[index].path.last()) {
+            (0, _) | (_, None) => None,
+            (_, Some(row)) => self.panels[index - 1].row_rect(*row),
+        };
+        let mut pos = self.panels[index].anchor;
+        match parent_row {
+            // Submenus open to the right of the parent row, or to the left if there is no room
+            Some(row) => {
+                pos = Vector::new(row.x() + row.width(), row.y() - MENU_PADDING);
+                if pos.x + size.x > right {
+                    pos.x = row.x() - size.x;
+                }
+            }
+            // Menus open below the anchor, or above it if there is no room
+            None => {
+                if pos.y + size.y > bottom {
+                    pos.y -= size.y;
+                }
+                if pos.x + size.x > right {
+                    pos.x = right - size.x;
+                }
+            }
+        }
+        pos.x = pos.x.max(bounds.x() + WINDOW_MARGIN);
+        pos.y = pos.y.min(bottom - size.y).max(bounds.y() + WINDOW_MARGIN);
+
+        let panel = &mut self.panels[index];
+        panel.frame = Some(Rectangle::new(pos, size));
+        panel.rows = rows;
+        panel.text = text;
+    }
+
+    /// Draw the MenuBar, which is below any modals
+    pub(super) fn render_bar(&mut self, theme: &mut Theme, window: &mut Window) {
+        let bar = match &mut self.bar {
+            Some(bar) => bar,
+            None => return,
+        };
+        let frame = bar.frame;
+        let mut graphics = MeshTask::new(0);
+        let mut content: Vec<MeshTask> = Vec::new();
+        let mut mesh = DrawShape::rectangle(&frame, Some(self.bar_bg_color), None, 0.0, 0.0);
+        graphics.append(&mut mesh);
+        let bottom = frame.y() + frame.height();
+        let pts = [&Vector::new(frame.x(), bottom), &Vector::new(frame.x() + frame.width(), bottom)];
+        let mut line = DrawShape::line(&pts, self.border_color, 1.0);
+        graphics.append(&mut line);
+
+        let mut x = frame.x();
+        for (index, entry) in bar.entries.iter_mut().enumerate() {
+            if entry.text.is_none() {
+                entry.text =
+                    MenuView::draw_text(theme, &entry.title, self.font_size, self.bar_fg_color, frame.height());
+            }
+            let width = entry.text.as_ref().map_or(0.0, |task| task.content_size.0) + MENU_BAR_PADDING * 2.0;
+            entry.rect = Rectangle::new((x, frame.y()), (width, frame.height()));
+            let mut color = self.bar_fg_color;
+            if self.bar_index == Some(index) {
+                let mut mesh = DrawShape::rectangle(&entry.rect, Some(self.select_bg_color), None, 0.0, 0.0);
+                graphics.append(&mut mesh);
+                color = self.select_fg_color;
+            }
+            if let Some(task) = &entry.text {
+                content.push(MenuView::place_text(task, Vector::new(x + MENU_BAR_PADDING, frame.y()), color));
+            }
+            x += width;
+        }
+        window.add_task(graphics);
+        for task in content {
+            window.add_task(task);
+        }
+    }
+
+    /// Draw the open menu and submenus with the alpha of the fade animation
+    pub(super) fn render(&mut self, theme: &mut Theme, window: &mut Window, bounds: &Rectangle) {
+        let alpha = self.layer.frame.height().max(0.0).min(1.0);
+        for index in 0..self.panels.len() {
+            if self.panels[index].frame.is_none() {
+                self.layout(index, theme, bounds);
+            }
+            self.render_panel(index, alpha, window);
+        }
+    }
+
+    fn render_panel(&mut self, index: usize, alpha: f32, window: &mut Window) {
+        let with_alpha = |color: Color| Color { a: color.a * alpha, ..color };
+        let menus = &self.menus;
+        let icons = &mut self.icons;
+        let panel = &self.panels[index];
+        let menu = match self.open_id.and_then(|id| menus.get(&id)).and_then(|menu| menu.submenu_at(&panel.path)) {
+            Some(menu) => menu,
+            None => return,
+        };
+        let frame = match panel.frame {
+            Some(frame) => frame,
+            None => return,
+        };
+
+        // Shapes are drawn first and the icons and text are drawn on top
+        let mut graphics = MeshTask::new(0);
+        let mut content: Vec<MeshTask> = Vec::new();
+        if let Some(shadow) = self.shadow {
+            let shadow = ShadowStyle { color: with_alpha(shadow.color), ..shadow };
+            graphics.append(&mut shadow.draw(&frame, [3.0; 4]));
+        }
+        let mut mesh = DrawShape::rectangle(
+            &frame,
+            Some(with_alpha(self.bg_color)),
+            Some(with_alpha(self.border_color)),
+            1.0,
+            3.0,
+        );
+        graphics.append(&mut mesh);
+        for (row, item) in menu.items.iter().enumerate() {
+            let rect = match panel.row_rect(row) {
+                Some(rect) => rect,
+                None => continue,
+            };
+            let center_y = rect.y() + rect.height() / 2.0;
+            if item.is_separator {
+                let x1 = rect.x() + MENU_GUTTER / 2.0;
+                let x2 = rect.x() + rect.width() - MENU_GUTTER / 2.0;
+                let pts = [&Vector::new(x1, center_y), &Vector::new(x2, center_y)];
+                let mut line = DrawShape::line(&pts, with_alpha(self.border_color), 1.0);
+                graphics.append(&mut line);
+                continue;
+            }
+            let selected = panel.highlight == Some(row);
+            let fg_color = match (selected, item.enabled) {
+                (true, _) => with_alpha(self.select_fg_color),
+                (false, true) => with_alpha(self.fg_color),
+                (false, false) => with_alpha(Color { a: self.fg_color.a * 0.4, ..self.fg_color }),
+            };
+            if selected {
+                let mut mesh = DrawShape::rectangle(&rect, Some(with_alpha(self.select_bg_color)), None, 0.0, 0.0);
+                graphics.append(&mut mesh);
+            }
+
+            // The checkmark and icon share the left gutter
+            if item.checked == Some(true) {
+                let x = rect.x() + MENU_GUTTER / 2.0;
+                let pt1 = Vector::new(x - 5.0, center_y);
+                let pt2 = Vector::new(x - 1.5, center_y + 4.0);
+                let pt3 = Vector::new(x + 5.0, center_y - 4.5);
+                for pts in &[[&pt1, &pt2], [&pt2, &pt3]] {
+                    let mut line = DrawShape::line(pts, fg_color, 1.5);
+                    graphics.append(&mut line);
+                }
+            } else if let Some(view) = item.icon.as_ref().and_then(|name| icons.get_mut(name)) {
+                if let Some(mut mesh) = view.content_mesh() {
+                    // The icon mesh was created at origin 0, 0
+                    let pos =
+                        Vector::new(rect.x() + (MENU_GUTTER - MENU_ICON_SIZE) / 2.0, center_y - MENU_ICON_SIZE / 2.0);
+                    for vertex in &mut mesh.vertices.iter_mut() {
+                        vertex.pos = Transform::translate(pos) * vertex.pos;
+                        vertex.col.a *= alpha;
+                    }
+                    content.push(mesh);
+                }
+            }
+
+            if let Some((title, hint)) = panel.text.get(row) {
+                if let Some(task) = title {
+                    content.push(MenuView::place_text(task, Vector::new(rect.x() + MENU_GUTTER, rect.y()), fg_color));
+                }
+                if let Some(task) = hint {
+                    let x = rect.x() + rect.width() - MENU_GUTTER - task.content_size.0;
+                    content.push(MenuView::place_text(task, Vector::new(x, rect.y()), fg_color));
+                }
+            }
+            if item.submenu.is_some() {
+                let x = rect.x() + rect.width() - MENU_GUTTER / 2.0;
+                let pt1 = Vector::new(x - 2.0, center_y - 4.0);
+                let pt2 = Vector::new(x + 2.0, center_y);
+                let pt3 = Vector::new(x - 2.0, center_y + 4.0);
+                for pts in &[[&pt1, &pt2], [&pt2, &pt3]] {
+                    let mut line = DrawShape::line(pts, fg_color, 1.5);
+                    graphics.append(&mut line);
+                }
+            }
+        }
+        window.add_task(graphics);
+        for task in content {
+            window.add_task(task);
+        }
+    }
+}
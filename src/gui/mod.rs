@@ -5,39 +5,73 @@
 //!
 //! rules
 
+pub use self::animated_image::*;
 pub use self::base::*;
 pub use self::button::*;
 pub use self::checkbox::*;
+pub use self::color_picker::*;
 pub use self::cursor::*;
+pub use self::date_picker::*;
 pub use self::image::*;
 pub use self::label::*;
 pub use self::layer::*;
 pub use self::list_box::*;
+pub use self::menu::*;
+pub use self::modal::*;
+pub use self::nav_controller::*;
 pub use self::option_group::*;
+pub use self::progress::*;
 pub use self::scene::*;
+pub use self::segmented_control::*;
 pub use self::shape::*;
+pub use self::split_view::*;
 pub use self::stage::*;
+pub use self::svg_view::*;
+pub use self::switch::*;
+pub use self::tab_view::*;
+pub use self::table::*;
 pub use self::text::*;
 pub use self::text_area::*;
 pub use self::text_field::*;
 pub use self::theme::*;
+pub use self::time_picker::*;
+pub use self::toast::*;
+pub use self::tooltip::*;
+pub use self::tree_view::*;
 
+mod animated_image;
 mod base;
 mod button;
 mod checkbox;
+mod color_picker;
 mod cursor;
+mod date_picker;
 mod image;
 mod label;
 mod layer;
 mod list_box;
+mod menu;
+mod modal;
+mod nav_controller;
 mod option_group;
+mod progress;
 mod scene;
+mod segmented_control;
 mod shape;
+mod split_view;
 mod stage;
+mod svg_view;
+mod switch;
+mod tab_view;
+mod table;
 mod text;
 mod text_area;
 mod text_field;
 mod theme;
+mod time_picker;
+mod toast;
+mod tooltip;
+mod tree_view;
 
 use crate::core::Timeline;
 use std::any::TypeId;
@@ -75,20 +109,34 @@ lazy_static! {
     #[allow(missing_docs)]
     pub static ref GUI_TYPES_MAP: HashMap<TypeId, &'static str> = {
         let mut map = HashMap::new();
+        map.insert(TypeId::of::<ActivityIndicator>(), "ActivityIndicator");
         map.insert(TypeId::of::<Button>(), "Button");
         map.insert(TypeId::of::<Checkbox>(), "Checkbox");
+        map.insert(TypeId::of::<ColorPicker>(), "ColorPicker");
         map.insert(TypeId::of::<Cursor>(), "Cursor");
+        map.insert(TypeId::of::<DatePicker>(), "DatePicker");
         map.insert(TypeId::of::<ImageView>(), "Image");
         map.insert(TypeId::of::<Label>(), "Label");
         map.insert(TypeId::of::<ListBox>(), "ListBox");
+        map.insert(TypeId::of::<NavController>(), "NavController");
         map.insert(TypeId::of::<OptionGroup>(), "OptionGroup");
+        map.insert(TypeId::of::<ProgressBar>(), "ProgressBar");
         map.insert(TypeId::of::<Scene>(), "Scene");
+        map.insert(TypeId::of::<SegmentedControl>(), "SegmentedControl");
+        map.insert(TypeId::of::<SplitView>(), "SplitView");
         map.insert(TypeId::of::<Stage>(), "Stage");
         map.insert(TypeId::of::<ShapeView>(), "Shape");
+        map.insert(TypeId::of::<SvgView>(), "Svg");
+        map.insert(TypeId::of::<Switch>(), "Switch");
+        map.insert(TypeId::of::<TabBar>(), "TabBar");
+        map.insert(TypeId::of::<TabView>(), "TabView");
+        map.insert(TypeId::of::<Table>(), "Table");
         map.insert(TypeId::of::<TextArea>(), "TextArea");
         map.insert(TypeId::of::<TextField>(), "TextField");
         map.insert(TypeId::of::<Text>(), "Text");
+        map.insert(TypeId::of::<TimePicker>(), "TimePicker");
         map.insert(TypeId::of::<Timeline>(), "Timeline");
+        map.insert(TypeId::of::<TreeView>(), "TreeView");
         map
     };
 }
@@ -97,20 +145,34 @@ lazy_static! {
     #[allow(missing_docs)]
     pub static ref GUI_NAMES_MAP: HashMap<&'static str, TypeId> = {
         let mut map = HashMap::new();
+        map.insert("ActivityIndicator", TypeId::of::<ActivityIndicator>());
         map.insert("Button", TypeId::of::<Button>());
         map.insert("Checkbox", TypeId::of::<Checkbox>());
+        map.insert("ColorPicker", TypeId::of::<ColorPicker>());
         map.insert("Cursor", TypeId::of::<Cursor>());
+        map.insert("DatePicker", TypeId::of::<DatePicker>());
         map.insert("Image", TypeId::of::<ImageView>());
         map.insert("Label", TypeId::of::<Label>());
         map.insert("ListBox", TypeId::of::<ListBox>());
+        map.insert("NavController", TypeId::of::<NavController>());
         map.insert("OptionGroup", TypeId::of::<OptionGroup>());
+        map.insert("ProgressBar", TypeId::of::<ProgressBar>());
         map.insert("Scene", TypeId::of::<Scene>());
+        map.insert("SegmentedControl", TypeId::of::<SegmentedControl>());
+        map.insert("SplitView", TypeId::of::<SplitView>());
         map.insert("Stage", TypeId::of::<Stage>());
         map.insert("Shape", TypeId::of::<ShapeView>());
+        map.insert("Svg", TypeId::of::<SvgView>());
+        map.insert("Switch", TypeId::of::<Switch>());
+        map.insert("TabBar", TypeId::of::<TabBar>());
+        map.insert("TabView", TypeId::of::<TabView>());
+        map.insert("Table", TypeId::of::<Table>());
         map.insert("TextArea", TypeId::of::<TextArea>());
         map.insert("TextField", TypeId::of::<TextField>());
         map.insert("Text", TypeId::of::<Text>());
+        map.insert("TimePicker", TypeId::of::<TimePicker>());
         map.insert("Timeline", TypeId::of::<Timeline>());
+        map.insert("TreeView", TypeId::of::<TreeView>());
         map
     };
 }
@@ -121,12 +183,24 @@ lazy_static! {
         let mut array = Vec::new();
         array.push(TypeId::of::<Button>());
         array.push(TypeId::of::<Checkbox>());
+        array.push(TypeId::of::<ColorPicker>());
+        array.push(TypeId::of::<DatePicker>());
         array.push(TypeId::of::<ListBox>());
+        array.push(TypeId::of::<NavController>());
         array.push(TypeId::of::<OptionGroup>());
+        array.push(TypeId::of::<ProgressBar>());
         array.push(TypeId::of::<Scene>());
+        array.push(TypeId::of::<SegmentedControl>());
+        array.push(TypeId::of::<SplitView>());
+        array.push(TypeId::of::<Switch>());
+        array.push(TypeId::of::<TabBar>());
+        array.push(TypeId::of::<TabView>());
+        array.push(TypeId::of::<Table>());
         array.push(TypeId::of::<TextArea>());
         array.push(TypeId::of::<TextField>());
         array.push(TypeId::of::<Text>());
+        array.push(TypeId::of::<TimePicker>());
+        array.push(TypeId::of::<TreeView>());
         array
     };
 }
@@ -136,9 +210,14 @@ lazy_static! {
     pub static ref GUI_INPUTS: Vec<TypeId> = {
         let mut array = Vec::new();
         array.push(TypeId::of::<Checkbox>());
+        array.push(TypeId::of::<ColorPicker>());
+        array.push(TypeId::of::<DatePicker>());
         array.push(TypeId::of::<ListBox>());
+        array.push(TypeId::of::<Table>());
         array.push(TypeId::of::<TextArea>());
         array.push(TypeId::of::<TextField>());
+        array.push(TypeId::of::<TimePicker>());
+        array.push(TypeId::of::<TreeView>());
         array.push(TypeId::of::<OptionGroup>());
         array
     };
@@ -0,0 +1,219 @@
+/// Modal scenes and dialogs presented by a Stage
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Vector},
+    graphics::Color,
+};
+
+use super::*;
+
+/// The duration in seconds of the present and dismiss animation
+const MODAL_DURATION: f64 = 0.25;
+/// The distance that a modal Scene slides up while it is presented
+const MODAL_OFFSET: f32 = 40.0;
+/// The padding inside a Dialog
+const DIALOG_PADDING: f32 = 16.0;
+/// The size of each Dialog button
+const DIALOG_BUTTON_SIZE: (f32, f32) = (90.0, 32.0);
+
+//-- Modal -----------------------------------------------------------------------
+
+/// A Scene that a Stage presents above its other scenes. The Layer is only used to run the Tween, where the
+/// frame height goes from 0.0 when hidden to 1.0 when presented.
+pub(super) struct Modal {
+    pub(super) scene: Scene,
+    /// Can Escape, NavEvent::Close or a click on the backdrop dismiss the modal?
+    pub(super) dismissable: bool,
+    /// The button that Return chooses in a Dialog
+    pub(super) default_choice: Option<usize>,
+    /// The button that is chosen when a Dialog is dismissed
+    pub(super) cancel_choice: Option<usize>,
+    /// Callback for the button that was chosen in a Dialog
+    pub(super) on_choice: Option<Box<dyn FnMut(usize, &mut AppState) + 'static>>,
+    /// Is the Scene built by a Dialog and styled by the theme?
+    pub(super) is_dialog: bool,
+    /// True while the dismiss animation runs
+    pub(super) closing: bool,
+    /// The position of the Scene when it is presented
+    home: Vector,
+    layer: Layer,
+}
+
+impl Modal {
+    pub(super) fn new(scene: Scene, dismissable: bool) -> Self {
+        let home = scene.get_frame().pos;
+        Modal {
+            scene,
+            dismissable,
+            default_choice: None,
+            cancel_choice: None,
+            on_choice: None,
+            is_dialog: false,
+            closing: false,
+            home,
+            layer: Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0))),
+        }
+    }
+
+    /// Start the present animation
+    pub(super) fn present(&mut self) {
+        self.closing = false;
+        self.animate(1.0);
+    }
+
+    /// Start the dismiss animation
+    pub(super) fn dismiss(&mut self) {
+        self.closing = true;
+        self.animate(0.0);
+    }
+
+    fn animate(&mut self, to: f32) {
+        let propset = PropSet::new(vec![size(1.0, to)], MODAL_DURATION).ease(Ease::SineOut);
+        self.layer.animate_with_props(propset, true);
+    }
+
+    /// The amount that the modal is presented, from 0.0 to 1.0
+    pub(super) fn progress(&self) -> f32 {
+        self.layer.frame.height().max(0.0).min(1.0)
+    }
+
+    pub(super) fn is_finished(&self) -> bool {
+        match &self.layer.animation {
+            Some(tween) => tween.state == PlayState::Completed,
+            None => true,
+        }
+    }
+
+    /// Run the Tween and slide the Scene into position
+    pub(super) fn tween_update(&mut self, state: &mut AppState) {
+        if self.is_finished() {
+            return;
+        }
+        self.layer.tween_update(state);
+        let offset = Vector::new(0.0, MODAL_OFFSET * (1.0 - self.progress()));
+        self.scene.align_view(self.home + offset);
+        // Clear the meshes that were cached at the old position
+        self.scene.notify(&DisplayEvent::Moved);
+    }
+
+    /// The backdrop color for the current progress
+    pub(super) fn backdrop_color(&self, color: Color) -> Color {
+        let mut color = color;
+        color.a *= self.progress();
+        color
+    }
+}
+
+// *****************************************************************************************************
+// Dialog
+// *****************************************************************************************************
+
+/// A modal alert or confirmation with a title, a message and a row of buttons. Add it to a Stage with
+/// Stage::add_dialog() and present it with Stage::present_modal() or NavEvent::Modal(id).
+///
+/// The chosen button index is passed to the on_choice callback and dispatched as ModalEvent::Choice. Return
+/// chooses the default button. If there is a cancel button, Escape and a click on the backdrop choose it.
+///
+pub struct Dialog {
+    /// The title text
+    pub title: String,
+    /// The message text, which can be multiple lines
+    pub message: String,
+    /// The button titles from left to right
+    pub buttons: Vec<String>,
+    /// The index of the button chosen by Return
+    pub default_button: usize,
+    /// The index of the button chosen when the dialog is dismissed, or None if it can't be dismissed
+    pub cancel_button: Option<usize>,
+    /// The width of the dialog
+    pub width: f32,
+    /// The height of the message area
+    pub message_height: f32,
+    on_choice: Option<Box<dyn FnMut(usize, &mut AppState) + 'static>>,
+}
+
+impl Dialog {
+    /// An alert with an OK button
+    pub fn alert(title: &str, message: &str) -> Self {
+        Dialog {
+            title: title.to_owned(),
+            message: message.to_owned(),
+            buttons: vec!["OK".to_owned()],
+            default_button: 0,
+            cancel_button: Some(0),
+            width: 360.0,
+            message_height: 60.0,
+            on_choice: None,
+        }
+    }
+
+    /// A confirmation with Cancel and OK buttons, where OK is the default at index 1
+    pub fn confirm(title: &str, message: &str) -> Self {
+        Dialog::alert(title, message).with_buttons(&["Cancel", "OK"], 1, Some(0))
+    }
+
+    /// Builder method to set the button titles, the default button and the cancel button
+    pub fn with_buttons(mut self, titles: &[&str], default_button: usize, cancel_button: Option<usize>) -> Self {
+        self.buttons = titles.iter().map(|title| title.to_string()).collect();
+        self.default_button = default_button;
+        self.cancel_button = cancel_button;
+        self
+    }
+
+    /// Builder method to set the callback for the button that was chosen
+    pub fn on_choice<C>(mut self, cb: C) -> Self
+    where
+        C: FnMut(usize, &mut AppState) + 'static,
+    {
+        self.on_choice = Some(Box::new(cb));
+        self
+    }
+
+    /// Build the modal with a Scene centered in the bounds. The buttons dispatch ModalEvent::Choice with
+    /// the modal id.
+    pub(super) fn build(self, modal_id: u32, bounds: &Rectangle) -> Modal {
+        let (button_w, button_h) = DIALOG_BUTTON_SIZE;
+        let height = DIALOG_PADDING * 4.0 + 24.0 + self.message_height + button_h;
+        let pos = bounds.pos + (bounds.size - Vector::new(self.width, height)) * 0.5;
+        let mut scene = Scene::new(Rectangle::new(pos, (self.width, height)));
+        scene.name = "Dialog".to_string();
+
+        let content_w = self.width - DIALOG_PADDING * 2.0;
+        let frame = scene.sub_frame((DIALOG_PADDING, DIALOG_PADDING), (content_w, 24.0));
+        let title = Text::new(frame, &self.title);
+        scene.add_control(Box::new(title));
+
+        let ypos = DIALOG_PADDING * 2.0 + 24.0;
+        let frame = scene.sub_frame((DIALOG_PADDING, ypos), (content_w, self.message_height));
+        let mut message = Text::new(frame, &self.message);
+        message.multiline = true;
+        message.vert_align = VertAlign::Top;
+        scene.add_control(Box::new(message));
+
+        // The buttons are aligned to the right
+        let ypos = height - DIALOG_PADDING - button_h;
+        let mut xpos = self.width - DIALOG_PADDING - (button_w + DIALOG_PADDING / 2.0) * self.buttons.len() as f32;
+        for (index, title) in self.buttons.iter().enumerate() {
+            xpos += DIALOG_PADDING / 2.0;
+            let frame = scene.sub_frame((xpos, ypos), (button_w, button_h));
+            let mut button = Button::new(frame).with_text(title);
+            button.layer.corner_radius = 3.0;
+            button.set_onclick(move |state| {
+                state.event_bus.dispatch_event(ModalEvent::Choice(modal_id, index), NodeID::default(), None);
+            });
+            scene.add_control(Box::new(button));
+            xpos += button_w;
+        }
+
+        let mut modal = Modal::new(scene, self.cancel_button.is_some());
+        modal.default_choice = Some(self.default_button);
+        modal.cancel_choice = self.cancel_button;
+        modal.on_choice = self.on_choice;
+        modal.is_dialog = true;
+        modal
+    }
+}
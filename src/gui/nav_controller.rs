@@ -0,0 +1,631 @@
+/// NavController
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Vector},
+    graphics::{Color, MeshTask},
+    input::Key,
+    lifecycle::Window,
+};
+
+use std::any::TypeId;
+
+use super::*;
+
+/// The default duration in seconds of a transition
+const TRANSITION_DURATION: f64 = 0.35;
+
+//-- Transitions -----------------------------------------------------------------------
+
+/// The animation used when the top Scene of a NavController changes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NavTransition {
+    /// Switch immediately
+    None,
+    /// The new Scene pushes the old Scene out horizontally
+    Slide,
+    /// The old Scene fades out and the new Scene fades in
+    Fade,
+    /// The new Scene slides up over the old Scene. When popping, the top Scene slides down.
+    Cover,
+    /// A panel grows from the center over the old Scene and the new Scene fades in
+    Zoom,
+}
+
+/// A change to the stack that is applied in the next update()
+enum NavAction {
+    Push(Box<Scene>, NavTransition),
+    Pop(NavTransition),
+    PopToRoot(NavTransition),
+    Replace(Box<Scene>, NavTransition),
+}
+
+/// A transition in progress. The Layer is only used to run the Tween, where the frame height goes from 0.0
+/// to 1.0.
+struct NavAnimation {
+    transition: NavTransition,
+    /// True for push and replace, false for pop
+    forward: bool,
+    /// The Scene that was removed from the stack and is animating out. For a push, the Scene that is
+    /// animating out is the one below the top of the stack.
+    leaving: Option<Scene>,
+    layer: Layer,
+}
+
+impl NavAnimation {
+    fn progress(&self) -> f32 {
+        self.layer.frame.height().max(0.0).min(1.0)
+    }
+
+    fn is_finished(&self) -> bool {
+        match &self.layer.animation {
+            Some(tween) => tween.state == PlayState::Completed,
+            None => true,
+        }
+    }
+}
+
+// *****************************************************************************************************
+// NavController
+// *****************************************************************************************************
+
+/// A container that manages a stack of Scenes where only the top Scene is displayed and receives input.
+/// Scenes lower in the stack are kept with all of their state and are displayed again when the Scenes
+/// above them are popped.
+///
+/// Each Scene should be created with the same frame as the NavController. Stack changes are applied in the
+/// next update() and animated with the transition that was current when the change was requested. The
+/// NavController dispatches SceneEvent::WillAppear, DidAppear, WillDisappear and DidDisappear with the
+/// NodeID of the Scene.
+///
+/// NavEvent::Back and Close pop the top Scene and NavEvent::Root and Home pop to the root Scene. When the
+/// stack only has the root Scene, these events are ignored.
+///
+pub struct NavController {
+    /// The base layer
+    pub layer: Layer,
+    /// The transition for push, pop, pop_to_root and replace
+    pub transition: NavTransition,
+    /// The duration of the transition in seconds
+    pub duration: f64,
+    /// The Scenes, where the last is displayed
+    stack: Vec<Scene>,
+    /// Stack changes waiting for the next update()
+    actions: Vec<NavAction>,
+    /// The transition in progress
+    animation: Option<NavAnimation>,
+    /// The color of the panels that cover a Scene during a transition
+    cover_color: Color,
+    /// The node path of this layer, which is saved to load scenes that are pushed later
+    parent_nodes: Option<Vec<NodeID>>,
+    /// Ids of scenes that were loaded in update() and need the theme in the next render()
+    theme_queue: Vec<u32>,
+    /// SceneEvents waiting to be dispatched in the next update()
+    pending_events: Vec<SceneEvent>,
+}
+
+impl NavController {
+    /// Constructor with the root Scene
+    pub fn new(frame: Rectangle, root: Scene) -> Self {
+        let layer = Layer::new(frame);
+
+        NavController {
+            layer,
+            transition: NavTransition::Slide,
+            duration: TRANSITION_DURATION,
+            stack: vec![root],
+            actions: Vec::new(),
+            animation: None,
+            cover_color: Color::WHITE,
+            parent_nodes: None,
+            theme_queue: Vec::new(),
+            pending_events: Vec::new(),
+        }
+    }
+
+    /// Builder method to set the transition
+    pub fn with_transition(mut self, transition: NavTransition, duration: f64) -> Self {
+        self.transition = transition;
+        self.duration = duration;
+        self
+    }
+
+    /// Push the Scene on top of the stack
+    pub fn push(&mut self, scene: Scene) {
+        self.actions.push(NavAction::Push(Box::new(scene), self.transition));
+    }
+
+    /// Remove the top Scene unless it is the root Scene
+    pub fn pop(&mut self) {
+        self.actions.push(NavAction::Pop(self.transition));
+    }
+
+    /// Remove all Scenes above the root Scene
+    pub fn pop_to_root(&mut self) {
+        self.actions.push(NavAction::PopToRoot(self.transition));
+    }
+
+    /// Replace the top Scene with the Scene
+    pub fn replace(&mut self, scene: Scene) {
+        self.actions.push(NavAction::Replace(Box::new(scene), self.transition));
+    }
+
+    /// The number of Scenes in the stack, including changes that have not been applied
+    pub fn depth(&self) -> usize {
+        let mut depth = self.stack.len();
+        for action in &self.actions {
+            depth = match action {
+                NavAction::Push(_, _) => depth + 1,
+                NavAction::Pop(_) => depth.saturating_sub(1).max(1),
+                NavAction::PopToRoot(_) => 1,
+                NavAction::Replace(_, _) => depth,
+            };
+        }
+        depth
+    }
+
+    /// The Scene that is displayed
+    pub fn top_scene(&self) -> Option<&Scene> {
+        self.stack.last()
+    }
+
+    /// The mutable Scene that is displayed
+    pub fn top_scene_mut(&mut self) -> Option<&mut Scene> {
+        self.stack.last_mut()
+    }
+
+    /// Returns true while a transition is running
+    pub fn is_animating(&self) -> bool {
+        self.animation.is_some()
+    }
+
+    fn load_scene(&mut self, scene: &mut Scene, state: &mut AppState) {
+        if let Some(parent_nodes) = &self.parent_nodes {
+            let mut ctx = StageContext::new();
+            scene.set_id(state.new_id());
+            scene.get_layer_mut().set_path(parent_nodes);
+            scene.view_will_load(&mut ctx, state);
+            scene.notify(&DisplayEvent::Ready);
+            self.theme_queue.push(scene.get_id());
+        }
+    }
+
+    /// Apply a stack change and start its transition
+    fn apply_action(&mut self, action: NavAction, state: &mut AppState) {
+        self.finish_animation();
+        match action {
+            NavAction::Push(mut scene, transition) => {
+                self.load_scene(&mut scene, state);
+                let outgoing = self.stack.last().map(|scene| scene.get_layer().node_id());
+                self.stack.push(*scene);
+                self.start_animation(transition, true, None, outgoing);
+            }
+            NavAction::Pop(transition) => {
+                if self.stack.len() > 1 {
+                    let leaving = self.stack.pop();
+                    self.start_animation(transition, false, leaving, None);
+                }
+            }
+            NavAction::PopToRoot(transition) => {
+                if self.stack.len() > 1 {
+                    let leaving = self.stack.pop();
+                    // Scenes between the root and top are not displayed, so they are dropped without events
+                    self.stack.truncate(1);
+                    self.start_animation(transition, false, leaving, None);
+                }
+            }
+            NavAction::Replace(mut scene, transition) => {
+                self.load_scene(&mut scene, state);
+                let leaving = self.stack.pop();
+                self.stack.push(*scene);
+                self.start_animation(transition, true, leaving, None);
+            }
+        }
+    }
+
+    /// Dispatch the will-appear and will-disappear events and start the Tween. The outgoing id is only
+    /// provided when the outgoing Scene stays in the stack.
+    fn start_animation(
+        &mut self,
+        transition: NavTransition,
+        forward: bool,
+        leaving: Option<Scene>,
+        outgoing: Option<NodeID>,
+    ) {
+        let outgoing = outgoing.or_else(|| leaving.as_ref().map(|scene| scene.get_layer().node_id()));
+        if let Some(node) = outgoing {
+            self.pending_events.push(SceneEvent::WillDisappear(node));
+        }
+        if let Some(scene) = self.stack.last() {
+            self.pending_events.push(SceneEvent::WillAppear(scene.get_layer().node_id()));
+        }
+        let mut layer = Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0)));
+        if transition != NavTransition::None && outgoing.is_some() {
+            let propset = PropSet::new(vec![size(1.0, 1.0)], self.duration).ease(Ease::SineInOut);
+            layer.animate_with_props(propset, true);
+        }
+        self.animation = Some(NavAnimation { transition, forward, leaving, layer });
+    }
+
+    /// Jump to the end of the transition, move the Scenes back into place and dispatch the did-appear and
+    /// did-disappear events.
+    fn finish_animation(&mut self) {
+        let animation = match self.animation.take() {
+            Some(animation) => animation,
+            None => return,
+        };
+        let home = self.layer.frame.pos;
+        let outgoing = match animation.leaving {
+            Some(scene) => Some(scene.get_layer().node_id()),
+            None if self.stack.len() > 1 => {
+                let index = self.stack.len() - 2;
+                NavController::move_scene(&mut self.stack[index], home);
+                Some(self.stack[index].get_layer().node_id())
+            }
+            None => None,
+        };
+        if let Some(node) = outgoing {
+            self.pending_events.push(SceneEvent::DidDisappear(node));
+        }
+        if let Some(scene) = self.stack.last_mut() {
+            NavController::move_scene(scene, home);
+            self.pending_events.push(SceneEvent::DidAppear(scene.get_layer().node_id()));
+        }
+    }
+
+    /// Returns the incoming and outgoing Scenes of the transition
+    fn transition_scenes(&mut self) -> (Option<&mut Scene>, Option<&mut Scene>) {
+        let animation = match &mut self.animation {
+            Some(animation) => animation,
+            None => return (self.stack.last_mut(), None),
+        };
+        if let Some(leaving) = &mut animation.leaving {
+            return (self.stack.last_mut(), Some(leaving));
+        }
+        match self.stack.split_last_mut() {
+            Some((top, lower)) => (Some(top), lower.last_mut()),
+            None => (None, None),
+        }
+    }
+
+    /// Move the Scenes for the Slide and Cover transitions
+    fn layout_scenes(&mut self) {
+        let (transition, forward, progress) = match &self.animation {
+            Some(animation) => (animation.transition, animation.forward, animation.progress()),
+            None => return,
+        };
+        let home = self.layer.frame.pos;
+        let width = self.layer.frame.width();
+        let height = self.layer.frame.height();
+        let (incoming_offset, outgoing_offset) = match (transition, forward) {
+            (NavTransition::Slide, true) => {
+                (Vector::new(width * (1.0 - progress), 0.0), Vector::new(-width * progress, 0.0))
+            }
+            (NavTransition::Slide, false) => {
+                (Vector::new(-width * (1.0 - progress), 0.0), Vector::new(width * progress, 0.0))
+            }
+            (NavTransition::Cover, true) => (Vector::new(0.0, height * (1.0 - progress)), Vector::ZERO),
+            (NavTransition::Cover, false) => (Vector::ZERO, Vector::new(0.0, height * progress)),
+            _ => return,
+        };
+        let (incoming, outgoing) = self.transition_scenes();
+        if let Some(scene) = incoming {
+            NavController::move_scene(scene, home + incoming_offset);
+        }
+        if let Some(scene) = outgoing {
+            NavController::move_scene(scene, home + outgoing_offset);
+        }
+    }
+
+    /// Like a completed Scene move, notify(Moved) clears the meshes that were cached at the old position
+    fn move_scene(scene: &mut Scene, origin: Vector) {
+        scene.align_view(origin);
+        scene.notify(&DisplayEvent::Moved);
+    }
+
+    fn cover_task(rect: &Rectangle, color: Color) -> MeshTask {
+        let mut task = MeshTask::new(0);
+        let mut mesh = DrawShape::rectangle(rect, Some(color), None, 0.0, 0.0);
+        task.append(&mut mesh);
+        task
+    }
+
+    /// The panel of the Zoom transition at the scale, centered in the frame
+    fn zoom_rect(frame: &Rectangle, scale: f32) -> Rectangle {
+        let size = frame.size * scale;
+        let pos = frame.pos + (frame.size - size) * 0.5;
+        Rectangle::new(pos, size)
+    }
+}
+
+// *****************************************************************************************************
+// NavController :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for NavController {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<NavController>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    fn align_view(&mut self, origin: Vector) {
+        self.layer.frame.pos = self.layer.anchor_pt + origin;
+        let home = self.layer.frame.pos;
+        for scene in &mut self.stack {
+            scene.align_view(home);
+        }
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        // Don't allow style to be locked
+        let _ = self.layer.apply_theme(theme);
+
+        self.cover_color = theme.bg_color;
+        for scene in &mut self.stack {
+            scene.set_theme(theme);
+        }
+    }
+
+    /// Handles NavEvent::Back, Close, Root and Home when there is a Scene to pop. Other events are passed
+    /// to the top Scene.
+    fn handle_event(&mut self, event: &EventBox, app_state: &mut AppState) {
+        if let Ok(evt) = event.downcast_ref::<NavEvent>() {
+            if self.depth() > 1 {
+                match evt {
+                    NavEvent::Back | NavEvent::Close => {
+                        self.pop();
+                        app_state.event_consumed = true;
+                        return;
+                    }
+                    NavEvent::Root | NavEvent::Home => {
+                        self.pop_to_root();
+                        app_state.event_consumed = true;
+                        return;
+                    }
+                    _ => (),
+                }
+            }
+        }
+        if let Some(scene) = self.stack.last_mut() {
+            scene.handle_event(event, app_state);
+        }
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+        for scene in &mut self.stack {
+            scene.notify(event);
+        }
+    }
+
+    fn update(&mut self, window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        if self.parent_nodes.is_some() {
+            let actions: Vec<NavAction> = self.actions.drain(..).collect();
+            for action in actions {
+                self.apply_action(action, state);
+            }
+        }
+
+        let finished = match &mut self.animation {
+            Some(animation) => {
+                animation.layer.tween_update(state);
+                animation.is_finished()
+            }
+            None => false,
+        };
+        if finished {
+            self.finish_animation();
+        } else {
+            self.layout_scenes();
+        }
+
+        let (incoming, outgoing) = self.transition_scenes();
+        if let Some(scene) = outgoing {
+            scene.update(window, state);
+        }
+        if let Some(scene) = incoming {
+            scene.update(window, state);
+        }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        self.layer.draw_background(window);
+        let ids: Vec<u32> = self.theme_queue.drain(..).collect();
+        for scene in self.stack.iter_mut().filter(|scene| ids.contains(&scene.get_id())) {
+            scene.set_theme(theme);
+        }
+
+        let frame = self.layer.frame;
+        let color = self.cover_color;
+        let (transition, forward, progress) = match &self.animation {
+            Some(animation) => (animation.transition, animation.forward, animation.progress()),
+            None => (NavTransition::None, true, 1.0),
+        };
+        let (incoming, outgoing) = self.transition_scenes();
+        match (transition, outgoing) {
+            (NavTransition::Slide, Some(outgoing)) => {
+                outgoing.render(theme, window);
+                if let Some(incoming) = incoming {
+                    incoming.render(theme, window);
+                }
+            }
+            // The Scene on top is drawn over a panel so that the Scene below does not show through
+            (NavTransition::Cover, Some(outgoing)) => {
+                let (lower, upper) = if forward { (Some(outgoing), incoming) } else { (incoming, Some(outgoing)) };
+                if let Some(scene) = lower {
+                    scene.render(theme, window);
+                }
+                if let Some(scene) = upper {
+                    window.add_task(NavController::cover_task(&scene.get_frame(), color));
+                    scene.render(theme, window);
+                }
+            }
+            // The first half covers the old Scene and the second half reveals the new Scene
+            (NavTransition::Fade, Some(outgoing)) | (NavTransition::Zoom, Some(outgoing)) => {
+                let first_half = progress < 0.5;
+                let scene = if first_half { Some(outgoing) } else { incoming };
+                if let Some(scene) = scene {
+                    scene.render(theme, window);
+                }
+                let amount = if first_half { progress * 2.0 } else { (1.0 - progress) * 2.0 };
+                let zoom = transition == NavTransition::Zoom && (forward == first_half);
+                if zoom {
+                    let rect = NavController::zoom_rect(&frame, amount);
+                    window.add_task(NavController::cover_task(&rect, color));
+                } else {
+                    let mut color = color;
+                    color.a = amount;
+                    window.add_task(NavController::cover_task(&frame, color));
+                }
+            }
+            _ => {
+                if let Some(scene) = incoming {
+                    scene.render(theme, window);
+                }
+            }
+        }
+        self.layer.draw_border(window);
+    }
+
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        if self.animation.is_some() {
+            return false;
+        }
+        if let Some(scene) = self.stack.last_mut() {
+            return scene.handle_mouse_at(pt, window);
+        }
+        false
+    }
+
+    /// Only the top Scene is searched and not while a transition is running
+    fn layer_at(&self, pt: &Vector, filter: &dyn Fn(&Layer) -> bool) -> Option<&Layer> {
+        if self.animation.is_some() {
+            return None;
+        }
+        if let Some(layer) = self.stack.last().and_then(|scene| scene.layer_at(pt, filter)) {
+            return Some(layer);
+        }
+        if pt.overlaps_rectangle(&self.layer.frame) && filter(&self.layer) {
+            return Some(&self.layer);
+        }
+        None
+    }
+
+    fn view_will_load(&mut self, ctx: &mut StageContext, app_state: &mut AppState) {
+        let parent_nodes = self.layer.node_path.nodes.clone();
+        app_state.append_node(self.layer.node_path.clone());
+
+        for scene in &mut self.stack {
+            scene.set_id(app_state.new_id());
+            scene.get_layer_mut().set_path(&parent_nodes);
+            scene.view_will_load(ctx, app_state);
+        }
+        self.parent_nodes = Some(parent_nodes);
+    }
+}
+
+// *****************************************************************************************************
+// NavController :: Responder
+// *****************************************************************************************************
+
+/// Input is only passed to the top Scene and is ignored while a transition is running
+impl Responder for NavController {
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        for scene in &mut self.stack {
+            if scene.set_field_value(value, type_id, layer_id) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns true for any click inside the frame so that key commands are passed to the top Scene
+    fn handle_mouse_down(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        if self.animation.is_some() {
+            return false;
+        }
+        if let Some(scene) = self.stack.last_mut() {
+            scene.handle_mouse_down(pt, state);
+        }
+        pt.overlaps_rectangle(&self.layer.frame)
+    }
+
+    fn handle_mouse_up(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        if self.animation.is_some() {
+            return false;
+        }
+        if let Some(scene) = self.stack.last_mut() {
+            return scene.handle_mouse_up(pt, state);
+        }
+        false
+    }
+
+    fn handle_mouse_scroll(&mut self, pt: &Vector, state: &mut AppState) {
+        if self.animation.is_some() {
+            return;
+        }
+        if let Some(scene) = self.stack.last_mut() {
+            scene.handle_mouse_scroll(pt, state);
+        }
+    }
+
+    fn handle_key_press(&mut self, c: char, window: &mut Window) {
+        if self.animation.is_some() {
+            return;
+        }
+        if let Some(scene) = self.stack.last_mut() {
+            scene.handle_key_press(c, window);
+        }
+    }
+
+    fn handle_text_input(&mut self, event: &TextInputEvent, window: &mut Window) -> bool {
+        if self.animation.is_some() {
+            return false;
+        }
+        if let Some(scene) = self.stack.last_mut() {
+            return scene.handle_text_input(event, window);
+        }
+        false
+    }
+
+    fn handle_key_command(&mut self, key: &Key, window: &mut Window) -> bool {
+        if self.animation.is_some() {
+            return false;
+        }
+        if let Some(scene) = self.stack.last_mut() {
+            return scene.handle_key_command(key, window);
+        }
+        false
+    }
+}
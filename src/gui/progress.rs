@@ -0,0 +1,455 @@
+/// Progress bars and activity indicators
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Vector},
+    graphics::{Color, MeshTask},
+    lifecycle::Window,
+};
+use std::any::TypeId;
+
+use super::*;
+
+/// The duration in seconds of the animation when the value of a ProgressBar changes
+const PROGRESS_DURATION: f64 = 0.3;
+/// The duration in seconds of one loop of an indeterminate ProgressBar
+const PROGRESS_LOOP_DURATION: f64 = 1.2;
+/// The fraction of the track covered by the moving segment of an indeterminate ProgressBar
+const PROGRESS_SEGMENT: f32 = 0.3;
+/// The duration in seconds of one turn of an ActivityIndicator
+const ACTIVITY_LOOP_DURATION: f64 = 1.0;
+/// The number of ticks in an ActivityIndicator
+const ACTIVITY_TICKS: usize = 12;
+
+/// Start a Tween on the driver layer that moves its frame height from 0.0 to 1.0 and repeats forever.
+/// Like the Cursor, this relies on Tween::repeat for the loop.
+fn start_loop(layer: &mut Layer, seconds: f64) {
+    layer.frame.size.y = 0.0;
+    let mut tween = Tween::with(0, &*layer).to(&[size(1.0, 1.0)]).duration(seconds).repeat(u32::max_value(), 0.0);
+    tween.play();
+    layer.animation = Some(tween);
+    layer.tween_type = TweenType::Animation;
+}
+
+/// Remove the looping Tween from the driver layer
+fn stop_loop(layer: &mut Layer) {
+    layer.animation = None;
+    layer.frame.size.y = 0.0;
+}
+
+//-- ProgressBar -----------------------------------------------------------------------
+
+/// The shape of a ProgressBar
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ProgressStyle {
+    /// A horizontal bar that fills from left to right
+    Linear,
+    /// A ring that fills clockwise from 12 o'clock
+    Circular,
+}
+
+/// A progress indicator that is either determinate, where it shows a value from 0.0 to 1.0, or indeterminate,
+/// where it loops until the work is done. Changes to the value are animated.
+/// The value can be set with set_field_value using FieldValue::Number, and FieldValue::None makes it indeterminate.
+pub struct ProgressBar {
+    /// The base layer
+    pub layer: Layer,
+    /// Linear or circular
+    pub style: ProgressStyle,
+    /// The width of the ring for the Circular style. The Linear style uses the height of the frame.
+    pub line_width: f32,
+    /// The duration in seconds of the animation when the value changes
+    pub duration: f64,
+    /// The target value from 0.0 to 1.0
+    value: f32,
+    /// Is the progress unknown?
+    indeterminate: bool,
+    track_color: Color,
+    fill_color: Color,
+    /// Layer used to animate the value, where the frame height is the displayed value
+    value_layer: Layer,
+    /// Layer used to loop the indeterminate animation, where the frame height is the phase of the loop
+    loop_layer: Layer,
+}
+
+impl ProgressBar {
+    /// Constructor
+    pub fn new(frame: Rectangle) -> Self {
+        ProgressBar {
+            layer: Layer::new(frame),
+            style: ProgressStyle::Linear,
+            line_width: 4.0,
+            duration: PROGRESS_DURATION,
+            value: 0.0,
+            indeterminate: false,
+            track_color: Color::from_hex("#E0E0E0"),
+            fill_color: Color::from_hex("#4373c2"),
+            value_layer: Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0))),
+            loop_layer: Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0))),
+        }
+    }
+
+    /// Builder method to set the style
+    pub fn with_style(mut self, style: ProgressStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Builder method to set the initial value without animation
+    pub fn with_value(mut self, value: f32) -> Self {
+        self.set_value(value, false);
+        self
+    }
+
+    /// Builder method to make the progress bar indeterminate
+    pub fn with_indeterminate(mut self) -> Self {
+        self.set_indeterminate(true);
+        self
+    }
+
+    /// Builder method to set the width of the ring for the Circular style
+    pub fn with_line_width(mut self, line_width: f32) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    /// Builder method to set the duration of the animation when the value changes
+    pub fn with_duration(mut self, seconds: f64) -> Self {
+        self.duration = seconds;
+        self
+    }
+
+    /// The target value from 0.0 to 1.0
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// The value that is currently displayed, which trails the target value while it animates
+    pub fn display_value(&self) -> f32 {
+        self.value_layer.frame.size.y
+    }
+
+    /// Set the value, which is clamped to 0.0 to 1.0. This also makes the progress bar determinate.
+    pub fn set_value(&mut self, value: f32, animated: bool) {
+        self.set_indeterminate(false);
+        self.value = value.max(0.0).min(1.0);
+        if animated && self.duration > 0.0 {
+            let propset = PropSet::new(vec![size(1.0, self.value)], self.duration).ease(Ease::SineOut);
+            self.value_layer.animate_with_props(propset, true);
+        } else {
+            self.value_layer.animation = None;
+            self.value_layer.frame.size.y = self.value;
+        }
+    }
+
+    /// Is the progress unknown?
+    pub fn is_indeterminate(&self) -> bool {
+        self.indeterminate
+    }
+
+    /// Start or stop the indeterminate loop animation
+    pub fn set_indeterminate(&mut self, indeterminate: bool) {
+        if indeterminate == self.indeterminate {
+            return;
+        }
+        self.indeterminate = indeterminate;
+        if indeterminate {
+            start_loop(&mut self.loop_layer, PROGRESS_LOOP_DURATION);
+        } else {
+            stop_loop(&mut self.loop_layer);
+        }
+    }
+
+    fn draw_linear(&self, task: &mut MeshTask) {
+        let frame = self.layer.frame;
+        let radius = frame.height() / 2.0;
+        let mut mesh = DrawShape::rectangle(&frame, Some(self.track_color), None, 0.0, radius);
+        task.append(&mut mesh);
+
+        // The start and end of the fill as fractions of the track
+        let (start, end) = {
+            if self.indeterminate {
+                // The segment enters on the left and leaves on the right
+                let head = self.loop_layer.frame.size.y * (1.0 + PROGRESS_SEGMENT);
+                ((head - PROGRESS_SEGMENT).max(0.0), head.min(1.0))
+            } else {
+                (0.0, self.display_value())
+            }
+        };
+        let width = (end - start) * frame.width();
+        if width <= 0.0 {
+            return;
+        }
+        let rect = Rectangle::new((frame.x() + start * frame.width(), frame.y()), (width, frame.height()));
+        let mut mesh = DrawShape::rectangle(&rect, Some(self.fill_color), None, 0.0, radius.min(width / 2.0));
+        task.append(&mut mesh);
+    }
+
+    fn draw_circular(&self, task: &mut MeshTask) {
+        let frame = self.layer.frame;
+        let center = frame.center();
+        let radius = frame.width().min(frame.height()) / 2.0 - self.line_width / 2.0;
+        let mut mesh = DrawShape::circle(&center, radius, None, Some(self.track_color), self.line_width);
+        task.append(&mut mesh);
+
+        let (start, sweep) = {
+            if self.indeterminate {
+                (self.loop_layer.frame.size.y * 360.0, PROGRESS_SEGMENT * 360.0)
+            } else {
+                (0.0, self.display_value() * 360.0)
+            }
+        };
+        let mut mesh = DrawShape::arc(&center, radius, start, sweep, self.fill_color, self.line_width);
+        task.append(&mut mesh);
+    }
+}
+
+// *****************************************************************************************************
+// ProgressBar :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for ProgressBar {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<ProgressBar>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        let ok = self.layer.apply_theme(theme);
+        if !ok {
+            return;
+        }
+        self.track_color = theme.progress_bg_color;
+        self.fill_color = theme.progress_fg_color;
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        self.value_layer.tween_update(state);
+        if self.indeterminate {
+            self.loop_layer.tween_update(state);
+        }
+    }
+
+    fn render(&mut self, _theme: &mut Theme, window: &mut Window) {
+        if self.layer.visibility != Visibility::Visible {
+            return;
+        }
+        let mut task = MeshTask::new(0);
+        match self.style {
+            ProgressStyle::Linear => self.draw_linear(&mut task),
+            ProgressStyle::Circular => self.draw_circular(&mut task),
+        }
+        window.add_task(task);
+    }
+}
+
+// *****************************************************************************************************
+// ProgressBar :: Responder
+// *****************************************************************************************************
+
+impl Responder for ProgressBar {
+    fn get_field_value(&self) -> FieldValue {
+        if self.indeterminate {
+            FieldValue::None
+        } else {
+            FieldValue::Number(self.value)
+        }
+    }
+
+    /// A Number animates to the new value and None makes the progress bar indeterminate
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if type_id == self.get_type_id() && layer_id == self.layer.get_id() {
+            match value {
+                FieldValue::Number(value) => {
+                    self.set_value(*value, true);
+                    return true;
+                }
+                FieldValue::None => {
+                    self.set_indeterminate(true);
+                    return true;
+                }
+                _ => (),
+            }
+        }
+        false
+    }
+}
+
+//-- ActivityIndicator -----------------------------------------------------------------------
+
+/// A spinner of ticks around a circle, where the highlight turns while it is animating.
+/// Use it when there is no progress to report, or a ProgressBar is too large.
+pub struct ActivityIndicator {
+    /// The base layer
+    pub layer: Layer,
+    /// Hide the indicator while it is not animating
+    pub hides_when_stopped: bool,
+    /// The width of each tick
+    pub line_width: f32,
+    color: Color,
+    is_animating: bool,
+    /// Layer used to loop the animation, where the frame height is the phase of the turn
+    loop_layer: Layer,
+}
+
+impl ActivityIndicator {
+    /// Constructor
+    pub fn new(frame: Rectangle) -> Self {
+        ActivityIndicator {
+            layer: Layer::new(frame),
+            hides_when_stopped: true,
+            line_width: 2.0,
+            color: Color::from_hex("#4373c2"),
+            is_animating: false,
+            loop_layer: Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0))),
+        }
+    }
+
+    /// Builder method to keep showing the indicator while it is not animating
+    pub fn with_hides_when_stopped(mut self, hides: bool) -> Self {
+        self.hides_when_stopped = hides;
+        self
+    }
+
+    /// Builder method to start the animation
+    pub fn with_animating(mut self) -> Self {
+        self.start_animating();
+        self
+    }
+
+    /// Start turning
+    pub fn start_animating(&mut self) {
+        if !self.is_animating {
+            self.is_animating = true;
+            start_loop(&mut self.loop_layer, ACTIVITY_LOOP_DURATION);
+        }
+    }
+
+    /// Stop turning
+    pub fn stop_animating(&mut self) {
+        if self.is_animating {
+            self.is_animating = false;
+            stop_loop(&mut self.loop_layer);
+        }
+    }
+
+    /// Is it turning?
+    pub fn is_animating(&self) -> bool {
+        self.is_animating
+    }
+}
+
+// *****************************************************************************************************
+// ActivityIndicator :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for ActivityIndicator {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<ActivityIndicator>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        let ok = self.layer.apply_theme(theme);
+        if !ok {
+            return;
+        }
+        self.color = theme.progress_fg_color;
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        if self.is_animating {
+            self.loop_layer.tween_update(state);
+        }
+    }
+
+    fn render(&mut self, _theme: &mut Theme, window: &mut Window) {
+        if self.layer.visibility != Visibility::Visible || (!self.is_animating && self.hides_when_stopped) {
+            return;
+        }
+        let frame = self.layer.frame;
+        let center = frame.center();
+        let outer = frame.width().min(frame.height()) / 2.0;
+        let inner = outer * 0.5;
+        // The tick at the head of the turn is opaque and the ticks behind it fade out
+        let head = (self.loop_layer.frame.size.y * ACTIVITY_TICKS as f32) as usize % ACTIVITY_TICKS;
+
+        let mut task = MeshTask::new(0);
+        for i in 0..ACTIVITY_TICKS {
+            let mut color = self.color;
+            if self.is_animating {
+                let behind = (head + ACTIVITY_TICKS - i) % ACTIVITY_TICKS;
+                color.a *= 1.0 - 0.85 * behind as f32 / ACTIVITY_TICKS as f32;
+            } else {
+                color.a *= 0.3;
+            }
+            let radians = (i as f32 * 360.0 / ACTIVITY_TICKS as f32 - 90.0).to_radians();
+            let direction = Vector::new(radians.cos(), radians.sin());
+            let pts: [&Vector; 2] = [&(center + direction * inner), &(center + direction * outer)];
+            let mut line = DrawShape::line(&pts, color, self.line_width);
+            task.append(&mut line);
+        }
+        window.add_task(task);
+    }
+}
@@ -6,7 +6,7 @@ use crate::tools::*;
 use std::{any::TypeId, collections::BTreeMap};
 
 use quicksilver::{
-    geom::{Rectangle, Vector},
+    geom::{Rectangle, Shape, Vector},
     graphics::MeshTask,
     input::Key,
     lifecycle::Window,
@@ -229,7 +229,7 @@ impl Displayable for Scene {
         }
     }
 
-    fn handle_event(&mut self, event: &EventBox, _app_state: &mut AppState) {
+    fn handle_event(&mut self, event: &EventBox, app_state: &mut AppState) {
         let sender = event.sender();
         if let Ok(evt) = event.downcast_ref::<TweenEvent>() {
             if sender == self.layer.node_id() {
@@ -263,6 +263,15 @@ impl Displayable for Scene {
                 _ => (),
             }
         }
+
+        if event.downcast_ref::<NavEvent>().is_ok() {
+            for view in &mut self.controls.values_mut() {
+                view.handle_event(event, app_state);
+                if app_state.event_consumed {
+                    break;
+                }
+            }
+        }
     }
 
     fn notify(&mut self, event: &DisplayEvent) {
@@ -379,6 +388,27 @@ impl Displayable for Scene {
         false
     }
 
+    /// Controls are searched before views, in reverse order since the last object is drawn on top
+    fn layer_at(&self, pt: &Vector, filter: &dyn Fn(&Layer) -> bool) -> Option<&Layer> {
+        if self.layer.visibility != Visibility::Visible || !self.layer.contains_point(pt) {
+            return None;
+        }
+        for view in self.controls.values().rev() {
+            if let Some(layer) = view.layer_at(pt, filter) {
+                return Some(layer);
+            }
+        }
+        for view in self.views.values().rev() {
+            if let Some(layer) = view.layer_at(pt, filter) {
+                return Some(layer);
+            }
+        }
+        if filter(&self.layer) {
+            return Some(&self.layer);
+        }
+        None
+    }
+
     /// This should find all of the views/controls that have been queued for creation
     /// and move them into the corresponding Maps, while assigning unique id values
     /// for each.
@@ -485,6 +515,15 @@ impl Responder for Scene {
         }
     }
 
+    fn handle_text_input(&mut self, event: &TextInputEvent, window: &mut Window) -> bool {
+        if let Some(active_field_id) = self.active_field_id {
+            if let Some(view) = &mut self.controls.get_mut(&active_field_id) {
+                return view.handle_text_input(event, window);
+            }
+        }
+        false
+    }
+
     fn handle_key_command(&mut self, key: &Key, window: &mut Window) -> bool {
         if let Some(active_field_id) = self.active_field_id {
             if let Some(view) = &mut self.controls.get_mut(&active_field_id) {
@@ -0,0 +1,376 @@
+/// SegmentedControl
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Transform, Vector},
+    graphics::{Color, MeshTask},
+    input::{Key, MouseCursor},
+    lifecycle::Window,
+};
+use std::any::TypeId;
+use std::collections::HashMap;
+
+use super::*;
+
+/// The duration in seconds of the highlight animation
+const HIGHLIGHT_DURATION: f64 = 0.2;
+/// The space between the border and the highlight
+const HIGHLIGHT_INSET: f32 = 2.0;
+/// The corner radius of the control
+const CORNER_RADIUS: f32 = 4.0;
+
+//-- SegmentedControl -----------------------------------------------------------------------
+
+/// A horizontal row of equal width segments where one segment is selected. The highlight behind the
+/// selected segment slides to the new selection.
+///
+/// Left and Right select the previous and next segment when the control has focus. A disabled control is
+/// dimmed and ignores input. Changes made by the user dispatch SelectionEvent::Changed.
+pub struct SegmentedControl {
+    /// The base layer
+    pub layer: Layer,
+    /// The titles of the segments
+    segments: Vec<String>,
+    /// The index of the selected segment
+    select_index: Option<usize>,
+    /// Does it respond to mouse and keyboard input?
+    is_enabled: bool,
+    /// Has the control been activated by its Scene?
+    has_focus: bool,
+    /// The frame of this layer is the highlight, relative to the frame of the control. It is moved with a Tween.
+    highlight: Layer,
+    /// Title text meshes keyed by segment index. Created at origin 0, 0.
+    text_cache: HashMap<usize, MeshTask>,
+    /// Theme colors
+    bg_color: Color,
+    border_color: Color,
+    highlight_color: Color,
+    select_fg_color: Color,
+    focus_color: Color,
+    /// SelectionEvents waiting to be dispatched in the next update()
+    pending_events: Vec<SelectionEvent>,
+}
+
+impl SegmentedControl {
+    /// Constructor
+    pub fn new(frame: Rectangle) -> Self {
+        SegmentedControl {
+            layer: Layer::new(frame),
+            segments: Vec::new(),
+            select_index: None,
+            is_enabled: true,
+            has_focus: false,
+            highlight: Layer::new(Rectangle::new((0.0, 0.0), (0.0, 0.0))),
+            text_cache: HashMap::new(),
+            bg_color: Color::from_hex("#FFFFFF"),
+            border_color: Color::from_hex("#AAAAAA"),
+            highlight_color: Color::from_hex("#4373c2"),
+            select_fg_color: Color::WHITE,
+            focus_color: Color::from_hex("#80A4C2"),
+            pending_events: Vec::new(),
+        }
+    }
+
+    /// Builder method to add the segments. The first segment is selected.
+    pub fn with_segments(mut self, titles: &[&str]) -> Self {
+        for title in titles {
+            self.add_segment(title);
+        }
+        self
+    }
+
+    /// Builder method to select the segment at the index without animation
+    pub fn with_selected(mut self, index: usize) -> Self {
+        self.select(index, false);
+        self
+    }
+
+    /// Builder method to enable or disable the control
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.set_enabled(enabled);
+        self
+    }
+
+    /// Add a segment at the end. The first segment is selected automatically.
+    pub fn add_segment(&mut self, title: &str) {
+        self.segments.push(title.to_owned());
+        self.text_cache.clear();
+        if self.select_index.is_none() {
+            self.select_index = Some(0);
+        }
+        self.update_highlight(false);
+    }
+
+    /// The number of segments
+    pub fn segment_count(&self) -> usize {
+        self.segments.len()
+    }
+
+    /// The index of the selected segment
+    pub fn selected_index(&self) -> Option<usize> {
+        self.select_index
+    }
+
+    /// Select the segment at the index, optionally animating the highlight. Returns false if the index is
+    /// out of range.
+    pub fn select(&mut self, index: usize, animated: bool) -> bool {
+        if index >= self.segments.len() {
+            return false;
+        }
+        if self.select_index != Some(index) {
+            self.select_index = Some(index);
+            self.update_highlight(animated);
+        }
+        true
+    }
+
+    /// Does it respond to mouse and keyboard input?
+    pub fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+
+    /// Enable or disable the control
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.is_enabled = enabled;
+    }
+
+    /// Select the segment as if the user clicked it
+    fn choose(&mut self, index: usize) {
+        if self.select_index != Some(index) && self.select(index, true) {
+            self.pending_events.push(SelectionEvent::Changed(1, Some(index)));
+        }
+    }
+
+    /// The frame of the segment at the index
+    fn segment_frame(&self, index: usize) -> Rectangle {
+        let frame = self.layer.frame;
+        let width = frame.width() / self.segments.len().max(1) as f32;
+        Rectangle::new((frame.x() + width * index as f32, frame.y()), (width, frame.height()))
+    }
+
+    /// The index of the segment at the point
+    fn segment_at_point(&self, pt: &Vector) -> Option<usize> {
+        (0..self.segments.len()).find(|index| pt.overlaps_rectangle(&self.segment_frame(*index)))
+    }
+
+    /// Move the highlight behind the selected segment
+    fn update_highlight(&mut self, animate: bool) {
+        let index = match self.select_index {
+            Some(index) => index,
+            None => {
+                self.highlight.frame.size = Vector::ZERO;
+                return;
+            }
+        };
+        let rect = UITools::padded_rect(&self.segment_frame(index), -HIGHLIGHT_INSET, -HIGHLIGHT_INSET);
+        let rect = Rectangle::new(rect.pos - self.layer.frame.pos, rect.size);
+        if animate && self.highlight.frame.width() > 0.0 {
+            let props = vec![position(rect.x(), rect.y()), size(rect.width(), rect.height())];
+            let propset = PropSet::new(props, HIGHLIGHT_DURATION).ease(Ease::SineInOut);
+            self.highlight.animate_with_props(propset, true);
+        } else {
+            self.highlight.animation = None;
+            self.highlight.frame = rect;
+        }
+    }
+}
+
+// *****************************************************************************************************
+// SegmentedControl :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for SegmentedControl {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<SegmentedControl>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        let ok = self.layer.apply_theme(theme);
+        if !ok {
+            return;
+        }
+        self.bg_color = theme.input_bg_color;
+        self.border_color = theme.border_color;
+        self.highlight_color = theme.button_bg_color;
+        self.select_fg_color = theme.button_fg_color;
+        self.focus_color = theme.cursor_color;
+        self.text_cache.clear();
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Activate => {
+                self.has_focus = true;
+            }
+            DisplayEvent::Deactivate => {
+                self.has_focus = false;
+            }
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        self.highlight.tween_update(state);
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        let alpha = if self.is_enabled { 1.0 } else { 0.5 };
+        let dim = |color: Color| Color { a: color.a * alpha, ..color };
+        let frame = self.layer.frame;
+        let border_color = if self.has_focus && self.is_enabled { self.focus_color } else { self.border_color };
+
+        // Shapes are drawn first and the text is drawn on top
+        let mut graphics = MeshTask::new(0);
+        let mut mesh = DrawShape::rectangle(&frame, Some(dim(self.bg_color)), None, 0.0, CORNER_RADIUS);
+        graphics.append(&mut mesh);
+        if self.highlight.frame.width() > 0.0 {
+            let rect = Rectangle::new(frame.pos + self.highlight.frame.pos, self.highlight.frame.size);
+            let mut mesh = DrawShape::rectangle(&rect, Some(dim(self.highlight_color)), None, 0.0, CORNER_RADIUS);
+            graphics.append(&mut mesh);
+        }
+        for index in 1..self.segments.len() {
+            // Dividers are hidden next to the selected segment
+            if self.select_index == Some(index) || self.select_index == Some(index - 1) {
+                continue;
+            }
+            let x = self.segment_frame(index).x();
+            let pts: [&Vector; 2] =
+                [&Vector::new(x, frame.y() + 4.0), &Vector::new(x, frame.y() + frame.height() - 4.0)];
+            let mut line = DrawShape::line(&pts, dim(self.border_color), 1.0);
+            graphics.append(&mut line);
+        }
+        let mut mesh = DrawShape::rectangle(&frame, None, Some(dim(border_color)), 1.0, CORNER_RADIUS);
+        graphics.append(&mut mesh);
+        window.add_task(graphics);
+
+        for index in 0..self.segments.len() {
+            let rect = self.segment_frame(index);
+            if !self.text_cache.contains_key(&index) {
+                let origin_rect = Rectangle::new((0.0, 0.0), rect.size);
+                let mut params = TextParams::new(self.layer.font_style)
+                    .frame(origin_rect)
+                    .text(&self.segments[index])
+                    .align(TextAlign::Center, VertAlign::Middle)
+                    .multiline(false);
+                params.subframe = Some(origin_rect);
+                if let Some(task) = theme.default_font.draw(params) {
+                    self.text_cache.insert(index, task);
+                }
+            }
+            if let Some(task) = self.text_cache.get(&index) {
+                let selected = self.select_index == Some(index);
+                let mut mesh = task.clone();
+                for vertex in mesh.vertices.iter_mut() {
+                    vertex.pos = Transform::translate(rect.pos) * vertex.pos;
+                    if selected {
+                        vertex.col = self.select_fg_color;
+                    }
+                    vertex.col.a *= alpha;
+                }
+                window.add_task(mesh);
+            }
+        }
+    }
+
+    fn set_hover_animation(&mut self, props: PropSet) {
+        self.layer.hover_effect = Some(props);
+    }
+
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        if self.is_enabled && pt.overlaps_rectangle(&self.layer.frame) {
+            self.layer.mouse_state = MouseState::Hover;
+            window.set_cursor(MouseCursor::Hand);
+            return true;
+        }
+        self.layer.mouse_state = MouseState::None;
+        false
+    }
+}
+
+// *****************************************************************************************************
+// SegmentedControl :: Responder
+// *****************************************************************************************************
+
+impl Responder for SegmentedControl {
+    /// The Selections value is the index of the selected segment
+    fn get_field_value(&self) -> FieldValue {
+        match self.select_index {
+            Some(index) => FieldValue::Selections(vec![index]),
+            None => FieldValue::None,
+        }
+    }
+
+    /// The Selections value is a list of segment indexes, where only the first is used
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if type_id == self.get_type_id() && layer_id == self.layer.get_id() {
+            if let FieldValue::Selections(indexes) = value {
+                if let Some(index) = indexes.first() {
+                    self.select(*index, true);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    fn handle_mouse_down(&mut self, pt: &Vector, _state: &mut AppState) -> bool {
+        if !self.is_enabled {
+            return false;
+        }
+        match self.segment_at_point(pt) {
+            Some(index) => {
+                self.choose(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Left and Right select the previous and next segment. Tab is handled so that the Scene moves the focus
+    /// to the next control.
+    fn handle_key_command(&mut self, key: &Key, _window: &mut Window) -> bool {
+        let index = self.select_index.unwrap_or(0);
+        match key {
+            Key::Left if self.is_enabled && index > 0 => {
+                self.choose(index - 1);
+                true
+            }
+            Key::Right if self.is_enabled && index + 1 < self.segments.len() => {
+                self.choose(index + 1);
+                true
+            }
+            Key::Tab => true,
+            _ => false,
+        }
+    }
+}
@@ -4,7 +4,7 @@ use super::*;
 /// ordering of MeshTasks.
 use crate::core::*;
 use crate::events::*;
-use crate::tools::DrawShape;
+use crate::tools::{DrawPath, DrawShape};
 
 use quicksilver::{
     geom::{Rectangle, Shape, Transform, Vector},
@@ -15,7 +15,7 @@ use quicksilver::{
 use std::any::TypeId;
 
 /// Enum type to define how a shape is drawn.
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ShapeDef {
     /// Line connecting two points
     Line(Vector, Vector),
@@ -29,6 +29,8 @@ pub enum ShapeDef {
     Triangle,
     /// Quad
     Quad,
+    /// A vector path with points relative to the top left of the frame
+    Path(DrawPath),
 }
 
 /// Enum to define how themes are applied to the shape
@@ -101,9 +103,10 @@ impl ShapeView {
                 }
             };
             let color = Some(self.layer.transition.color);
-            match self.shape_def {
+            match &self.shape_def {
                 ShapeDef::Rectangle => {
-                    DrawShape::rectangle(&self.layer.frame, color, border.0, border.1, self.layer.corner_radius)
+                    let radii = self.layer.current_radii();
+                    DrawShape::rounded_rectangle(&self.layer.frame, color, border.0, border.1, radii)
                 }
                 ShapeDef::Line(start, end) => {
                     let end_pt: Vector = {
@@ -120,7 +123,7 @@ impl ShapeView {
                         }
                         pt
                     };
-                    let pts: [&Vector; 2] = [&start, &end_pt];
+                    let pts: [&Vector; 2] = [start, &end_pt];
                     // log::debug!("start={:?} end={:?}", start, end_pt);
                     DrawShape::line(&pts, self.layer.transition.color, border.1)
                 }
@@ -131,6 +134,7 @@ impl ShapeView {
                     border.0,
                     border.1,
                 ),
+                ShapeDef::Path(path) => path.translated(self.layer.frame.pos).draw(color, border.0, border.1),
                 _ => Mesh::new(),
             }
         };
@@ -0,0 +1,734 @@
+/// SplitView
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Vector},
+    graphics::{Color, MeshTask},
+    input::{Key, MouseCursor},
+    lifecycle::Window,
+};
+
+use std::any::TypeId;
+
+use super::*;
+
+/// The duration in seconds of the collapse and expand animation
+const COLLAPSE_DURATION: f64 = 0.25;
+/// The default thickness of a divider
+const DIVIDER_WIDTH: f32 = 6.0;
+/// The extra distance on each side of a divider that can be dragged
+const DIVIDER_SLOP: f32 = 3.0;
+/// The maximum time in seconds between two clicks on a divider for a double-click
+const DOUBLE_CLICK_TIME: f64 = 0.4;
+
+//-- SplitPane -----------------------------------------------------------------------
+
+/// The direction in which the panes of a SplitView are arranged
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SplitAxis {
+    /// Panes are arranged left to right with vertical dividers
+    Horizontal,
+    /// Panes are arranged top to bottom with horizontal dividers
+    Vertical,
+}
+
+/// A Scene in a SplitView with its size limits. Sizes are measured along the axis of the SplitView.
+pub struct SplitPane {
+    /// The Scene that is displayed in the pane. Build it with a 0,0 origin since the SplitView moves it.
+    pub scene: Scene,
+    /// The smallest size the pane can be dragged to
+    pub min_size: f32,
+    /// The largest size the pane can be dragged to
+    pub max_size: f32,
+    /// Can the pane be collapsed by double-clicking its divider?
+    pub collapsible: bool,
+    /// The initial size. Panes without one share the remaining space.
+    preferred_size: Option<f32>,
+    /// The current size
+    size: f32,
+    /// The size to restore when the pane is expanded
+    restore_size: f32,
+    is_collapsed: bool,
+    /// The frame from the last layout, used to skip moving the Scene when nothing changed
+    frame: Rectangle,
+    /// Called with the Scene and its new frame when the pane is moved or resized
+    on_layout: Option<Box<dyn FnMut(&mut Scene, &Rectangle) + 'static>>,
+}
+
+impl SplitPane {
+    /// Constructor
+    pub fn new(scene: Scene) -> Self {
+        SplitPane {
+            scene,
+            min_size: 0.0,
+            max_size: std::f32::MAX,
+            collapsible: false,
+            preferred_size: None,
+            size: 0.0,
+            restore_size: 0.0,
+            is_collapsed: false,
+            frame: Rectangle::new_sized((0.0, 0.0)),
+            on_layout: None,
+        }
+    }
+
+    /// Builder method to set the initial size
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.preferred_size = Some(size);
+        self
+    }
+
+    /// Builder method to set the smallest and largest sizes
+    pub fn with_limits(mut self, min_size: f32, max_size: f32) -> Self {
+        self.min_size = min_size.max(0.0);
+        self.max_size = max_size.max(self.min_size);
+        self
+    }
+
+    /// Builder method to allow collapsing the pane
+    pub fn with_collapsible(mut self, collapsible: bool) -> Self {
+        self.collapsible = collapsible;
+        self
+    }
+
+    /// Builder method to set a callback that moves or resizes the views in the Scene to fit the new frame of
+    /// the pane. It is called whenever the frame changes, including during a drag or animation.
+    pub fn with_layout(mut self, cb: Box<dyn FnMut(&mut Scene, &Rectangle) + 'static>) -> Self {
+        self.on_layout = Some(cb);
+        self
+    }
+
+    /// The current size along the axis of the SplitView
+    pub fn size(&self) -> f32 {
+        self.size
+    }
+
+    /// Is the pane collapsed?
+    pub fn is_collapsed(&self) -> bool {
+        self.is_collapsed
+    }
+
+    /// The current frame of the pane
+    pub fn frame(&self) -> Rectangle {
+        self.frame
+    }
+
+    /// Limit the size to min_size and max_size
+    fn clamp(&self, size: f32) -> f32 {
+        size.max(self.min_size).min(self.max_size)
+    }
+}
+
+/// A collapse or expand animation between a pane and its neighbor
+struct SplitAnimation {
+    pane: usize,
+    neighbor: usize,
+    /// The sizes of the pane and neighbor at the start
+    from: (f32, f32),
+    /// The sizes of the pane and neighbor at the end
+    to: (f32, f32),
+    /// Drives the animation. The frame height goes from 0.0 to 1.0.
+    driver: Layer,
+}
+
+//-- SplitView -----------------------------------------------------------------------
+
+/// A container that displays two or more Scenes side by side, separated by dividers that can be dragged to
+/// resize the panes on either side within their size limits. Double-clicking a divider collapses the
+/// collapsible pane next to it, or expands it again, with an animation.
+///
+/// The Scenes of the panes are moved and resized as the dividers move. Views inside the Scenes keep their
+/// positions relative to the Scene unless the pane has a layout callback. Since Scenes are not clipped, panes
+/// that are smaller than their minimum size are drawn first so that the other panes cover their overflow.
+pub struct SplitView {
+    /// The base layer
+    pub layer: Layer,
+    /// Horizontal or Vertical
+    pub axis: SplitAxis,
+    /// The thickness of the dividers
+    pub divider_width: f32,
+    /// The panes in order
+    panes: Vec<SplitPane>,
+    /// The divider being dragged, with the starting mouse position and the starting sizes of the panes on
+    /// either side
+    drag: Option<(usize, f32, f32, f32)>,
+    /// The divider under the mouse
+    hover_divider: Option<usize>,
+    /// The pane under the mouse, which receives scroll events
+    hover_pane: Option<usize>,
+    /// The pane that was last clicked, which receives key events
+    active_pane: Option<usize>,
+    /// The divider and time of the last click, to detect double-clicks
+    last_click: Option<(usize, f64)>,
+    animation: Option<SplitAnimation>,
+    /// Theme colors
+    divider_color: Color,
+    highlight_color: Color,
+    /// The node path of this layer, which is saved to load panes that are added later
+    parent_nodes: Option<Vec<NodeID>>,
+    /// Indexes of panes waiting to be loaded
+    load_queue: Vec<usize>,
+    /// Indexes of panes that were loaded in update() and need the theme in the next render()
+    theme_queue: Vec<usize>,
+    /// SplitEvents waiting to be dispatched in the next update()
+    pending_events: Vec<SplitEvent>,
+}
+
+impl SplitView {
+    /// Constructor
+    pub fn new(frame: Rectangle, axis: SplitAxis) -> Self {
+        SplitView {
+            layer: Layer::new(frame),
+            axis,
+            divider_width: DIVIDER_WIDTH,
+            panes: Vec::new(),
+            drag: None,
+            hover_divider: None,
+            hover_pane: None,
+            active_pane: None,
+            last_click: None,
+            animation: None,
+            divider_color: Color::from_hex("#CCCCCC"),
+            highlight_color: Color::from_hex("#80A4C2"),
+            parent_nodes: None,
+            load_queue: Vec::new(),
+            theme_queue: Vec::new(),
+            pending_events: Vec::new(),
+        }
+    }
+
+    /// Builder method to add panes
+    pub fn with_panes(mut self, panes: Vec<SplitPane>) -> Self {
+        for pane in panes {
+            self.add_pane(pane);
+        }
+        self
+    }
+
+    /// Builder method to set the thickness of the dividers
+    pub fn with_divider_width(mut self, width: f32) -> Self {
+        self.divider_width = width.max(1.0);
+        self.fit_panes();
+        self
+    }
+
+    /// Add a pane at the end and return its index. The sizes of all panes are recalculated.
+    pub fn add_pane(&mut self, pane: SplitPane) -> usize {
+        self.panes.push(pane);
+        let index = self.panes.len() - 1;
+        if self.parent_nodes.is_some() {
+            self.load_queue.push(index);
+        }
+        self.fit_panes();
+        index
+    }
+
+    /// The number of panes
+    pub fn pane_count(&self) -> usize {
+        self.panes.len()
+    }
+
+    /// The pane at the index
+    pub fn pane(&self, index: usize) -> Option<&SplitPane> {
+        self.panes.get(index)
+    }
+
+    /// The mutable pane at the index
+    pub fn pane_mut(&mut self, index: usize) -> Option<&mut SplitPane> {
+        self.panes.get_mut(index)
+    }
+
+    /// Move the divider so that the pane before it has the size, within the limits of both panes
+    pub fn set_divider_position(&mut self, divider: usize, size: f32) {
+        if divider + 1 >= self.panes.len() {
+            return;
+        }
+        self.finish_animation();
+        let total = self.panes[divider].size + self.panes[divider + 1].size;
+        self.resize_pair(divider, size, total);
+        self.layout_panes(false);
+    }
+
+    /// Collapse the pane, giving its space to the pane after it, or before it for the last pane. Returns false
+    /// if the pane cannot be collapsed.
+    pub fn collapse_pane(&mut self, index: usize, animated: bool) -> bool {
+        let neighbor = match self.neighbor_of(index) {
+            Some(neighbor) if self.panes[index].collapsible && !self.panes[index].is_collapsed => neighbor,
+            _ => return false,
+        };
+        self.finish_animation();
+        let (size, other) = (self.panes[index].size, self.panes[neighbor].size);
+        self.panes[index].restore_size = size;
+        self.panes[index].is_collapsed = true;
+        self.start_animation(index, neighbor, (0.0, size + other), animated);
+        true
+    }
+
+    /// Expand a collapsed pane to the size it had before it was collapsed. Returns false if the pane is not
+    /// collapsed.
+    pub fn expand_pane(&mut self, index: usize, animated: bool) -> bool {
+        let neighbor = match self.neighbor_of(index) {
+            Some(neighbor) if self.panes[index].is_collapsed => neighbor,
+            _ => return false,
+        };
+        self.finish_animation();
+        let total = self.panes[index].size + self.panes[neighbor].size;
+        let pane = &self.panes[index];
+        let size = pane.clamp(pane.restore_size).min(total - self.panes[neighbor].min_size).max(0.0);
+        self.panes[index].is_collapsed = false;
+        self.start_animation(index, neighbor, (size, total - size), animated);
+        true
+    }
+
+    /// Collapse or expand the collapsible pane next to the divider, preferring the pane before it
+    pub fn toggle_divider(&mut self, divider: usize, animated: bool) -> bool {
+        if divider + 1 >= self.panes.len() {
+            return false;
+        }
+        let index = if self.panes[divider].collapsible { divider } else { divider + 1 };
+        if self.panes[index].is_collapsed {
+            self.expand_pane(index, animated)
+        } else {
+            self.collapse_pane(index, animated)
+        }
+    }
+
+    /// The length of the frame along the axis
+    fn length(&self) -> f32 {
+        match self.axis {
+            SplitAxis::Horizontal => self.layer.frame.width(),
+            SplitAxis::Vertical => self.layer.frame.height(),
+        }
+    }
+
+    /// The position of a point along the axis
+    fn coordinate(&self, pt: &Vector) -> f32 {
+        match self.axis {
+            SplitAxis::Horizontal => pt.x,
+            SplitAxis::Vertical => pt.y,
+        }
+    }
+
+    /// The pane that takes the space of a collapsed pane
+    fn neighbor_of(&self, index: usize) -> Option<usize> {
+        if index >= self.panes.len() || self.panes.len() < 2 {
+            return None;
+        }
+        Some(if index + 1 < self.panes.len() { index + 1 } else { index - 1 })
+    }
+
+    /// Calculate the sizes of all panes from their preferred sizes and limits so that they fill the frame
+    fn fit_panes(&mut self) {
+        if self.panes.is_empty() {
+            return;
+        }
+        let available = (self.length() - self.divider_width * (self.panes.len() - 1) as f32).max(0.0);
+        let fixed: f32 = self.panes.iter().filter_map(|pane| pane.preferred_size).sum();
+        let flexible = self.panes.iter().filter(|pane| pane.preferred_size.is_none()).count();
+        let share = if flexible > 0 { (available - fixed).max(0.0) / flexible as f32 } else { 0.0 };
+        for pane in &mut self.panes {
+            pane.size = if pane.is_collapsed { 0.0 } else { pane.clamp(pane.preferred_size.unwrap_or(share)) };
+        }
+        // Give any remaining space to the panes from the last to the first, within their limits
+        let mut remaining = available - self.panes.iter().map(|pane| pane.size).sum::<f32>();
+        for pane in self.panes.iter_mut().rev().filter(|pane| !pane.is_collapsed) {
+            if remaining.abs() < 0.5 {
+                break;
+            }
+            let size = pane.clamp(pane.size + remaining);
+            remaining -= size - pane.size;
+            pane.size = size;
+        }
+        self.layout_panes(true);
+    }
+
+    /// Set the size of the pane before the divider and give the rest of the total to the pane after it
+    fn resize_pair(&mut self, divider: usize, size: f32, total: f32) {
+        let (first, second) = (&self.panes[divider], &self.panes[divider + 1]);
+        let low = first.min_size.max(total - second.max_size);
+        let high = first.max_size.min(total - second.min_size);
+        let size = if low > high { first.size } else { size.max(low).min(high) };
+        self.panes[divider].size = size;
+        self.panes[divider].is_collapsed = false;
+        self.panes[divider + 1].size = total - size;
+        self.panes[divider + 1].is_collapsed = false;
+    }
+
+    /// Animate or set the sizes of a pane and its neighbor
+    fn start_animation(&mut self, pane: usize, neighbor: usize, to: (f32, f32), animated: bool) {
+        let from = (self.panes[pane].size, self.panes[neighbor].size);
+        let mut driver = Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0)));
+        if animated {
+            let propset = PropSet::new(vec![size(1.0, 1.0)], COLLAPSE_DURATION).ease(Ease::SineInOut);
+            driver.animate_with_props(propset, true);
+        }
+        self.animation = Some(SplitAnimation { pane, neighbor, from, to, driver });
+        if !animated {
+            self.finish_animation();
+        }
+    }
+
+    /// Jump to the end of the animation and dispatch its event
+    fn finish_animation(&mut self) {
+        if let Some(animation) = self.animation.take() {
+            self.panes[animation.pane].size = animation.to.0;
+            self.panes[animation.neighbor].size = animation.to.1;
+            let event = {
+                if self.panes[animation.pane].is_collapsed {
+                    SplitEvent::Collapsed(animation.pane)
+                } else {
+                    SplitEvent::Expanded(animation.pane)
+                }
+            };
+            self.pending_events.push(event);
+            self.layout_panes(false);
+        }
+    }
+
+    /// The frame of the divider after the pane at the index
+    fn divider_frame(&self, divider: usize) -> Rectangle {
+        let frame = self.layer.frame;
+        let offset: f32 =
+            self.panes[..=divider].iter().map(|pane| pane.size + self.divider_width).sum::<f32>() - self.divider_width;
+        match self.axis {
+            SplitAxis::Horizontal => {
+                Rectangle::new((frame.x() + offset, frame.y()), (self.divider_width, frame.height()))
+            }
+            SplitAxis::Vertical => Rectangle::new((frame.x(), frame.y() + offset), (frame.width(), self.divider_width)),
+        }
+    }
+
+    /// The divider at the point, including the slop on each side
+    fn divider_at(&self, pt: &Vector) -> Option<usize> {
+        (0..self.panes.len().saturating_sub(1)).find(|divider| {
+            let rect = self.divider_frame(*divider);
+            let rect = match self.axis {
+                SplitAxis::Horizontal => UITools::padded_rect(&rect, DIVIDER_SLOP, 0.0),
+                SplitAxis::Vertical => UITools::padded_rect(&rect, 0.0, DIVIDER_SLOP),
+            };
+            pt.overlaps_rectangle(&rect)
+        })
+    }
+
+    /// The pane at the point
+    fn pane_at(&self, pt: &Vector) -> Option<usize> {
+        self.panes.iter().position(|pane| pane.size >= 1.0 && pt.overlaps_rectangle(&pane.frame))
+    }
+
+    /// Move and resize the Scenes of the panes whose frames changed, or all of them if forced
+    fn layout_panes(&mut self, force: bool) {
+        let frame = self.layer.frame;
+        let axis = self.axis;
+        let mut offset = 0.0;
+        for pane in &mut self.panes {
+            let rect = match axis {
+                SplitAxis::Horizontal => Rectangle::new((frame.x() + offset, frame.y()), (pane.size, frame.height())),
+                SplitAxis::Vertical => Rectangle::new((frame.x(), frame.y() + offset), (frame.width(), pane.size)),
+            };
+            offset += pane.size + self.divider_width;
+            if !force && rect == pane.frame {
+                continue;
+            }
+            pane.frame = rect;
+            pane.scene.layer.frame.size = rect.size;
+            // Like a completed Scene move, notify(Moved) clears the meshes that were cached at the old position
+            pane.scene.align_view(rect.pos);
+            pane.scene.notify(&DisplayEvent::Moved);
+            if let Some(cb) = &mut pane.on_layout {
+                cb(&mut pane.scene, &rect);
+            }
+        }
+    }
+
+    fn load_scene(scene: &mut Scene, parent_nodes: &[NodeID], ctx: &mut StageContext, app_state: &mut AppState) {
+        scene.set_id(app_state.new_id());
+        scene.get_layer_mut().set_path(parent_nodes);
+        scene.view_will_load(ctx, app_state);
+    }
+
+    fn active_scene_mut(&mut self) -> Option<&mut Scene> {
+        let index = self.active_pane?;
+        self.panes.get_mut(index).map(|pane| &mut pane.scene)
+    }
+
+    fn resize_cursor(&self) -> MouseCursor {
+        match self.axis {
+            SplitAxis::Horizontal => MouseCursor::ColResize,
+            SplitAxis::Vertical => MouseCursor::RowResize,
+        }
+    }
+}
+
+// *****************************************************************************************************
+// SplitView :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for SplitView {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<SplitView>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+        self.layout_panes(false);
+    }
+
+    fn align_view(&mut self, origin: Vector) {
+        self.layer.frame.pos = self.layer.anchor_pt + origin;
+        self.layout_panes(false);
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        // Don't allow style to be locked
+        let _ = self.layer.apply_theme(theme);
+
+        self.divider_color = theme.border_color;
+        self.highlight_color = theme.cursor_color;
+        for pane in &mut self.panes {
+            pane.scene.set_theme(theme);
+        }
+    }
+
+    fn handle_event(&mut self, event: &EventBox, app_state: &mut AppState) {
+        for pane in &mut self.panes {
+            pane.scene.handle_event(event, app_state);
+        }
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+        for pane in &mut self.panes {
+            pane.scene.notify(event);
+        }
+    }
+
+    fn update(&mut self, window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        if let Some(parent_nodes) = &self.parent_nodes {
+            let mut ctx = StageContext::new();
+            for index in self.load_queue.drain(..) {
+                if let Some(pane) = self.panes.get_mut(index) {
+                    SplitView::load_scene(&mut pane.scene, parent_nodes, &mut ctx, state);
+                    pane.scene.notify(&DisplayEvent::Ready);
+                    self.theme_queue.push(index);
+                }
+            }
+        }
+
+        let finished = match &mut self.animation {
+            Some(animation) => {
+                animation.driver.tween_update(state);
+                let progress = animation.driver.frame.height().max(0.0).min(1.0);
+                let lerp = |from: f32, to: f32| from + (to - from) * progress;
+                self.panes[animation.pane].size = lerp(animation.from.0, animation.to.0);
+                self.panes[animation.neighbor].size = lerp(animation.from.1, animation.to.1);
+                animation.driver.animation.as_ref().map_or(true, |tween| tween.state == PlayState::Completed)
+            }
+            None => false,
+        };
+        if finished {
+            self.finish_animation();
+        } else if self.animation.is_some() {
+            self.layout_panes(false);
+        }
+
+        for pane in &mut self.panes {
+            pane.scene.update(window, state);
+        }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        self.layer.draw_background(window);
+        for index in self.theme_queue.drain(..) {
+            if let Some(pane) = self.panes.get_mut(index) {
+                pane.scene.set_theme(theme);
+            }
+        }
+        // Panes squeezed below their minimum size are drawn first so the other panes cover their overflow
+        let (squeezed, others): (Vec<&mut SplitPane>, Vec<&mut SplitPane>) =
+            self.panes.iter_mut().filter(|pane| pane.size >= 1.0).partition(|pane| pane.size < pane.min_size);
+        for pane in squeezed.into_iter().chain(others) {
+            pane.scene.render(theme, window);
+        }
+
+        let mut task = MeshTask::new(0);
+        for divider in 0..self.panes.len().saturating_sub(1) {
+            let rect = self.divider_frame(divider);
+            let active = self.drag.map(|drag| drag.0).or(self.hover_divider) == Some(divider);
+            if active {
+                let color = Color { a: 0.5, ..self.highlight_color };
+                let mut mesh = DrawShape::rectangle(&rect, Some(color), None, 0.0, 0.0);
+                task.append(&mut mesh);
+            }
+            let center = rect.center();
+            let pts: [&Vector; 2] = match self.axis {
+                SplitAxis::Horizontal => {
+                    [&Vector::new(center.x, rect.y()), &Vector::new(center.x, rect.y() + rect.height())]
+                }
+                SplitAxis::Vertical => {
+                    [&Vector::new(rect.x(), center.y), &Vector::new(rect.x() + rect.width(), center.y)]
+                }
+            };
+            let mut line = DrawShape::line(&pts, self.divider_color, 1.0);
+            task.append(&mut line);
+        }
+        window.add_task(task);
+        self.layer.draw_border(window);
+    }
+
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        if let Some((divider, start, first, second)) = self.drag {
+            // A click without movement may be the first half of a double-click
+            if self.coordinate(pt) == start {
+                return true;
+            }
+            let size = first + self.coordinate(pt) - start;
+            self.resize_pair(divider, size, first + second);
+            self.layout_panes(false);
+            window.set_cursor(self.resize_cursor());
+            return true;
+        }
+        self.hover_divider = self.divider_at(pt);
+        if self.hover_divider.is_some() && self.animation.is_none() {
+            self.hover_pane = None;
+            window.set_cursor(self.resize_cursor());
+            return true;
+        }
+        self.hover_pane = self.pane_at(pt);
+        if let Some(index) = self.hover_pane {
+            return self.panes[index].scene.handle_mouse_at(pt, window);
+        }
+        false
+    }
+
+    fn layer_at(&self, pt: &Vector, filter: &dyn Fn(&Layer) -> bool) -> Option<&Layer> {
+        if let Some(index) = self.pane_at(pt) {
+            if let Some(layer) = self.panes[index].scene.layer_at(pt, filter) {
+                return Some(layer);
+            }
+        }
+        if pt.overlaps_rectangle(&self.layer.frame) && filter(&self.layer) {
+            return Some(&self.layer);
+        }
+        None
+    }
+
+    fn view_will_load(&mut self, ctx: &mut StageContext, app_state: &mut AppState) {
+        let parent_nodes = self.layer.node_path.nodes.clone();
+        app_state.append_node(self.layer.node_path.clone());
+
+        for pane in &mut self.panes {
+            SplitView::load_scene(&mut pane.scene, &parent_nodes, ctx, app_state);
+        }
+        self.load_queue.clear();
+        self.parent_nodes = Some(parent_nodes);
+        self.layout_panes(true);
+    }
+}
+
+// *****************************************************************************************************
+// SplitView :: Responder
+// *****************************************************************************************************
+
+impl Responder for SplitView {
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        for pane in &mut self.panes {
+            if pane.scene.set_field_value(value, type_id, layer_id) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Dragging a divider resizes the panes on either side and double-clicking it collapses or expands a pane.
+    /// Returns true for any click inside the frame so that key commands are passed to the clicked pane.
+    fn handle_mouse_down(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        if let Some(divider) = self.divider_at(pt) {
+            let now = state.clock.current_time();
+            match self.last_click {
+                Some((last, time)) if last == divider && now - time < DOUBLE_CLICK_TIME => {
+                    self.last_click = None;
+                    self.toggle_divider(divider, true);
+                }
+                _ => {
+                    self.last_click = Some((divider, now));
+                    self.finish_animation();
+                    let (first, second) = (self.panes[divider].size, self.panes[divider + 1].size);
+                    self.drag = Some((divider, self.coordinate(pt), first, second));
+                }
+            }
+            return true;
+        }
+        self.active_pane = self.pane_at(pt);
+        if let Some(scene) = self.active_scene_mut() {
+            scene.handle_mouse_down(pt, state);
+        }
+        pt.overlaps_rectangle(&self.layer.frame)
+    }
+
+    fn handle_mouse_up(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        if let Some((divider, start, _, _)) = self.drag.take() {
+            if (self.coordinate(pt) - start).abs() > 0.0 {
+                self.pending_events.push(SplitEvent::Resized(divider));
+            }
+            return true;
+        }
+        if let Some(scene) = self.active_scene_mut() {
+            return scene.handle_mouse_up(pt, state);
+        }
+        false
+    }
+
+    fn handle_mouse_scroll(&mut self, pt: &Vector, state: &mut AppState) {
+        if let Some(pane) = self.hover_pane.and_then(|index| self.panes.get_mut(index)) {
+            pane.scene.handle_mouse_scroll(pt, state);
+        }
+    }
+
+    fn handle_key_press(&mut self, c: char, window: &mut Window) {
+        if let Some(scene) = self.active_scene_mut() {
+            scene.handle_key_press(c, window);
+        }
+    }
+
+    fn handle_text_input(&mut self, event: &TextInputEvent, window: &mut Window) -> bool {
+        if let Some(scene) = self.active_scene_mut() {
+            return scene.handle_text_input(event, window);
+        }
+        false
+    }
+
+    fn handle_key_command(&mut self, key: &Key, window: &mut Window) -> bool {
+        if let Some(scene) = self.active_scene_mut() {
+            return scene.handle_key_command(key, window);
+        }
+        false
+    }
+}
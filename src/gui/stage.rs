@@ -3,6 +3,7 @@
 use super::*;
 use crate::core::*;
 use crate::events::*;
+use crate::tools::*;
 
 use std::{
     any::TypeId,
@@ -10,9 +11,10 @@ use std::{
 };
 
 use quicksilver::{
-    geom::{Rectangle, Vector},
+    geom::{Rectangle, Shape, Vector},
+    graphics::{Color, MeshTask},
     input::Key,
-    lifecycle::Window,
+    lifecycle::{Asset, Window},
 };
 
 //-- Main -----------------------------------------------------------------------
@@ -26,13 +28,42 @@ pub struct Stage {
     pub(crate) scenes: BTreeMap<u32, Scene>,
     /// Holds event listeners
     pub context: StageContext,
+    /// The color of the backdrop behind a modal that is fully presented
+    pub backdrop_color: Color,
+    /// The modal scenes, where the key is the modal id
+    modals: BTreeMap<u32, Modal>,
+    /// The ids of the presented modals from bottom to top
+    modal_stack: Vec<u32>,
+    /// The next modal id
+    next_modal_id: u32,
+    /// ModalEvents waiting to be dispatched in the next update()
+    pending_events: Vec<ModalEvent>,
+    /// Shows the tooltip of the Layer under the mouse above all scenes
+    tooltip_view: TooltipView,
+    /// The menus, the MenuBar and the open menu
+    menu_view: MenuView,
+    /// The queue and stack of toasts
+    toast_view: ToastView,
 }
 
 impl Stage {
     /// Constructor
     pub fn new(frame: Rectangle) -> Self {
         let layer = Layer::new(frame);
-        Stage { layer, title: String::default(), scenes: BTreeMap::new(), context: StageContext::new() }
+        Stage {
+            layer,
+            title: String::default(),
+            scenes: BTreeMap::new(),
+            context: StageContext::new(),
+            backdrop_color: Color { r: 0.0, g: 0.0, b: 0.0, a: 0.4 },
+            modals: BTreeMap::new(),
+            modal_stack: Vec::new(),
+            next_modal_id: 1,
+            pending_events: Vec::new(),
+            tooltip_view: TooltipView::new(),
+            menu_view: MenuView::new(),
+            toast_view: ToastView::new(),
+        }
     }
 
     pub fn add_scene(&mut self, scene: Scene) {
@@ -40,6 +71,189 @@ impl Stage {
         self.scenes.insert(id, scene);
     }
 
+    /// Add a Scene that can be presented modally and return the modal id. If dismissable is true, Escape,
+    /// NavEvent::Close and a click on the backdrop dismiss it. Modals must be added before stage_ready().
+    pub fn add_modal(&mut self, scene: Scene, dismissable: bool) -> u32 {
+        let id = self.next_modal_id;
+        self.next_modal_id += 1;
+        self.modals.insert(id, Modal::new(scene, dismissable));
+        id
+    }
+
+    /// Add an alert or confirmation Dialog centered in the Stage and return the modal id.
+    pub fn add_dialog(&mut self, dialog: Dialog) -> u32 {
+        let id = self.next_modal_id;
+        self.next_modal_id += 1;
+        self.modals.insert(id, dialog.build(id, &self.layer.frame));
+        id
+    }
+
+    /// Present the modal above the scenes and any other presented modals. Returns false if there is no
+    /// modal with the id or it is already presented.
+    pub fn present_modal(&mut self, id: u32) -> bool {
+        if self.modal_stack.contains(&id) {
+            return false;
+        }
+        if let Some(modal) = self.modals.get_mut(&id) {
+            modal.present();
+            self.modal_stack.push(id);
+            return true;
+        }
+        false
+    }
+
+    /// Start the dismiss animation of the modal. The modal keeps blocking input until the animation has
+    /// finished.
+    pub fn dismiss_modal(&mut self, id: u32) -> bool {
+        if !self.modal_stack.contains(&id) {
+            return false;
+        }
+        if let Some(modal) = self.modals.get_mut(&id) {
+            if !modal.closing {
+                modal.dismiss();
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns true if any modal is presented
+    pub fn has_modal(&self) -> bool {
+        !self.modal_stack.is_empty()
+    }
+
+    /// The id of the modal on top
+    pub fn top_modal_id(&self) -> Option<u32> {
+        self.modal_stack.last().cloned()
+    }
+
+    /// The modal Scene for the id
+    pub fn modal_scene_mut(&mut self, id: u32) -> Option<&mut Scene> {
+        self.modals.get_mut(&id).map(|modal| &mut modal.scene)
+    }
+
+    /// Dismiss the top modal if it is dismissable. A Dialog chooses its cancel button, which is handled
+    /// when the ModalEvent::Choice comes back through handle_event().
+    fn cancel_top_modal(&mut self) {
+        let id = match self.modal_stack.last() {
+            Some(id) => *id,
+            None => return,
+        };
+        if let Some(modal) = self.modals.get(&id) {
+            if !modal.dismissable || modal.closing {
+                return;
+            }
+            match modal.cancel_choice {
+                Some(index) => self.pending_events.push(ModalEvent::Choice(id, index)),
+                None => {
+                    self.dismiss_modal(id);
+                }
+            }
+        }
+    }
+
+    /// The top modal that can receive input. Returns None if there are no modals and Some(None) while the
+    /// top modal is animating, which blocks input to everything.
+    fn input_modal(&mut self) -> Option<Option<&mut Modal>> {
+        let id = *self.modal_stack.last()?;
+        match self.modals.get_mut(&id) {
+            Some(modal) if !modal.closing && modal.is_finished() => Some(Some(modal)),
+            _ => Some(None),
+        }
+    }
+
+    /// Add a Menu that can be opened with popup_menu() or as the context menu of a Layer and return the menu
+    /// id. The accelerators of the menu items are added to the accelerator registry of the Stage.
+    pub fn add_menu(&mut self, menu: Menu) -> u32 {
+        self.menu_view.add_menu(menu)
+    }
+
+    /// Set the MenuBar, which is drawn above the scenes. The menus of the bar are added to the Stage.
+    pub fn set_menu_bar(&mut self, bar: MenuBar) {
+        self.menu_view.set_bar(bar);
+    }
+
+    /// Add an icon image that menu items can refer to by name
+    pub fn add_menu_icon(&mut self, name: &str, asset: Asset<Vec<u8>>) {
+        self.menu_view.add_icon(name, asset);
+    }
+
+    /// The menu for the id, which can be used to change the checked and enabled state of its items
+    pub fn menu_mut(&mut self, id: u32) -> Option<&mut Menu> {
+        self.menu_view.menus.get_mut(&id)
+    }
+
+    /// The keyboard shortcuts of all menu items
+    pub fn accelerators(&self) -> &AcceleratorRegistry {
+        &self.menu_view.accelerators
+    }
+
+    /// Open the menu with its top left corner at the point. Returns false if there is no menu with the id.
+    pub fn popup_menu(&mut self, id: u32, pt: &Vector) -> bool {
+        self.tooltip_view.dismiss();
+        self.menu_view.open(id, *pt, None)
+    }
+
+    /// Open the context menu of the topmost Layer at the point. AppDelegate calls this on a right click.
+    pub fn show_context_menu(&mut self, pt: &Vector) -> bool {
+        match self.layer_at(pt, &|layer| layer.context_menu.is_some()).and_then(|layer| layer.context_menu) {
+            Some(id) => self.popup_menu(id, pt),
+            None => {
+                self.menu_view.close();
+                false
+            }
+        }
+    }
+
+    /// Close the open menu
+    pub fn close_menu(&mut self) {
+        self.menu_view.close();
+    }
+
+    /// Returns true if a menu is open
+    pub fn has_menu(&self) -> bool {
+        self.menu_view.is_open()
+    }
+
+    /// Show the toast after the toasts that are already queued. Toasts can also be shown from anywhere with
+    /// Toast::show(), which dispatches them onto the EventBus.
+    pub fn show_toast(&mut self, toast: Toast) {
+        self.toast_view.add(toast);
+    }
+
+    /// Dismiss the toasts that are shown and clear the queue
+    pub fn dismiss_toasts(&mut self) {
+        self.toast_view.dismiss_all();
+    }
+
+    /// The menus receive input while one is open or when no modal is presented
+    fn menus_active(&self) -> bool {
+        self.menu_view.is_open() || !self.has_modal()
+    }
+
+    fn load_scene(scene: &mut Scene, ctx: &mut StageContext, app_state: &mut AppState) {
+        // Stage doesn't exist as a parent path, so set empty slice as path for scene
+        scene.get_layer_mut().set_path(&[]);
+        // Pass AppState into scene to let it build the tree
+        log::debug!("STAGE LOADING SCENE {}", scene.name);
+        scene.view_will_load(ctx, app_state);
+
+        let subscriber = scene.get_layer().node_path.clone();
+
+        // Assign tag if it exists
+        if let Some(tag) = scene.get_layer().tag {
+            app_state.assign_tag(tag, subscriber.clone());
+        }
+        // If the scene is subscriber to notifications, add them here.
+        for key in &scene.get_layer().queued_observers {
+            app_state.register_observer(key.clone(), subscriber.clone())
+        }
+        // Add event listeners from node to AppState
+        for (key, cb) in scene.get_layer_mut().event_listeners.drain() {
+            ctx.add_event_listener(key, cb, subscriber.clone());
+        }
+    }
+
     /// This method must be called when the application is ready to run.
     /// It loads the view hierarchy into AppState and loads event listeners
     /// It overwrites the default/empty StageContext when finished.
@@ -63,7 +277,12 @@ impl Stage {
     pub fn find_view_by_path(&mut self, path: NodePath) -> Option<&mut Layer> {
         let mut i: usize = 0;
         if let Some(node) = path.nodes.get(i) {
-            if let Some(scene) = self.scenes.get_mut(&node.id) {
+            let modals = &mut self.modals;
+            let scene = match self.scenes.get_mut(&node.id) {
+                Some(scene) => Some(scene),
+                None => modals.values_mut().map(|modal| &mut modal.scene).find(|scene| scene.get_id() == node.id),
+            };
+            if let Some(scene) = scene {
                 // Top level is always a Scene.
                 i += 1;
                 if let Some(node) = path.nodes.get(i) {
@@ -107,26 +326,12 @@ impl Displayable for Stage {
             app_state.set_next_id(*id);
             // And this forces an increment on app_state.next_id and redundantly sets the scene.id
             scene.set_id(app_state.new_id());
-            // Stage doesn't exist as a parent path, so set empty slice as path for scene
-            scene.get_layer_mut().set_path(&[]);
-            // Pass AppState into scene to let it build the tree
-            log::debug!("STAGE LOADING SCENE {}", scene.name);
-            scene.view_will_load(ctx, app_state);
-
-            let subscriber = scene.get_layer().node_path.clone();
-
-            // Assign tag if it exists
-            if let Some(tag) = scene.get_layer().tag {
-                app_state.assign_tag(tag, subscriber.clone());
-            }
-            // If the scene is subscriber to notifications, add them here.
-            for key in &scene.get_layer().queued_observers {
-                app_state.register_observer(key.clone(), subscriber.clone())
-            }
-            // Add event listeners from node to AppState
-            for (key, cb) in scene.get_layer_mut().event_listeners.drain() {
-                ctx.add_event_listener(key, cb, subscriber.clone());
-            }
+            Stage::load_scene(scene, ctx, app_state);
+        }
+        // Modal scenes use the ids after the last scene
+        for modal in self.modals.values_mut() {
+            modal.scene.set_id(app_state.new_id());
+            Stage::load_scene(&mut modal.scene, ctx, app_state);
         }
         app_state.print_tree();
     }
@@ -164,9 +369,48 @@ impl Displayable for Stage {
         for scene in &mut self.scenes.values_mut() {
             scene.set_theme(theme);
         }
+        for modal in self.modals.values_mut() {
+            modal.scene.set_theme(theme);
+            if modal.is_dialog {
+                modal.scene.layer.bg_style = BackgroundStyle::Solid(theme.bg_color);
+                modal.scene.layer.border_style = BorderStyle::SolidLine(theme.border_color, theme.border_width);
+            }
+        }
+        self.tooltip_view.set_theme(theme);
+        self.menu_view.set_theme(theme);
+        self.toast_view.set_theme(theme);
     }
 
     fn handle_event(&mut self, event: &EventBox, app_state: &mut AppState) {
+        if let Ok(toast) = event.downcast_ref::<Toast>() {
+            self.toast_view.add(toast.clone());
+            return;
+        }
+        if let Ok(evt) = event.downcast_ref::<ModalEvent>() {
+            if let ModalEvent::Choice(id, index) = evt {
+                if let Some(modal) = self.modals.get_mut(id) {
+                    if let Some(cb) = &mut modal.on_choice {
+                        (&mut *cb)(*index, app_state);
+                    }
+                }
+                self.dismiss_modal(*id);
+            }
+            return;
+        }
+        // Modals handle NavEvent::Modal and Close before the scenes
+        if let Ok(evt) = event.downcast_ref::<NavEvent>() {
+            match evt {
+                NavEvent::Modal(id) => {
+                    self.present_modal(*id);
+                    return;
+                }
+                NavEvent::Close if self.has_modal() => {
+                    self.cancel_top_modal();
+                    return;
+                }
+                _ => (),
+            }
+        }
         // Try to locate the event using the event string and u32 sender.id
         // The sender is provided in EventBus::dispatch_event
         let sender = event.sender();
@@ -189,6 +433,16 @@ impl Displayable for Stage {
                 _ => (),
             }
         }
+        // Navigation requests are passed to the scenes so that containers like TabView can respond. Only the
+        // first container that consumes the event acts on it.
+        if event.downcast_ref::<NavEvent>().is_ok() {
+            for scene in &mut self.scenes.values_mut() {
+                scene.handle_event(event, app_state);
+                if app_state.event_consumed {
+                    break;
+                }
+            }
+        }
     }
 
     fn notify(&mut self, event: &DisplayEvent) {
@@ -204,12 +458,43 @@ impl Displayable for Stage {
                 }
             }
         }
+        for modal in self.modals.values_mut() {
+            modal.scene.notify(event);
+        }
     }
 
     fn update(&mut self, window: &mut Window, state: &mut AppState) {
         for scene in &mut self.scenes.values_mut() {
             scene.update(window, state);
         }
+        let mut dismissed: Vec<u32> = Vec::new();
+        for id in &self.modal_stack {
+            if let Some(modal) = self.modals.get_mut(id) {
+                let animating = !modal.is_finished();
+                modal.tween_update(state);
+                modal.scene.update(window, state);
+                if animating && modal.is_finished() {
+                    if modal.closing {
+                        dismissed.push(*id);
+                    } else {
+                        self.pending_events.push(ModalEvent::Presented(*id));
+                    }
+                }
+            }
+        }
+        for id in dismissed {
+            self.modal_stack.retain(|x| *x != id);
+            if let Some(modal) = self.modals.get_mut(&id) {
+                modal.closing = false;
+            }
+            self.pending_events.push(ModalEvent::Dismissed(id));
+        }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), None);
+        }
+        self.tooltip_view.update(state);
+        self.menu_view.update(state, self.layer.node_id());
+        self.toast_view.update(window, state, self.layer.node_id());
         // TODO: Implement observer actions
         state.send_notifications.clear();
     }
@@ -221,9 +506,55 @@ impl Displayable for Stage {
         for scene in &mut self.scenes.values_mut() {
             scene.render(theme, window);
         }
+        self.menu_view.render_bar(theme, window);
+        // Each modal dims everything below it
+        for id in &self.modal_stack {
+            if let Some(modal) = self.modals.get_mut(id) {
+                let color = modal.backdrop_color(self.backdrop_color);
+                let mut task = MeshTask::new(0);
+                let mut mesh = DrawShape::rectangle(&self.layer.frame, Some(color), None, 0.0, 0.0);
+                task.append(&mut mesh);
+                window.add_task(task);
+                modal.scene.render(theme, window);
+            }
+        }
+        self.toast_view.render(theme, window, &self.layer.frame);
+        self.menu_view.render(theme, window, &self.layer.frame);
+        self.tooltip_view.render(theme, window, &self.layer.frame);
+    }
+
+    /// While a modal is presented, only the top modal is searched
+    fn layer_at(&self, pt: &Vector, filter: &dyn Fn(&Layer) -> bool) -> Option<&Layer> {
+        if let Some(id) = self.modal_stack.last() {
+            return self.modals.get(id).and_then(|modal| modal.scene.layer_at(pt, filter));
+        }
+        for scene in self.scenes.values().rev() {
+            if let Some(layer) = scene.layer_at(pt, filter) {
+                return Some(layer);
+            }
+        }
+        None
     }
 
     fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        if self.menus_active() && self.menu_view.handle_mouse_at(pt) {
+            self.tooltip_view.hover(pt, None);
+            return true;
+        }
+        if self.toast_view.handle_mouse_at(pt) {
+            self.tooltip_view.hover(pt, None);
+            return true;
+        }
+        let target = self
+            .layer_at(pt, &|layer| layer.tooltip.is_some())
+            .and_then(|layer| layer.tooltip.clone().map(|tooltip| (layer.node_id(), tooltip)));
+        self.tooltip_view.hover(pt, target);
+        if let Some(modal) = self.input_modal() {
+            if let Some(modal) = modal {
+                modal.scene.handle_mouse_at(pt, window);
+            }
+            return true;
+        }
         for scene in &mut self.scenes.values_mut() {
             let hover = scene.handle_mouse_at(pt, window);
             if hover {
@@ -249,7 +580,25 @@ impl Responder for Stage {
         false
     }
 
+    /// While a modal is presented, only the top modal receives input and a click on the backdrop dismisses it
     fn handle_mouse_down(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        self.tooltip_view.dismiss();
+        if self.menus_active() && self.menu_view.handle_mouse_down(pt) {
+            return false;
+        }
+        if self.toast_view.handle_mouse_down(pt) {
+            return false;
+        }
+        if let Some(modal) = self.input_modal() {
+            if let Some(modal) = modal {
+                if pt.overlaps_rectangle(&modal.scene.get_frame()) {
+                    modal.scene.handle_mouse_down(pt, state);
+                    return false;
+                }
+                self.cancel_top_modal();
+            }
+            return false;
+        }
         for scene in &mut self.scenes.values_mut() {
             scene.handle_mouse_down(pt, state);
         }
@@ -257,6 +606,12 @@ impl Responder for Stage {
     }
 
     fn handle_mouse_up(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        if let Some(modal) = self.input_modal() {
+            if let Some(modal) = modal {
+                modal.scene.handle_mouse_up(pt, state);
+            }
+            return false;
+        }
         for scene in &mut self.scenes.values_mut() {
             scene.handle_mouse_up(pt, state);
         }
@@ -264,18 +619,70 @@ impl Responder for Stage {
     }
 
     fn handle_mouse_scroll(&mut self, pt: &Vector, state: &mut AppState) {
+        if let Some(modal) = self.input_modal() {
+            if let Some(modal) = modal {
+                modal.scene.handle_mouse_scroll(pt, state);
+            }
+            return;
+        }
         for scene in &mut self.scenes.values_mut() {
             scene.handle_mouse_scroll(pt, state);
         }
     }
 
     fn handle_key_press(&mut self, c: char, window: &mut Window) {
+        if self.menu_view.is_open() {
+            return;
+        }
+        if let Some(modal) = self.input_modal() {
+            if let Some(modal) = modal {
+                modal.scene.handle_key_press(c, window);
+            }
+            return;
+        }
         for scene in &mut self.scenes.values_mut() {
             scene.handle_key_press(c, window);
         }
     }
 
+    fn handle_text_input(&mut self, event: &TextInputEvent, window: &mut Window) -> bool {
+        if let Some(modal) = self.input_modal() {
+            return modal.map_or(false, |modal| modal.scene.handle_text_input(event, window));
+        }
+        let mut handled = false;
+        for scene in &mut self.scenes.values_mut() {
+            handled = scene.handle_text_input(event, window) || handled;
+        }
+        handled
+    }
+
+    /// While a modal is presented, Escape dismisses it and Return chooses the default button of a Dialog
+    /// unless the focused control handles the key.
     fn handle_key_command(&mut self, key: &Key, window: &mut Window) -> bool {
+        let modifiers = KeyModifiers::from_keyboard(window.keyboard());
+        if self.menus_active() && self.menu_view.handle_key(key, &modifiers) {
+            return true;
+        }
+        let top_id = self.modal_stack.last().cloned();
+        if let Some(modal) = self.input_modal() {
+            let modal = match modal {
+                Some(modal) => modal,
+                None => return true,
+            };
+            if modal.scene.handle_key_command(key, window) {
+                return true;
+            }
+            match key {
+                Key::Escape => self.cancel_top_modal(),
+                Key::Return => {
+                    if let (Some(id), Some(index)) = (top_id, modal.default_choice) {
+                        self.pending_events.push(ModalEvent::Choice(id, index));
+                    }
+                }
+                _ => (),
+            }
+            return true;
+        }
         for scene in &mut self.scenes.values_mut() {
             scene.handle_key_command(key, window);
         }
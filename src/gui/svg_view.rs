@@ -0,0 +1,227 @@
+/// SvgView is a Tweenable object that displays an SVG document
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Vector},
+    graphics::{Color, MeshTask},
+    lifecycle::{Asset, Window},
+};
+
+use std::any::TypeId;
+
+use super::*;
+
+//-- Main -----------------------------------------------------------------------
+
+/// A view for resolution-independent icons and artwork, which tessellates the SVG document for the current frame
+/// size. The tint replaces the color of every shape and keeps its opacity, like template images in iOS, and it
+/// can be animated with Prop::Tint.
+pub struct SvgView {
+    /// The base layer
+    pub layer: Layer,
+    /// Optional file bytes that are parsed when loaded
+    loader: Option<Asset<Vec<u8>>>,
+    /// The parsed document
+    svg: Option<DrawSvg>,
+    /// How to scale the document to fit in the Layer frame
+    scale_mode: ImageScaleMode,
+    /// The color that replaces the colors of the document
+    tint: Option<Color>,
+    /// The last known tint of the layer transition, for detecting tint animations
+    transition_tint: Color,
+    /// The mesh at position 0,0 with the frame size and tint it was drawn for
+    content: Option<(MeshTask, Vector, Option<Color>)>,
+}
+
+impl SvgView {
+    /// Constructor with the bytes of an SVG file that is loaded as an asset
+    pub fn new(frame: Rectangle, asset: Option<Asset<Vec<u8>>>) -> Self {
+        let layer = Layer::new(frame);
+        let transition_tint = layer.transition.tint;
+        SvgView {
+            layer,
+            loader: asset,
+            svg: None,
+            scale_mode: ImageScaleMode::AspectFit,
+            tint: None,
+            transition_tint,
+            content: None,
+        }
+    }
+
+    /// Constructor with a document that is already parsed
+    pub fn with_svg(frame: Rectangle, svg: DrawSvg) -> Self {
+        let mut view = SvgView::new(frame, None);
+        view.svg = Some(svg);
+        view
+    }
+
+    /// Builder method to set the ImageScaleMode. The default is AspectFit.
+    pub fn with_scale_mode(mut self, scale_mode: ImageScaleMode) -> Self {
+        self.scale_mode = scale_mode;
+        self
+    }
+
+    /// Builder method to set the tint color
+    pub fn with_tint(mut self, color: Color) -> Self {
+        self.set_tint(color);
+        self
+    }
+
+    /// Replace the document with the bytes of another SVG file
+    pub fn set_asset(&mut self, asset: Option<Asset<Vec<u8>>>) {
+        self.loader = asset;
+        self.svg = None;
+        self.content = None;
+    }
+
+    /// Remove the tint and show the colors of the document
+    pub fn clear_tint(&mut self) {
+        self.tint = None;
+    }
+
+    /// Parse the asset after it has loaded. Invalid documents are logged and dropped.
+    fn load_svg(&mut self) {
+        if self.svg.is_some() {
+            return;
+        }
+        let mut result: Option<Result<DrawSvg, SvgError>> = None;
+        if let Some(loader) = &mut self.loader {
+            loader
+                .execute(|bytes| {
+                    result = Some(DrawSvg::from_bytes(bytes.as_slice()));
+                    Ok(())
+                })
+                .expect("Asset loading failed");
+        }
+        match result {
+            Some(Ok(svg)) => {
+                self.svg = Some(svg);
+                self.loader = None;
+            }
+            Some(Err(err)) => {
+                log::error!("Unable to load SVG: {}", err);
+                self.loader = None;
+            }
+            None => (),
+        }
+    }
+
+    /// The area of the frame with origin 0,0 where the document is drawn
+    fn content_rect(&self, svg_size: Vector) -> Rectangle {
+        let frame_size = self.layer.frame.size;
+        let scale = match self.scale_mode {
+            ImageScaleMode::ScaleToFill | ImageScaleMode::NineSlice(_) => return Rectangle::new_sized(frame_size),
+            ImageScaleMode::Original => return Rectangle::new_sized(svg_size),
+            ImageScaleMode::AspectFit => (frame_size.x / svg_size.x).min(frame_size.y / svg_size.y),
+            ImageScaleMode::AspectFill => (frame_size.x / svg_size.x).max(frame_size.y / svg_size.y),
+        };
+        let size = svg_size * scale;
+        Rectangle::new((frame_size - size) / 2.0, size)
+    }
+
+    /// Get the mesh for the current frame size and tint, which is cached until either changes
+    fn content_mesh(&mut self) -> Option<MeshTask> {
+        let key = (self.layer.frame.size, self.tint);
+        if let Some((task, size, tint)) = &self.content {
+            if (*size, *tint) == key {
+                return Some(task.clone());
+            }
+        }
+        let svg_size = self.svg.as_ref()?.size();
+        if svg_size.x <= 0.0 || svg_size.y <= 0.0 {
+            return None;
+        }
+        let rect = self.content_rect(svg_size);
+        let mut task = self.svg.as_mut()?.draw(&rect);
+        if let Some(tint) = self.tint {
+            for vertex in task.vertices.iter_mut() {
+                vertex.col = Color { a: vertex.col.a * tint.a, ..tint };
+            }
+        }
+        self.content = Some((task.clone(), key.0, key.1));
+        Some(task)
+    }
+}
+
+impl Displayable for SvgView {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<SvgView>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn get_content_size(&self) -> Vector {
+        match &self.svg {
+            Some(svg) => svg.size(),
+            None => self.get_frame().size,
+        }
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        let ok = self.layer.apply_theme(theme);
+        if !ok {
+            return;
+        }
+    }
+
+    fn set_tint(&mut self, color: Color) {
+        self.tint = Some(color);
+        // Tint animations start from the current tint
+        self.layer.transition.tint = color;
+        self.transition_tint = color;
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        if self.layer.transition.tint != self.transition_tint {
+            // A Prop::Tint animation changed the tint
+            self.transition_tint = self.layer.transition.tint;
+            self.tint = Some(self.transition_tint);
+        }
+    }
+
+    fn render(&mut self, _theme: &mut Theme, window: &mut Window) {
+        if self.layer.debug {
+            self.layer.draw_border(window);
+        }
+        self.load_svg();
+        if let Some(mut task) = self.content_mesh() {
+            let offset = self.layer.frame.pos;
+            for vertex in task.vertices.iter_mut() {
+                vertex.pos = vertex.pos + offset;
+            }
+            window.add_task(task);
+        }
+    }
+}
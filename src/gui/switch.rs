@@ -0,0 +1,327 @@
+/// Switch
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Transform, Vector},
+    graphics::{Color, MeshTask},
+    input::{Key, MouseCursor},
+    lifecycle::Window,
+};
+use std::any::TypeId;
+
+use super::*;
+
+/// The duration in seconds of the thumb animation
+const SWITCH_DURATION: f64 = 0.2;
+/// The maximum height of the track. Taller frames center the track vertically.
+const SWITCH_HEIGHT: f32 = 24.0;
+/// The space between the track and the thumb
+const THUMB_INSET: f32 = 2.0;
+/// The space between the track and the text
+const TEXT_MARGIN: f32 = 10.0;
+
+/// Convert a Color to a Color Prop, which uses rgba values in the range 0.0 to 255.0
+fn color_prop(color: Color) -> Prop {
+    Prop::Color(ColorRGBA::new(color.r * 255.0, color.g * 255.0, color.b * 255.0, color.a * 255.0))
+}
+
+//-- Switch -----------------------------------------------------------------------
+
+/// A toggle switch with optional text on the right. Changing the value slides the thumb to the other end
+/// of the track while the track color changes between the off and on colors.
+///
+/// Space or Return toggles the switch when it has focus. A disabled switch is dimmed and ignores input,
+/// but its value can still be set with set_field_value. Changes made by the user dispatch FieldEvent::Changed.
+pub struct Switch {
+    /// The base layer
+    pub layer: Layer,
+    /// Text to display next to the switch
+    pub text: String,
+    /// Is it on?
+    is_on: bool,
+    /// Does it respond to mouse and keyboard input?
+    is_enabled: bool,
+    /// Has the switch been activated by its Scene?
+    has_focus: bool,
+    /// The frame of this layer is the thumb, relative to the frame of the switch, and the transition color is
+    /// the track color. Both are animated with a Tween.
+    thumb: Layer,
+    /// Theme colors
+    on_color: Color,
+    off_color: Color,
+    thumb_color: Color,
+    focus_color: Color,
+    /// The text mesh at origin 0, 0
+    text_cache: Option<MeshTask>,
+    /// FieldEvents waiting to be dispatched in the next update()
+    pending_events: Vec<FieldEvent>,
+}
+
+impl Switch {
+    /// Constructor
+    pub fn new(frame: Rectangle) -> Self {
+        let mut switch = Switch {
+            layer: Layer::new(frame),
+            text: String::default(),
+            is_on: false,
+            is_enabled: true,
+            has_focus: false,
+            thumb: Layer::new(Rectangle::new((0.0, 0.0), (0.0, 0.0))),
+            on_color: Color::from_hex("#4373c2"),
+            off_color: Color::from_hex("#CCCCCC"),
+            thumb_color: Color::WHITE,
+            focus_color: Color::from_hex("#80A4C2"),
+            text_cache: None,
+            pending_events: Vec::new(),
+        };
+        switch.update_thumb(false);
+        switch
+    }
+
+    /// Builder method to set the text and the initial value
+    pub fn with_text(mut self, text: &str, is_on: bool) -> Self {
+        self.text = text.to_owned();
+        self.text_cache = None;
+        self.set_on(is_on, false);
+        self
+    }
+
+    /// Builder method to enable or disable the switch
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.set_enabled(enabled);
+        self
+    }
+
+    /// Is it on?
+    pub fn is_on(&self) -> bool {
+        self.is_on
+    }
+
+    /// Set the value, optionally animating the thumb and track color
+    pub fn set_on(&mut self, is_on: bool, animated: bool) {
+        if is_on != self.is_on {
+            self.is_on = is_on;
+            self.update_thumb(animated);
+        }
+    }
+
+    /// Does it respond to mouse and keyboard input?
+    pub fn is_enabled(&self) -> bool {
+        self.is_enabled
+    }
+
+    /// Enable or disable the switch
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.is_enabled = enabled;
+    }
+
+    /// Toggle the value as if the user clicked the switch
+    fn toggle(&mut self) {
+        let is_on = !self.is_on;
+        self.set_on(is_on, true);
+        self.pending_events.push(FieldEvent::Changed);
+    }
+
+    /// The frame of the track, on the left side of the frame
+    fn track_frame(&self) -> Rectangle {
+        let height = self.layer.frame.height().min(SWITCH_HEIGHT);
+        let rect = Rectangle::new((0.0, 0.0), (height * 1.75, height));
+        UITools::position_left_middle(&self.layer.frame, &rect, 0.0)
+    }
+
+    /// Move the thumb to the end of the track for the current value and change the track color
+    fn update_thumb(&mut self, animate: bool) {
+        let track = self.track_frame();
+        let diameter = track.height() - THUMB_INSET * 2.0;
+        let x = {
+            if self.is_on {
+                track.x() + track.width() - THUMB_INSET - diameter
+            } else {
+                track.x() + THUMB_INSET
+            }
+        };
+        let y = track.y() + THUMB_INSET;
+        let (x, y) = (x - self.layer.frame.x(), y - self.layer.frame.y());
+        let color = if self.is_on { self.on_color } else { self.off_color };
+        if animate {
+            // Start from the current color in case the previous animation was interrupted
+            self.thumb.bg_style = BackgroundStyle::Solid(self.thumb.transition.color);
+            let props = vec![position(x, y), color_prop(color)];
+            let propset = PropSet::new(props, SWITCH_DURATION).ease(Ease::SineInOut);
+            self.thumb.animate_with_props(propset, true);
+        } else {
+            self.thumb.animation = None;
+            self.thumb.frame = Rectangle::new((x, y), (diameter, diameter));
+            self.thumb.bg_style = BackgroundStyle::Solid(color);
+            self.thumb.transition.color = color;
+        }
+    }
+}
+
+// *****************************************************************************************************
+// Switch :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for Switch {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<Switch>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        let ok = self.layer.apply_theme(theme);
+        if !ok {
+            return;
+        }
+        self.on_color = theme.button_bg_color;
+        self.off_color = theme.border_color;
+        self.thumb_color = theme.input_bg_color;
+        self.focus_color = theme.cursor_color;
+        self.text_cache = None;
+        self.update_thumb(false);
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Activate => {
+                self.has_focus = true;
+            }
+            DisplayEvent::Deactivate => {
+                self.has_focus = false;
+            }
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        self.thumb.tween_update(state);
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        let alpha = if self.is_enabled { 1.0 } else { 0.5 };
+        let dim = |color: Color| Color { a: color.a * alpha, ..color };
+
+        let track = self.track_frame();
+        let radius = track.height() / 2.0;
+        let mut task = MeshTask::new(0);
+        if self.has_focus && self.is_enabled {
+            let ring = UITools::padded_rect(&track, 2.0, 2.0);
+            let mut mesh = DrawShape::rectangle(&ring, None, Some(self.focus_color), 2.0, radius + 2.0);
+            task.append(&mut mesh);
+        }
+        let mut mesh = DrawShape::rectangle(&track, Some(dim(self.thumb.transition.color)), None, 0.0, radius);
+        task.append(&mut mesh);
+        let thumb = Rectangle::new(self.layer.frame.pos + self.thumb.frame.pos, self.thumb.frame.size);
+        let mut mesh = DrawShape::circle(&thumb.center(), thumb.width() / 2.0, Some(dim(self.thumb_color)), None, 0.0);
+        task.append(&mut mesh);
+        window.add_task(task);
+
+        if self.text.is_empty() {
+            return;
+        }
+        let left = track.width() + TEXT_MARGIN;
+        if self.text_cache.is_none() {
+            let frame = self.layer.frame;
+            let rect = Rectangle::new((0.0, 0.0), ((frame.width() - left).max(0.0), frame.height()));
+            let params = TextParams::new(self.layer.font_style)
+                .frame(rect)
+                .text(&self.text)
+                .align(TextAlign::Left, VertAlign::Middle)
+                .multiline(false);
+            self.text_cache = theme.default_font.draw(params);
+        }
+        if let Some(task) = &self.text_cache {
+            let mut mesh = task.clone();
+            let origin = self.layer.frame.pos + Vector::new(left, 0.0);
+            for vertex in mesh.vertices.iter_mut() {
+                vertex.pos = Transform::translate(origin) * vertex.pos;
+                vertex.col.a *= alpha;
+            }
+            window.add_task(mesh);
+        }
+    }
+
+    fn set_hover_animation(&mut self, props: PropSet) {
+        self.layer.hover_effect = Some(props);
+    }
+
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        if self.is_enabled && pt.overlaps_rectangle(&self.layer.frame) {
+            self.layer.mouse_state = MouseState::Hover;
+            window.set_cursor(MouseCursor::Hand);
+            return true;
+        }
+        self.layer.mouse_state = MouseState::None;
+        false
+    }
+}
+
+// *****************************************************************************************************
+// Switch :: Responder
+// *****************************************************************************************************
+
+impl Responder for Switch {
+    fn get_field_value(&self) -> FieldValue {
+        FieldValue::Checkbox(self.is_on)
+    }
+
+    /// The Checkbox value sets the switch with animation
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if type_id == self.get_type_id() && layer_id == self.layer.get_id() {
+            if let FieldValue::Checkbox(is_on) = value {
+                self.set_on(*is_on, true);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn handle_mouse_down(&mut self, pt: &Vector, _state: &mut AppState) -> bool {
+        if self.is_enabled && pt.overlaps_rectangle(&self.layer.frame) {
+            self.toggle();
+            return true;
+        }
+        false
+    }
+
+    /// Space and Return toggle the switch. Tab is handled so that the Scene moves the focus to the next control.
+    fn handle_key_command(&mut self, key: &Key, _window: &mut Window) -> bool {
+        match key {
+            Key::Space | Key::Return if self.is_enabled => {
+                self.toggle();
+                true
+            }
+            Key::Tab => true,
+            _ => false,
+        }
+    }
+}
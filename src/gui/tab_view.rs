@@ -0,0 +1,874 @@
+/// TabBar and TabView
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Transform, Vector},
+    graphics::{Color, MeshTask},
+    input::{Key, MouseCursor},
+    lifecycle::Window,
+};
+
+use std::any::TypeId;
+use std::collections::{BTreeMap, HashMap};
+
+use super::*;
+
+/// The duration in seconds of the selection indicator animation
+const INDICATOR_DURATION: f64 = 0.25;
+/// The duration in seconds of the content fade when the active tab changes
+const FADE_DURATION: f64 = 0.2;
+/// The width and height of the close button
+const CLOSE_SIZE: f32 = 12.0;
+/// The horizontal padding inside a tab
+const TAB_PADDING: f32 = 8.0;
+/// The distance the mouse must move before a tab is dragged
+const DRAG_THRESHOLD: f32 = 4.0;
+/// The default height of the TabBar in a TabView
+const BAR_HEIGHT: f32 = 32.0;
+
+//-- Tabs -----------------------------------------------------------------------
+
+/// A tab in a TabBar. The id is assigned by the TabBar and is used to identify the tab in TabEvents.
+#[derive(Clone, Debug)]
+pub struct TabItem {
+    /// Unique id of the tab
+    pub id: u32,
+    /// The text to display
+    pub title: String,
+    /// If true, the tab has a close button
+    pub closeable: bool,
+}
+
+/// The state of a mouse press on a tab, which becomes a drag once the mouse moves past DRAG_THRESHOLD
+struct TabDrag {
+    id: u32,
+    start_x: f32,
+    dragging: bool,
+}
+
+// *****************************************************************************************************
+// TabBar
+// *****************************************************************************************************
+
+/// A horizontal row of fixed width tabs with an animated selection indicator. Tabs can have a close
+/// button and can be reordered by dragging when reorderable is true.
+///
+/// The TabBar emits TabEvents. It can be used by itself or as part of a TabView.
+///
+pub struct TabBar {
+    /// The base layer
+    pub layer: Layer,
+    /// The width of every tab. Titles that are too long are clipped.
+    pub tab_width: f32,
+    /// The height of the selection indicator
+    pub indicator_height: f32,
+    /// If true, tabs can be dragged to a new position
+    pub reorderable: bool,
+    /// The tabs in display order
+    tabs: Vec<TabItem>,
+    /// The id of the selected tab
+    select_id: Option<u32>,
+    /// The next tab id
+    next_id: u32,
+    /// The frame of this layer is the selection indicator, which is moved with a Tween
+    indicator: Layer,
+    /// The mouse press or drag in progress
+    drag: Option<TabDrag>,
+    /// Title text meshes keyed by tab id. Created at origin 0, 0.
+    text_cache: HashMap<u32, MeshTask>,
+    /// Theme colors
+    select_bg_color: Color,
+    indicator_color: Color,
+    divider_color: Color,
+    /// TabEvents waiting to be dispatched in the next update()
+    pending_events: Vec<TabEvent>,
+}
+
+impl TabBar {
+    /// Constructor
+    pub fn new(frame: Rectangle) -> Self {
+        let layer = Layer::new(frame);
+        let indicator = Layer::new(Rectangle::new((frame.x(), frame.y() + frame.height()), (0.0, 0.0)));
+
+        TabBar {
+            layer,
+            tab_width: 120.0,
+            indicator_height: 3.0,
+            reorderable: true,
+            tabs: Vec::new(),
+            select_id: None,
+            next_id: 1,
+            indicator,
+            drag: None,
+            text_cache: HashMap::new(),
+            select_bg_color: Color::from_hex("#FFFFFF"),
+            indicator_color: Color::from_hex("#0096FF"),
+            divider_color: Color::from_hex("#CCCCCC"),
+            pending_events: Vec::new(),
+        }
+    }
+
+    /// Builder method to set the width of every tab
+    pub fn with_tab_width(mut self, width: f32) -> Self {
+        self.tab_width = width;
+        self.update_indicator(false);
+        self
+    }
+
+    /// Add a tab at the end and return its id. The first tab is selected automatically.
+    pub fn add_tab(&mut self, title: &str, closeable: bool) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.tabs.push(TabItem { id, title: title.to_owned(), closeable });
+        if self.select_id.is_none() {
+            self.select_id = Some(id);
+            self.update_indicator(false);
+        }
+        id
+    }
+
+    /// Remove the tab with the id and queue a Closed event. If it was selected, the next tab is selected
+    /// after that. Returns false if there is no tab with the id.
+    pub fn remove_tab(&mut self, id: u32) -> bool {
+        let index = match self.index_of(id) {
+            Some(index) => index,
+            None => return false,
+        };
+        self.tabs.remove(index);
+        self.text_cache.remove(&id);
+        self.pending_events.push(TabEvent::Closed(id));
+        if self.select_id == Some(id) {
+            self.select_id = None;
+            if !self.tabs.is_empty() {
+                let next = self.tabs[index.min(self.tabs.len() - 1)].id;
+                self.select_tab(next);
+            }
+        } else {
+            self.update_indicator(true);
+        }
+        true
+    }
+
+    /// Select the tab with the id. Returns false if there is no tab with the id.
+    pub fn select_tab(&mut self, id: u32) -> bool {
+        if self.index_of(id).is_none() {
+            return false;
+        }
+        if self.select_id != Some(id) {
+            self.select_id = Some(id);
+            self.pending_events.push(TabEvent::Selected(id));
+            self.update_indicator(true);
+        }
+        true
+    }
+
+    /// Move the tab with the id to the index. Returns false if there is no tab with the id.
+    pub fn move_tab(&mut self, id: u32, index: usize) -> bool {
+        let from = match self.index_of(id) {
+            Some(from) => from,
+            None => return false,
+        };
+        let index = index.min(self.tabs.len() - 1);
+        if from != index {
+            let tab = self.tabs.remove(from);
+            self.tabs.insert(index, tab);
+            self.pending_events.push(TabEvent::Moved(id, index));
+            self.update_indicator(true);
+        }
+        true
+    }
+
+    /// Change the title of the tab with the id
+    pub fn set_title(&mut self, id: u32, title: &str) {
+        if let Some(index) = self.index_of(id) {
+            self.tabs[index].title = title.to_owned();
+            self.text_cache.remove(&id);
+        }
+    }
+
+    /// The id of the selected tab
+    pub fn selected_id(&self) -> Option<u32> {
+        self.select_id
+    }
+
+    /// The tabs in display order
+    pub fn tabs(&self) -> &[TabItem] {
+        &self.tabs
+    }
+
+    /// The number of tabs
+    pub fn tab_count(&self) -> usize {
+        self.tabs.len()
+    }
+
+    /// The display index of the tab with the id
+    pub fn index_of(&self, id: u32) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.id == id)
+    }
+
+    fn tab_frame(&self, index: usize) -> Rectangle {
+        let frame = self.layer.frame;
+        Rectangle::new((frame.x() + index as f32 * self.tab_width, frame.y()), (self.tab_width, frame.height()))
+    }
+
+    fn close_frame(&self, index: usize) -> Rectangle {
+        let rect = self.tab_frame(index);
+        let x = rect.x() + rect.width() - TAB_PADDING - CLOSE_SIZE;
+        let y = rect.y() + (rect.height() - CLOSE_SIZE) / 2.0;
+        Rectangle::new((x, y), (CLOSE_SIZE, CLOSE_SIZE))
+    }
+
+    fn tab_at_point(&self, pt: &Vector) -> Option<usize> {
+        if !pt.overlaps_rectangle(&self.layer.frame) {
+            return None;
+        }
+        let index = ((pt.x - self.layer.frame.x()) / self.tab_width) as usize;
+        if index < self.tabs.len() {
+            return Some(index);
+        }
+        None
+    }
+
+    /// Move the indicator below the selected tab
+    fn update_indicator(&mut self, animate: bool) {
+        let index = match self.select_id.and_then(|id| self.index_of(id)) {
+            Some(index) => index,
+            None => {
+                self.indicator.frame.size = Vector::ZERO;
+                return;
+            }
+        };
+        let rect = self.tab_frame(index);
+        let y = rect.y() + rect.height() - self.indicator_height;
+        if animate && self.indicator.frame.width() > 0.0 {
+            let props = vec![position(rect.x(), y), size(rect.width(), self.indicator_height)];
+            let propset = PropSet::new(props, INDICATOR_DURATION).ease(Ease::SineInOut);
+            self.indicator.animate_with_props(propset, true);
+        } else {
+            self.indicator.animation = None;
+            self.indicator.frame = Rectangle::new((rect.x(), y), (rect.width(), self.indicator_height));
+        }
+    }
+}
+
+// *****************************************************************************************************
+// TabBar :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for TabBar {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<TabBar>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+        self.update_indicator(false);
+    }
+
+    fn align_view(&mut self, origin: Vector) {
+        self.layer.frame.pos = self.layer.anchor_pt + origin;
+        self.update_indicator(false);
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        // Don't allow style to be locked
+        let _ = self.layer.apply_theme(theme);
+
+        self.select_bg_color = theme.input_bg_color;
+        self.indicator_color = theme.button_bg_color;
+        self.divider_color = theme.border_color;
+        self.text_cache.clear();
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+                self.update_indicator(false);
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        self.indicator.tween_update(state);
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        self.layer.draw_background(window);
+        let frame = self.layer.frame;
+        let fg_color = self.layer.font_style.get_color();
+
+        // Shapes are drawn first and the text is drawn on top
+        let mut graphics = MeshTask::new(0);
+        let mut content: Vec<MeshTask> = Vec::new();
+        for index in 0..self.tabs.len() {
+            let tab = self.tabs[index].clone();
+            let rect = self.tab_frame(index);
+            if rect.x() >= frame.x() + frame.width() {
+                break;
+            }
+            if self.select_id == Some(tab.id) {
+                let mut mesh = DrawShape::rectangle(&rect, Some(self.select_bg_color), None, 0.0, 0.0);
+                graphics.append(&mut mesh);
+            }
+            let right = rect.x() + rect.width();
+            let pts: [&Vector; 2] = [&Vector::new(right, rect.y()), &Vector::new(right, rect.y() + rect.height())];
+            let mut line = DrawShape::line(&pts, self.divider_color, 1.0);
+            graphics.append(&mut line);
+
+            let mut text_width = rect.width() - TAB_PADDING * 2.0;
+            if tab.closeable {
+                let close = self.close_frame(index);
+                let (x1, y1) = (close.x() + 2.0, close.y() + 2.0);
+                let (x2, y2) = (close.x() + close.width() - 2.0, close.y() + close.height() - 2.0);
+                let mut line = DrawShape::line(&[&Vector::new(x1, y1), &Vector::new(x2, y2)], fg_color, 1.5);
+                graphics.append(&mut line);
+                let mut line = DrawShape::line(&[&Vector::new(x2, y1), &Vector::new(x1, y2)], fg_color, 1.5);
+                graphics.append(&mut line);
+                text_width -= CLOSE_SIZE + TAB_PADDING;
+            }
+
+            let text_width = text_width.max(0.0);
+            if !self.text_cache.contains_key(&tab.id) {
+                let rect = Rectangle::new((0.0, 0.0), (text_width, rect.height()));
+                let mut params = TextParams::new(self.layer.font_style)
+                    .frame(rect)
+                    .text(&tab.title)
+                    .align(TextAlign::Center, VertAlign::Middle)
+                    .multiline(false);
+                params.subframe = Some(rect);
+                if let Some(task) = theme.default_font.draw(params) {
+                    self.text_cache.insert(tab.id, task);
+                }
+            }
+            if let Some(task) = self.text_cache.get(&tab.id) {
+                let mut mesh = task.clone();
+                for vertex in &mut mesh.vertices.iter_mut() {
+                    vertex.pos = Transform::translate((rect.x() + TAB_PADDING, rect.y())) * vertex.pos;
+                }
+                content.push(mesh);
+            }
+        }
+        if self.indicator.frame.width() > 0.0 {
+            let rect = self.indicator.frame;
+            let mut mesh = DrawShape::rectangle(&rect, Some(self.indicator_color), None, 0.0, 0.0);
+            graphics.append(&mut mesh);
+        }
+        window.add_task(graphics);
+        for mesh in content {
+            window.add_task(mesh);
+        }
+        self.layer.draw_border(window);
+    }
+
+    fn set_hover_animation(&mut self, props: PropSet) {
+        self.layer.hover_effect = Some(props);
+    }
+
+    /// While a tab is pressed, moving the mouse past the neighbouring tab moves it to that position
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        if let Some(drag) = &mut self.drag {
+            if !drag.dragging && (pt.x - drag.start_x).abs() >= DRAG_THRESHOLD {
+                drag.dragging = self.reorderable;
+            }
+            if drag.dragging {
+                let id = drag.id;
+                let offset = (pt.x - self.layer.frame.x()).max(0.0);
+                let index = (offset / self.tab_width) as usize;
+                self.move_tab(id, index);
+                window.set_cursor(MouseCursor::Grabbing);
+                return true;
+            }
+        }
+        if pt.overlaps_rectangle(&self.layer.frame) {
+            self.layer.mouse_state = MouseState::Hover;
+            window.set_cursor(MouseCursor::Hand);
+            return self.tab_at_point(pt).is_some();
+        }
+        self.layer.mouse_state = MouseState::None;
+        false
+    }
+}
+
+// *****************************************************************************************************
+// TabBar :: Responder
+// *****************************************************************************************************
+
+impl Responder for TabBar {
+    /// The Selections value is the index of the selected tab
+    fn get_field_value(&self) -> FieldValue {
+        match self.select_id.and_then(|id| self.index_of(id)) {
+            Some(index) => FieldValue::Selections(vec![index]),
+            None => FieldValue::None,
+        }
+    }
+
+    /// The Selections value is a list of tab indexes, where only the first is used
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if type_id == self.get_type_id() && layer_id == self.layer.get_id() {
+            if let FieldValue::Selections(indexes) = value {
+                if let Some(tab) = indexes.first().and_then(|index| self.tabs.get(*index)) {
+                    let id = tab.id;
+                    self.select_tab(id);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// A click on the close button closes the tab and a click elsewhere selects it
+    fn handle_mouse_down(&mut self, pt: &Vector, _state: &mut AppState) -> bool {
+        let index = match self.tab_at_point(pt) {
+            Some(index) => index,
+            None => return false,
+        };
+        let id = self.tabs[index].id;
+        if self.tabs[index].closeable && pt.overlaps_rectangle(&self.close_frame(index)) {
+            self.remove_tab(id);
+            return true;
+        }
+        self.select_tab(id);
+        self.drag = Some(TabDrag { id, start_x: pt.x, dragging: false });
+        true
+    }
+
+    fn handle_mouse_up(&mut self, _pt: &Vector, _state: &mut AppState) -> bool {
+        self.drag.take().map_or(false, |drag| drag.dragging)
+    }
+
+    /// Left and Right select the previous and next tab
+    fn handle_key_command(&mut self, key: &Key, _window: &mut Window) -> bool {
+        let index = match self.select_id.and_then(|id| self.index_of(id)) {
+            Some(index) => index,
+            None => return false,
+        };
+        let target = match key {
+            Key::Left if index > 0 => index - 1,
+            Key::Right if index + 1 < self.tabs.len() => index + 1,
+            _ => return false,
+        };
+        let id = self.tabs[target].id;
+        self.select_tab(id)
+    }
+}
+
+// *****************************************************************************************************
+// TabView
+// *****************************************************************************************************
+
+/// A container with a TabBar and a child Scene for each tab. Only the Scene of the selected tab is
+/// updated, rendered and receives input. When the selected tab changes, the new Scene fades in.
+///
+/// Each Scene should be created with the frame returned by content_frame(). Tabs that are closed drop
+/// their Scene. A NavEvent::Selected(id) event selects the tab with the id, and NavEvent::Next and Back
+/// select the next and previous tab.
+///
+/// Scenes added after the TabView has loaded are loaded in the next update(). Event listeners that were
+/// added to the views of those Scenes are not registered with the Stage.
+///
+pub struct TabView {
+    /// The base layer
+    pub layer: Layer,
+    /// The tabs
+    pub tab_bar: TabBar,
+    /// The child scenes, keyed by tab id
+    scenes: BTreeMap<u32, Scene>,
+    /// The tab id of the Scene that is displayed
+    active_id: Option<u32>,
+    /// Drives the fade. The frame height goes from 0.0 to 1.0.
+    fade: Option<Layer>,
+    /// The color of the cover that fades out over the new Scene
+    fade_color: Color,
+    /// The node path of this layer, which is saved to load scenes that are added later
+    parent_nodes: Option<Vec<NodeID>>,
+    /// Tab ids of scenes waiting to be loaded
+    load_queue: Vec<u32>,
+    /// Tab ids of scenes that were loaded in update() and need the theme in the next render()
+    theme_queue: Vec<u32>,
+}
+
+impl TabView {
+    /// Constructor
+    pub fn new(frame: Rectangle) -> Self {
+        let layer = Layer::new(frame);
+        let tab_bar = TabBar::new(Rectangle::new(frame.pos, (frame.width(), BAR_HEIGHT)));
+
+        TabView {
+            layer,
+            tab_bar,
+            scenes: BTreeMap::new(),
+            active_id: None,
+            fade: None,
+            fade_color: Color::WHITE,
+            parent_nodes: None,
+            load_queue: Vec::new(),
+            theme_queue: Vec::new(),
+        }
+    }
+
+    /// The frame below the TabBar where the Scenes are displayed
+    pub fn content_frame(&self) -> Rectangle {
+        let frame = self.layer.frame;
+        let bar = self.tab_bar.layer.frame.height();
+        Rectangle::new((frame.x(), frame.y() + bar), (frame.width(), (frame.height() - bar).max(0.0)))
+    }
+
+    /// Add a tab that displays the Scene and return the tab id
+    pub fn add_tab(&mut self, title: &str, scene: Scene, closeable: bool) -> u32 {
+        let id = self.tab_bar.add_tab(title, closeable);
+        self.scenes.insert(id, scene);
+        if self.parent_nodes.is_some() {
+            self.load_queue.push(id);
+        }
+        self.sync_tabs(false);
+        id
+    }
+
+    /// Remove the tab and its Scene
+    pub fn remove_tab(&mut self, id: u32) -> bool {
+        let removed = self.tab_bar.remove_tab(id);
+        self.sync_tabs(true);
+        removed
+    }
+
+    /// Select the tab with the id
+    pub fn select_tab(&mut self, id: u32) -> bool {
+        let selected = self.tab_bar.select_tab(id);
+        self.sync_tabs(true);
+        selected
+    }
+
+    /// The tab id of the Scene that is displayed
+    pub fn active_id(&self) -> Option<u32> {
+        self.active_id
+    }
+
+    /// The Scene for the tab id
+    pub fn scene(&self, id: u32) -> Option<&Scene> {
+        self.scenes.get(&id)
+    }
+
+    /// The mutable Scene for the tab id
+    pub fn scene_mut(&mut self, id: u32) -> Option<&mut Scene> {
+        self.scenes.get_mut(&id)
+    }
+
+    /// Drop the scenes of closed tabs and start the fade if the selected tab changed
+    fn sync_tabs(&mut self, animate: bool) {
+        let tab_bar = &self.tab_bar;
+        self.scenes.retain(|id, _| tab_bar.index_of(*id).is_some());
+        let selected = self.tab_bar.selected_id();
+        if selected == self.active_id {
+            return;
+        }
+        if animate && self.active_id.is_some() && selected.is_some() {
+            let mut layer = Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0)));
+            let propset = PropSet::new(vec![size(1.0, 1.0)], FADE_DURATION).ease(Ease::SineOut);
+            layer.animate_with_props(propset, true);
+            self.fade = Some(layer);
+        } else {
+            self.fade = None;
+        }
+        self.active_id = selected;
+    }
+
+    fn load_scene(scene: &mut Scene, parent_nodes: &[NodeID], ctx: &mut StageContext, app_state: &mut AppState) {
+        scene.set_id(app_state.new_id());
+        scene.get_layer_mut().set_path(parent_nodes);
+        scene.view_will_load(ctx, app_state);
+    }
+
+    fn active_scene_mut(&mut self) -> Option<&mut Scene> {
+        let id = self.active_id?;
+        self.scenes.get_mut(&id)
+    }
+}
+
+// *****************************************************************************************************
+// TabView :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for TabView {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<TabView>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+        self.tab_bar.move_to(pos);
+    }
+
+    fn set_origin(&mut self, origin: Vector) {
+        self.layer.anchor_pt = self.layer.frame.pos - origin;
+        self.tab_bar.set_origin(origin);
+    }
+
+    fn align_view(&mut self, origin: Vector) {
+        self.layer.frame.pos = self.layer.anchor_pt + origin;
+        self.tab_bar.align_view(origin);
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        // Don't allow style to be locked
+        let _ = self.layer.apply_theme(theme);
+
+        self.fade_color = theme.bg_color;
+        self.tab_bar.set_theme(theme);
+        for scene in self.scenes.values_mut() {
+            scene.set_theme(theme);
+        }
+    }
+
+    /// A NavEvent::Selected(id) selects the tab, and NavEvent::Next and Back select the next and previous
+    /// tab. These events are consumed. Other events are passed to the child scenes.
+    fn handle_event(&mut self, event: &EventBox, app_state: &mut AppState) {
+        if let Ok(evt) = event.downcast_ref::<NavEvent>() {
+            let index = self.tab_bar.selected_id().and_then(|id| self.tab_bar.index_of(id));
+            let target = match (evt, index) {
+                (NavEvent::Selected(id), _) => self.tab_bar.index_of(*id),
+                (NavEvent::Next, Some(index)) if index + 1 < self.tab_bar.tab_count() => Some(index + 1),
+                (NavEvent::Back, Some(index)) if index > 0 => Some(index - 1),
+                _ => None,
+            };
+            if let Some(target) = target {
+                let id = self.tab_bar.tabs()[target].id;
+                self.select_tab(id);
+                app_state.event_consumed = true;
+                return;
+            }
+        }
+        for scene in self.scenes.values_mut() {
+            scene.handle_event(event, app_state);
+        }
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+        self.tab_bar.notify(event);
+        for scene in self.scenes.values_mut() {
+            scene.notify(event);
+        }
+    }
+
+    fn update(&mut self, window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        if let Some(parent_nodes) = &self.parent_nodes {
+            let mut ctx = StageContext::new();
+            for id in self.load_queue.drain(..) {
+                if let Some(scene) = self.scenes.get_mut(&id) {
+                    TabView::load_scene(scene, parent_nodes, &mut ctx, state);
+                    scene.notify(&DisplayEvent::Ready);
+                    self.theme_queue.push(id);
+                }
+            }
+        }
+
+        self.tab_bar.update(window, state);
+        self.sync_tabs(true);
+
+        let finished = match &mut self.fade {
+            Some(layer) => {
+                layer.tween_update(state);
+                layer.animation.as_ref().map_or(true, |tween| tween.state == PlayState::Completed)
+            }
+            None => false,
+        };
+        if finished {
+            self.fade = None;
+        }
+        if let Some(scene) = self.active_scene_mut() {
+            scene.update(window, state);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        self.layer.draw_background(window);
+        for id in self.theme_queue.drain(..) {
+            if let Some(scene) = self.scenes.get_mut(&id) {
+                scene.set_theme(theme);
+            }
+        }
+        if let Some(scene) = self.active_scene_mut() {
+            scene.render(theme, window);
+        }
+        // The cover starts opaque and fades out over the new Scene
+        if let Some(layer) = &self.fade {
+            let mut color = self.fade_color;
+            color.a = (1.0 - layer.frame.height()).max(0.0).min(1.0);
+            let rect = self.content_frame();
+            let mut task = MeshTask::new(0);
+            let mut mesh = DrawShape::rectangle(&rect, Some(color), None, 0.0, 0.0);
+            task.append(&mut mesh);
+            window.add_task(task);
+        }
+        self.tab_bar.render(theme, window);
+        self.layer.draw_border(window);
+    }
+
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        if self.tab_bar.handle_mouse_at(pt, window) {
+            return true;
+        }
+        if let Some(scene) = self.active_scene_mut() {
+            return scene.handle_mouse_at(pt, window);
+        }
+        false
+    }
+
+    fn layer_at(&self, pt: &Vector, filter: &dyn Fn(&Layer) -> bool) -> Option<&Layer> {
+        if let Some(layer) = self.tab_bar.layer_at(pt, filter) {
+            return Some(layer);
+        }
+        if let Some(scene) = self.active_id.and_then(|id| self.scenes.get(&id)) {
+            if let Some(layer) = scene.layer_at(pt, filter) {
+                return Some(layer);
+            }
+        }
+        if pt.overlaps_rectangle(&self.layer.frame) && filter(&self.layer) {
+            return Some(&self.layer);
+        }
+        None
+    }
+
+    fn view_will_load(&mut self, ctx: &mut StageContext, app_state: &mut AppState) {
+        let parent_nodes = self.layer.node_path.nodes.clone();
+        app_state.append_node(self.layer.node_path.clone());
+
+        self.tab_bar.set_id(app_state.new_id());
+        self.tab_bar.get_layer_mut().set_path(&parent_nodes);
+        self.tab_bar.view_will_load(ctx, app_state);
+
+        for scene in self.scenes.values_mut() {
+            TabView::load_scene(scene, &parent_nodes, ctx, app_state);
+        }
+        self.load_queue.clear();
+        self.parent_nodes = Some(parent_nodes);
+    }
+}
+
+// *****************************************************************************************************
+// TabView :: Responder
+// *****************************************************************************************************
+
+impl Responder for TabView {
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if self.tab_bar.set_field_value(value, type_id, layer_id) {
+            self.sync_tabs(true);
+            return true;
+        }
+        for scene in self.scenes.values_mut() {
+            if scene.set_field_value(value, type_id, layer_id) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Returns true for any click inside the frame so that key commands are passed to the active Scene
+    fn handle_mouse_down(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        if self.tab_bar.handle_mouse_down(pt, state) {
+            self.sync_tabs(true);
+            return true;
+        }
+        if let Some(scene) = self.active_scene_mut() {
+            scene.handle_mouse_down(pt, state);
+        }
+        pt.overlaps_rectangle(&self.layer.frame)
+    }
+
+    fn handle_mouse_up(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        if self.tab_bar.handle_mouse_up(pt, state) {
+            return true;
+        }
+        if let Some(scene) = self.active_scene_mut() {
+            return scene.handle_mouse_up(pt, state);
+        }
+        false
+    }
+
+    fn handle_mouse_scroll(&mut self, pt: &Vector, state: &mut AppState) {
+        if let Some(scene) = self.active_scene_mut() {
+            scene.handle_mouse_scroll(pt, state);
+        }
+    }
+
+    fn handle_key_press(&mut self, c: char, window: &mut Window) {
+        if let Some(scene) = self.active_scene_mut() {
+            scene.handle_key_press(c, window);
+        }
+    }
+
+    fn handle_text_input(&mut self, event: &TextInputEvent, window: &mut Window) -> bool {
+        if let Some(scene) = self.active_scene_mut() {
+            return scene.handle_text_input(event, window);
+        }
+        false
+    }
+
+    /// Ctrl+Tab selects the next tab and Ctrl+Shift+Tab selects the previous tab. Other keys are passed
+    /// to the active Scene.
+    fn handle_key_command(&mut self, key: &Key, window: &mut Window) -> bool {
+        let modifiers = KeyModifiers::from_keyboard(window.keyboard());
+        if let (Key::Tab, true) = (key, modifiers.ctrl) {
+            let count = self.tab_bar.tab_count();
+            let index = self.tab_bar.selected_id().and_then(|id| self.tab_bar.index_of(id));
+            if let Some(index) = index {
+                let target = if modifiers.shift { (index + count - 1) % count } else { (index + 1) % count };
+                let id = self.tab_bar.tabs()[target].id;
+                self.select_tab(id);
+                return true;
+            }
+            return false;
+        }
+        if let Some(scene) = self.active_scene_mut() {
+            return scene.handle_key_command(key, window);
+        }
+        false
+    }
+}
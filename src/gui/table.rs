@@ -0,0 +1,1191 @@
+/// Table
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Transform, Vector},
+    graphics::{Background::Col, Color, MeshTask},
+    input::{Key, MouseCursor},
+    lifecycle::Window,
+};
+
+use std::any::TypeId;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::f32;
+use std::ops::Range;
+
+use super::*;
+
+/// This is the multiplier that affects how quickly scrolling occurs.
+const SCROLL_FACTOR: f32 = 1.0;
+/// The distance from a header divider where a drag will resize the column
+const RESIZE_MARGIN: f32 = 4.0;
+/// The horizontal inset of text within a cell
+const CELL_PADDING: f32 = 6.0;
+/// The size of the sort indicator triangle in the header
+const SORT_ARROW_SIZE: f32 = 8.0;
+
+//-- Columns -----------------------------------------------------------------------
+
+/// Defines how the cells in a column can be edited
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CellEditor {
+    /// The cells are read-only
+    None,
+    /// A TextField is shown over the cell. Editing starts by clicking a cell in the selected row or
+    /// pressing Return, and the text is saved with Return or Tab.
+    Text,
+    /// The cells are drawn as a Checkbox that toggles when clicked. Cell values are "true" or "false".
+    Checkbox,
+}
+
+/// The definition of a Table column
+#[derive(Clone, Debug)]
+pub struct TableColumn {
+    /// The title shown in the header
+    pub title: String,
+    /// The current width
+    pub width: f32,
+    /// The horizontal alignment of the header and cell text
+    pub text_align: TextAlign,
+    /// The minimum width when resizing
+    pub min_width: f32,
+    /// The maximum width when resizing
+    pub max_width: f32,
+    /// If true, clicking the header sorts the rows by this column
+    pub sortable: bool,
+    /// How the cells can be edited
+    pub editor: CellEditor,
+}
+
+impl TableColumn {
+    /// Constructor
+    pub fn new(title: &str, width: f32) -> Self {
+        TableColumn {
+            title: title.to_owned(),
+            width,
+            text_align: TextAlign::Left,
+            min_width: 20.0,
+            max_width: f32::MAX,
+            sortable: true,
+            editor: CellEditor::None,
+        }
+    }
+
+    /// Builder method to set the text alignment
+    pub fn with_align(mut self, text_align: TextAlign) -> Self {
+        self.text_align = text_align;
+        self
+    }
+
+    /// Builder method to set the min and max width when resizing
+    pub fn with_limits(mut self, min_width: f32, max_width: f32) -> Self {
+        self.min_width = min_width;
+        self.max_width = max_width.max(min_width);
+        self.width = self.clamp_width(self.width);
+        self
+    }
+
+    /// Builder method to enable or disable sorting
+    pub fn with_sorting(mut self, sortable: bool) -> Self {
+        self.sortable = sortable;
+        self
+    }
+
+    /// Builder method to set the cell editor
+    pub fn with_editor(mut self, editor: CellEditor) -> Self {
+        self.editor = editor;
+        self
+    }
+
+    /// Limit a width to the min and max width
+    fn clamp_width(&self, width: f32) -> f32 {
+        width.max(self.min_width).min(self.max_width)
+    }
+}
+
+//-- Datasource -----------------------------------------------------------------------
+
+/// Trait for providing cells to a Table on demand. The Table only asks for cells in the visible rows,
+/// except when sorting.
+pub trait TableDataSource {
+    /// The total number of rows
+    fn row_count(&self) -> usize;
+
+    /// The text of the cell at the row and column index
+    fn cell_at(&self, row: usize, column: usize) -> String;
+
+    /// Save an edited cell value. Return false if the datasource is read-only or rejects the value.
+    fn set_cell(&mut self, _row: usize, _column: usize, _value: &str) -> bool {
+        false
+    }
+}
+
+/// A simple in-memory TableDataSource that can be loaded from CSV text
+#[derive(Clone, Debug, Default)]
+pub struct TableData {
+    /// The column titles
+    pub headers: Vec<String>,
+    /// The cells of each row
+    pub rows: Vec<Vec<String>>,
+}
+
+impl TableData {
+    /// Constructor
+    pub fn new(headers: Vec<String>, rows: Vec<Vec<String>>) -> Self {
+        TableData { headers, rows }
+    }
+
+    /// Parse CSV text where the first line has the column titles. Quoted fields may contain commas and
+    /// doubled quotes, but not line breaks. Blank lines are skipped.
+    pub fn from_csv(text: &str) -> Self {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty()).map(TableData::parse_csv_line);
+        let headers = lines.next().unwrap_or_default();
+        let rows = lines.collect();
+        TableData { headers, rows }
+    }
+
+    /// Create a column for each header with the same width
+    pub fn columns(&self, width: f32) -> Vec<TableColumn> {
+        self.headers.iter().map(|title| TableColumn::new(title, width)).collect()
+    }
+
+    fn parse_csv_line(line: &str) -> Vec<String> {
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => quoted = !quoted,
+                ',' if !quoted => fields.push(std::mem::replace(&mut field, String::new())),
+                _ => field.push(c),
+            }
+        }
+        fields.push(field);
+        fields
+    }
+}
+
+impl TableDataSource for TableData {
+    fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn cell_at(&self, row: usize, column: usize) -> String {
+        self.rows.get(row).and_then(|cells| cells.get(column)).cloned().unwrap_or_default()
+    }
+
+    fn set_cell(&mut self, row: usize, column: usize, value: &str) -> bool {
+        if let Some(cells) = self.rows.get_mut(row) {
+            if column >= cells.len() {
+                cells.resize(column + 1, String::new());
+            }
+            cells[column] = value.to_owned();
+            return true;
+        }
+        false
+    }
+}
+
+/// The TextField shown over a cell while editing
+struct CellEdit {
+    /// The datasource row index
+    index: usize,
+    column: usize,
+    field: TextField,
+    /// Editing starts in render() after the field has the current theme
+    started: bool,
+}
+
+// *****************************************************************************************************
+// Table
+// *****************************************************************************************************
+
+/// A data grid with a frozen header row and columns that can be sorted and resized. Like the ListBox,
+/// only the visible rows are materialized and the text of each visible cell is cached as a mesh until
+/// the row scrolls out of view.
+///
+/// Row indexes come in two forms. The datasource index identifies the data and is used in selections
+/// and events. The display index is the position of the row after sorting.
+///
+pub struct Table {
+    /// The base layer
+    pub layer: Layer,
+    /// The column definitions
+    pub columns: Vec<TableColumn>,
+    /// The height of the header row
+    pub header_height: f32,
+    /// The height of every row
+    pub row_height: f32,
+    /// If true, alternate rows are drawn with the Theme stripe_bg_color
+    pub striped: bool,
+    /// The datasource index of the selected row
+    pub select_row: Option<usize>,
+    /// The datasource which provides cells on demand
+    datasource: Box<dyn TableDataSource>,
+    /// The datasource index for each display index, after sorting
+    row_map: Vec<usize>,
+    /// The sort column and true if ascending
+    sort_column: Option<(usize, bool)>,
+    /// The display index of the row with keyboard focus
+    focus_row: Option<usize>,
+    /// Text meshes for the visible cells, keyed by datasource index and column. Created at origin 0, 0.
+    cell_cache: HashMap<(usize, usize), MeshTask>,
+    /// Text meshes for the header titles, keyed by column
+    header_cache: HashMap<usize, MeshTask>,
+    /// The Checkbox used to draw the cells of Checkbox columns
+    check_view: Option<Checkbox>,
+    /// The active text editor, if any
+    text_editor: Option<CellEdit>,
+    /// While dragging a header divider: the column, the starting mouse x and the starting width
+    resizing: Option<(usize, f32, f32)>,
+    /// Stores the scroll offset
+    scroll_offset: f32,
+    /// Theme colors for the header and selected rows
+    header_bg_color: Color,
+    select_bg_color: Color,
+    select_fg_color: Color,
+    grid_color: Color,
+    /// SelectionEvents waiting to be dispatched in the next update()
+    selection_events: Vec<SelectionEvent>,
+    /// TableEvents waiting to be dispatched in the next update()
+    pending_events: Vec<TableEvent>,
+}
+
+impl Table {
+    /// Constructor
+    pub fn new(frame: Rectangle) -> Self {
+        let layer = Layer::new(frame);
+
+        Table {
+            layer,
+            columns: Vec::new(),
+            header_height: 28.0,
+            row_height: 24.0,
+            striped: true,
+            select_row: None,
+            datasource: Box::new(TableData::default()),
+            row_map: Vec::new(),
+            sort_column: None,
+            focus_row: None,
+            cell_cache: HashMap::new(),
+            header_cache: HashMap::new(),
+            check_view: None,
+            text_editor: None,
+            resizing: None,
+            scroll_offset: 0.0,
+            header_bg_color: Color::from_hex("#EEEEEE"),
+            select_bg_color: Color::from_hex("#0096FF"),
+            select_fg_color: Color::WHITE,
+            grid_color: Color::from_hex("#AAAAAA"),
+            selection_events: Vec::new(),
+            pending_events: Vec::new(),
+        }
+    }
+
+    /// Builder method to set the columns
+    pub fn with_columns(mut self, columns: Vec<TableColumn>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Builder method to set the header and row heights
+    pub fn with_row_height(mut self, header_height: f32, row_height: f32) -> Self {
+        self.header_height = header_height;
+        self.row_height = row_height;
+        self
+    }
+
+    /// Replace the datasource. This clears the selection and the sort order.
+    pub fn set_source<D>(&mut self, source: D)
+    where
+        D: TableDataSource + 'static,
+    {
+        self.text_editor = None;
+        self.datasource = Box::new(source);
+        self.select_row = None;
+        self.focus_row = None;
+        self.sort_column = None;
+        self.scroll_offset = 0.0;
+        self.reload_data();
+    }
+
+    /// Call this after the datasource has changed. The sort order is applied again and the cached
+    /// cell text is discarded.
+    pub fn reload_data(&mut self) {
+        self.commit_edit();
+        let count = self.datasource.row_count();
+        if self.select_row.map_or(false, |index| index >= count) {
+            self.select_row = None;
+        }
+        self.cell_cache.clear();
+        self.header_cache.clear();
+        self.apply_sort();
+        let upper_limit = (self.content_height() - self.body_frame().height()).max(0.0);
+        self.scroll_offset = self.scroll_offset.min(upper_limit);
+    }
+
+    /// The number of rows
+    pub fn row_count(&self) -> usize {
+        self.row_map.len()
+    }
+
+    /// The total height of all rows, not including the header
+    pub fn content_height(&self) -> f32 {
+        self.row_map.len() as f32 * self.row_height
+    }
+
+    /// Get the text of the cell at the display row index and column
+    pub fn cell_text(&self, row: usize, column: usize) -> Option<String> {
+        self.row_map.get(row).map(|index| self.datasource.cell_at(*index, column))
+    }
+
+    /// The current sort column and true if ascending
+    pub fn sort_column(&self) -> Option<(usize, bool)> {
+        self.sort_column
+    }
+
+    /// Sort the rows by the text in a column. Cells that are all numbers are compared as numbers.
+    pub fn sort_by(&mut self, column: usize, ascending: bool) {
+        if column >= self.columns.len() {
+            return;
+        }
+        self.commit_edit();
+        self.sort_column = Some((column, ascending));
+        self.apply_sort();
+        self.pending_events.push(TableEvent::Sorted(column, ascending));
+    }
+
+    /// Remove the sort order and show the rows in datasource order
+    pub fn clear_sort(&mut self) {
+        self.sort_column = None;
+        self.apply_sort();
+    }
+
+    /// Change the width of a column within its min and max width
+    pub fn set_column_width(&mut self, column: usize, width: f32) {
+        if let Some(col) = self.columns.get_mut(column) {
+            let width = col.clamp_width(width);
+            if width != col.width {
+                col.width = width;
+                // Columns to the right move and the last visible column may be truncated differently
+                self.cell_cache.clear();
+                self.header_cache.clear();
+                self.commit_edit();
+            }
+        }
+    }
+
+    /// Select the row with the datasource index and scroll it into view
+    pub fn select_index(&mut self, index: usize) {
+        if let Some(row) = self.row_map.iter().position(|i| *i == index) {
+            self.select_display_row(row);
+            self.scroll_to_row(row);
+        }
+    }
+
+    /// Remove the selection
+    pub fn clear_selection(&mut self) {
+        if self.select_row.is_some() {
+            self.select_row = None;
+            self.focus_row = None;
+            self.selection_events.push(SelectionEvent::Changed(0, None));
+        }
+    }
+
+    /// Show a TextField over a cell, where index is the datasource row index. Returns false if the
+    /// column does not have a Text editor.
+    pub fn begin_edit(&mut self, index: usize, column: usize) -> bool {
+        self.commit_edit();
+        match self.columns.get(column) {
+            Some(col) if col.editor == CellEditor::Text => (),
+            _ => return false,
+        }
+        if let Some(row) = self.row_map.iter().position(|i| *i == index) {
+            self.scroll_to_row(row);
+            let mut field = TextField::new(self.cell_frame(row, column), true).with_padding(CELL_PADDING, 2.0);
+            field.set_text(&self.datasource.cell_at(index, column));
+            self.text_editor = Some(CellEdit { index, column, field, started: false });
+            return true;
+        }
+        false
+    }
+
+    /// Save the text of the active cell editor to the datasource
+    pub fn commit_edit(&mut self) {
+        if let Some(edit) = self.text_editor.take() {
+            // Invalid text is discarded
+            if let FieldValue::Text(text) = edit.field.get_field_value() {
+                if text != self.datasource.cell_at(edit.index, edit.column) {
+                    self.save_cell(edit.index, edit.column, &text);
+                }
+            }
+        }
+    }
+
+    /// Close the active cell editor without saving
+    pub fn cancel_edit(&mut self) {
+        self.text_editor = None;
+    }
+
+    /// Returns true if a cell editor is active
+    pub fn is_editing(&self) -> bool {
+        self.text_editor.is_some()
+    }
+
+    fn save_cell(&mut self, index: usize, column: usize, value: &str) {
+        if self.datasource.set_cell(index, column, value) {
+            self.cell_cache.remove(&(index, column));
+            self.pending_events.push(TableEvent::CellEdited(index, column));
+        }
+    }
+
+    fn toggle_cell(&mut self, index: usize, column: usize) {
+        let checked = Table::is_checked(&self.datasource.cell_at(index, column));
+        let value = if checked { "false" } else { "true" };
+        self.save_cell(index, column, value);
+    }
+
+    /// The text values that are shown as a checked Checkbox
+    fn is_checked(text: &str) -> bool {
+        match text.trim().to_lowercase().as_str() {
+            "true" | "yes" | "1" | "x" => true,
+            _ => false,
+        }
+    }
+
+    /// Compare two cells for sorting. Numbers sort before text so that mixed columns have a total order.
+    fn compare_cells(a: &str, b: &str) -> Ordering {
+        let number = |s: &str| s.trim().parse::<f64>().ok().filter(|x| !x.is_nan());
+        match (number(a), number(b)) {
+            (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.to_lowercase().cmp(&b.to_lowercase()),
+        }
+    }
+
+    /// Rebuild the row_map with the current sort order. The sort is stable, so equal cells keep their
+    /// datasource order.
+    fn apply_sort(&mut self) {
+        let count = self.datasource.row_count();
+        let mut row_map: Vec<usize> = (0..count).collect();
+        if let Some((column, ascending)) = self.sort_column {
+            let datasource = &self.datasource;
+            let mut keyed: Vec<(usize, String)> =
+                row_map.iter().map(|index| (*index, datasource.cell_at(*index, column))).collect();
+            keyed.sort_by(|a, b| {
+                let order = Table::compare_cells(&a.1, &b.1);
+                if ascending {
+                    order
+                } else {
+                    order.reverse()
+                }
+            });
+            row_map = keyed.into_iter().map(|(index, _)| index).collect();
+        }
+        self.row_map = row_map;
+        self.focus_row = self.select_row.and_then(|index| self.row_map.iter().position(|i| *i == index));
+    }
+
+    fn select_display_row(&mut self, row: usize) {
+        let index = self.row_map[row];
+        self.focus_row = Some(row);
+        if self.select_row != Some(index) {
+            self.select_row = Some(index);
+            self.selection_events.push(SelectionEvent::Changed(1, Some(index)));
+        }
+    }
+
+    /// Change the scroll offset so that the row at the display index is fully visible
+    fn scroll_to_row(&mut self, row: usize) {
+        let top = row as f32 * self.row_height;
+        let height = self.body_frame().height();
+        self.scroll_offset = UITools::scroll_to_span(self.scroll_offset, top, top + self.row_height, height);
+    }
+
+    // *****************************************************************************************************
+    // Layout
+    // *****************************************************************************************************
+
+    /// The area below the header where rows are drawn
+    fn body_frame(&self) -> Rectangle {
+        let frame = self.layer.frame;
+        let header_height = self.header_height.min(frame.height());
+        Rectangle::new((frame.x(), frame.y() + header_height), (frame.width(), frame.height() - header_height))
+    }
+
+    /// The left edge of a column in window coordinates
+    fn column_x(&self, column: usize) -> f32 {
+        self.layer.frame.x() + self.columns.iter().take(column).map(|col| col.width).sum::<f32>()
+    }
+
+    /// Find the column at the window x position
+    fn column_at(&self, x: f32) -> Option<usize> {
+        let mut left = self.layer.frame.x();
+        for (i, col) in self.columns.iter().enumerate() {
+            if x >= left && x < left + col.width {
+                return Some(i);
+            }
+            left += col.width;
+        }
+        None
+    }
+
+    /// Find the column whose right edge is within RESIZE_MARGIN of the window x position
+    fn divider_at(&self, x: f32) -> Option<usize> {
+        let mut right = self.layer.frame.x();
+        for (i, col) in self.columns.iter().enumerate() {
+            right += col.width;
+            if (x - right).abs() <= RESIZE_MARGIN {
+                return Some(i);
+            }
+        }
+        None
+    }
+
+    /// Find the display index at the window position
+    fn row_at_point(&self, pt: &Vector) -> Option<usize> {
+        let body = self.body_frame();
+        if !pt.overlaps_rectangle(&body) {
+            return None;
+        }
+        let row = ((pt.y - body.y() + self.scroll_offset) / self.row_height).floor() as usize;
+        if row < self.row_count() {
+            Some(row)
+        } else {
+            None
+        }
+    }
+
+    /// Based on the scroll_offset, determine what rows are visible below the header
+    fn get_visible_range(&self) -> Range<usize> {
+        let count = self.row_count();
+        let start = ((self.scroll_offset / self.row_height).floor() as usize).min(count);
+        let end = (((self.scroll_offset + self.body_frame().height()) / self.row_height).ceil() as usize).min(count);
+        start..end
+    }
+
+    /// The frame of a row in window coordinates, which may overflow the body
+    fn row_frame(&self, row: usize) -> Rectangle {
+        let body = self.body_frame();
+        let ypos = body.y() + row as f32 * self.row_height - self.scroll_offset;
+        Rectangle::new((body.x(), ypos), (body.width(), self.row_height))
+    }
+
+    /// The frame of a cell in window coordinates. The width is truncated at the right edge of the Table.
+    fn cell_frame(&self, row: usize, column: usize) -> Rectangle {
+        let frame = self.layer.frame;
+        let row_rect = self.row_frame(row);
+        let x = self.column_x(column);
+        let width = self.columns[column].width.min(frame.x() + frame.width() - x).max(0.0);
+        Rectangle::new((x, row_rect.y()), (width, self.row_height))
+    }
+
+    /// The part of a rect that is inside the body, if any
+    fn visible_part(&self, rect: &Rectangle) -> Option<Rectangle> {
+        let body = self.body_frame();
+        let top = rect.y().max(body.y());
+        let bottom = (rect.y() + rect.height()).min(body.y() + body.height());
+        if bottom > top {
+            Some(Rectangle::new((rect.x(), top), (rect.width(), bottom - top)))
+        } else {
+            None
+        }
+    }
+
+    // *****************************************************************************************************
+    // Render
+    // *****************************************************************************************************
+
+    /// Draw text within a cell-sized rect at origin 0, 0 and clip the glyphs at the cell edges
+    fn draw_cell_text(
+        text: &str,
+        size: Vector,
+        text_align: TextAlign,
+        style: FontStyle,
+        theme: &mut Theme,
+    ) -> Option<MeshTask> {
+        if text.is_empty() {
+            return Some(MeshTask::new(0));
+        }
+        let rect = Rectangle::new((CELL_PADDING, 0.0), ((size.x - CELL_PADDING * 2.0).max(0.0), size.y));
+        let mut params =
+            TextParams::new(style).frame(rect).text(text).align(text_align, VertAlign::Middle).multiline(false);
+        params.subframe = Some(rect);
+        theme.default_font.draw(params)
+    }
+
+    fn render_text_cell(
+        &mut self,
+        index: usize,
+        column: usize,
+        selected: bool,
+        cell: &Rectangle,
+        theme: &mut Theme,
+    ) -> Option<MeshTask> {
+        let key = (index, column);
+        if !self.cell_cache.contains_key(&key) {
+            let text = self.datasource.cell_at(index, column);
+            let task =
+                Table::draw_cell_text(&text, cell.size, self.columns[column].text_align, self.layer.font_style, theme)?;
+            self.cell_cache.insert(key, task);
+        }
+        let mut mesh = self.cell_cache[&key].clone();
+        // All text meshes were created at origin 0, 0 and thus need to be translated to the cell position
+        for vertex in &mut mesh.vertices.iter_mut() {
+            vertex.pos = Transform::translate(cell.pos) * vertex.pos;
+            if selected {
+                vertex.col = self.select_fg_color;
+            }
+        }
+        // Clip the text of the first and last visible rows
+        let body = self.body_frame();
+        let y_overflow = body.y() - cell.y();
+        if y_overflow > 0.0 {
+            let bounds = Rectangle::new((cell.x(), body.y()), (cell.width(), cell.height() - y_overflow));
+            UITools::clip_mesh(&mut mesh, &bounds, RectSide::Top);
+        }
+        let y_overflow = (cell.y() + cell.height()) - (body.y() + body.height());
+        if y_overflow > 0.0 {
+            let bounds = Rectangle::new(cell.pos, (cell.width(), cell.height() - y_overflow));
+            UITools::clip_mesh(&mut mesh, &bounds, RectSide::Bottom);
+        }
+        Some(mesh)
+    }
+
+    /// Draw a Checkbox cell. The Checkbox cannot be clipped, so it is skipped if the row is partly hidden.
+    fn render_check_cell(
+        &mut self,
+        index: usize,
+        column: usize,
+        cell: &Rectangle,
+        theme: &mut Theme,
+        window: &mut Window,
+    ) {
+        let body = self.body_frame();
+        if cell.y() < body.y() || cell.y() + cell.height() > body.y() + body.height() {
+            return;
+        }
+        // The Checkbox draws a 20px box inset 5px from the left of its frame
+        let x = match self.columns[column].text_align {
+            TextAlign::Left => cell.x() + CELL_PADDING - 5.0,
+            TextAlign::Center => cell.x() + (cell.width() - 30.0) / 2.0,
+            TextAlign::Right => cell.x() + cell.width() - CELL_PADDING - 25.0,
+        };
+        let checked = Table::is_checked(&self.datasource.cell_at(index, column));
+        let view = self.check_view.get_or_insert_with(|| {
+            let mut view = Checkbox::new(*cell);
+            view.set_theme(theme);
+            view
+        });
+        view.update_frame(Rectangle::new((x, cell.y()), (30.0, cell.height())));
+        view.is_checked = checked;
+        view.clear_draw_cache();
+        view.render(theme, window);
+    }
+
+    /// Draw the header background, titles, column dividers and the sort indicator
+    fn render_header(&mut self, theme: &mut Theme, window: &mut Window) {
+        let frame = self.layer.frame;
+        let header = Rectangle::new(frame.pos, (frame.width(), self.header_height.min(frame.height())));
+        let right = frame.x() + frame.width();
+
+        let mut graphics = MeshTask::new(0);
+        let mut mesh = DrawShape::rectangle(&header, Some(self.header_bg_color), None, 0.0, 0.0);
+        graphics.append(&mut mesh);
+        let bottom = header.y() + header.height();
+        let pts: [&Vector; 2] = [&Vector::new(header.x(), bottom), &Vector::new(right, bottom)];
+        let mut line = DrawShape::line(&pts, self.grid_color, 1.0);
+        graphics.append(&mut line);
+
+        let mut titles: Vec<MeshTask> = Vec::new();
+        for column in 0..self.columns.len() {
+            let x = self.column_x(column);
+            if x >= right {
+                break;
+            }
+            let col = &self.columns[column];
+            let width = col.width.min(right - x);
+            let divider = x + col.width;
+            if divider < right {
+                let pts: [&Vector; 2] = [&Vector::new(divider, header.y()), &Vector::new(divider, bottom)];
+                let mut line = DrawShape::line(&pts, self.grid_color, 1.0);
+                graphics.append(&mut line);
+            }
+
+            // Leave room for the sort indicator on the right
+            let mut text_width = width;
+            if col.sortable {
+                text_width = (width - SORT_ARROW_SIZE - CELL_PADDING).max(0.0);
+            }
+            if let Some((sort_column, ascending)) = self.sort_column {
+                if sort_column == column && width > SORT_ARROW_SIZE + CELL_PADDING {
+                    let ax = x + width - CELL_PADDING - SORT_ARROW_SIZE;
+                    let cy = header.y() + header.height() / 2.0;
+                    let half = SORT_ARROW_SIZE / 2.0;
+                    let (tip, base) = if ascending { (cy - half, cy + half) } else { (cy + half, cy - half) };
+                    let pts: [&Vector; 3] = [
+                        &Vector::new(ax, base),
+                        &Vector::new(ax + SORT_ARROW_SIZE, base),
+                        &Vector::new(ax + half, tip),
+                    ];
+                    let mut mesh = DrawShape::triangle(&pts, Some(self.layer.font_style.get_color()), None, 0.0);
+                    graphics.append(&mut mesh);
+                }
+            }
+
+            if !self.header_cache.contains_key(&column) {
+                let size = Vector::new(text_width, header.height());
+                if let Some(task) =
+                    Table::draw_cell_text(&col.title, size, col.text_align, self.layer.font_style, theme)
+                {
+                    self.header_cache.insert(column, task);
+                }
+            }
+            if let Some(task) = self.header_cache.get(&column) {
+                let mut mesh = task.clone();
+                for vertex in &mut mesh.vertices.iter_mut() {
+                    vertex.pos = Transform::translate((x, header.y())) * vertex.pos;
+                }
+                titles.push(mesh);
+            }
+        }
+        window.add_task(graphics);
+        for mesh in titles {
+            window.add_task(mesh);
+        }
+    }
+}
+
+// *****************************************************************************************************
+// Table :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for Table {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<Table>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    /// The cached cell text and the Checkbox are discarded and created again during the render phase.
+    fn set_theme(&mut self, theme: &mut Theme) {
+        // Don't allow style to be locked
+        let _ = self.layer.apply_theme(theme);
+
+        self.header_bg_color = theme.input_bg_color;
+        self.select_bg_color = theme.button_bg_color;
+        self.select_fg_color = theme.button_fg_color;
+        self.grid_color = theme.border_color;
+        self.cell_cache.clear();
+        self.header_cache.clear();
+        self.check_view = None;
+        if let Some(edit) = &mut self.text_editor {
+            edit.field.set_theme(theme);
+        }
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        if let Some(edit) = &mut self.text_editor {
+            edit.field.update(window, state);
+        }
+        for evt in self.selection_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        self.layer.draw_background(window);
+        let range = self.get_visible_range();
+
+        // Discard cell text for rows that are out of view
+        let visible: Vec<usize> = range.clone().map(|row| self.row_map[row]).collect();
+        self.cell_cache.retain(|(index, _), _| visible.contains(index));
+
+        // Row backgrounds for striping and selection
+        let mut graphics = MeshTask::new(0);
+        for row in range.clone() {
+            let color = {
+                if self.select_row == Some(self.row_map[row]) {
+                    Some(self.select_bg_color)
+                } else if self.striped && row % 2 == 1 {
+                    Some(theme.stripe_bg_color)
+                } else {
+                    None
+                }
+            };
+            if let (Some(color), Some(rect)) = (color, self.visible_part(&self.row_frame(row))) {
+                let mut mesh = DrawShape::rectangle(&rect, Some(color), None, 0.0, 0.0);
+                graphics.append(&mut mesh);
+            }
+        }
+        window.add_task(graphics);
+
+        let editing = self.text_editor.as_ref().map(|edit| (edit.index, edit.column));
+        for row in range {
+            let index = self.row_map[row];
+            let selected = self.select_row == Some(index);
+            for column in 0..self.columns.len() {
+                let cell = self.cell_frame(row, column);
+                if cell.width() <= 0.0 {
+                    break;
+                }
+                if editing == Some((index, column)) {
+                    continue;
+                }
+                match self.columns[column].editor {
+                    CellEditor::Checkbox => self.render_check_cell(index, column, &cell, theme, window),
+                    _ => {
+                        if let Some(mesh) = self.render_text_cell(index, column, selected, &cell, theme) {
+                            window.add_task(mesh);
+                        }
+                    }
+                }
+            }
+        }
+
+        // The header is frozen and drawn on top of the rows
+        self.render_header(theme, window);
+
+        if let Some(edit) = &mut self.text_editor {
+            if !edit.started {
+                edit.field.set_theme(theme);
+                edit.field.begin_editing();
+                edit.started = true;
+            }
+            edit.field.render(theme, window);
+        }
+
+        let body = self.body_frame();
+        if let Some(rect) = UITools::get_scrollbar_frame(self.content_height(), &body, self.scroll_offset) {
+            // FIXME: use mesh
+            window.draw(&rect, Col(Color::from_hex(UITools::SCROLLBAR_COLOR)));
+        }
+        self.layer.draw_border(window);
+    }
+
+    fn set_hover_animation(&mut self, props: PropSet) {
+        self.layer.hover_effect = Some(props);
+    }
+
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        if let Some((column, start_x, start_width)) = self.resizing {
+            self.set_column_width(column, start_width + pt.x - start_x);
+            window.set_cursor(MouseCursor::ColResize);
+            return true;
+        }
+        if let Some(edit) = &mut self.text_editor {
+            if edit.field.handle_mouse_at(pt, window) {
+                return true;
+            }
+        }
+        if pt.overlaps_rectangle(&self.layer.frame) {
+            self.layer.mouse_state = MouseState::Hover;
+            if pt.y < self.body_frame().y() && self.divider_at(pt.x).is_some() {
+                window.set_cursor(MouseCursor::ColResize);
+            } else {
+                window.set_cursor(MouseCursor::Hand);
+            }
+            return true;
+        }
+        window.set_cursor(MouseCursor::Default);
+        self.layer.mouse_state = MouseState::None;
+        false
+    }
+}
+
+// *****************************************************************************************************
+// Table :: Responder
+// *****************************************************************************************************
+
+impl Responder for Table {
+    fn get_field_value(&self) -> FieldValue {
+        match self.select_row {
+            Some(index) => FieldValue::Selections(vec![index]),
+            None => FieldValue::None,
+        }
+    }
+
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if type_id == self.get_type_id() && layer_id == self.layer.get_id() {
+            match value {
+                FieldValue::Selections(rows) => {
+                    if let Some(index) = rows.first() {
+                        self.select_index(*index);
+                    } else {
+                        self.clear_selection();
+                    }
+                    return true;
+                }
+                FieldValue::None => {
+                    self.clear_selection();
+                    return true;
+                }
+                _ => (),
+            }
+        }
+        false
+    }
+
+    /// A click on a header divider starts a column resize and a click on a sortable header toggles the
+    /// sort order. A click on a row selects it. Checkbox cells toggle on click and Text cells start
+    /// editing if the row was already selected.
+    fn handle_mouse_down(&mut self, pt: &Vector, state: &mut AppState) -> bool {
+        if let Some(edit) = &mut self.text_editor {
+            if edit.field.handle_mouse_down(pt, state) {
+                return true;
+            }
+            self.commit_edit();
+        }
+        if !pt.overlaps_rectangle(&self.layer.frame) {
+            return false;
+        }
+        if pt.y < self.body_frame().y() {
+            if let Some(column) = self.divider_at(pt.x) {
+                self.resizing = Some((column, pt.x, self.columns[column].width));
+            } else if let Some(column) = self.column_at(pt.x) {
+                if self.columns[column].sortable {
+                    let ascending = match self.sort_column {
+                        Some((sort_column, ascending)) if sort_column == column => !ascending,
+                        _ => true,
+                    };
+                    self.sort_by(column, ascending);
+                }
+            }
+            return true;
+        }
+        if let Some(row) = self.row_at_point(pt) {
+            let index = self.row_map[row];
+            state.row_target = Some(index);
+            let was_selected = self.select_row == Some(index);
+            self.select_display_row(row);
+            if let Some(column) = self.column_at(pt.x) {
+                match self.columns[column].editor {
+                    CellEditor::Checkbox => self.toggle_cell(index, column),
+                    CellEditor::Text if was_selected => {
+                        self.begin_edit(index, column);
+                    }
+                    _ => (),
+                }
+            }
+        }
+        true
+    }
+
+    fn handle_mouse_up(&mut self, _pt: &Vector, _state: &mut AppState) -> bool {
+        if let Some((column, _, _)) = self.resizing.take() {
+            self.pending_events.push(TableEvent::ColumnResized(column));
+            return true;
+        }
+        false
+    }
+
+    /// Add or subtract from the scroll offset. An active cell editor is committed first since it does
+    /// not scroll with the rows.
+    fn handle_mouse_scroll(&mut self, pt: &Vector, _state: &mut AppState) {
+        match self.layer.mouse_state {
+            MouseState::Hover => {
+                self.commit_edit();
+                let upper_limit = (self.content_height() - self.body_frame().height()).max(0.0);
+                let eval_y = ((self.scroll_offset + pt.y) * SCROLL_FACTOR).min(upper_limit);
+                self.scroll_offset = eval_y.max(0.0);
+            }
+            _ => (),
+        }
+    }
+
+    fn handle_key_press(&mut self, c: char, window: &mut Window) {
+        if let Some(edit) = &mut self.text_editor {
+            edit.field.handle_key_press(c, window);
+        }
+    }
+
+    fn handle_text_input(&mut self, event: &TextInputEvent, window: &mut Window) -> bool {
+        if let Some(edit) = &mut self.text_editor {
+            return edit.field.handle_text_input(event, window);
+        }
+        false
+    }
+
+    /// While editing, Return and Tab save the cell and Escape cancels. Otherwise the arrow keys, Home,
+    /// End, PageUp and PageDown move the selection and Return edits the first Text cell of the row.
+    fn handle_key_command(&mut self, key: &Key, window: &mut Window) -> bool {
+        if let Some(edit) = &mut self.text_editor {
+            match key {
+                Key::Return | Key::Tab => {
+                    edit.field.handle_key_command(key, window);
+                    self.commit_edit();
+                }
+                Key::Escape => self.cancel_edit(),
+                _ => {
+                    edit.field.handle_key_command(key, window);
+                }
+            }
+            return true;
+        }
+        let page = UITools::page_size(self.body_frame().height(), self.row_height);
+        if let Some(target) = UITools::row_for_key(key, self.focus_row, self.row_count(), page) {
+            self.select_display_row(target);
+            self.scroll_to_row(target);
+            return true;
+        }
+        match key {
+            Key::Return => {
+                if let Some(row) = self.focus_row {
+                    let index = self.row_map[row];
+                    let column = self.columns.iter().position(|col| col.editor == CellEditor::Text);
+                    if let Some(column) = column {
+                        self.begin_edit(index, column);
+                    } else {
+                        self.selection_events.push(SelectionEvent::Activated(index));
+                    }
+                    return true;
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_mixed_column() {
+        let mut cells = vec!["pear", "10", "Apple", "NaN", "2.5", "", "-3", "apple", "inf", "10"];
+        cells.sort_by(|a, b| Table::compare_cells(a, b));
+        assert_eq!(cells, vec!["-3", "2.5", "10", "10", "inf", "", "Apple", "apple", "NaN", "pear"]);
+
+        // Every pair must agree in both directions for sort_by to be well defined
+        for a in &cells {
+            for b in &cells {
+                assert_eq!(Table::compare_cells(a, b), Table::compare_cells(b, a).reverse());
+            }
+        }
+        // Transitivity across a number, text that looks numeric and plain text
+        assert_eq!(Table::compare_cells("9", "1e3"), Ordering::Less);
+        assert_eq!(Table::compare_cells("1e3", "a"), Ordering::Less);
+        assert_eq!(Table::compare_cells("9", "a"), Ordering::Less);
+    }
+
+    fn table() -> Table {
+        let data = TableData::from_csv("Name,City\nAnn,Oslo\nBob,Rome\n");
+        let columns = vec![TableColumn::new("Name", 100.0).with_limits(50.0, 150.0), TableColumn::new("City", 100.0)];
+        let mut table = Table::new(Rectangle::new((0.0, 0.0), (300.0, 200.0)))
+            .with_columns(columns.into_iter().map(|col| col.with_editor(CellEditor::Text)).collect());
+        table.set_source(data);
+        table
+    }
+
+    #[test]
+    fn test_from_csv() {
+        let text = "Name,Note,Price\n\n\"Smith, Ann\",\"She said \"\"hi\"\"\",9.50\nBob\n,,\n";
+        let data = TableData::from_csv(text);
+        assert_eq!(data.headers, vec!["Name", "Note", "Price"]);
+        assert_eq!(data.row_count(), 3);
+        assert_eq!(data.rows[0], vec!["Smith, Ann", "She said \"hi\"", "9.50"]);
+        // Short rows return empty text for the missing cells
+        assert_eq!(data.rows[1], vec!["Bob"]);
+        assert_eq!(data.cell_at(1, 2), "");
+        assert_eq!(data.cell_at(2, 0), "");
+        assert_eq!(data.cell_at(5, 0), "");
+
+        let mut data = data;
+        assert!(data.set_cell(1, 2, "4.00"));
+        assert_eq!(data.rows[1], vec!["Bob", "", "4.00"]);
+        assert!(!data.set_cell(5, 0, "x"));
+    }
+
+    #[test]
+    fn test_column_width_limits() {
+        let mut table = table();
+        table.set_column_width(0, 10.0);
+        assert_eq!(table.columns[0].width, 50.0);
+        table.set_column_width(0, 500.0);
+        assert_eq!(table.columns[0].width, 150.0);
+        table.set_column_width(0, 120.0);
+        assert_eq!(table.columns[0].width, 120.0);
+        // The default min width still applies without limits
+        table.set_column_width(1, 0.0);
+        assert_eq!(table.columns[1].width, 20.0);
+        // A max width below the min width is raised to it
+        assert_eq!(TableColumn::new("A", 10.0).with_limits(40.0, 30.0).max_width, 40.0);
+    }
+
+    #[test]
+    fn test_edit_commit_cancel() {
+        let mut table = table();
+        assert!(table.begin_edit(1, 1));
+        assert!(table.is_editing());
+        table.text_editor.as_mut().unwrap().field.set_text("Paris");
+        table.commit_edit();
+        assert!(!table.is_editing());
+        assert_eq!(table.cell_text(1, 1), Some("Paris".to_string()));
+        assert_eq!(table.pending_events, vec![TableEvent::CellEdited(1, 1)]);
+
+        assert!(table.begin_edit(0, 0));
+        table.text_editor.as_mut().unwrap().field.set_text("Eve");
+        table.cancel_edit();
+        assert!(!table.is_editing());
+        assert_eq!(table.cell_text(0, 0), Some("Ann".to_string()));
+
+        // Committing unchanged text does not emit an event
+        table.pending_events.clear();
+        assert!(table.begin_edit(0, 0));
+        table.commit_edit();
+        assert!(table.pending_events.is_empty());
+
+        // Beginning another edit commits the active one
+        assert!(table.begin_edit(0, 0));
+        table.text_editor.as_mut().unwrap().field.set_text("Eve");
+        assert!(table.begin_edit(1, 0));
+        assert_eq!(table.cell_text(0, 0), Some("Eve".to_string()));
+
+        // Only Text columns can be edited
+        table.columns[1].editor = CellEditor::None;
+        assert!(!table.begin_edit(0, 1));
+        assert!(!table.begin_edit(9, 0));
+    }
+}
@@ -115,11 +115,36 @@ impl TextArea {
 
     /// Switches to read-only state
     fn stop_editing(&mut self) {
+        self.editor.ctx.cancel_composition();
         self.is_editing = false;
         self.cursor = None;
         self.update_rendered_text();
     }
 
+    /// Draw the text with the uncommitted IME composition inserted at the cursor and underlined.
+    /// Returns the position of the cursor baseline.
+    fn render_composition(&mut self, window: &mut Window) -> (f32, f32) {
+        let (text, range) = self.editor.ctx.display_text();
+        let range = range.unwrap_or(0..0);
+        let preedit_cursor = self.editor.ctx.composition().map_or(0, |p| p.cursor);
+        let preedit: String = text.chars().skip(range.start).take(range.end - range.start).collect();
+        let before_cursor: String = preedit.chars().take(preedit_cursor).collect();
+
+        let params = TextParams::new(self.layer.font_style).frame(self.input_frame.clone()).text(&text).multiline(true);
+        if let Some(task) = self.editor.ctx.draw_font.draw(params) {
+            window.add_task(task);
+        }
+        // The preedit text starts at the cursor and is assumed to fit on the current line
+        let (x, y) = self.editor.ctx.cursor_origin;
+        let end_x = x + self.editor.ctx.measure_text(&preedit).0;
+        let pts: [&Vector; 2] = [&Vector::new(x, y + 2.0), &Vector::new(end_x, y + 2.0)];
+        let mut line = DrawShape::line(&pts, self.layer.font_style.get_color(), 1.0);
+        let mut task = MeshTask::new(0);
+        task.append(&mut line);
+        window.add_task(task);
+        (x + self.editor.ctx.measure_text(&before_cursor).0, y)
+    }
+
     fn update_rendered_text(&mut self) {
         self.editor.tex_info = None;
         if self.get_text().len() == 0 {
@@ -232,7 +257,10 @@ impl Displayable for TextArea {
 
         if self.is_editing {
             self.editor.update_textarea();
-            if self.get_text().len() > 0 {
+            let mut cursor_pt = self.editor.ctx.cursor_origin;
+            if self.editor.ctx.is_composing() {
+                cursor_pt = self.render_composition(window);
+            } else if self.get_text().len() > 0 {
                 if let Some(mesh_task) = &self.editor.ctx.draw_font.cached_mesh {
                     window.add_task(mesh_task.clone());
                 } else {
@@ -248,7 +276,7 @@ impl Displayable for TextArea {
                 }
             }
             if let Some(cursor) = &mut self.cursor {
-                let mut pt = self.editor.ctx.cursor_origin;
+                let mut pt = cursor_pt;
 
                 let cursor_height = theme.font_size;
                 let y2 = pt.1 + cursor_height * 0.2;
@@ -325,16 +353,21 @@ impl Responder for TextArea {
     }
 
     fn handle_key_press(&mut self, c: char, _window: &mut Window) {
-        if c.is_ascii_control() {
+        if c.is_control() {
             return;
         }
-        if self.can_edit && c.is_ascii() {
+        if self.can_edit {
             self.editor.ctx.insert_char(c);
-        } else {
-            // log::debug!("### non ascii={}", c);
         }
     }
 
+    fn handle_text_input(&mut self, event: &TextInputEvent, _window: &mut Window) -> bool {
+        if !self.can_edit || !self.is_editing {
+            return false;
+        }
+        self.editor.ctx.handle_text_input(event)
+    }
+
     fn handle_key_command(&mut self, key: &Key, _window: &mut Window) -> bool {
         match key {
             Key::Back => {
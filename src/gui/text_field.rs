@@ -20,13 +20,25 @@
 ///   – If size of rendered text is larger than width of input_frame, anchor text input to right side.
 ///     Also, calculate the approximate number of characters to display left of the cursor, since there
 ///     is no easy means of masking rendered text. Also shorter text means better performance.
-/// * If cursor is inserting within the string, a temporary buffer is
+/// * If cursor is inserting within the string, a temporary buffer is used to measure the text left of
+///   the cursor and place the cursor there.
+///
+/// Validation and formatting:
+/// * A FieldValidator can be attached with with_validator() and is evaluated when editing stops.
+///   Once an error is displayed, the field is revalidated on every change so the error clears live.
+///   The field border is drawn in the theme error_color and the validation_message() is drawn
+///   below the field.
+/// * An InputMask formats fixed-pattern input as the user types.
+/// * A formatter closure can rewrite the text when editing stops, e.g. to trim or normalize it.
+///
 /// TODO:
 /// * Hyperlink fieldtype
 /// * Password fieldtype
 ///
-/// Known Issues:
-/// * Special chars cause crash. Try: textfield.set_text("čćdđe ёєжзѕиіїйјк");
+/// IME composition:
+/// * Uncommitted preedit text from handle_text_input() is drawn underlined at the cursor and is only
+///   inserted into the text when the composition is committed.
+///
 ///
 use super::*;
 use crate::core::*;
@@ -44,15 +56,56 @@ use std::any::TypeId;
 
 /// A type specifier that allows the textfield to have different behaviors, such as password input,
 /// URL rendering, etc.
-/// Future: date input
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum TextFieldType {
     /// Basic text
     Normal,
     /// Passwords and other text that are masked with the specified char
     Secure(char),
-    /// It should display as hyperlink with click to open browser
+    /// It should display as hyperlink with click to open browser. Validated as an http(s) link.
     /// TODO: Implement
     Url,
+    /// Decimal number. Only digits, '.' and '-' can be typed.
+    Numeric,
+    /// Whole number. Only digits and '-' can be typed.
+    Integer,
+    /// Email address
+    Email,
+    /// Date in YYYY-MM-DD format
+    Date,
+}
+
+impl TextFieldType {
+    /// Check the text against the built-in format for this type. Empty text is always valid here and
+    /// should be handled with ValidationRule::Required.
+    pub fn validate(&self, text: &str) -> Result<(), ValidationError> {
+        if text.is_empty() {
+            return Ok(());
+        }
+        let (ok, err) = match self {
+            TextFieldType::Url => (Validators::is_url(text), ValidationError::InvalidUrl),
+            TextFieldType::Numeric => (Validators::is_number(text), ValidationError::InvalidNumber),
+            TextFieldType::Integer => (Validators::is_integer(text), ValidationError::InvalidInteger),
+            TextFieldType::Email => (Validators::is_email(text), ValidationError::InvalidEmail),
+            TextFieldType::Date => (Validators::is_date(text), ValidationError::InvalidDate),
+            _ => (true, ValidationError::Custom),
+        };
+        if ok {
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+
+    /// Returns false if the char can never appear in this type of field
+    pub fn accepts(&self, c: char) -> bool {
+        match self {
+            TextFieldType::Numeric => c.is_ascii_digit() || c == '.' || c == '-',
+            TextFieldType::Integer => c.is_ascii_digit() || c == '-',
+            TextFieldType::Date => c.is_ascii_digit() || c == '-',
+            _ => true,
+        }
+    }
 }
 
 /// Dot char for secure text masking
@@ -83,6 +136,20 @@ pub struct TextField {
     is_hovering: bool,
     can_edit: bool,
     scroll_offset: Vector,
+    /// Optional rules that are checked when editing stops
+    validator: Option<FieldValidator>,
+    /// Optional mask that formats input as the user types
+    input_mask: Option<InputMask>,
+    /// Optional limit on the number of chars that can be typed
+    max_length: Option<usize>,
+    /// Optional function to reformat the text when editing stops
+    formatter: Option<Box<dyn Fn(&str) -> String + 'static>>,
+    /// The current validation error, if any
+    validation_error: Option<ValidationError>,
+    /// Cached copy of the rendered validation message
+    image_message: Option<Image>,
+    /// FieldEvents waiting to be dispatched in the next update()
+    pending_events: Vec<FieldEvent>,
 }
 
 impl TextField {
@@ -110,6 +177,13 @@ impl TextField {
             is_hovering: false,
             can_edit,
             scroll_offset: Vector::new(0.0, 0.0),
+            validator: None,
+            input_mask: None,
+            max_length: None,
+            formatter: None,
+            validation_error: None,
+            image_message: None,
+            pending_events: Vec::new(),
         }
     }
 
@@ -119,9 +193,144 @@ impl TextField {
         self
     }
 
+    /// Builder method to set the validation rules
+    pub fn with_validator(mut self, validator: FieldValidator) -> Self {
+        self.validator = Some(validator);
+        self
+    }
+
+    /// Builder method to set an input mask, such as "(###) ###-####"
+    pub fn with_mask(mut self, pattern: &str) -> Self {
+        self.input_mask = Some(InputMask::new(pattern));
+        self
+    }
+
+    /// Builder method to limit the number of chars that can be typed. With an input mask, only the
+    /// typed chars count and not the literals inserted by the mask.
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    /// Builder method to set a function that reformats the text when editing stops
+    pub fn with_formatter<C>(mut self, cb: C) -> Self
+    where
+        C: Fn(&str) -> String + 'static,
+    {
+        self.formatter = Some(Box::new(cb));
+        self
+    }
+
+    /// Builder method to change the inset of the text from the edges of the field. The default is 10.0
+    /// on all sides, which is too much for small fields like table cells.
+    pub fn with_padding(mut self, horizontal: f32, vertical: f32) -> Self {
+        self.input_frame = self.layer.inset_by(horizontal, vertical, horizontal, vertical);
+        let rect = self.input_frame;
+        self.editor = self.editor.with_frame((rect.x(), rect.y()), (rect.width(), rect.height()));
+        self
+    }
+
     /// Set the text in the field
     pub fn set_text(&mut self, text: &str) {
         self.editor.ctx.set_text(text);
+        self.image_text = None;
+    }
+
+    /// Get the user-facing message for the current validation error
+    pub fn validation_message(&self) -> Option<String> {
+        self.validation_error.map(|err| err.description())
+    }
+
+    /// Check the text against the field type, the input mask and the validator
+    fn check_text(&self, text: &str) -> Result<(), ValidationError> {
+        if let Some(validator) = &self.validator {
+            validator.validate(text)?;
+        }
+        if let Some(mask) = &self.input_mask {
+            let raw = mask.strip(text);
+            if !raw.is_empty() && !mask.is_complete(&raw) {
+                return Err(ValidationError::Incomplete);
+            }
+        }
+        self.field_type.validate(text)
+    }
+
+    /// Draw the text with the uncommitted IME composition inserted at the cursor and underlined.
+    /// Returns the x position of the cursor.
+    fn render_composition(&mut self, window: &mut Window) -> f32 {
+        let (text, range) = self.editor.ctx.display_text();
+        let range = range.unwrap_or(0..0);
+        let preedit_cursor = self.editor.ctx.composition().map_or(0, |p| p.cursor);
+        let prefix: String = text.chars().take(range.start).collect();
+        let preedit: String = text.chars().skip(range.start).take(range.end - range.start).collect();
+        let before_cursor: String = text.chars().take(range.start + preedit_cursor).collect();
+
+        // Start from the same scroll position as the committed text and then shift it so that the
+        // composition cursor stays inside the input frame.
+        let left = self.input_frame.x();
+        let right = left + self.input_frame.width();
+        let mut origin_x = left - self.editor.ctx.scroll_x();
+        let cursor_x = origin_x + self.editor.ctx.measure_text(&before_cursor).0;
+        if cursor_x > right {
+            origin_x -= cursor_x - right;
+        } else if cursor_x < left {
+            origin_x += left - cursor_x;
+        }
+        let start_x = origin_x + self.editor.ctx.measure_text(&prefix).0;
+        let end_x = start_x + self.editor.ctx.measure_text(&preedit).0;
+        let cursor_x = origin_x + self.editor.ctx.measure_text(&before_cursor).0;
+
+        let text_width = self.editor.ctx.measure_text(&text).0.max(self.input_frame.width());
+        let text_frame = Rectangle::new((origin_x, self.input_frame.y()), (text_width, self.input_frame.height()));
+        let mut params = TextParams::new(self.layer.font_style).text(&text).frame(text_frame).multiline(false);
+        params.subframe = Some(self.input_frame.clone());
+        if let Some(mut task) = self.editor.ctx.draw_font.draw(params) {
+            self.layer.clip_to_corners(&mut task);
+            window.add_task(task);
+        }
+        let y = self.input_frame.y() + (self.input_frame.height() + self.editor.ctx.font_size) / 2.0 + 1.0;
+        let (start_x, end_x) = (start_x.max(left).min(right), end_x.max(left).min(right));
+        let pts: [&Vector; 2] = [&Vector::new(start_x, y), &Vector::new(end_x, y)];
+        let mut line = DrawShape::line(&pts, self.layer.font_style.get_color(), 1.0);
+        let mut task = MeshTask::new(0);
+        task.append(&mut line);
+        window.add_task(task);
+        cursor_x
+    }
+
+    /// Draw the validation message in the error color below the field
+    fn render_message(&mut self, theme: &mut Theme, window: &mut Window) {
+        if self.image_message.is_none() {
+            if let Some(message) = self.validation_message() {
+                let style = FontStyle::new(theme.font_size * 0.8, theme.error_color);
+                let frame = self.layer.frame;
+                let rect =
+                    Rectangle::new((frame.x(), frame.y() + frame.height() + 4.0), (frame.width(), frame.height()));
+                self.image_message = self.editor.ctx.draw_font.render(&message, &style, &rect, false);
+            }
+        }
+        if let Some(img) = &self.image_message {
+            let frame = self.layer.frame;
+            let pos = Vector::new(frame.x(), frame.y() + frame.height() + 4.0);
+            window.draw(&img.area().translate(pos), Img(&img));
+        }
+    }
+
+    /// Validate the current text and queue a Valid or Invalid event if the state has changed
+    /// or if always_notify is true
+    fn validate(&mut self, always_notify: bool) {
+        let result = self.check_text(self.get_text()).err();
+        if always_notify || result != self.validation_error {
+            let event = match result {
+                Some(err) => FieldEvent::Invalid(err),
+                None => FieldEvent::Valid,
+            };
+            self.pending_events.push(event);
+        }
+        if result != self.validation_error {
+            self.image_message = None;
+        }
+        self.validation_error = result;
     }
 
     /// Get the text in the field
@@ -134,6 +343,15 @@ impl TextField {
         self.placeholder = Some(text.to_string());
     }
 
+    /// Start editing with the cursor at the end of the text. Used by parent controls that embed a
+    /// TextField as an editor, since normally editing starts with a mouse click.
+    pub fn begin_editing(&mut self) {
+        if self.can_edit {
+            let pos = Some(self.get_text().chars().count());
+            self.start_editing(pos);
+        }
+    }
+
     /// Switch to editing mode
     fn start_editing(&mut self, position: Option<usize>) {
         self.layer.mouse_state = MouseState::Focus;
@@ -150,6 +368,17 @@ impl TextField {
 
     /// Switch read mode
     fn stop_editing(&mut self) {
+        self.editor.ctx.cancel_composition();
+        if self.is_editing {
+            if let Some(cb) = &self.formatter {
+                let text = cb(self.get_text());
+                if text != self.get_text() {
+                    self.editor.ctx.replace_text(&text);
+                    self.pending_events.push(FieldEvent::Changed);
+                }
+            }
+            self.validate(true);
+        }
         self.is_editing = false;
         self.image_text = None;
         self.cursor = None;
@@ -187,6 +416,7 @@ impl Displayable for TextField {
         self.editor.ctx.set_font_data(data, theme.font_size);
         self.layer.border_style = BorderStyle::SolidLine(theme.border_color, theme.border_width);
         self.layer.bg_style = BackgroundStyle::None;
+        self.image_message = None;
 
         if let Some(cursor) = &mut self.cursor {
             cursor.set_theme(theme);
@@ -228,6 +458,9 @@ impl Displayable for TextField {
         if let Some(cursor) = &mut self.cursor {
             cursor.update(window, state);
         }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
     }
 
     fn render(&mut self, theme: &mut Theme, window: &mut Window) {
@@ -236,15 +469,19 @@ impl Displayable for TextField {
             self.editor.update_textfield();
             let mut cursor_x = self.editor.ctx.cursor_origin.0;
 
-            if self.get_text().len() > 0 {
+            if self.editor.ctx.is_composing() {
+                cursor_x = self.render_composition(window);
+            } else if self.get_text().len() > 0 {
                 if let Some(mesh_task) = &self.editor.ctx.draw_font.cached_mesh {
-                    window.add_task(mesh_task.clone());
+                    let mut mesh_task = mesh_task.clone();
+                    self.layer.clip_to_corners(&mut mesh_task);
+                    window.add_task(mesh_task);
                 } else {
                     if let Some(text) = self.editor.get_visible_text(0.0) {
                         let text = {
                             match self.field_type {
                                 TextFieldType::Secure(c) => {
-                                    let mask = c.to_string().repeat(text.len());
+                                    let mask = c.to_string().repeat(text.chars().count());
                                     let size = self.editor.ctx.measure_text(&mask);
                                     cursor_x = self.input_frame.x() + size.0;
                                     mask
@@ -280,7 +517,8 @@ impl Displayable for TextField {
                             }
                             _ => (),
                         }
-                        if let Some(task) = self.editor.ctx.draw_font.draw(params) {
+                        if let Some(mut task) = self.editor.ctx.draw_font.draw(params) {
+                            self.layer.clip_to_corners(&mut task);
                             window.add_task(task);
                         }
                     }
@@ -305,7 +543,7 @@ impl Displayable for TextField {
                     if let Some(text) = self.editor.get_visible_text(0.0) {
                         let text = {
                             match self.field_type {
-                                TextFieldType::Secure(c) => c.to_string().repeat(text.len()),
+                                TextFieldType::Secure(c) => c.to_string().repeat(text.chars().count()),
                                 _ => text,
                             }
                         };
@@ -335,7 +573,17 @@ impl Displayable for TextField {
         }
 
         // Draw border
-        self.layer.draw_border(window);
+        if self.validation_error.is_some() {
+            let width = self.layer.border_style.get_border().1.max(1.0);
+            let radii = self.layer.current_radii();
+            let mut mesh = DrawShape::rounded_rectangle(&self.layer.frame, None, Some(theme.error_color), width, radii);
+            let mut task = MeshTask::new(0);
+            task.append(&mut mesh);
+            window.add_task(task);
+            self.render_message(theme, window);
+        } else {
+            self.layer.draw_border(window);
+        }
     }
 
     fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
@@ -355,25 +603,93 @@ impl Displayable for TextField {
 
 impl Responder for TextField {
     fn get_field_value(&self) -> FieldValue {
-        FieldValue::Text(self.get_text().to_owned())
+        let text = self.get_text().to_owned();
+        match self.check_text(&text) {
+            Ok(_) => FieldValue::Text(text),
+            Err(err) => FieldValue::Invalid(text, err),
+        }
     }
 
     fn handle_key_press(&mut self, c: char, _window: &mut Window) {
-        if c.is_ascii_control() {
+        if c.is_control() {
             return;
         }
-        if self.can_edit && c.is_ascii() {
-            self.editor.ctx.insert_char(c);
+        if self.can_edit {
+            if !self.field_type.accepts(c) {
+                return;
+            }
+            if let Some(mask) = &self.input_mask {
+                // Insert into the raw input at the cursor and format it again
+                let mut raw: Vec<char> = mask.strip(self.get_text()).chars().collect();
+                let index = mask.raw_index(self.editor.ctx.cursor_pos).min(raw.len());
+                raw.insert(index, c);
+                let raw: String = raw.into_iter().collect();
+                if !mask.fits(&raw) || self.max_length.map_or(false, |max_length| raw.chars().count() > max_length) {
+                    return;
+                }
+                let text = mask.apply(&raw);
+                let cursor = mask.formatted_index(index + 1);
+                self.editor.ctx.replace_text(&text);
+                self.editor.ctx.cursor_pos = cursor.min(self.editor.ctx.char_count());
+            } else {
+                if let Some(max_length) = self.max_length {
+                    if self.get_text().chars().count() >= max_length {
+                        return;
+                    }
+                }
+                self.editor.ctx.insert_char(c);
+            }
             self.image_text = None;
-        } else {
-            log::debug!("### non ascii={}", c);
+            self.pending_events.push(FieldEvent::Changed);
+            if self.validation_error.is_some() {
+                self.validate(false);
+            }
+        }
+    }
+
+    fn handle_text_input(&mut self, event: &TextInputEvent, window: &mut Window) -> bool {
+        if !self.can_edit || !self.is_editing {
+            return false;
         }
+        match event {
+            TextInputEvent::CompositionCommit(text) => {
+                // Insert through handle_key_press so that the field type, mask and max length apply
+                self.editor.ctx.cancel_composition();
+                for c in text.chars() {
+                    self.handle_key_press(c, window);
+                }
+            }
+            _ => {
+                self.editor.ctx.handle_text_input(event);
+            }
+        }
+        self.image_text = None;
+        true
     }
 
     fn handle_key_command(&mut self, key: &Key, _window: &mut Window) -> bool {
         match key {
             Key::Back => {
-                self.editor.ctx.delete_char();
+                if let Some(mask) = &self.input_mask {
+                    // Remove the raw char before the cursor, skipping over literals
+                    let mut raw: Vec<char> = mask.strip(self.get_text()).chars().collect();
+                    let index = mask.raw_index(self.editor.ctx.cursor_pos).min(raw.len());
+                    if index == 0 {
+                        return false;
+                    }
+                    raw.remove(index - 1);
+                    let raw: String = raw.into_iter().collect();
+                    let text = mask.apply(&raw);
+                    let cursor = mask.formatted_index(index - 1);
+                    self.editor.ctx.replace_text(&text);
+                    self.editor.ctx.cursor_pos = cursor.min(self.editor.ctx.char_count());
+                } else {
+                    self.editor.ctx.delete_char();
+                }
+                self.pending_events.push(FieldEvent::Changed);
+                if self.validation_error.is_some() {
+                    self.validate(false);
+                }
             }
             Key::Left => {
                 self.editor.ctx.move_cursor(-1);
@@ -405,7 +721,7 @@ impl Responder for TextField {
                     return true;
                 }
                 _ => {
-                    let pos = Some(self.get_text().len());
+                    let pos = Some(self.editor.ctx.char_count());
                     self.start_editing(pos);
                     return true;
                 }
@@ -1,8 +1,9 @@
+use super::ShadowStyle;
 use crate::core::*;
 use crate::events::*;
 use crate::tools::*;
 
-use quicksilver::graphics::Color;
+use quicksilver::{geom::Vector, graphics::Color};
 
 use std::{
     collections::HashMap,
@@ -51,8 +52,26 @@ pub struct Theme {
     pub button_fg_color: Color,
     /// Scrollbar slider color
     pub scrollbar_fg_color: Color,
+    /// Background color for alternating rows in tables
+    pub stripe_bg_color: Color,
+    /// Background color for tooltips
+    pub tooltip_bg_color: Color,
+    /// Text color for tooltips
+    pub tooltip_fg_color: Color,
+    /// Accent color for success messages
+    pub success_color: Color,
+    /// Accent color for warning messages
+    pub warning_color: Color,
+    /// Track color for progress bars and spinners
+    pub progress_bg_color: Color,
+    /// Fill color for progress bars and spinners
+    pub progress_fg_color: Color,
+    /// Shadow for menus and dropdown popups. None for flat popups.
+    pub popup_shadow: Option<ShadowStyle>,
     /// Cursor color
     pub cursor_color: Color,
+    /// Border and message color for input fields that fail validation
+    pub error_color: Color,
     /// Border width
     pub border_width: f32,
     /// Default margins (internal padding) for horizontal and vertical
@@ -69,6 +88,8 @@ pub struct Theme {
     /// this font, regardless of font size or color. A DrawFont maintains its own GPU texture which
     /// makes performance good.
     pub default_font: DrawFont,
+    /// Shared texture pages for registered images. Like the default font, this keeps the number of GPU textures low.
+    pub image_atlas: ImageAtlas,
     /// A simple Fonts table
     fonts: Fonts,
 }
@@ -94,7 +115,16 @@ impl Default for Theme {
             button_bg_color: Color::from_hex("#4373c2"),
             button_fg_color: Color::from_hex("#FFFFFF"),
             cursor_color: Color::from_hex("#80A4C2"),
+            error_color: Color::from_hex("#D0342C"),
             scrollbar_fg_color: Color::from_hex("#CCCCCC"),
+            stripe_bg_color: Color::from_hex("#F4F4F4"),
+            tooltip_bg_color: Color::from_hex("#333333"),
+            tooltip_fg_color: Color::from_hex("#FFFFFF"),
+            success_color: Color::from_hex("#2E7D32"),
+            warning_color: Color::from_hex("#F9A825"),
+            progress_bg_color: Color::from_hex("#E0E0E0"),
+            progress_fg_color: Color::from_hex("#4373c2"),
+            popup_shadow: Some(ShadowStyle::new(Color { a: 0.25, ..Color::BLACK }, Vector::new(0.0, 2.0), 8.0)),
             border_width: 1.0,
             margins: (5.0, 5.0),
             field_height: 20.0,
@@ -102,6 +132,7 @@ impl Default for Theme {
             on_button_click: PropSet::new([color("#AAAAAA")].to_vec(), 0.2).for_type(TweenType::Click),
             on_view_hover: PropSet::new(Vec::new(), 0.0).for_type(TweenType::Hover),
             default_font: font,
+            image_atlas: ImageAtlas::new(),
             fonts: Fonts::new(),
         }
     }
@@ -303,7 +334,16 @@ impl ThemeBuilder {
             button_bg_color: Color::from_hex("#4373c2"), // selection.background
             button_fg_color: Color::from_hex("#ffffff"), // button.foreground
             cursor_color: Color::from_hex("#80a4c2"),    // editorCursor.foreground
+            error_color: Color::from_hex("#ef5350"),     // inputValidation.errorBorder
             scrollbar_fg_color: Color::from_hex("#084D81"),
+            stripe_bg_color: Color::from_hex("#0b2942"), // list.inactiveSelectionBackground
+            tooltip_bg_color: Color::from_hex("#0b2942"),
+            tooltip_fg_color: Color::from_hex("#d6deeb"),
+            success_color: Color::from_hex("#addb67"),
+            warning_color: Color::from_hex("#ecc48d"),
+            progress_bg_color: Color::from_hex("#1d3b53"),
+            progress_fg_color: Color::from_hex("#7fdbca"),
+            popup_shadow: Some(ShadowStyle::new(Color { a: 0.5, ..Color::BLACK }, Vector::new(0.0, 3.0), 10.0)),
             on_button_hover: PropSet::new([color("#EEEEEE")].to_vec(), 0.2).for_type(TweenType::Hover),
             on_button_click: PropSet::new([shift(3.0, 3.0)].to_vec(), 0.0).for_type(TweenType::Click),
             on_view_hover: PropSet::new(Vec::new(), 0.0).for_type(TweenType::Hover),
@@ -311,6 +351,7 @@ impl ThemeBuilder {
             margins: (5.0, 5.0),
             field_height: 20.0,
             default_font: font,
+            image_atlas: ImageAtlas::new(),
             fonts: Fonts::new(),
         }
     }
@@ -334,7 +375,16 @@ impl ThemeBuilder {
             button_bg_color: Color::from_hex("#7a8181"), // selection.background
             button_fg_color: Color::from_hex("#F0F0F0"), // button.foreground
             cursor_color: Color::from_hex("#90A7B2"),    // editorCursor.foreground
+            error_color: Color::from_hex("#de3d3b"),     // inputValidation.errorBorder
             scrollbar_fg_color: Color::from_hex("#CCCCCC"),
+            stripe_bg_color: Color::from_hex("#F2F2F2"), // list.hoverBackground
+            tooltip_bg_color: Color::from_hex("#403f53"),
+            tooltip_fg_color: Color::from_hex("#FBFBFB"),
+            success_color: Color::from_hex("#08916a"),
+            warning_color: Color::from_hex("#daaa01"),
+            progress_bg_color: Color::from_hex("#E0E0E0"),
+            progress_fg_color: Color::from_hex("#4876d6"),
+            popup_shadow: Some(ShadowStyle::new(Color { a: 0.2, ..Color::BLACK }, Vector::new(0.0, 2.0), 8.0)),
             on_button_hover: PropSet::new([color("#AAAAAA")].to_vec(), 0.2).for_type(TweenType::Hover),
             on_button_click: PropSet::new([shift(3.0, 3.0)].to_vec(), 0.0).for_type(TweenType::Click),
             on_view_hover: PropSet::new(Vec::new(), 0.0).for_type(TweenType::Hover),
@@ -342,6 +392,7 @@ impl ThemeBuilder {
             margins: (5.0, 5.0),
             field_height: 20.0,
             default_font: font,
+            image_atlas: ImageAtlas::new(),
             fonts: Fonts::new(),
         }
     }
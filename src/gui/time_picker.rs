@@ -0,0 +1,575 @@
+/// TimePicker
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Vector},
+    graphics::{Color, MeshTask},
+    input::{Key, MouseCursor},
+    lifecycle::Window,
+};
+use std::any::TypeId;
+use std::fmt;
+
+use super::*;
+
+/// The width of each spinner column
+const COLUMN_WIDTH: f32 = 48.0;
+/// The width of the separator between the hour and minute columns
+const SEPARATOR_WIDTH: f32 = 12.0;
+/// The height of the arrow buttons above and below the value
+const ARROW_HEIGHT: f32 = 24.0;
+/// The padding inside the spinners
+const SPINNER_PADDING: f32 = 6.0;
+
+//-- TimeOfDay -----------------------------------------------------------------------
+
+/// A time of day with minute precision and no time zone. Times are ordered chronologically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TimeOfDay {
+    /// The hour from 0 to 23
+    pub hour: u32,
+    /// The minute from 0 to 59
+    pub minute: u32,
+}
+
+impl TimeOfDay {
+    /// Constructor. Returns None if the hour or minute is out of range.
+    pub fn new(hour: u32, minute: u32) -> Option<Self> {
+        if hour > 23 || minute > 59 {
+            return None;
+        }
+        Some(TimeOfDay { hour, minute })
+    }
+
+    /// Parse a time in 24-hour HH:MM format
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.trim().split(':');
+        let hour = parts.next()?.parse::<u32>().ok()?;
+        let minute = parts.next()?.parse::<u32>().ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        TimeOfDay::new(hour, minute)
+    }
+
+    /// Is the hour after noon?
+    pub fn is_pm(&self) -> bool {
+        self.hour >= 12
+    }
+
+    /// The hour on a 12-hour clock, from 1 to 12
+    pub fn hour12(&self) -> u32 {
+        match self.hour % 12 {
+            0 => 12,
+            hour => hour,
+        }
+    }
+
+    /// Format the time for display on a 12-hour or 24-hour clock
+    pub fn format(&self, is_24_hour: bool) -> String {
+        if is_24_hour {
+            self.to_string()
+        } else {
+            format!("{}:{:02} {}", self.hour12(), self.minute, if self.is_pm() { "PM" } else { "AM" })
+        }
+    }
+}
+
+impl fmt::Display for TimeOfDay {
+    /// Format as HH:MM
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+/// The columns of a TimePicker
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TimeColumn {
+    Hour,
+    Minute,
+    Period,
+}
+
+//-- TimePicker -----------------------------------------------------------------------
+
+/// Spinners for picking the hour and minute, and AM or PM on a 12-hour clock. Each spinner has arrows
+/// above and below the value, and the new value slides in vertically when it changes. Scrolling the mouse
+/// wheel over a spinner also changes it.
+///
+/// In Dropdown mode, the frame is a field that shows the time and opens the spinners below it.
+/// When the picker has focus, Up and Down change the focused spinner and Left and Right move between spinners.
+///
+/// The value is a FieldValue::Time and changes made by the user dispatch FieldEvent::Changed.
+pub struct TimePicker {
+    /// The base layer
+    pub layer: Layer,
+    /// Inline or Dropdown
+    pub mode: PickerMode,
+    /// The number of minutes the minute spinner moves by
+    pub minute_step: u32,
+    /// Use a 24-hour clock instead of hours 1 to 12 with AM and PM
+    pub is_24_hour: bool,
+    /// The picked time
+    time: TimeOfDay,
+    /// The spinner with the keyboard focus
+    focus_column: TimeColumn,
+    /// The spinner under the mouse, for scrolling
+    hover_column: Option<TimeColumn>,
+    /// Has the picker been activated by its Scene?
+    has_focus: bool,
+    /// Is the popup open in Dropdown mode?
+    is_open: bool,
+    /// Drives the fade of the popup
+    popup: Layer,
+    /// Drives the spinner slide
+    spin: Layer,
+    /// The spinner that is sliding, the text that slides out and the direction, where 1.0 is an increase
+    spin_from: Option<(TimeColumn, String, f32)>,
+    labels: LabelCache,
+    colors: PickerColors,
+    /// FieldEvents waiting to be dispatched in the next update()
+    pending_events: Vec<FieldEvent>,
+}
+
+impl TimePicker {
+    /// Constructor
+    pub fn new(frame: Rectangle, time: TimeOfDay) -> Self {
+        TimePicker {
+            layer: Layer::new(frame),
+            mode: PickerMode::Inline,
+            minute_step: 1,
+            is_24_hour: true,
+            time,
+            focus_column: TimeColumn::Hour,
+            hover_column: None,
+            has_focus: false,
+            is_open: false,
+            popup: Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0))),
+            spin: Layer::new(Rectangle::new((0.0, 0.0), (1.0, 1.0))),
+            spin_from: None,
+            labels: LabelCache::new(),
+            colors: PickerColors::new(),
+            pending_events: Vec::new(),
+        }
+    }
+
+    /// Builder method to set the mode
+    pub fn with_mode(mut self, mode: PickerMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Builder method to use a 24-hour clock or a 12-hour clock with AM and PM
+    pub fn with_24_hour(mut self, is_24_hour: bool) -> Self {
+        self.is_24_hour = is_24_hour;
+        self
+    }
+
+    /// Builder method to set the number of minutes the minute spinner moves by, such as 5 or 15
+    pub fn with_minute_step(mut self, step: u32) -> Self {
+        self.minute_step = step.max(1).min(30);
+        self
+    }
+
+    /// The picked time
+    pub fn time(&self) -> TimeOfDay {
+        self.time
+    }
+
+    /// Set the time without animation
+    pub fn set_time(&mut self, time: TimeOfDay) {
+        self.time = time;
+        self.spin_from = None;
+    }
+
+    /// Is the popup open in Dropdown mode?
+    pub fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    /// Open or close the popup in Dropdown mode
+    pub fn set_open(&mut self, open: bool) {
+        if self.mode != PickerMode::Dropdown || open == self.is_open {
+            return;
+        }
+        self.is_open = open;
+        if open {
+            start_progress(&mut self.popup);
+        } else {
+            self.popup.animation = None;
+            self.popup.frame.size.y = 0.0;
+        }
+    }
+
+    /// The spinners shown, from left to right
+    fn columns(&self) -> Vec<TimeColumn> {
+        if self.is_24_hour {
+            vec![TimeColumn::Hour, TimeColumn::Minute]
+        } else {
+            vec![TimeColumn::Hour, TimeColumn::Minute, TimeColumn::Period]
+        }
+    }
+
+    /// The text of the spinner for the time
+    fn column_text(&self, column: TimeColumn, time: &TimeOfDay) -> String {
+        match column {
+            TimeColumn::Hour if self.is_24_hour => format!("{:02}", time.hour),
+            TimeColumn::Hour => time.hour12().to_string(),
+            TimeColumn::Minute => format!("{:02}", time.minute),
+            TimeColumn::Period => if time.is_pm() { "PM" } else { "AM" }.to_owned(),
+        }
+    }
+
+    /// The time after moving the spinner one step up (1) or down (-1). Spinners wrap around without
+    /// changing the other spinners.
+    fn stepped(&self, column: TimeColumn, direction: i32) -> TimeOfDay {
+        let mut time = self.time;
+        match column {
+            TimeColumn::Hour => {
+                time.hour = (time.hour as i32 + direction).rem_euclid(24) as u32;
+                if !self.is_24_hour {
+                    // Keep AM or PM, which has its own spinner
+                    time.hour = time.hour % 12 + if self.time.is_pm() { 12 } else { 0 };
+                }
+            }
+            TimeColumn::Minute => {
+                let step = self.minute_step as i32;
+                let minute = time.minute as i32 / step * step;
+                let minute =
+                    if direction < 0 && minute < time.minute as i32 { minute } else { minute + step * direction };
+                time.minute = minute.rem_euclid(60) as u32;
+            }
+            TimeColumn::Period => {
+                time.hour = (time.hour + 12) % 24;
+            }
+        }
+        time
+    }
+
+    /// Move the spinner as if the user clicked an arrow
+    fn spin_column(&mut self, column: TimeColumn, direction: i32) {
+        let time = self.stepped(column, direction);
+        if time == self.time {
+            return;
+        }
+        let text = self.column_text(column, &self.time);
+        self.spin_from = Some((column, text, direction as f32));
+        start_progress(&mut self.spin);
+        self.time = time;
+        self.pending_events.push(FieldEvent::Changed);
+    }
+
+    /// The frame of the spinners, which is below the field in Dropdown mode
+    fn spinner_frame(&self) -> Rectangle {
+        let frame = self.layer.frame;
+        match self.mode {
+            PickerMode::Inline => frame,
+            PickerMode::Dropdown => {
+                let count = self.columns().len() as f32;
+                let width = COLUMN_WIDTH * count + SEPARATOR_WIDTH * (count - 1.0) + SPINNER_PADDING * 2.0;
+                let height = ARROW_HEIGHT * 3.0 + SPINNER_PADDING * 2.0;
+                Rectangle::new((frame.x(), frame.y() + frame.height() + POPUP_MARGIN), (width, height))
+            }
+        }
+    }
+
+    /// Are the spinners shown?
+    fn spinner_visible(&self) -> bool {
+        self.mode == PickerMode::Inline || self.is_open
+    }
+
+    /// The frame of the spinner at the index, from the top of the up arrow to the bottom of the down arrow
+    fn column_frame(&self, index: usize) -> Rectangle {
+        let rect = UITools::padded_rect(&self.spinner_frame(), -SPINNER_PADDING, -SPINNER_PADDING);
+        let x = rect.x() + (COLUMN_WIDTH + SEPARATOR_WIDTH) * index as f32;
+        Rectangle::new((x, rect.y()), (COLUMN_WIDTH, rect.height()))
+    }
+
+    /// The up arrow, value and down arrow frames of a spinner
+    fn column_parts(&self, index: usize) -> (Rectangle, Rectangle, Rectangle) {
+        let rect = self.column_frame(index);
+        let value_height = (rect.height() - ARROW_HEIGHT * 2.0).max(0.0);
+        let up = Rectangle::new(rect.pos, (rect.width(), ARROW_HEIGHT));
+        let value = Rectangle::new((rect.x(), rect.y() + ARROW_HEIGHT), (rect.width(), value_height));
+        let down = Rectangle::new((rect.x(), rect.y() + ARROW_HEIGHT + value_height), (rect.width(), ARROW_HEIGHT));
+        (up, value, down)
+    }
+
+    /// The index and column of the spinner at the point
+    fn column_at_point(&self, pt: &Vector) -> Option<(usize, TimeColumn)> {
+        let columns = self.columns();
+        (0..columns.len())
+            .find(|index| pt.overlaps_rectangle(&self.column_frame(*index)))
+            .map(|index| (index, columns[index]))
+    }
+
+    /// Draw the spinners with the alpha of the popup
+    fn draw_spinners(&mut self, theme: &mut Theme, alpha: f32, window: &mut Window) {
+        let colors = self.colors;
+        let style = self.layer.font_style;
+        let dim = |color: Color| Color { a: color.a * alpha, ..color };
+
+        let mut task = MeshTask::new(0);
+        if self.mode == PickerMode::Dropdown {
+            if let Some(shadow) = theme.popup_shadow {
+                let shadow = ShadowStyle { color: dim(shadow.color), ..shadow };
+                task.append(&mut shadow.draw(&self.spinner_frame(), [4.0; 4]));
+            }
+            let mut mesh =
+                DrawShape::rectangle(&self.spinner_frame(), Some(dim(colors.bg)), Some(dim(colors.border)), 1.0, 4.0);
+            task.append(&mut mesh);
+        }
+        let columns = self.columns();
+        for index in 0..columns.len() {
+            let (up, value, down) = self.column_parts(index);
+            if self.has_focus && self.focus_column == columns[index] {
+                let mut mesh = DrawShape::rectangle(&value, None, Some(dim(colors.focus)), 2.0, 4.0);
+                task.append(&mut mesh);
+            }
+            draw_arrow(&mut task, &up.center(), -2.0, dim(colors.fg));
+            draw_arrow(&mut task, &down.center(), 2.0, dim(colors.fg));
+        }
+        window.add_task(task);
+
+        for index in 0..columns.len() {
+            let column = columns[index];
+            let (_, value, _) = self.column_parts(index);
+            if index > 0 && column == TimeColumn::Minute {
+                let rect = Rectangle::new((value.x() - SEPARATOR_WIDTH, value.y()), (SEPARATOR_WIDTH, value.height()));
+                if let Some(task) = self.labels.draw(theme, style, ":", &rect, Some(colors.fg), alpha) {
+                    window.add_task(task);
+                }
+            }
+            let text = self.column_text(column, &self.time);
+            let sliding = match &self.spin_from {
+                Some((from_column, from_text, direction)) if *from_column == column => {
+                    Some((from_text.clone(), *direction))
+                }
+                _ => None,
+            };
+            match sliding {
+                Some((from_text, direction)) => {
+                    // An increase slides the old value up and the new value in from below
+                    let progress = self.spin.frame.size.y;
+                    let height = value.height();
+                    let old = Rectangle::new((value.x(), value.y() - direction * progress * height), value.size);
+                    let new =
+                        Rectangle::new((value.x(), value.y() + direction * (1.0 - progress) * height), value.size);
+                    let old_alpha = alpha * visible_fraction(&old, &value);
+                    let new_alpha = alpha * visible_fraction(&new, &value);
+                    if let Some(task) = self.labels.draw(theme, style, &from_text, &old, Some(colors.fg), old_alpha) {
+                        window.add_task(task);
+                    }
+                    if let Some(task) = self.labels.draw(theme, style, &text, &new, Some(colors.fg), new_alpha) {
+                        window.add_task(task);
+                    }
+                }
+                None => {
+                    if let Some(task) = self.labels.draw(theme, style, &text, &value, Some(colors.fg), alpha) {
+                        window.add_task(task);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Move the focus to the spinner before (-1) or after (1) the focused spinner
+    fn move_focus(&mut self, direction: i32) -> bool {
+        let columns = self.columns();
+        let index = columns.iter().position(|column| *column == self.focus_column).unwrap_or(0) as i32 + direction;
+        if index < 0 || index >= columns.len() as i32 {
+            return false;
+        }
+        self.focus_column = columns[index as usize];
+        true
+    }
+}
+
+// *****************************************************************************************************
+// TimePicker :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for TimePicker {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<TimePicker>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        let ok = self.layer.apply_theme(theme);
+        if !ok {
+            return;
+        }
+        self.layer.font_style = FontStyle::new(theme.font_size, theme.input_fg_color);
+        self.colors = PickerColors::from_theme(theme);
+        self.labels.clear();
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Activate => {
+                self.has_focus = true;
+            }
+            DisplayEvent::Deactivate => {
+                self.has_focus = false;
+                self.set_open(false);
+            }
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        self.popup.tween_update(state);
+        if self.spin_from.is_some() {
+            self.spin.tween_update(state);
+            if self.spin.frame.size.y >= 1.0 {
+                self.spin_from = None;
+            }
+        }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        match self.mode {
+            PickerMode::Inline => {
+                self.layer.draw_background(window);
+                self.draw_spinners(theme, 1.0, window);
+                self.layer.draw_border(window);
+            }
+            PickerMode::Dropdown => {
+                let text = self.time.format(self.is_24_hour);
+                let (frame, style, colors) = (self.layer.frame, self.layer.font_style, self.colors);
+                draw_dropdown_field(&mut self.labels, theme, style, &frame, &text, &colors, self.has_focus, window);
+                if self.is_open {
+                    let alpha = self.popup.frame.size.y.max(0.0).min(1.0);
+                    self.draw_spinners(theme, alpha, window);
+                }
+            }
+        }
+    }
+
+    fn set_hover_animation(&mut self, props: PropSet) {
+        self.layer.hover_effect = Some(props);
+    }
+
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        self.hover_column = None;
+        let spinner = self.spinner_visible() && pt.overlaps_rectangle(&self.spinner_frame());
+        if spinner || pt.overlaps_rectangle(&self.layer.frame) {
+            if spinner {
+                self.hover_column = self.column_at_point(pt).map(|(_, column)| column);
+            }
+            window.set_cursor(MouseCursor::Hand);
+            self.layer.mouse_state = MouseState::Hover;
+            return true;
+        }
+        self.layer.mouse_state = MouseState::None;
+        false
+    }
+}
+
+// *****************************************************************************************************
+// TimePicker :: Responder
+// *****************************************************************************************************
+
+impl Responder for TimePicker {
+    fn get_field_value(&self) -> FieldValue {
+        FieldValue::Time(self.time)
+    }
+
+    /// The Time value sets the time
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if type_id == self.get_type_id() && layer_id == self.layer.get_id() {
+            if let FieldValue::Time(time) = value {
+                self.set_time(*time);
+                return true;
+            }
+        }
+        false
+    }
+
+    fn handle_mouse_down(&mut self, pt: &Vector, _state: &mut AppState) -> bool {
+        if self.mode == PickerMode::Dropdown && pt.overlaps_rectangle(&self.layer.frame) {
+            let open = !self.is_open;
+            self.set_open(open);
+            return true;
+        }
+        if !self.spinner_visible() || !pt.overlaps_rectangle(&self.spinner_frame()) {
+            self.set_open(false);
+            return false;
+        }
+        if let Some((index, column)) = self.column_at_point(pt) {
+            self.focus_column = column;
+            let (up, _, down) = self.column_parts(index);
+            if pt.overlaps_rectangle(&up) {
+                self.spin_column(column, 1);
+            } else if pt.overlaps_rectangle(&down) {
+                self.spin_column(column, -1);
+            }
+        }
+        true
+    }
+
+    /// Scrolling away from the user increases the spinner under the mouse
+    fn handle_mouse_scroll(&mut self, pt: &Vector, _state: &mut AppState) {
+        if !self.spinner_visible() || pt.y == 0.0 {
+            return;
+        }
+        if let Some(column) = self.hover_column {
+            self.spin_column(column, if pt.y > 0.0 { 1 } else { -1 });
+        }
+    }
+
+    /// Up and Down change the focused spinner and Left and Right move the focus between spinners. In Dropdown
+    /// mode, Space, Return and Down open the popup, and Return or Escape close it.
+    fn handle_key_command(&mut self, key: &Key, _window: &mut Window) -> bool {
+        if let Key::Tab = key {
+            self.set_open(false);
+            return true;
+        }
+        if !self.spinner_visible() {
+            if let Key::Space | Key::Return | Key::Down = key {
+                self.set_open(true);
+                return true;
+            }
+            return false;
+        }
+        match key {
+            Key::Up => self.spin_column(self.focus_column, 1),
+            Key::Down => self.spin_column(self.focus_column, -1),
+            Key::Left => return self.move_focus(-1),
+            Key::Right => return self.move_focus(1),
+            Key::Return | Key::Escape if self.is_open => self.set_open(false),
+            _ => return false,
+        }
+        true
+    }
+}
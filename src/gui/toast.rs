@@ -0,0 +1,490 @@
+/// Toast notifications shown by a Stage
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Transform, Vector},
+    graphics::{Color, MeshTask},
+    lifecycle::Window,
+};
+
+use std::any::TypeId;
+use std::collections::VecDeque;
+
+use super::*;
+
+/// The default time in seconds before a toast is dismissed
+const TOAST_DURATION: f64 = 4.0;
+/// The duration in seconds of the animations
+const TOAST_ANIMATION: f64 = 0.25;
+/// The maximum number of toasts shown at once. The others wait in the queue.
+const TOAST_MAX_VISIBLE: usize = 3;
+/// The maximum width of the message before it wraps
+const TOAST_MAX_WIDTH: f32 = 360.0;
+/// The minimum width of a toast
+const TOAST_MIN_WIDTH: f32 = 240.0;
+/// The padding inside a toast
+const TOAST_PADDING: f32 = 12.0;
+/// The width of the accent bar on the left, which has the color of the ToastLevel
+const TOAST_ACCENT_WIDTH: f32 = 4.0;
+/// The space between toasts and between the bottom toast and the edge of the window
+const TOAST_SPACING: f32 = 8.0;
+/// The distance that toasts slide while they animate in and out
+const TOAST_OFFSET: f32 = 20.0;
+/// The delay in seconds between toasts that slide to new positions in the stack
+const TOAST_STAGGER: f64 = 0.05;
+
+//-- Toast -----------------------------------------------------------------------
+
+/// The severity of a Toast, which sets its accent color
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToastLevel {
+    /// Uses Theme::button_bg_color
+    Info,
+    /// Uses Theme::success_color
+    Success,
+    /// Uses Theme::warning_color
+    Warning,
+    /// Uses Theme::error_color
+    Error,
+}
+
+/// A transient message that the Stage shows at the bottom of the window. Show it from anywhere with
+/// toast.show(state), which dispatches it onto the EventBus.
+#[derive(Clone, Debug)]
+pub struct Toast {
+    /// The id reported in ToastEvents
+    pub id: u32,
+    /// The message text, which wraps at the maximum width
+    pub message: String,
+    /// The severity
+    pub level: ToastLevel,
+    /// The time in seconds before the toast is dismissed, or 0.0 to keep it until it is clicked
+    pub duration: f64,
+    /// Optional title of the action button
+    pub action: Option<String>,
+}
+
+impl Toast {
+    /// Constructor
+    pub fn new(message: &str, level: ToastLevel) -> Self {
+        Toast { id: 0, message: message.to_owned(), level, duration: TOAST_DURATION, action: None }
+    }
+
+    /// Constructor for an Info toast
+    pub fn info(message: &str) -> Self {
+        Toast::new(message, ToastLevel::Info)
+    }
+
+    /// Constructor for a Success toast
+    pub fn success(message: &str) -> Self {
+        Toast::new(message, ToastLevel::Success)
+    }
+
+    /// Constructor for a Warning toast
+    pub fn warning(message: &str) -> Self {
+        Toast::new(message, ToastLevel::Warning)
+    }
+
+    /// Constructor for an Error toast
+    pub fn error(message: &str) -> Self {
+        Toast::new(message, ToastLevel::Error)
+    }
+
+    /// Builder method to set the id reported in ToastEvents
+    pub fn with_id(mut self, id: u32) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Builder method to set the time in seconds before the toast is dismissed
+    pub fn with_duration(mut self, duration: f64) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Builder method to add an action button, which dispatches ToastEvent::Action when clicked
+    pub fn with_action(mut self, title: &str) -> Self {
+        self.action = Some(title.to_owned());
+        self
+    }
+
+    /// Dispatch the toast onto the EventBus for the Stage to show
+    pub fn show(self, state: &mut AppState) {
+        state.event_bus.dispatch_message(self, NodeID::default());
+    }
+}
+
+// *****************************************************************************************************
+// ToastView
+// *****************************************************************************************************
+
+/// The sprite that a toast Timeline animates. The frame position is the position of the toast and the frame
+/// height goes from 0.0 when hidden to 1.0 when shown.
+struct ToastMotion {
+    layer: Layer,
+}
+
+impl Displayable for ToastMotion {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<ToastMotion>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        self.layer.frame
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos = Vector::new(pos.0, pos.1);
+    }
+
+    fn set_theme(&mut self, _theme: &mut Theme) {}
+
+    fn notify(&mut self, _event: &DisplayEvent) {}
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+    }
+}
+
+/// A Toast that is shown
+struct ToastCard {
+    toast: Toast,
+    /// The size, which is set when the toast is drawn
+    size: Option<Vector>,
+    /// The text meshes at origin 0, 0 for the message and the action title
+    text: Vec<MeshTask>,
+    action_text: Option<MeshTask>,
+    /// The position in the stack
+    target: Option<Vector>,
+    /// The time counted toward the duration before the timer was last paused
+    shown_time: f64,
+    /// The time when the timer last resumed, or None while it is paused
+    resumed_at: Option<f64>,
+    /// Is the mouse over the toast? This pauses the timer.
+    hover: bool,
+    /// True while the dismiss animation runs
+    closing: bool,
+    /// Plays the slide and fade of the ToastMotion sprite
+    timeline: Timeline,
+}
+
+impl ToastCard {
+    fn new(toast: Toast) -> Self {
+        ToastCard {
+            toast,
+            size: None,
+            text: Vec::new(),
+            action_text: None,
+            target: None,
+            shown_time: 0.0,
+            resumed_at: None,
+            hover: false,
+            closing: false,
+            timeline: Timeline::new(Rectangle::new((0.0, 0.0), (1.0, 0.0))),
+        }
+    }
+
+    /// The animated frame, where the height is the alpha
+    fn motion(&self) -> Rectangle {
+        match self.timeline.sprites.values().next() {
+            Some(sprite) => sprite.view.get_frame(),
+            None => self.timeline.layer.frame,
+        }
+    }
+
+    fn frame(&self) -> Rectangle {
+        Rectangle::new(self.motion().pos, self.size.unwrap_or(Vector::ZERO))
+    }
+
+    /// The area of the action button on the right
+    fn action_rect(&self) -> Option<Rectangle> {
+        let task = self.action_text.as_ref()?;
+        let frame = self.frame();
+        let width = task.content_size.0 + TOAST_PADDING * 2.0;
+        Some(Rectangle::new((frame.x() + frame.width() - width, frame.y()), (width, frame.height())))
+    }
+
+    /// Replace the Timeline with one that animates from the current frame to the position and alpha after
+    /// the delay in seconds
+    fn animate(&mut self, from: Rectangle, pos: Vector, alpha: f32, delay: f64) {
+        let mut motion = ToastMotion { layer: Layer::new(from) };
+        let props = vec![position(pos.x, pos.y), size(1.0, alpha)];
+        let propset = PropSet::new(props, TOAST_ANIMATION).ease(Ease::SineOut);
+        motion.layer.animate_with_props(propset, false);
+
+        let mut timeline = Timeline::new(from);
+        timeline.start_delay = 0.0;
+        timeline.add_sprite(Box::new(motion), delay);
+        timeline.play();
+        timeline.load_sprites();
+        self.timeline = timeline;
+    }
+
+    /// Start the dismiss animation
+    fn close(&mut self) {
+        if !self.closing {
+            self.closing = true;
+            let from = self.motion();
+            self.animate(from, from.pos + Vector::new(0.0, TOAST_OFFSET), 0.0, 0.0);
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.timeline.sprites.is_empty() || self.timeline.is_completed()
+    }
+}
+
+/// Queues the toasts of a Stage and shows them stacked at the bottom center of the window, above all scenes and
+/// modals. The newest toast is at the bottom.
+pub(super) struct ToastView {
+    /// Toasts waiting for room in the stack
+    queue: VecDeque<Toast>,
+    /// The toasts that are shown, from oldest to newest
+    cards: Vec<ToastCard>,
+    /// ToastEvents waiting to be dispatched in the next update()
+    pending_events: Vec<ToastEvent>,
+    font_size: f32,
+    bg_color: Color,
+    fg_color: Color,
+    border_color: Color,
+    info_color: Color,
+    success_color: Color,
+    warning_color: Color,
+    error_color: Color,
+}
+
+impl ToastView {
+    pub(super) fn new() -> Self {
+        ToastView {
+            queue: VecDeque::new(),
+            cards: Vec::new(),
+            pending_events: Vec::new(),
+            font_size: 14.0,
+            bg_color: Color::from_hex("#333333"),
+            fg_color: Color::WHITE,
+            border_color: Color::from_hex("#AAAAAA"),
+            info_color: Color::from_hex("#4373c2"),
+            success_color: Color::from_hex("#2E7D32"),
+            warning_color: Color::from_hex("#F9A825"),
+            error_color: Color::from_hex("#D0342C"),
+        }
+    }
+
+    pub(super) fn set_theme(&mut self, theme: &Theme) {
+        self.font_size = theme.font_size * 0.8;
+        self.bg_color = theme.tooltip_bg_color;
+        self.fg_color = theme.tooltip_fg_color;
+        self.border_color = theme.border_color;
+        self.info_color = theme.button_bg_color;
+        self.success_color = theme.success_color;
+        self.warning_color = theme.warning_color;
+        self.error_color = theme.error_color;
+        // Discard the text that used the previous theme
+        for card in &mut self.cards {
+            card.size = None;
+            card.text.clear();
+            card.action_text = None;
+        }
+    }
+
+    /// Add the toast to the queue
+    pub(super) fn add(&mut self, toast: Toast) {
+        self.queue.push_back(toast);
+    }
+
+    /// Dismiss all toasts and clear the queue
+    pub(super) fn dismiss_all(&mut self) {
+        self.queue.clear();
+        for card in &mut self.cards {
+            card.close();
+        }
+    }
+
+    /// Set the hover state of each toast. Returns true if the mouse is over a toast.
+    pub(super) fn handle_mouse_at(&mut self, pt: &Vector) -> bool {
+        let mut hover = false;
+        for card in &mut self.cards {
+            card.hover = !card.closing && card.size.is_some() && pt.overlaps_rectangle(&card.frame());
+            hover = hover || card.hover;
+        }
+        hover
+    }
+
+    /// A click on the action button dispatches ToastEvent::Action and a click anywhere on a toast dismisses it.
+    /// Returns true if the click was on a toast.
+    pub(super) fn handle_mouse_down(&mut self, pt: &Vector) -> bool {
+        let card = self
+            .cards
+            .iter_mut()
+            .rev()
+            .find(|card| !card.closing && card.size.is_some() && pt.overlaps_rectangle(&card.frame()));
+        if let Some(card) = card {
+            if card.action_rect().map_or(false, |rect| pt.overlaps_rectangle(&rect)) {
+                self.pending_events.push(ToastEvent::Action(card.toast.id));
+            }
+            card.close();
+            return true;
+        }
+        false
+    }
+
+    /// Run the timers, remove the dismissed toasts and show queued toasts when there is room
+    pub(super) fn update(&mut self, window: &mut Window, state: &mut AppState, sender: NodeID) {
+        let now = state.clock.current_time();
+        for card in &mut self.cards {
+            card.timeline.update(window, state);
+            if card.closing || card.toast.duration <= 0.0 || card.target.is_none() {
+                continue;
+            }
+            // The timer is paused while the mouse is over the toast
+            match (card.hover, card.resumed_at) {
+                (true, Some(start)) => {
+                    card.shown_time += state.clock.elapsed_time(start);
+                    card.resumed_at = None;
+                }
+                (false, None) => card.resumed_at = Some(now),
+                _ => (),
+            }
+            let elapsed = card.resumed_at.map_or(0.0, |start| state.clock.elapsed_time(start));
+            if card.shown_time + elapsed >= card.toast.duration {
+                card.close();
+            }
+        }
+
+        let pending_events = &mut self.pending_events;
+        self.cards.retain(|card| {
+            if card.closing && card.is_finished() {
+                pending_events.push(ToastEvent::Dismissed(card.toast.id));
+                return false;
+            }
+            true
+        });
+        while self.cards.iter().filter(|card| !card.closing).count() < TOAST_MAX_VISIBLE {
+            let toast = match self.queue.pop_front() {
+                Some(toast) => toast,
+                None => break,
+            };
+            self.pending_events.push(ToastEvent::Shown(toast.id));
+            self.cards.push(ToastCard::new(toast));
+        }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, sender, None);
+        }
+    }
+
+    /// Create the text meshes and set the size of the toast
+    fn layout(&self, card: &mut ToastCard, theme: &mut Theme) {
+        let style = FontStyle::new(self.font_size, self.fg_color);
+        let rect = Rectangle::new((0.0, 0.0), (TOAST_MAX_WIDTH, 1000.0));
+        let params = TextParams::new(style)
+            .frame(rect)
+            .text(&card.toast.message)
+            .align(TextAlign::Left, VertAlign::Top)
+            .multiline(true);
+        let (mut width, mut height) = (TOAST_ACCENT_WIDTH + TOAST_PADDING * 2.0, TOAST_PADDING * 2.0);
+        if let Some(task) = theme.default_font.draw(params) {
+            width += task.content_size.0;
+            height += task.content_size.1;
+            card.text = vec![task];
+        }
+        if let Some(title) = &card.toast.action {
+            let params =
+                TextParams::new(style).frame(rect).text(title).align(TextAlign::Left, VertAlign::Top).multiline(false);
+            if let Some(task) = theme.default_font.draw(params) {
+                width += task.content_size.0 + TOAST_PADDING * 2.0;
+                card.action_text = Some(task);
+            }
+        }
+        card.size = Some(Vector::new(width.max(TOAST_MIN_WIDTH), height));
+    }
+
+    /// Draw the toasts with the alpha of their animations
+    pub(super) fn render(&mut self, theme: &mut Theme, window: &mut Window, bounds: &Rectangle) {
+        let mut cards = std::mem::take(&mut self.cards);
+        for card in cards.iter_mut().filter(|card| card.size.is_none()) {
+            self.layout(card, theme);
+        }
+
+        // Stack the toasts from the bottom up and slide them to their new positions. Toasts higher in the stack
+        // start moving a little later.
+        let mut bottom = bounds.y() + bounds.height() - TOAST_SPACING;
+        for (i, card) in cards.iter_mut().rev().filter(|card| !card.closing).enumerate() {
+            let size = card.size.unwrap_or(Vector::ZERO);
+            let target = Vector::new(bounds.x() + (bounds.width() - size.x) / 2.0, bottom - size.y);
+            bottom -= size.y + TOAST_SPACING;
+            if card.target == Some(target) {
+                continue;
+            }
+            let from = match card.target {
+                Some(_) => card.motion(),
+                // New toasts slide up from below their position
+                None => Rectangle::new(target + Vector::new(0.0, TOAST_OFFSET), (1.0, 0.0)),
+            };
+            card.target = Some(target);
+            card.animate(from, target, 1.0, i as f64 * TOAST_STAGGER);
+        }
+
+        for card in &cards {
+            self.render_card(card, window);
+        }
+        self.cards = cards;
+    }
+
+    fn render_card(&self, card: &ToastCard, window: &mut Window) {
+        let alpha = card.motion().height().max(0.0).min(1.0);
+        if alpha <= 0.0 {
+            return;
+        }
+        let with_alpha = |color: Color| Color { a: color.a * alpha, ..color };
+        let frame = card.frame();
+        let accent = match card.toast.level {
+            ToastLevel::Info => self.info_color,
+            ToastLevel::Success => self.success_color,
+            ToastLevel::Warning => self.warning_color,
+            ToastLevel::Error => self.error_color,
+        };
+
+        let mut task = MeshTask::new(0);
+        let mut mesh = DrawShape::rectangle(&frame, Some(with_alpha(self.bg_color)), None, 0.0, 4.0);
+        task.append(&mut mesh);
+        let rect = Rectangle::new(frame.pos, (TOAST_ACCENT_WIDTH, frame.height()));
+        let mut mesh = DrawShape::rectangle(&rect, Some(with_alpha(accent)), None, 0.0, 0.0);
+        task.append(&mut mesh);
+        if let Some(rect) = card.action_rect() {
+            // A divider between the message and the action button
+            let pts = [
+                &Vector::new(rect.x(), rect.y() + TOAST_PADDING / 2.0),
+                &Vector::new(rect.x(), rect.y() + rect.height() - TOAST_PADDING / 2.0),
+            ];
+            let mut line = DrawShape::line(&pts, with_alpha(self.border_color), 1.0);
+            task.append(&mut line);
+        }
+        window.add_task(task);
+
+        let origin = frame.pos + Vector::new(TOAST_ACCENT_WIDTH + TOAST_PADDING, TOAST_PADDING);
+        let mut tasks: Vec<(Vector, &MeshTask)> = card.text.iter().map(|task| (origin, task)).collect();
+        if let (Some(rect), Some(task)) = (card.action_rect(), &card.action_text) {
+            let pos = Vector::new(rect.x() + TOAST_PADDING, rect.y() + (rect.height() - task.content_size.1) / 2.0);
+            tasks.push((pos, task));
+        }
+        for (pos, task) in tasks {
+            let mut task = task.clone();
+            for vertex in &mut task.vertices.iter_mut() {
+                vertex.pos = Transform::translate(pos) * vertex.pos;
+                vertex.col.a *= alpha;
+            }
+            window.add_task(task);
+        }
+    }
+}
@@ -0,0 +1,240 @@
+/// Tooltips
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Transform, Vector},
+    graphics::{Color, MeshTask},
+    lifecycle::Window,
+};
+
+use super::*;
+
+/// The default time in seconds that the mouse must hover before a tooltip is shown
+const TOOLTIP_DELAY: f64 = 0.6;
+/// The duration in seconds of the fade in and fade out animation
+const TOOLTIP_DURATION: f64 = 0.15;
+/// The maximum width of the text before it wraps
+const TOOLTIP_MAX_WIDTH: f32 = 280.0;
+/// The padding around the text
+const TOOLTIP_PADDING: f32 = 6.0;
+/// The distance between the mouse and the tooltip
+const TOOLTIP_OFFSET: f32 = 20.0;
+/// The minimum distance between the tooltip and the edge of the window
+const WINDOW_MARGIN: f32 = 4.0;
+
+//-- Tooltip -----------------------------------------------------------------------
+
+/// The content of a Tooltip
+#[derive(Clone, Debug)]
+pub enum TooltipContent {
+    /// Text that wraps at the maximum width
+    Text(String),
+    /// A title in a larger font above detail text that wraps at the maximum width
+    Rich {
+        /// The title
+        title: String,
+        /// The detail text
+        detail: String,
+    },
+}
+
+/// A tooltip that is shown when the mouse hovers over a Layer. Set it with layer.tooltip = Some(tooltip).
+/// The Stage shows the tooltip of the topmost Layer under the mouse above all scenes.
+#[derive(Clone, Debug)]
+pub struct Tooltip {
+    /// The content
+    pub content: TooltipContent,
+    /// The time in seconds that the mouse must hover before the tooltip is shown
+    pub delay: f64,
+}
+
+impl Tooltip {
+    /// Constructor for a text tooltip
+    pub fn new(text: &str) -> Self {
+        Tooltip { content: TooltipContent::Text(text.to_owned()), delay: TOOLTIP_DELAY }
+    }
+
+    /// Constructor for a tooltip with a title and detail text
+    pub fn rich(title: &str, detail: &str) -> Self {
+        let content = TooltipContent::Rich { title: title.to_owned(), detail: detail.to_owned() };
+        Tooltip { content, delay: TOOLTIP_DELAY }
+    }
+
+    /// Builder method to set the hover delay in seconds
+    pub fn with_delay(mut self, delay: f64) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+// *****************************************************************************************************
+// TooltipView
+// *****************************************************************************************************
+
+/// Tracks the Layer under the mouse for a Stage and displays its tooltip after the delay. The Layer is only
+/// used to run the Tween, where the frame height goes from 0.0 when hidden to 1.0 when shown.
+pub(super) struct TooltipView {
+    /// The node and tooltip under the mouse, set by hover()
+    target: Option<(NodeID, Tooltip)>,
+    /// The node of the tooltip that is shown or waiting for the delay
+    shown_id: Option<NodeID>,
+    /// The time when the mouse started hovering over the target
+    hover_start: Option<f64>,
+    /// The mouse position
+    mouse: Vector,
+    /// The position of the tooltip, which does not follow the mouse once shown
+    frame: Rectangle,
+    /// Text meshes created at origin 0, 0 for the tooltip that is shown
+    text: Vec<(Vector, MeshTask)>,
+    /// True once the fade in started
+    visible: bool,
+    layer: Layer,
+    bg_color: Color,
+    fg_color: Color,
+    border_color: Color,
+}
+
+impl TooltipView {
+    pub(super) fn new() -> Self {
+        TooltipView {
+            target: None,
+            shown_id: None,
+            hover_start: None,
+            mouse: Vector::ZERO,
+            frame: Rectangle::new_sized((0.0, 0.0)),
+            text: Vec::new(),
+            visible: false,
+            layer: Layer::new(Rectangle::new((0.0, 0.0), (1.0, 0.0))),
+            bg_color: Color::from_hex("#333333"),
+            fg_color: Color::WHITE,
+            border_color: Color::from_hex("#333333"),
+        }
+    }
+
+    pub(super) fn set_theme(&mut self, theme: &Theme) {
+        self.bg_color = theme.tooltip_bg_color;
+        self.fg_color = theme.tooltip_fg_color;
+        self.border_color = theme.border_color;
+        self.text.clear();
+    }
+
+    /// Set the tooltip under the mouse
+    pub(super) fn hover(&mut self, pt: &Vector, target: Option<(NodeID, Tooltip)>) {
+        self.mouse = *pt;
+        self.target = target;
+    }
+
+    /// Hide the tooltip immediately, such as when the mouse is clicked
+    pub(super) fn dismiss(&mut self) {
+        self.target = None;
+        self.shown_id = None;
+        self.hover_start = None;
+        self.visible = false;
+        self.text.clear();
+        self.layer.animation = None;
+        self.layer.frame.size.y = 0.0;
+    }
+
+    fn animate(&mut self, to: f32) {
+        let propset = PropSet::new(vec![size(1.0, to)], TOOLTIP_DURATION).ease(Ease::SineOut);
+        self.layer.animate_with_props(propset, true);
+    }
+
+    pub(super) fn update(&mut self, state: &mut AppState) {
+        self.layer.tween_update(state);
+        let target_id = self.target.as_ref().map(|(id, _)| *id);
+        if target_id != self.shown_id {
+            // The mouse moved to another Layer, so fade out the tooltip and restart the delay
+            if self.visible {
+                self.visible = false;
+                self.animate(0.0);
+            }
+            self.shown_id = target_id;
+            self.hover_start = target_id.map(|_| state.clock.current_time());
+        }
+        if self.visible {
+            return;
+        }
+        let delay = match &self.target {
+            Some((_, tooltip)) => tooltip.delay,
+            None => return,
+        };
+        if let Some(start) = self.hover_start {
+            if state.clock.elapsed_time(start) >= delay {
+                self.visible = true;
+                self.text.clear();
+                self.animate(1.0);
+            }
+        }
+    }
+
+    /// Create the text meshes and the frame that fits them inside the window
+    fn layout(&mut self, theme: &mut Theme, bounds: &Rectangle) {
+        let content = match &self.target {
+            Some((_, tooltip)) => tooltip.content.clone(),
+            None => return,
+        };
+        let lines = match content {
+            TooltipContent::Text(text) => vec![(text, theme.font_size * 0.8)],
+            TooltipContent::Rich { title, detail } => vec![(title, theme.font_size), (detail, theme.font_size * 0.8)],
+        };
+        let mut width: f32 = 0.0;
+        let mut height: f32 = 0.0;
+        for (text, font_size) in lines {
+            let rect = Rectangle::new((0.0, 0.0), (TOOLTIP_MAX_WIDTH, 1000.0));
+            let style = FontStyle::new(font_size, self.fg_color);
+            let params =
+                TextParams::new(style).frame(rect).text(&text).align(TextAlign::Left, VertAlign::Top).multiline(true);
+            if let Some(task) = theme.default_font.draw(params) {
+                let (w, h) = task.content_size;
+                self.text.push((Vector::new(0.0, height), task));
+                width = width.max(w);
+                height += h + 2.0;
+            }
+        }
+        let box_size = Vector::new(width + TOOLTIP_PADDING * 2.0, height - 2.0 + TOOLTIP_PADDING * 2.0);
+
+        // Below the mouse unless that goes outside the window
+        let mut pos = Vector::new(self.mouse.x, self.mouse.y + TOOLTIP_OFFSET);
+        let right = bounds.x() + bounds.width() - WINDOW_MARGIN;
+        let bottom = bounds.y() + bounds.height() - WINDOW_MARGIN;
+        if pos.y + box_size.y > bottom {
+            pos.y = self.mouse.y - TOOLTIP_OFFSET / 2.0 - box_size.y;
+        }
+        pos.x = pos.x.min(right - box_size.x).max(bounds.x() + WINDOW_MARGIN);
+        pos.y = pos.y.max(bounds.y() + WINDOW_MARGIN);
+        self.frame = Rectangle::new(pos, box_size);
+    }
+
+    /// Draw the tooltip with the alpha of the fade animation
+    pub(super) fn render(&mut self, theme: &mut Theme, window: &mut Window, bounds: &Rectangle) {
+        let alpha = self.layer.frame.height().max(0.0).min(1.0);
+        if alpha <= 0.0 {
+            return;
+        }
+        if self.text.is_empty() {
+            self.layout(theme, bounds);
+        }
+        let mut bg_color = self.bg_color;
+        bg_color.a *= alpha;
+        let mut border_color = self.border_color;
+        border_color.a *= alpha;
+        let mut task = MeshTask::new(0);
+        let mut mesh = DrawShape::rectangle(&self.frame, Some(bg_color), Some(border_color), 1.0, 3.0);
+        task.append(&mut mesh);
+        window.add_task(task);
+
+        let origin = self.frame.pos + Vector::new(TOOLTIP_PADDING, TOOLTIP_PADDING);
+        for (offset, task) in &self.text {
+            let mut task = task.clone();
+            for vertex in &mut task.vertices.iter_mut() {
+                vertex.pos = Transform::translate(origin + *offset) * vertex.pos;
+                vertex.col.a *= alpha;
+            }
+            window.add_task(task);
+        }
+    }
+}
@@ -0,0 +1,943 @@
+/// TreeView
+///
+use crate::core::*;
+use crate::events::*;
+use crate::tools::*;
+
+use quicksilver::{
+    geom::{Rectangle, Shape, Transform, Vector},
+    graphics::{Background::Col, Color, Mesh, MeshTask},
+    input::{Key, MouseCursor},
+    lifecycle::{Asset, Window},
+};
+
+use std::any::TypeId;
+use std::collections::HashMap;
+
+use super::*;
+
+/// This is the multiplier that affects how quickly scrolling occurs.
+const SCROLL_FACTOR: f32 = 1.0;
+/// The duration in seconds of the expand and collapse animation
+const DISCLOSURE_DURATION: f64 = 0.2;
+/// The size of the disclosure arrow
+const ARROW_SIZE: f32 = 8.0;
+/// The space between an icon and the text
+const ICON_SPACING: f32 = 4.0;
+
+//-- Nodes -----------------------------------------------------------------------
+
+/// A node in a TreeView. The id is defined by the app and is used to identify the node in TreeEvents.
+#[derive(Clone, Debug)]
+pub struct TreeNode {
+    /// Unique id of the node
+    pub id: u32,
+    /// The text to display
+    pub title: String,
+    /// Optional name of an icon that was added with TreeView::add_icon
+    pub icon: Option<String>,
+    /// The child nodes
+    pub children: Vec<TreeNode>,
+    /// Are the children visible?
+    pub expanded: bool,
+    /// If true, the children are requested from the TreeView loader the first time the node is expanded
+    pub lazy: bool,
+}
+
+impl TreeNode {
+    /// Constructor
+    pub fn new(id: u32, title: &str) -> Self {
+        TreeNode { id, title: title.to_owned(), icon: None, children: Vec::new(), expanded: false, lazy: false }
+    }
+
+    /// Builder method to set the icon name
+    pub fn with_icon(mut self, name: &str) -> Self {
+        self.icon = Some(name.to_owned());
+        self
+    }
+
+    /// Builder method to add child nodes
+    pub fn with_children(mut self, children: Vec<TreeNode>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Builder method to mark the node as having children that are loaded on demand
+    pub fn with_lazy_children(mut self) -> Self {
+        self.lazy = true;
+        self
+    }
+
+    /// Builder method to show the children initially
+    pub fn with_expanded(mut self, expanded: bool) -> Self {
+        self.expanded = expanded;
+        self
+    }
+
+    /// Returns true if the node has children or may load children
+    pub fn is_expandable(&self) -> bool {
+        self.lazy || !self.children.is_empty()
+    }
+}
+
+/// A visible row in the flattened tree
+#[derive(Clone, Debug)]
+struct TreeRow {
+    /// The child index at each level from the root nodes
+    path: Vec<usize>,
+    /// The node id
+    id: u32,
+}
+
+impl TreeRow {
+    fn depth(&self) -> usize {
+        self.path.len() - 1
+    }
+}
+
+/// The state of an expand or collapse animation. The Layer is only used to run the Tween, where the frame
+/// height is the revealed height of the child rows and the rotation is the angle of the disclosure arrow.
+struct Disclosure {
+    /// The row of the node that is expanding or collapsing
+    row: usize,
+    /// The number of child rows that are revealed or hidden
+    count: usize,
+    expanding: bool,
+    layer: Layer,
+}
+
+impl Disclosure {
+    /// The height of the child rows that is currently hidden
+    fn hidden_height(&self, row_height: f32) -> f32 {
+        (self.count as f32 * row_height - self.layer.frame.height()).max(0.0)
+    }
+
+    fn angle(&self) -> f32 {
+        self.layer.transition.rotation
+    }
+
+    fn is_finished(&self) -> bool {
+        match &self.layer.animation {
+            Some(tween) => tween.state == PlayState::Completed,
+            None => true,
+        }
+    }
+}
+
+// *****************************************************************************************************
+// TreeView
+// *****************************************************************************************************
+
+/// A vertical scrolling list of TreeNodes where each node can be expanded to show its children. The tree
+/// is flattened into rows of visible nodes and, like the ListBox, only the rows in view are rendered.
+///
+/// Children can be loaded on demand by marking a node with with_lazy_children() and providing a loader
+/// closure that returns the children for a node id.
+///
+pub struct TreeView {
+    /// The base layer
+    pub layer: Layer,
+    /// The height of every row
+    pub row_height: f32,
+    /// The horizontal indent for each level, which is also the width of the disclosure arrow area
+    pub indent: f32,
+    /// The width and height of icons
+    pub icon_size: f32,
+    /// If true, vertical lines are drawn at each indent level
+    pub show_guides: bool,
+    /// The root nodes
+    nodes: Vec<TreeNode>,
+    /// The visible rows, including the rows of a node that is collapsing
+    rows: Vec<TreeRow>,
+    /// Optional closure that returns the children of a lazy node
+    loader: Option<Box<dyn Fn(u32) -> Vec<TreeNode>>>,
+    /// Icon images by name. Each image is rendered once at origin 0, 0 and translated to each row.
+    icons: HashMap<String, ImageView>,
+    /// The id of the selected node
+    select_id: Option<u32>,
+    /// The expand or collapse animation in progress
+    disclosure: Option<Disclosure>,
+    /// Text meshes for the visible rows, keyed by node id. Created at origin 0, 0.
+    text_cache: HashMap<u32, MeshTask>,
+    /// Stores the scroll offset
+    scroll_offset: f32,
+    /// Theme colors
+    select_bg_color: Color,
+    select_fg_color: Color,
+    guide_color: Color,
+    /// TreeEvents waiting to be dispatched in the next update()
+    pending_events: Vec<TreeEvent>,
+}
+
+impl TreeView {
+    /// Constructor
+    pub fn new(frame: Rectangle) -> Self {
+        let layer = Layer::new(frame);
+
+        TreeView {
+            layer,
+            row_height: 24.0,
+            indent: 18.0,
+            icon_size: 16.0,
+            show_guides: true,
+            nodes: Vec::new(),
+            rows: Vec::new(),
+            loader: None,
+            icons: HashMap::new(),
+            select_id: None,
+            disclosure: None,
+            text_cache: HashMap::new(),
+            scroll_offset: 0.0,
+            select_bg_color: Color::from_hex("#0096FF"),
+            select_fg_color: Color::WHITE,
+            guide_color: Color::from_hex("#CCCCCC"),
+            pending_events: Vec::new(),
+        }
+    }
+
+    /// Builder method to set the closure that loads the children of lazy nodes
+    pub fn with_loader<C>(mut self, cb: C) -> Self
+    where
+        C: Fn(u32) -> Vec<TreeNode> + 'static,
+    {
+        self.loader = Some(Box::new(cb));
+        self
+    }
+
+    /// Replace all nodes
+    pub fn set_nodes(&mut self, nodes: Vec<TreeNode>) {
+        self.nodes = nodes;
+        self.disclosure = None;
+        self.select_id = None;
+        self.scroll_offset = 0.0;
+        self.text_cache.clear();
+        self.rebuild_rows();
+    }
+
+    /// Add an icon image that nodes can refer to by name
+    pub fn add_icon(&mut self, name: &str, asset: Asset<Vec<u8>>) {
+        let frame = Rectangle::new_sized((self.icon_size, self.icon_size));
+        let view = ImageView::new(frame, Some(asset)).with_scale_mode(ImageScaleMode::AspectFit);
+        self.icons.insert(name.to_owned(), view);
+    }
+
+    /// Get the node with the id, if it has been loaded
+    pub fn node(&self, id: u32) -> Option<&TreeNode> {
+        let mut path = Vec::new();
+        if TreeView::find_path(&self.nodes, id, &mut path) {
+            return TreeView::node_at(&self.nodes, &path);
+        }
+        None
+    }
+
+    /// The id of the selected node
+    pub fn selected_id(&self) -> Option<u32> {
+        self.select_id
+    }
+
+    /// The number of visible rows
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// The total height of the visible rows
+    pub fn content_height(&self) -> f32 {
+        let hidden = self.disclosure.as_ref().map_or(0.0, |d| d.hidden_height(self.row_height));
+        self.rows.len() as f32 * self.row_height - hidden
+    }
+
+    /// Select the node with the id and expand its parents if needed. Returns false if the node has not
+    /// been loaded.
+    pub fn select(&mut self, id: u32) -> bool {
+        self.finish_disclosure();
+        let mut path = Vec::new();
+        if !TreeView::find_path(&self.nodes, id, &mut path) {
+            return false;
+        }
+        let mut changed = false;
+        for depth in 1..path.len() {
+            if let Some(node) = TreeView::node_at_mut(&mut self.nodes, &path[..depth]) {
+                changed |= !node.expanded;
+                node.expanded = true;
+            }
+        }
+        if changed {
+            self.rebuild_rows();
+        }
+        if let Some(row) = self.row_of(id) {
+            self.select_row(row);
+            self.scroll_to_row(row);
+        }
+        true
+    }
+
+    /// Remove the selection
+    pub fn clear_selection(&mut self) {
+        self.select_id = None;
+    }
+
+    /// Expand the visible node with the id. Returns false if the node is not visible.
+    pub fn expand(&mut self, id: u32) -> bool {
+        self.finish_disclosure();
+        if let Some(row) = self.row_of(id) {
+            self.expand_row(row);
+            return true;
+        }
+        false
+    }
+
+    /// Collapse the visible node with the id. Returns false if the node is not visible.
+    pub fn collapse(&mut self, id: u32) -> bool {
+        self.finish_disclosure();
+        if let Some(row) = self.row_of(id) {
+            self.collapse_row(row);
+            return true;
+        }
+        false
+    }
+
+    // *****************************************************************************************************
+    // Tree
+    // *****************************************************************************************************
+
+    fn node_at<'a>(nodes: &'a [TreeNode], path: &[usize]) -> Option<&'a TreeNode> {
+        let (first, rest) = path.split_first()?;
+        let node = nodes.get(*first)?;
+        if rest.is_empty() {
+            Some(node)
+        } else {
+            TreeView::node_at(&node.children, rest)
+        }
+    }
+
+    fn node_at_mut<'a>(nodes: &'a mut [TreeNode], path: &[usize]) -> Option<&'a mut TreeNode> {
+        let (first, rest) = path.split_first()?;
+        let node = nodes.get_mut(*first)?;
+        if rest.is_empty() {
+            Some(node)
+        } else {
+            TreeView::node_at_mut(&mut node.children, rest)
+        }
+    }
+
+    /// Search the loaded nodes for the id and fill in the path to it
+    fn find_path(nodes: &[TreeNode], id: u32, path: &mut Vec<usize>) -> bool {
+        for (i, node) in nodes.iter().enumerate() {
+            path.push(i);
+            if node.id == id || TreeView::find_path(&node.children, id, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    fn flatten(nodes: &[TreeNode], path: &mut Vec<usize>, rows: &mut Vec<TreeRow>) {
+        for (i, node) in nodes.iter().enumerate() {
+            path.push(i);
+            rows.push(TreeRow { path: path.clone(), id: node.id });
+            if node.expanded {
+                TreeView::flatten(&node.children, path, rows);
+            }
+            path.pop();
+        }
+    }
+
+    /// Flatten the expanded nodes into rows
+    fn rebuild_rows(&mut self) {
+        let mut rows = Vec::new();
+        TreeView::flatten(&self.nodes, &mut Vec::new(), &mut rows);
+        self.rows = rows;
+        let upper_limit = (self.content_height() - self.layer.frame.height()).max(0.0);
+        self.scroll_offset = self.scroll_offset.min(upper_limit);
+    }
+
+    fn row_of(&self, id: u32) -> Option<usize> {
+        self.rows.iter().position(|r| r.id == id)
+    }
+
+    /// The number of visible rows below a node
+    fn descendant_count(&self, row: usize) -> usize {
+        let depth = self.rows[row].depth();
+        self.rows[row + 1..].iter().take_while(|r| r.depth() > depth).count()
+    }
+
+    fn parent_row(&self, row: usize) -> Option<usize> {
+        let depth = self.rows[row].depth();
+        self.rows[..row].iter().rposition(|r| r.depth() < depth)
+    }
+
+    /// Expand a node, loading its children first if it is lazy. Returns false if the node is already
+    /// expanded or has no children.
+    fn expand_row(&mut self, row: usize) -> bool {
+        let path = self.rows[row].path.clone();
+        let loader = &self.loader;
+        let node = match TreeView::node_at_mut(&mut self.nodes, &path) {
+            Some(node) => node,
+            None => return false,
+        };
+        if node.expanded || !node.is_expandable() {
+            return false;
+        }
+        if node.lazy {
+            if let Some(cb) = loader {
+                node.children = cb(node.id);
+            }
+            node.lazy = false;
+        }
+        if node.children.is_empty() {
+            return false;
+        }
+        node.expanded = true;
+        let id = node.id;
+        self.rebuild_rows();
+        let count = self.descendant_count(row);
+        self.start_disclosure(row, count, true);
+        self.pending_events.push(TreeEvent::Expanded(id));
+        true
+    }
+
+    /// Collapse a node. The child rows remain until the animation completes. Returns false if the node
+    /// is not expanded.
+    fn collapse_row(&mut self, row: usize) -> bool {
+        let path = self.rows[row].path.clone();
+        let id = match TreeView::node_at_mut(&mut self.nodes, &path) {
+            Some(node) if node.expanded => {
+                node.expanded = false;
+                node.id
+            }
+            _ => return false,
+        };
+        let count = self.descendant_count(row);
+        // Move the selection to the collapsed node if it is about to be hidden
+        if let Some(select_id) = self.select_id {
+            if self.rows[row + 1..row + 1 + count].iter().any(|r| r.id == select_id) {
+                self.select_row(row);
+            }
+        }
+        self.start_disclosure(row, count, false);
+        self.pending_events.push(TreeEvent::Collapsed(id));
+        true
+    }
+
+    fn toggle_row(&mut self, row: usize) {
+        let expanded = TreeView::node_at(&self.nodes, &self.rows[row].path).map_or(false, |node| node.expanded);
+        if expanded {
+            self.collapse_row(row);
+        } else {
+            self.expand_row(row);
+        }
+    }
+
+    /// Animate the height of the child rows and the rotation of the disclosure arrow
+    fn start_disclosure(&mut self, row: usize, count: usize, expanding: bool) {
+        if count == 0 {
+            self.rebuild_rows();
+            return;
+        }
+        let full = count as f32 * self.row_height;
+        let (from, to) = if expanding { (0.0, full) } else { (full, 0.0) };
+        let mut layer = Layer::new(Rectangle::new((0.0, 0.0), (1.0, from)));
+        layer.rotation = if expanding { 0.0 } else { 90.0 };
+        let angle = if expanding { 90.0 } else { 0.0 };
+        let propset = PropSet::new(vec![size(1.0, to), rotate(angle)], DISCLOSURE_DURATION).ease(Ease::SineOut);
+        layer.animate_with_props(propset, true);
+        self.disclosure = Some(Disclosure { row, count, expanding, layer });
+    }
+
+    /// Jump to the end of the animation
+    fn finish_disclosure(&mut self) {
+        if let Some(disclosure) = self.disclosure.take() {
+            if !disclosure.expanding {
+                self.rebuild_rows();
+            }
+        }
+    }
+
+    fn select_row(&mut self, row: usize) {
+        let id = self.rows[row].id;
+        if self.select_id != Some(id) {
+            self.select_id = Some(id);
+            self.pending_events.push(TreeEvent::Selected(id));
+        }
+    }
+
+    /// Change the scroll offset so that the row is fully visible
+    fn scroll_to_row(&mut self, row: usize) {
+        let top = row as f32 * self.row_height;
+        let height = self.layer.frame.height();
+        self.scroll_offset = UITools::scroll_to_span(self.scroll_offset, top, top + self.row_height, height);
+    }
+
+    // *****************************************************************************************************
+    // Layout
+    // *****************************************************************************************************
+
+    /// The top of a row in window coordinates. Rows below an animating node are shifted up by the height
+    /// that is still hidden.
+    fn row_y(&self, row: usize) -> f32 {
+        let mut y = self.layer.frame.y() + row as f32 * self.row_height - self.scroll_offset;
+        if let Some(disclosure) = &self.disclosure {
+            if row > disclosure.row {
+                y -= disclosure.hidden_height(self.row_height);
+            }
+        }
+        y
+    }
+
+    /// The y position where a row is clipped at the top. The child rows of an animating node slide out
+    /// from under it.
+    fn clip_top(&self, row: usize) -> f32 {
+        let top = self.layer.frame.y();
+        if let Some(disclosure) = &self.disclosure {
+            if row > disclosure.row && row <= disclosure.row + disclosure.count {
+                return top.max(self.row_y(disclosure.row) + self.row_height);
+            }
+        }
+        top
+    }
+
+    /// The rows that are at least partly visible, from the top and bottom edges of each row after clipping
+    fn get_visible_rows(&self) -> Vec<(usize, f32, f32)> {
+        let frame = self.layer.frame;
+        let bottom = frame.y() + frame.height();
+        let mut results = Vec::new();
+        let mut row = ((self.scroll_offset / self.row_height).floor() as usize).min(self.rows.len());
+        while row < self.rows.len() {
+            let y = self.row_y(row);
+            if y >= bottom {
+                break;
+            }
+            let top = y.max(self.clip_top(row));
+            let end = (y + self.row_height).min(bottom);
+            if end > top {
+                results.push((row, top, end));
+            }
+            row += 1;
+        }
+        results
+    }
+
+    /// Find the row at the window position
+    fn row_at_point(&self, pt: &Vector) -> Option<usize> {
+        if !pt.overlaps_rectangle(&self.layer.frame) {
+            return None;
+        }
+        self.get_visible_rows().iter().find(|(_, top, bottom)| pt.y >= *top && pt.y < *bottom).map(|(row, _, _)| *row)
+    }
+
+    // *****************************************************************************************************
+    // Render
+    // *****************************************************************************************************
+
+    /// Clip a mesh that was translated to a row where the row overflows the visible top or bottom
+    fn clip_row_mesh(mesh: &mut MeshTask, rect: &Rectangle, top: f32, bottom: f32) {
+        if rect.y() < top {
+            let bounds = Rectangle::new((rect.x(), top), (rect.width(), rect.y() + rect.height() - top));
+            UITools::clip_mesh(mesh, &bounds, RectSide::Top);
+        }
+        if rect.y() + rect.height() > bottom {
+            let bounds = Rectangle::new(rect.pos, (rect.width(), bottom - rect.y()));
+            UITools::clip_mesh(mesh, &bounds, RectSide::Bottom);
+        }
+    }
+
+    /// Draw the disclosure arrow as a triangle pointing right and rotated by the angle
+    fn draw_arrow(center: Vector, angle: f32, color: Color) -> Mesh {
+        let half = ARROW_SIZE / 2.0;
+        let transform = Transform::translate(center) * Transform::rotate(angle);
+        let pt1 = transform * Vector::new(-half / 2.0, -half);
+        let pt2 = transform * Vector::new(half, 0.0);
+        let pt3 = transform * Vector::new(-half / 2.0, half);
+        DrawShape::triangle(&[&pt1, &pt2, &pt3], Some(color), None, 0.0)
+    }
+}
+
+// *****************************************************************************************************
+// TreeView :: Displayable
+// *****************************************************************************************************
+
+impl Displayable for TreeView {
+    fn get_type_id(&self) -> TypeId {
+        TypeId::of::<TreeView>()
+    }
+
+    fn get_layer(&self) -> &Layer {
+        &self.layer
+    }
+
+    fn get_layer_mut(&mut self) -> &mut Layer {
+        &mut self.layer
+    }
+
+    fn get_frame(&self) -> Rectangle {
+        return self.layer.frame;
+    }
+
+    fn move_to(&mut self, pos: (f32, f32)) {
+        self.layer.frame.pos.x = pos.0;
+        self.layer.frame.pos.y = pos.1;
+    }
+
+    fn set_theme(&mut self, theme: &mut Theme) {
+        // Don't allow style to be locked
+        let _ = self.layer.apply_theme(theme);
+
+        self.select_bg_color = theme.button_bg_color;
+        self.select_fg_color = theme.button_fg_color;
+        self.guide_color = theme.border_color;
+        self.text_cache.clear();
+    }
+
+    fn notify(&mut self, event: &DisplayEvent) {
+        match event {
+            DisplayEvent::Ready => {
+                self.layer.on_ready();
+            }
+            DisplayEvent::Moved => {
+                self.layer.on_move_complete();
+            }
+            _ => {}
+        }
+    }
+
+    fn update(&mut self, _window: &mut Window, state: &mut AppState) {
+        self.layer.tween_update(state);
+        let finished = match &mut self.disclosure {
+            Some(disclosure) => {
+                disclosure.layer.tween_update(state);
+                disclosure.is_finished()
+            }
+            None => false,
+        };
+        if finished {
+            self.finish_disclosure();
+        }
+        for evt in self.pending_events.drain(..) {
+            state.event_bus.dispatch_event(evt, self.layer.node_id(), self.layer.tag);
+        }
+    }
+
+    fn render(&mut self, theme: &mut Theme, window: &mut Window) {
+        self.layer.draw_background(window);
+        let frame = self.layer.frame;
+        let visible = self.get_visible_rows();
+
+        // Discard text for rows that are out of view
+        let ids: Vec<u32> = visible.iter().map(|(row, _, _)| self.rows[*row].id).collect();
+        self.text_cache.retain(|id, _| ids.contains(id));
+
+        // Shapes are drawn first and the icons and text are drawn on top
+        let mut graphics = MeshTask::new(0);
+        let mut content: Vec<MeshTask> = Vec::new();
+        let arrow_color = self.layer.font_style.get_color();
+        for (row, top, bottom) in visible {
+            let y = self.row_y(row);
+            let depth = self.rows[row].depth();
+            let id = self.rows[row].id;
+            let selected = self.select_id == Some(id);
+            let x = frame.x() + depth as f32 * self.indent;
+
+            if selected {
+                let rect = Rectangle::new((frame.x(), top), (frame.width(), bottom - top));
+                let mut mesh = DrawShape::rectangle(&rect, Some(self.select_bg_color), None, 0.0, 0.0);
+                graphics.append(&mut mesh);
+            }
+            // Indentation guides pass through the arrow area of each parent level
+            if self.show_guides {
+                for level in 0..depth {
+                    let gx = frame.x() + level as f32 * self.indent + self.indent / 2.0;
+                    let pts: [&Vector; 2] = [&Vector::new(gx, top), &Vector::new(gx, bottom)];
+                    let mut line = DrawShape::line(&pts, self.guide_color, 1.0);
+                    graphics.append(&mut line);
+                }
+            }
+
+            let (title, icon, expandable, expanded) = match TreeView::node_at(&self.nodes, &self.rows[row].path) {
+                Some(node) => (node.title.clone(), node.icon.clone(), node.is_expandable(), node.expanded),
+                None => continue,
+            };
+            let center = Vector::new(x + self.indent / 2.0, y + self.row_height / 2.0);
+            let half = ARROW_SIZE / 2.0;
+            if expandable && center.y - half >= top && center.y + half <= bottom {
+                let angle = match &self.disclosure {
+                    Some(disclosure) if disclosure.row == row => disclosure.angle(),
+                    _ if expanded => 90.0,
+                    _ => 0.0,
+                };
+                let mut mesh = TreeView::draw_arrow(center, angle, arrow_color);
+                graphics.append(&mut mesh);
+            }
+
+            let mut text_x = x + self.indent;
+            if let Some(name) = icon {
+                let icon_y = y + (self.row_height - self.icon_size) / 2.0;
+                if let Some(view) = self.icons.get_mut(&name) {
+                    if let Some(mut mesh) = view.content_mesh() {
+                        // The icon mesh was created at origin 0, 0
+                        for vertex in &mut mesh.vertices.iter_mut() {
+                            vertex.pos = Transform::translate((text_x, icon_y)) * vertex.pos;
+                        }
+                        let rect = Rectangle::new((text_x, icon_y), (self.icon_size, self.icon_size));
+                        TreeView::clip_row_mesh(&mut mesh, &rect, top, bottom);
+                        content.push(mesh);
+                    }
+                }
+                text_x += self.icon_size + ICON_SPACING;
+            }
+
+            let width = (frame.x() + frame.width() - text_x).max(0.0);
+            if !self.text_cache.contains_key(&id) {
+                let rect = Rectangle::new((0.0, 0.0), (width, self.row_height));
+                let mut params = TextParams::new(self.layer.font_style)
+                    .frame(rect)
+                    .text(&title)
+                    .align(TextAlign::Left, VertAlign::Middle)
+                    .multiline(false);
+                params.subframe = Some(rect);
+                if let Some(task) = theme.default_font.draw(params) {
+                    self.text_cache.insert(id, task);
+                }
+            }
+            if let Some(task) = self.text_cache.get(&id) {
+                let mut mesh = task.clone();
+                for vertex in &mut mesh.vertices.iter_mut() {
+                    vertex.pos = Transform::translate((text_x, y)) * vertex.pos;
+                    if selected {
+                        vertex.col = self.select_fg_color;
+                    }
+                }
+                let rect = Rectangle::new((text_x, y), (width, self.row_height));
+                TreeView::clip_row_mesh(&mut mesh, &rect, top, bottom);
+                content.push(mesh);
+            }
+        }
+        window.add_task(graphics);
+        for mesh in content {
+            window.add_task(mesh);
+        }
+
+        if let Some(rect) = UITools::get_scrollbar_frame(self.content_height(), &frame, self.scroll_offset) {
+            // FIXME: use mesh
+            window.draw(&rect, Col(Color::from_hex(UITools::SCROLLBAR_COLOR)));
+        }
+        self.layer.draw_border(window);
+    }
+
+    fn set_hover_animation(&mut self, props: PropSet) {
+        self.layer.hover_effect = Some(props);
+    }
+
+    fn handle_mouse_at(&mut self, pt: &Vector, window: &mut Window) -> bool {
+        if pt.overlaps_rectangle(&self.layer.frame) {
+            self.layer.mouse_state = MouseState::Hover;
+            window.set_cursor(MouseCursor::Hand);
+            return self.row_at_point(pt).is_some();
+        }
+        window.set_cursor(MouseCursor::Default);
+        self.layer.mouse_state = MouseState::None;
+        false
+    }
+}
+
+// *****************************************************************************************************
+// TreeView :: Responder
+// *****************************************************************************************************
+
+impl Responder for TreeView {
+    fn get_field_value(&self) -> FieldValue {
+        match self.select_id {
+            Some(id) => FieldValue::Selections(vec![id as usize]),
+            None => FieldValue::None,
+        }
+    }
+
+    /// The Selections value is a list of node ids, where only the first is used
+    fn set_field_value(&mut self, value: &FieldValue, type_id: TypeId, layer_id: u32) -> bool {
+        if type_id == self.get_type_id() && layer_id == self.layer.get_id() {
+            match value {
+                FieldValue::Selections(ids) => {
+                    match ids.first() {
+                        Some(id) => {
+                            self.select(*id as u32);
+                        }
+                        None => self.clear_selection(),
+                    }
+                    return true;
+                }
+                FieldValue::None => {
+                    self.clear_selection();
+                    return true;
+                }
+                _ => (),
+            }
+        }
+        false
+    }
+
+    /// A click on the disclosure arrow toggles the node and a click elsewhere in the row selects it
+    fn handle_mouse_down(&mut self, pt: &Vector, _state: &mut AppState) -> bool {
+        if !pt.overlaps_rectangle(&self.layer.frame) {
+            return false;
+        }
+        self.finish_disclosure();
+        if let Some(row) = self.row_at_point(pt) {
+            let arrow_x = self.layer.frame.x() + self.rows[row].depth() as f32 * self.indent;
+            let expandable =
+                TreeView::node_at(&self.nodes, &self.rows[row].path).map_or(false, |node| node.is_expandable());
+            if expandable && pt.x >= arrow_x && pt.x < arrow_x + self.indent {
+                self.toggle_row(row);
+            } else {
+                self.select_row(row);
+            }
+            return true;
+        }
+        false
+    }
+
+    /// Add or subtract from the scroll offset
+    fn handle_mouse_scroll(&mut self, pt: &Vector, _state: &mut AppState) {
+        match self.layer.mouse_state {
+            MouseState::Hover => {
+                let upper_limit = (self.content_height() - self.layer.frame.height()).max(0.0);
+                let eval_y = ((self.scroll_offset + pt.y) * SCROLL_FACTOR).min(upper_limit);
+                self.scroll_offset = eval_y.max(0.0);
+            }
+            _ => (),
+        }
+    }
+
+    /// Up, Down, Home, End, PageUp and PageDown move the selection. Right expands the selected node or
+    /// moves to its first child and Left collapses it or moves to its parent. Return activates the node.
+    fn handle_key_command(&mut self, key: &Key, _window: &mut Window) -> bool {
+        self.finish_disclosure();
+        let count = self.rows.len();
+        if count == 0 {
+            return false;
+        }
+        let focus = self.select_id.and_then(|id| self.row_of(id));
+        let page = UITools::page_size(self.layer.frame.height(), self.row_height);
+        if let Some(target) = UITools::row_for_key(key, focus, count, page) {
+            self.select_row(target);
+            self.scroll_to_row(target);
+            return true;
+        }
+        let target = match key {
+            Key::Right => match focus {
+                Some(row) => {
+                    let expanded = TreeView::node_at(&self.nodes, &self.rows[row].path).map_or(false, |n| n.expanded);
+                    if !expanded {
+                        return self.expand_row(row);
+                    }
+                    if self.descendant_count(row) == 0 {
+                        return false;
+                    }
+                    row + 1
+                }
+                None => 0,
+            },
+            Key::Left => match focus {
+                Some(row) => {
+                    let expanded = TreeView::node_at(&self.nodes, &self.rows[row].path).map_or(false, |n| n.expanded);
+                    if expanded {
+                        return self.collapse_row(row);
+                    }
+                    match self.parent_row(row) {
+                        Some(parent) => parent,
+                        None => return false,
+                    }
+                }
+                None => 0,
+            },
+            Key::Return => {
+                if let Some(id) = self.select_id {
+                    self.pending_events.push(TreeEvent::Activated(id));
+                    return true;
+                }
+                return false;
+            }
+            _ => return false,
+        };
+        self.select_row(target);
+        self.scroll_to_row(target);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 1 > (2 > (3, 4), 5 lazy), 6
+    fn tree() -> TreeView {
+        let mut tree = TreeView::new(Rectangle::new((0.0, 0.0), (200.0, 100.0)))
+            .with_loader(|id| vec![TreeNode::new(id * 10, "Loaded"), TreeNode::new(id * 10 + 1, "Loaded")]);
+        let branch = TreeNode::new(2, "Branch").with_children(vec![TreeNode::new(3, "A"), TreeNode::new(4, "B")]);
+        let root = TreeNode::new(1, "Root")
+            .with_children(vec![branch.with_expanded(true), TreeNode::new(5, "Lazy").with_lazy_children()])
+            .with_expanded(true);
+        tree.set_nodes(vec![root, TreeNode::new(6, "Other")]);
+        tree
+    }
+
+    #[test]
+    fn test_rows() {
+        let tree = tree();
+        assert_eq!(tree.rows.iter().map(|r| r.id).collect::<Vec<u32>>(), vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(tree.row_of(4), Some(3));
+        assert_eq!(tree.row_of(50), None);
+
+        assert_eq!(tree.parent_row(0), None);
+        assert_eq!(tree.parent_row(1), Some(0));
+        assert_eq!(tree.parent_row(3), Some(1));
+        assert_eq!(tree.parent_row(4), Some(0));
+        assert_eq!(tree.parent_row(5), None);
+
+        assert_eq!(tree.descendant_count(0), 4);
+        assert_eq!(tree.descendant_count(1), 2);
+        assert_eq!(tree.descendant_count(2), 0);
+        assert_eq!(tree.descendant_count(5), 0);
+    }
+
+    #[test]
+    fn test_collapse() {
+        let mut tree = tree();
+        assert!(tree.select(3));
+        assert!(tree.collapse(2));
+        // The selection moves to the collapsed node and the rows remain until the animation ends
+        assert_eq!(tree.selected_id(), Some(2));
+        assert_eq!(tree.row_count(), 6);
+        tree.finish_disclosure();
+        assert_eq!(tree.rows.iter().map(|r| r.id).collect::<Vec<u32>>(), vec![1, 2, 5, 6]);
+        assert_eq!(tree.pending_events.last(), Some(&TreeEvent::Collapsed(2)));
+        assert!(!tree.collapse_row(1));
+
+        // Selecting a hidden node expands its parents
+        assert!(tree.select(4));
+        assert_eq!(tree.row_of(4), Some(3));
+    }
+
+    #[test]
+    fn test_lazy_loading() {
+        let mut tree = tree();
+        assert!(tree.node(5).unwrap().is_expandable());
+        assert!(tree.node(50).is_none());
+        assert!(tree.expand(5));
+        assert_eq!(tree.row_of(50), Some(5));
+        assert_eq!(tree.row_of(51), Some(6));
+        assert_eq!(tree.parent_row(5), Some(4));
+        assert_eq!(tree.pending_events.last(), Some(&TreeEvent::Expanded(5)));
+        assert!(!tree.node(5).unwrap().lazy);
+
+        // The children are only loaded once
+        tree.finish_disclosure();
+        assert!(tree.collapse(5));
+        tree.finish_disclosure();
+        assert!(tree.expand(5));
+        assert_eq!(tree.node(5).unwrap().children.len(), 2);
+
+        // Leaf nodes cannot be expanded
+        let row = tree.row_of(6).unwrap();
+        assert!(!tree.expand_row(row));
+    }
+}
@@ -12,6 +12,8 @@ use crate::core::*;
 use crate::events::*;
 use crate::gui::*;
 
+use super::CompositionSource;
+
 use std::any::TypeId;
 
 use quicksilver::{
@@ -41,6 +43,7 @@ pub struct AppDelegate {
     stage_builders: Vec<Box<dyn Fn() -> Stage + 'static>>,
     view_index: usize,
     frames: usize,
+    composition_source: Option<Box<dyn CompositionSource>>,
 }
 
 impl AppDelegate {
@@ -75,6 +78,7 @@ impl AppDelegate {
             stage_builders: Vec::new(),
             view_index: 0,
             frames: 0,
+            composition_source: None,
         };
         app
     }
@@ -91,15 +95,37 @@ impl AppDelegate {
         self.stage_builders.push(Box::new(cb));
     }
 
+    /// Set the source of IME composition events. Quicksilver does not provide IME events, so a
+    /// backend can provide its own source which is polled on every update.
+    pub fn set_composition_source(&mut self, source: Box<dyn CompositionSource>) {
+        self.composition_source = Some(source);
+    }
+
+    /// Hook for backends to pass a platform IME event to the active text control
+    pub fn handle_text_input(&mut self, event: &TextInputEvent, window: &mut Window) -> bool {
+        let mut handled = false;
+        if self.nav_scene.is_interactive {
+            handled = self.nav_scene.handle_text_input(event, window);
+        }
+        self.stage.handle_text_input(event, window) || handled
+    }
+
     /// Application lifecycle event called before runloop starts
     pub fn application_ready(&mut self) {
         if let Some(theme) = self.theme_picker.load_theme(LIGHT_THEME) {
             println!(">>> application_ready: LIGHT_THEME");
-            self.theme = theme;
+            self.change_theme(theme);
         }
         self.load_scene();
     }
 
+    /// Replace the current theme. The image atlas moves to the new theme, so registered images and uploaded
+    /// atlas pages survive the change.
+    fn change_theme(&mut self, mut theme: Theme) {
+        std::mem::swap(&mut theme.image_atlas, &mut self.theme.image_atlas);
+        self.theme = theme;
+    }
+
     pub fn load_scene(&mut self) {
         if let Some(cb) = self.stage_builders.get_mut(self.view_index) {
             let stage = cb();
@@ -140,6 +166,12 @@ impl State for AppDelegate {
                 log::debug!("NavEvent={:?}", evt);
                 match evt {
                     NavEvent::Next => {
+                        // A TabView in the stage selects its next tab before the stages are cycled
+                        self.app_state.event_consumed = false;
+                        self.stage.handle_event(&event, &mut self.app_state);
+                        if self.app_state.event_consumed {
+                            continue;
+                        }
                         self.view_index += 1;
                         if self.view_index == self.stage_builders.len() {
                             self.view_index = 0;
@@ -148,6 +180,12 @@ impl State for AppDelegate {
                         return Ok(());
                     }
                     NavEvent::Back => {
+                        // A NavController or TabView in the stage handles Back before the stages are cycled
+                        self.app_state.event_consumed = false;
+                        self.stage.handle_event(&event, &mut self.app_state);
+                        if self.app_state.event_consumed {
+                            continue;
+                        }
                         if self.view_index == 0 {
                             self.view_index = self.stage_builders.len() - 1;
                         } else {
@@ -163,16 +201,24 @@ impl State for AppDelegate {
                 match evt {
                     ThemeEvent::Change(id) => {
                         if let Some(theme) = self.theme_picker.load_theme(*id) {
-                            self.theme = theme;
+                            self.change_theme(theme);
                             self.stage.set_theme(&mut self.theme);
                         }
                     } // _ => ()
                 }
             }
             // FIXME: Borrow error
+            self.app_state.event_consumed = false;
             self.stage.handle_event(&event, &mut self.app_state);
         }
 
+        if let Some(mut source) = self.composition_source.take() {
+            while let Some(event) = source.poll_event() {
+                self.handle_text_input(&event, window);
+            }
+            self.composition_source = Some(source);
+        }
+
         let _ = self.nav_scene.update(window, &mut self.app_state);
         let _ = self.stage.update(window, &mut self.app_state);
 
@@ -206,7 +252,7 @@ impl State for AppDelegate {
                 let mut hover: bool = false;
                 // FIXME: This hover value overrides previous result.
                 // hover = self.scene.handle_mouse_at(pt);
-                if self.nav_scene.is_interactive {
+                if self.nav_scene.is_interactive && !self.stage.has_modal() {
                     hover = self.nav_scene.handle_mouse_at(pt, window);
                 }
                 if !hover {
@@ -215,12 +261,15 @@ impl State for AppDelegate {
             }
             Event::MouseButton(MouseButton::Left, ButtonState::Pressed) => {
                 self.stage.handle_mouse_down(&window.mouse().pos(), &mut self.app_state);
-                if self.nav_scene.is_interactive {
+                if self.nav_scene.is_interactive && !self.stage.has_modal() {
                     self.nav_scene.handle_mouse_down(&window.mouse().pos(), &mut self.app_state);
                 }
             }
+            Event::MouseButton(MouseButton::Right, ButtonState::Pressed) => {
+                self.stage.show_context_menu(&window.mouse().pos());
+            }
             Event::MouseButton(MouseButton::Left, ButtonState::Released) => {
-                if self.nav_scene.is_interactive {
+                if self.nav_scene.is_interactive && !self.stage.has_modal() {
                     self.nav_scene.handle_mouse_up(&window.mouse().pos(), &mut self.app_state);
                 }
                 self.stage.handle_mouse_up(&window.mouse().pos(), &mut self.app_state);
@@ -228,8 +277,13 @@ impl State for AppDelegate {
             Event::MouseWheel(xy) => {
                 self.stage.handle_mouse_scroll(xy, &mut self.app_state);
             }
+            Event::Key(key, ButtonState::Pressed) if self.app_state.modifiers.update(key, true) => {}
+            Event::Key(key, ButtonState::Released) => {
+                self.app_state.modifiers.update(key, false);
+            }
             Event::Key(key, ButtonState::Pressed) => match key {
-                Key::Escape => {
+                // Escape closes a menu or dismisses a modal before it closes the window
+                Key::Escape if !self.stage.has_modal() && !self.stage.has_menu() => {
                     window.close();
                 }
                 _ => {
@@ -0,0 +1,558 @@
+/// Texture atlases combine many small images into a few shared GPU textures
+///
+use super::*;
+
+use quicksilver::{
+    geom::{Rectangle, Vector},
+    graphics::{PixelFormat, Texture},
+};
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// The default page size in pixels
+const DEFAULT_PAGE_SIZE: u32 = 512;
+/// The default maximum size that a page can grow to
+const DEFAULT_MAX_PAGE_SIZE: u32 = 2048;
+/// The default number of pages before the least recently used page is evicted
+const DEFAULT_MAX_PAGES: usize = 4;
+
+//-- Support -----------------------------------------------------------------------
+
+/// The reason an image could not be added to an atlas
+#[derive(Clone, Debug, PartialEq)]
+pub enum AtlasError {
+    /// The image and its padding do not fit in a page of the maximum size
+    TooLarge(String),
+    /// No image has been registered with the name
+    NotFound(String),
+    /// The image bytes could not be decoded
+    Decode(String),
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AtlasError::TooLarge(name) => write!(f, "Image {} is larger than the maximum page size", name),
+            AtlasError::NotFound(name) => write!(f, "Image {} has not been registered", name),
+            AtlasError::Decode(name) => write!(f, "Image {} could not be decoded", name),
+        }
+    }
+}
+
+/// The location of an image in an atlas
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AtlasEntry {
+    /// The index of the page
+    pub page: usize,
+    /// The region of the page in pixels, not including padding
+    pub region: Rectangle,
+}
+
+/// A row of images with the height of the tallest image that started it
+#[derive(Clone, Copy, Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    width: u32,
+}
+
+/// Places rectangles in rows from top to bottom. Each rectangle is placed in the shelf with the least wasted height.
+#[derive(Clone, Debug)]
+pub struct ShelfPacker {
+    size: u32,
+    padding: u32,
+    shelves: Vec<Shelf>,
+}
+
+impl ShelfPacker {
+    /// Constructor for a square area with padding around each rectangle
+    pub fn new(size: u32, padding: u32) -> Self {
+        ShelfPacker { size, padding, shelves: Vec::new() }
+    }
+
+    /// The width and height of the area
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Find a place for a rectangle and reserve it. Returns the position inside the padding.
+    pub fn pack(&mut self, width: u32, height: u32) -> Option<(u32, u32)> {
+        let cell_w = width + self.padding * 2;
+        let cell_h = height + self.padding * 2;
+        if cell_w > self.size || cell_h > self.size {
+            return None;
+        }
+        let size = self.size;
+        let best = self
+            .shelves
+            .iter_mut()
+            .filter(|shelf| shelf.height >= cell_h && shelf.width + cell_w <= size)
+            .min_by_key(|shelf| shelf.height - cell_h);
+        if let Some(shelf) = best {
+            let pos = (shelf.width + self.padding, shelf.y + self.padding);
+            shelf.width += cell_w;
+            return Some(pos);
+        }
+        let y = self.shelves.last().map_or(0, |shelf| shelf.y + shelf.height);
+        if y + cell_h > self.size {
+            return None;
+        }
+        self.shelves.push(Shelf { y, height: cell_h, width: cell_w });
+        Some((self.padding, y + self.padding))
+    }
+
+    /// Remove all rectangles
+    pub fn clear(&mut self) {
+        self.shelves.clear();
+    }
+}
+
+/// A square page of an atlas
+#[derive(Clone, Debug)]
+pub struct AtlasPage {
+    packer: ShelfPacker,
+    /// The names of the images in the page
+    names: Vec<String>,
+    /// The atlas clock when an image in the page was last used
+    last_used: u64,
+    /// True if the page has changed since its texture was uploaded
+    pub dirty: bool,
+}
+
+impl AtlasPage {
+    fn new(size: u32, padding: u32) -> Self {
+        AtlasPage { packer: ShelfPacker::new(size, padding), names: Vec::new(), last_used: 0, dirty: true }
+    }
+
+    /// The width and height of the page in pixels
+    pub fn size(&self) -> u32 {
+        self.packer.size()
+    }
+
+    /// The names of the images in the page
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+}
+
+//-- Main -----------------------------------------------------------------------
+
+/// The packing logic of an atlas, separate from any GPU texture. Images are added to the first page with room. When
+/// no page has room, a page is doubled in size up to the maximum page size, then a new page is added up to the
+/// maximum number of pages, and finally the least recently used page is evicted.
+#[derive(Clone, Debug)]
+pub struct TextureAtlas {
+    page_size: u32,
+    max_page_size: u32,
+    max_pages: usize,
+    padding: u32,
+    pages: Vec<AtlasPage>,
+    entries: HashMap<String, AtlasEntry>,
+    clock: u64,
+    generation: u32,
+}
+
+impl TextureAtlas {
+    /// Constructor with the size of new pages
+    pub fn new(page_size: u32) -> Self {
+        TextureAtlas {
+            page_size,
+            max_page_size: DEFAULT_MAX_PAGE_SIZE.max(page_size),
+            max_pages: DEFAULT_MAX_PAGES,
+            padding: 1,
+            pages: Vec::new(),
+            entries: HashMap::new(),
+            clock: 0,
+            generation: 0,
+        }
+    }
+
+    /// Builder method to set the empty pixels around each image, which prevents bleeding when scaled. Default is 1.
+    pub fn with_padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Builder method to set the size that pages can grow to
+    pub fn with_max_page_size(mut self, size: u32) -> Self {
+        self.max_page_size = size.max(self.page_size);
+        self
+    }
+
+    /// Builder method to set the number of pages before pages are evicted
+    pub fn with_max_pages(mut self, count: usize) -> Self {
+        self.max_pages = count.max(1);
+        self
+    }
+
+    /// Add an image of the given size, or replace an image with the same name.
+    pub fn insert(&mut self, name: &str, width: u32, height: u32) -> Result<AtlasEntry, AtlasError> {
+        if let Some(entry) = self.entries.get(name) {
+            if entry.region.width() as u32 == width && entry.region.height() as u32 == height {
+                let entry = *entry;
+                self.pages[entry.page].dirty = true;
+                return Ok(entry);
+            }
+            self.remove(name);
+        }
+        if width + self.padding * 2 > self.max_page_size || height + self.padding * 2 > self.max_page_size {
+            return Err(AtlasError::TooLarge(name.to_string()));
+        }
+        // A page with room
+        for page in 0..self.pages.len() {
+            if let Some(entry) = self.place(page, name, width, height) {
+                return Ok(entry);
+            }
+        }
+        // A page that can grow
+        for page in 0..self.pages.len() {
+            if self.grow(page, name, width, height) {
+                return Ok(self.entries[name]);
+            }
+        }
+        // A new page, or the least recently used page after it is emptied
+        let page = if self.pages.len() < self.max_pages {
+            self.pages.push(AtlasPage::new(self.page_size, self.padding));
+            self.pages.len() - 1
+        } else {
+            let page = (0..self.pages.len()).min_by_key(|page| self.pages[*page].last_used).unwrap_or(0);
+            self.evict(page);
+            page
+        };
+        if let Some(entry) = self.place(page, name, width, height) {
+            return Ok(entry);
+        }
+        if self.grow(page, name, width, height) {
+            return Ok(self.entries[name]);
+        }
+        Err(AtlasError::TooLarge(name.to_string()))
+    }
+
+    /// Reserve a region in the page and save the entry
+    fn place(&mut self, page: usize, name: &str, width: u32, height: u32) -> Option<AtlasEntry> {
+        let (x, y) = self.pages[page].packer.pack(width, height)?;
+        let entry = AtlasEntry { page, region: Rectangle::new((x as f32, y as f32), (width as f32, height as f32)) };
+        self.clock += 1;
+        let atlas_page = &mut self.pages[page];
+        atlas_page.names.push(name.to_string());
+        atlas_page.last_used = self.clock;
+        atlas_page.dirty = true;
+        self.entries.insert(name.to_string(), entry);
+        Some(entry)
+    }
+
+    /// Double the page size until the existing images and the new one fit. Existing images move, so the generation
+    /// changes.
+    fn grow(&mut self, page: usize, name: &str, width: u32, height: u32) -> bool {
+        let mut items: Vec<(String, u32, u32)> = self.pages[page]
+            .names
+            .iter()
+            .map(|name| {
+                let region = self.entries[name].region;
+                (name.clone(), region.width() as u32, region.height() as u32)
+            })
+            .collect();
+        items.push((name.to_string(), width, height));
+        // Taller images first packs shelves more tightly
+        items.sort_by(|a, b| b.2.cmp(&a.2).then(b.1.cmp(&a.1)));
+
+        let mut size = self.pages[page].size() * 2;
+        while size <= self.max_page_size {
+            let mut packer = ShelfPacker::new(size, self.padding);
+            let positions: Vec<Option<(u32, u32)>> = items.iter().map(|item| packer.pack(item.1, item.2)).collect();
+            if positions.iter().all(|pos| pos.is_some()) {
+                let atlas_page = &mut self.pages[page];
+                atlas_page.packer = packer;
+                atlas_page.names = items.iter().map(|item| item.0.clone()).collect();
+                atlas_page.dirty = true;
+                for (item, pos) in items.iter().zip(positions.into_iter()) {
+                    let (x, y) = pos.unwrap_or_default();
+                    let region = Rectangle::new((x as f32, y as f32), (item.1 as f32, item.2 as f32));
+                    self.entries.insert(item.0.clone(), AtlasEntry { page, region });
+                }
+                self.clock += 1;
+                self.pages[page].last_used = self.clock;
+                self.generation += 1;
+                return true;
+            }
+            size *= 2;
+        }
+        false
+    }
+
+    /// Remove all images from a page
+    fn evict(&mut self, page: usize) {
+        let atlas_page = &mut self.pages[page];
+        for name in atlas_page.names.drain(..) {
+            self.entries.remove(&name);
+        }
+        atlas_page.packer.clear();
+        atlas_page.dirty = true;
+        self.generation += 1;
+    }
+
+    /// Remove an image. Its space is reclaimed when the page is evicted.
+    pub fn remove(&mut self, name: &str) -> bool {
+        match self.entries.remove(name) {
+            Some(entry) => {
+                self.pages[entry.page].names.retain(|item| item != name);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the location of an image and mark its page as recently used
+    pub fn get(&mut self, name: &str) -> Option<AtlasEntry> {
+        let entry = *self.entries.get(name)?;
+        self.clock += 1;
+        self.pages[entry.page].last_used = self.clock;
+        Some(entry)
+    }
+
+    /// True if the image is in a page
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    /// The pages in index order
+    pub fn pages(&self) -> &[AtlasPage] {
+        &self.pages
+    }
+
+    /// A number that changes whenever images move or are evicted. Meshes that were built with an older generation
+    /// must be rebuilt.
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    /// Get the indexes of pages that changed since the last call and mark them as clean
+    pub fn take_dirty_pages(&mut self) -> Vec<usize> {
+        let mut dirty = Vec::new();
+        for (index, page) in self.pages.iter_mut().enumerate() {
+            if page.dirty {
+                page.dirty = false;
+                dirty.push(index);
+            }
+        }
+        dirty
+    }
+}
+
+/// An atlas of registered images that uploads its pages to the GPU when they are drawn. The pixels of every
+/// registered image are kept, so evicted images are packed again the next time they are requested.
+pub struct ImageAtlas {
+    atlas: TextureAtlas,
+    /// The width, height and RGBA pixels of each image
+    images: HashMap<String, (u32, u32, Vec<u8>)>,
+    /// The GPU texture of each page
+    textures: Vec<Option<PageTexture>>,
+}
+
+/// The GPU texture of an atlas page. It is activated once and the page is uploaded into the same texture index
+/// until the page size changes.
+struct PageTexture {
+    texture: Texture,
+    idx: usize,
+    size: u32,
+}
+
+impl ImageAtlas {
+    /// Constructor with the default page sizes
+    pub fn new() -> Self {
+        ImageAtlas::with_atlas(TextureAtlas::new(DEFAULT_PAGE_SIZE))
+    }
+
+    /// Constructor with a configured TextureAtlas
+    pub fn with_atlas(atlas: TextureAtlas) -> Self {
+        ImageAtlas { atlas, images: HashMap::new(), textures: Vec::new() }
+    }
+
+    /// Register the RGBA pixels of an image. An image with the same name is replaced.
+    pub fn register_rgba(&mut self, name: &str, width: u32, height: u32, data: Vec<u8>) -> Result<(), AtlasError> {
+        self.atlas.insert(name, width, height)?;
+        self.images.insert(name.to_string(), (width, height, data));
+        Ok(())
+    }
+
+    /// Register the bytes of a PNG or JPEG file
+    pub fn register_bytes(&mut self, name: &str, bytes: &[u8]) -> Result<(), AtlasError> {
+        let image = image_rs::load_from_memory(bytes).map_err(|_| AtlasError::Decode(name.to_string()))?.to_rgba();
+        let (width, height) = image.dimensions();
+        self.register_rgba(name, width, height, image.into_raw())
+    }
+
+    /// True if an image has been registered with the name
+    pub fn contains(&self, name: &str) -> bool {
+        self.images.contains_key(name)
+    }
+
+    /// See TextureAtlas::generation
+    pub fn generation(&self) -> u32 {
+        self.atlas.generation()
+    }
+
+    /// Get the page texture and the region of an image, uploading pages that have changed. This must be called
+    /// after the window has been created.
+    pub fn get(&mut self, name: &str) -> Result<GPUTexture, AtlasError> {
+        if !self.atlas.contains(name) {
+            let (width, height, _) = self.images.get(name).ok_or_else(|| AtlasError::NotFound(name.to_string()))?;
+            self.atlas.insert(name, *width, *height)?;
+        }
+        let entry = self.atlas.get(name).ok_or_else(|| AtlasError::NotFound(name.to_string()))?;
+        for page in self.atlas.take_dirty_pages() {
+            self.upload_page(page);
+        }
+        let size = self.atlas.pages()[entry.page].size();
+        let idx = match self.textures.get(entry.page) {
+            Some(Some(page)) => page.idx,
+            _ => return Err(AtlasError::NotFound(name.to_string())),
+        };
+        Ok(GPUTexture::new(idx, size, size).with_region(entry.region))
+    }
+
+    /// Copy the pixels of every image in the page and upload them
+    fn upload_page(&mut self, page: usize) {
+        let atlas_page = &self.atlas.pages()[page];
+        let size = atlas_page.size() as usize;
+        let mut data = vec![0u8; size * size * 4];
+        for name in atlas_page.names() {
+            let (entry, (width, height, pixels)) = match (self.atlas.entries.get(name), self.images.get(name)) {
+                (Some(entry), Some(image)) => (entry, image),
+                _ => continue,
+            };
+            let row_len = *width as usize * 4;
+            if row_len == 0 {
+                continue;
+            }
+            let x = entry.region.x() as usize;
+            let y = entry.region.y() as usize;
+            for (row, line) in pixels.chunks(row_len).take(*height as usize).enumerate() {
+                let start = ((y + row) * size + x) * 4;
+                data[start..start + line.len()].copy_from_slice(line);
+            }
+        }
+        if self.textures.len() <= page {
+            self.textures.resize_with(page + 1, || None);
+        }
+        let size = size as u32;
+        let reuse = self.textures[page].as_ref().map_or(false, |texture| texture.size == size);
+        if !reuse {
+            self.textures[page] = PageTexture::activate(page, size);
+        }
+        if let Some(texture) = &mut self.textures[page] {
+            let result = texture.texture.upload(texture.idx, data.as_slice(), size, size, PixelFormat::RGBA);
+            if result.is_err() {
+                log::error!("Failed to upload atlas page {}: {:?}", page, result);
+            }
+        }
+    }
+}
+
+impl PageTexture {
+    /// Create and activate the texture for a page. This allocates a new texture index.
+    fn activate(page: usize, size: u32) -> Option<Self> {
+        let mut texture = DrawImage::new_texture(&format!("atlas-page-{}", page));
+        match texture.activate() {
+            Ok(idx) => Some(PageTexture { texture, idx, size }),
+            Err(err) => {
+                log::error!("Failed to activate atlas page {}: {:?}", page, err);
+                None
+            }
+        }
+    }
+}
+
+// ************************************************************************************
+// Tests
+// ************************************************************************************
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overlaps(a: &Rectangle, b: &Rectangle) -> bool {
+        a.x() < b.x() + b.width()
+            && b.x() < a.x() + a.width()
+            && a.y() < b.y() + b.height()
+            && b.y() < a.y() + a.height()
+    }
+
+    #[test]
+    fn test_packer_padding() {
+        let mut packer = ShelfPacker::new(64, 2);
+        assert_eq!(packer.pack(10, 10), Some((2, 2)));
+        assert_eq!(packer.pack(10, 10), Some((16, 2)));
+        // Shorter images reuse the shelf, taller ones start a new shelf
+        assert_eq!(packer.pack(10, 4), Some((30, 2)));
+        assert_eq!(packer.pack(10, 20), Some((2, 16)));
+        assert_eq!(packer.pack(61, 1), None);
+    }
+
+    #[test]
+    fn test_atlas_regions_do_not_overlap() {
+        let mut atlas = TextureAtlas::new(128).with_padding(1);
+        let mut entries = Vec::new();
+        for i in 0..37 {
+            entries.push(atlas.insert(&format!("icon-{}", i), 16 + (i % 3) * 4, 16).unwrap());
+        }
+        for (i, a) in entries.iter().enumerate() {
+            assert!(a.region.x() >= 1.0 && a.region.y() >= 1.0);
+            assert!(a.region.x() + a.region.width() <= atlas.pages()[a.page].size() as f32 - 1.0);
+            for b in entries.iter().skip(i + 1) {
+                assert!(a.page != b.page || !overlaps(&a.region, &b.region));
+            }
+        }
+    }
+
+    #[test]
+    fn test_atlas_grows_page() {
+        let mut atlas = TextureAtlas::new(32).with_padding(0).with_max_page_size(64);
+        atlas.insert("a", 32, 32).unwrap();
+        assert_eq!(atlas.generation(), 0);
+        let entry = atlas.insert("b", 32, 32).unwrap();
+        assert_eq!(atlas.pages().len(), 1);
+        assert_eq!(atlas.pages()[0].size(), 64);
+        assert_eq!(entry.page, 0);
+        assert_eq!(atlas.generation(), 1);
+        assert!(atlas.contains("a"));
+    }
+
+    #[test]
+    fn test_atlas_evicts_least_recently_used() {
+        let mut atlas = TextureAtlas::new(32).with_padding(0).with_max_page_size(32).with_max_pages(2);
+        atlas.insert("a", 32, 32).unwrap();
+        atlas.insert("b", 32, 32).unwrap();
+        assert_eq!(atlas.pages().len(), 2);
+        // Using "a" makes the page of "b" the least recently used
+        atlas.get("a");
+        let entry = atlas.insert("c", 32, 32).unwrap();
+        assert_eq!(entry.page, 1);
+        assert!(atlas.contains("a"));
+        assert!(!atlas.contains("b"));
+        assert_eq!(atlas.generation(), 1);
+    }
+
+    #[test]
+    fn test_atlas_rejects_large_images() {
+        let mut atlas = TextureAtlas::new(32).with_padding(1).with_max_page_size(64);
+        assert_eq!(atlas.insert("big", 63, 10), Err(AtlasError::TooLarge("big".to_string())));
+        assert!(atlas.insert("fits", 62, 10).is_ok());
+    }
+
+    #[test]
+    fn test_atlas_replace_and_dirty_pages() {
+        let mut atlas = TextureAtlas::new(64);
+        let first = atlas.insert("a", 10, 10).unwrap();
+        assert_eq!(atlas.take_dirty_pages(), vec![0]);
+        assert!(atlas.take_dirty_pages().is_empty());
+        // Same size keeps the region and only redraws the page
+        assert_eq!(atlas.insert("a", 10, 10).unwrap(), first);
+        assert_eq!(atlas.take_dirty_pages(), vec![0]);
+        // A new size moves the image
+        let second = atlas.insert("a", 12, 12).unwrap();
+        assert_ne!(second.region, first.region);
+        assert_eq!(atlas.pages()[0].names().len(), 1);
+    }
+}
@@ -0,0 +1,166 @@
+/// Support for IME composition and dead-key input.
+/// Quicksilver only delivers fully typed chars, so platform IME events have to be fed in by the
+/// backend through a CompositionSource or by calling AppDelegate::handle_text_input() directly.
+///
+use crate::events::TextInputEvent;
+
+use std::collections::VecDeque;
+
+//-- Main -----------------------------------------------------------------------
+
+/// Trait for a backend object that produces TextInputEvents. AppDelegate polls the source on every
+/// update() and passes the events to the active text control.
+pub trait CompositionSource {
+    /// Get the next pending event, if any
+    fn poll_event(&mut self) -> Option<TextInputEvent>;
+}
+
+/// A scripted CompositionSource that replays a queue of events. Use it to simulate IME input in
+/// examples and tests where no platform IME is available.
+#[derive(Debug, Default)]
+pub struct SyntheticComposition {
+    queue: VecDeque<TextInputEvent>,
+}
+
+impl SyntheticComposition {
+    /// Constructor
+    pub fn new() -> Self {
+        SyntheticComposition { queue: VecDeque::new() }
+    }
+
+    /// Builder method to queue the start of a composition
+    pub fn start(mut self) -> Self {
+        self.queue.push_back(TextInputEvent::CompositionStart);
+        self
+    }
+
+    /// Builder method to queue a preedit update where cursor is the char index within the text
+    pub fn update(mut self, text: &str, cursor: usize) -> Self {
+        self.queue.push_back(TextInputEvent::CompositionUpdate { text: text.to_owned(), cursor });
+        self
+    }
+
+    /// Builder method to queue the committed text
+    pub fn commit(mut self, text: &str) -> Self {
+        self.queue.push_back(TextInputEvent::CompositionCommit(text.to_owned()));
+        self
+    }
+
+    /// Builder method to queue a cancel
+    pub fn cancel(mut self) -> Self {
+        self.queue.push_back(TextInputEvent::CompositionCancel);
+        self
+    }
+
+    /// Builder method to queue a full composition where each preedit string is shown in turn with the
+    /// cursor at the end, followed by the committed text.
+    pub fn compose(self, steps: &[&str], result: &str) -> Self {
+        let mut source = self.start();
+        for step in steps {
+            source = source.update(step, step.chars().count());
+        }
+        source.commit(result)
+    }
+
+    /// Builder method to queue a dead-key sequence. The mark is shown as preedit text until the base
+    /// char is typed. If the pair cannot be combined, both chars are committed.
+    pub fn dead_key(self, mark: char, base: char) -> Self {
+        let result = match DeadKeys::combine(mark, base) {
+            Some(c) => c.to_string(),
+            None => format!("{}{}", mark, base),
+        };
+        self.start().update(&mark.to_string(), 1).commit(&result)
+    }
+
+    /// The number of queued events
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// True if all events have been consumed
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+impl CompositionSource for SyntheticComposition {
+    fn poll_event(&mut self) -> Option<TextInputEvent> {
+        self.queue.pop_front()
+    }
+}
+
+// ************************************************************************************
+// Dead keys
+// ************************************************************************************
+
+/// Lookup of common Latin dead-key combinations for backends that report the mark and base char
+/// separately.
+pub struct DeadKeys {}
+
+impl DeadKeys {
+    /// Returns true if the char is a mark that should start a dead-key composition
+    pub fn is_mark(c: char) -> bool {
+        match c {
+            '`' | '´' | '^' | '~' | '¨' | '¸' => true,
+            _ => false,
+        }
+    }
+
+    /// Combine a mark and a base char into a precomposed char
+    pub fn combine(mark: char, base: char) -> Option<char> {
+        let (from, to) = match mark {
+            '`' => ("aeiouAEIOU", "àèìòùÀÈÌÒÙ"),
+            '´' => ("aeiouyAEIOUY", "áéíóúýÁÉÍÓÚÝ"),
+            '^' => ("aeiouAEIOU", "âêîôûÂÊÎÔÛ"),
+            '~' => ("anoANO", "ãñõÃÑÕ"),
+            '¨' => ("aeiouyAEIOU", "äëïöüÿÄËÏÖÜ"),
+            '¸' => ("cC", "çÇ"),
+            _ => return None,
+        };
+        let index = from.chars().position(|c| c == base)?;
+        to.chars().nth(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dead_keys() {
+        assert_eq!(DeadKeys::combine('´', 'e'), Some('é'));
+        assert_eq!(DeadKeys::combine('¨', 'U'), Some('Ü'));
+        assert_eq!(DeadKeys::combine('~', 'n'), Some('ñ'));
+        assert_eq!(DeadKeys::combine('¸', 'c'), Some('ç'));
+        assert_eq!(DeadKeys::combine('^', 'x'), None);
+        assert_eq!(DeadKeys::combine('a', 'e'), None);
+        assert!(DeadKeys::is_mark('`'));
+        assert!(!DeadKeys::is_mark('é'));
+    }
+
+    #[test]
+    fn test_synthetic_dead_key() {
+        let mut source = SyntheticComposition::new().dead_key('`', 'a').dead_key('~', 'x');
+        let events: Vec<TextInputEvent> = std::iter::from_fn(|| source.poll_event()).collect();
+        assert_eq!(
+            events,
+            vec![
+                TextInputEvent::CompositionStart,
+                TextInputEvent::CompositionUpdate { text: "`".to_string(), cursor: 1 },
+                TextInputEvent::CompositionCommit("à".to_string()),
+                TextInputEvent::CompositionStart,
+                TextInputEvent::CompositionUpdate { text: "~".to_string(), cursor: 1 },
+                TextInputEvent::CompositionCommit("~x".to_string()),
+            ]
+        );
+        assert!(source.is_empty());
+    }
+
+    #[test]
+    fn test_synthetic_compose() {
+        let mut source = SyntheticComposition::new().compose(&["ㅎ", "하", "한"], "한");
+        assert_eq!(source.len(), 5);
+        source.poll_event();
+        assert_eq!(source.poll_event(), Some(TextInputEvent::CompositionUpdate { text: "ㅎ".to_string(), cursor: 1 }));
+    }
+}
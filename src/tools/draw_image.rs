@@ -17,15 +17,84 @@ use quicksilver::{
 
 use std::path::Path;
 
+/// A texture that has been uploaded to the GPU along with its pixel size. If the texture is an atlas page, the
+/// region is the part of the page that holds the image.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct GPUTexture {
     pub idx: usize,
     pub width: u32,
     pub height: u32,
+    pub region: Option<Rectangle>,
 }
 
 impl GPUTexture {
     pub fn new(idx: usize, width: u32, height: u32) -> Self {
-        GPUTexture { idx, width, height }
+        GPUTexture { idx, width, height, region: None }
+    }
+
+    /// Builder method to set the region of the image in pixels
+    pub fn with_region(mut self, region: Rectangle) -> Self {
+        self.region = Some(region);
+        self
+    }
+
+    /// The pixel size of the image as a Vector
+    pub fn size(&self) -> Vector {
+        match self.region {
+            Some(region) => region.size,
+            None => self.page_size(),
+        }
+    }
+
+    /// The pixel size of the whole texture as a Vector
+    pub fn page_size(&self) -> Vector {
+        Vector::new(self.width as f32, self.height as f32)
+    }
+
+    /// Calculate the texture coordinates for a region of the image
+    pub fn tex_quad(&self, region: Rectangle) -> [Vector; 4] {
+        let offset = self.region.map_or(Vector::ZERO, |image| image.pos);
+        DrawImage::normalize_tex_quad(self.page_size(), Rectangle::new(region.pos + offset, region.size))
+    }
+}
+
+//-- Nine-slice -----------------------------------------------------------------------
+
+/// How the edges and center of a nine-slice image fill the space between the corners
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SliceFill {
+    /// Stretch each region to fill its space
+    Stretch,
+    /// Repeat each region at its original size. The last tile is cropped.
+    Tile,
+}
+
+/// Insets in image pixels that divide an image into 9 patches. The corners are never scaled,
+/// the edges are scaled in one direction and the center in both.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NineSlice {
+    pub top: f32,
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub fill: SliceFill,
+}
+
+impl NineSlice {
+    /// Constructor with insets in the order top, left, bottom, right
+    pub fn new(top: f32, left: f32, bottom: f32, right: f32) -> Self {
+        NineSlice { top, left, bottom, right, fill: SliceFill::Stretch }
+    }
+
+    /// Constructor with the same inset on all sides
+    pub fn uniform(inset: f32) -> Self {
+        NineSlice::new(inset, inset, inset, inset)
+    }
+
+    /// Builder method to set the fill mode for edges and center
+    pub fn with_fill(mut self, fill: SliceFill) -> Self {
+        self.fill = fill;
+        self
     }
 }
 
@@ -58,15 +127,20 @@ impl DrawImage {
         }
     }
 
-    /// Upload bytes to GPU, which returns a Texture index number from the backend
-    /// If bytes array is empty, the Texture is created anyway with the expectation of writing to it later.
-    pub fn upload_image(name: &str, data: &[u8], width: u32, height: u32) -> Option<usize> {
-        let mut texture = Texture::new(name).with_shaders(VERTEX_SHADER, FRAGMENT_SHADER).with_fields(
+    /// Create a Texture with the image shaders. It must be activated before uploading to it.
+    pub fn new_texture(name: &str) -> Texture {
+        Texture::new(name).with_shaders(VERTEX_SHADER, FRAGMENT_SHADER).with_fields(
             TEX_FIELDS,
             serialize_vertex,
             "outColor",
             "font_tex",
-        );
+        )
+    }
+
+    /// Upload bytes to GPU, which returns a Texture index number from the backend
+    /// If bytes array is empty, the Texture is created anyway with the expectation of writing to it later.
+    pub fn upload_image(name: &str, data: &[u8], width: u32, height: u32) -> Option<usize> {
+        let mut texture = DrawImage::new_texture(name);
         let result = texture.activate();
         if result.is_err() {
             log::error!("activate: {:?}", result);
@@ -83,6 +157,20 @@ impl DrawImage {
         None
     }
 
+    /// Decode PNG or JPEG bytes and upload the pixels to the GPU
+    pub fn upload_image_bytes(name: &str, bytes: &[u8]) -> Option<GPUTexture> {
+        let image = match image_rs::load_from_memory(bytes) {
+            Ok(image) => image.to_rgba(),
+            Err(err) => {
+                log::error!("Failed to decode image {:?}: {:?}", name, err);
+                return None;
+            }
+        };
+        let (width, height) = image.dimensions();
+        let idx = DrawImage::upload_image(name, image.into_raw().as_slice(), width, height)?;
+        Some(GPUTexture::new(idx, width, height))
+    }
+
     /// Calculate the 0..1 points that make up the four corners of a texture mapping
     pub fn normalize_tex_quad(texture_size: Vector, region: Rectangle) -> [Vector; 4] {
         let scale = texture_size.recip();
@@ -165,6 +253,101 @@ impl DrawImage {
 
         Some(task)
     }
+
+    /// Draw a texture into the frame using nine-slice scaling. Corners keep their pixel size unless the frame is
+    /// too small to fit them, in which case they shrink proportionally.
+    pub fn nine_slice(texture: &GPUTexture, frame: &Rectangle, slice: &NineSlice, color: Color) -> MeshTask {
+        let mut task = MeshTask::new(texture.idx);
+        let tex_size = texture.size();
+
+        // Insets in the texture, clamped to the texture size
+        let src_x = DrawImage::slice_axis(slice.left, slice.right, tex_size.x);
+        let src_y = DrawImage::slice_axis(slice.top, slice.bottom, tex_size.y);
+        // Insets in the frame, scaled down if the corners don't fit
+        let dst_x = DrawImage::fit_axis(src_x, frame.width());
+        let dst_y = DrawImage::fit_axis(src_y, frame.height());
+
+        for row in 0..3 {
+            for col in 0..3 {
+                let src = Rectangle::new(
+                    (src_x[col], src_y[row]),
+                    (src_x[col + 1] - src_x[col], src_y[row + 1] - src_y[row]),
+                );
+                let dst = Rectangle::new(
+                    (frame.x() + dst_x[col], frame.y() + dst_y[row]),
+                    (dst_x[col + 1] - dst_x[col], dst_y[row + 1] - dst_y[row]),
+                );
+                if src.width() <= 0.0 || src.height() <= 0.0 || dst.width() <= 0.0 || dst.height() <= 0.0 {
+                    continue;
+                }
+                // Corners are always stretched, which is a no-op unless they were scaled down to fit
+                let tile_x = slice.fill == SliceFill::Tile && col == 1;
+                let tile_y = slice.fill == SliceFill::Tile && row == 1;
+                DrawImage::fill_patch(&mut task, texture, src, dst, tile_x, tile_y, color);
+            }
+        }
+        task
+    }
+
+    /// The 4 boundaries of the 3 slices along one axis of the texture
+    fn slice_axis(start: f32, end: f32, length: f32) -> [f32; 4] {
+        let start = start.max(0.0).min(length);
+        let end = end.max(0.0).min(length - start);
+        [0.0, start, length - end, length]
+    }
+
+    /// The 4 boundaries of the 3 slices along one axis of the frame
+    fn fit_axis(src: [f32; 4], length: f32) -> [f32; 4] {
+        let start = src[1];
+        let end = src[3] - src[2];
+        let scale = if start + end > length && start + end > 0.0 { length / (start + end) } else { 1.0 };
+        [0.0, start * scale, length - end * scale, length]
+    }
+
+    /// Fill the dst rect with the src region of the texture, either stretched or tiled on each axis
+    fn fill_patch(
+        task: &mut MeshTask,
+        texture: &GPUTexture,
+        src: Rectangle,
+        dst: Rectangle,
+        tile_x: bool,
+        tile_y: bool,
+        color: Color,
+    ) {
+        let step_x = if tile_x { src.width() } else { dst.width() };
+        let step_y = if tile_y { src.height() } else { dst.height() };
+        let mut y = 0.0;
+        while y < dst.height() {
+            let h = step_y.min(dst.height() - y);
+            let mut x = 0.0;
+            while x < dst.width() {
+                let w = step_x.min(dst.width() - x);
+                // Crop the source region for a partial tile
+                let src_w = if tile_x { w } else { src.width() };
+                let src_h = if tile_y { h } else { src.height() };
+                let region = Rectangle::new(src.pos, (src_w, src_h));
+                let quad = texture.tex_quad(region);
+                let rect = Rectangle::new((dst.x() + x, dst.y() + y), (w, h));
+                if let Some(part) = DrawImage::sub_texture(texture.idx, rect, Some(quad), color) {
+                    DrawImage::append_task(task, part);
+                }
+                x += step_x;
+            }
+            y += step_y;
+        }
+    }
+
+    /// Merge the vertices and triangles of another task with the same texture
+    fn append_task(task: &mut MeshTask, part: MeshTask) {
+        let base = task.vertices.len() as u32;
+        task.vertices.extend(part.vertices);
+        for mut triangle in part.triangles {
+            for index in triangle.indices.iter_mut() {
+                *index += base;
+            }
+            task.triangles.push(triangle);
+        }
+    }
 }
 
 // ************************************************************************************
@@ -1,21 +1,775 @@
-/// A tool for drawing various kinds of SVG
+/// A tool for drawing vector paths
 ///
-
 use quicksilver::{
-    geom::{Rectangle, Shape, Vector},
-    graphics::{Color, Mesh, ShapeRenderer},
+    geom::{Rectangle, Vector},
+    graphics::{Background::Col, Color, GpuTriangle, Mesh, MeshTask, ShapeRenderer, Vertex},
     lyon::{
         geom::math::*,
-        tessellation::{
-            basic_shapes::*,
-            {FillOptions, StrokeOptions},
-        },
+        tessellation::{basic_shapes::*, LineCap as LyonCap, LineJoin as LyonJoin, StrokeOptions},
     },
 };
 
-pub struct DrawPath {}
+/// The default maximum distance between a curve and the line segments that approximate it
+const PATH_TOLERANCE: f32 = 0.1;
+/// The maximum number of line segments for one curve
+const MAX_SEGMENTS: usize = 256;
+/// Distances smaller than this are treated as zero
+const PATH_EPSILON: f32 = 0.0001;
+
+//-- Path parts -----------------------------------------------------------------------
+
+/// Defines which areas of a path with overlapping or nested subpaths are filled
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FillRule {
+    /// Points where the subpaths wind around a non-zero number of times are filled. Nested subpaths in
+    /// opposite directions make holes.
+    NonZero,
+    /// Points inside an odd number of subpaths are filled. Every nested subpath makes a hole.
+    EvenOdd,
+}
+
+/// The shape of the corners where stroked segments meet
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineJoin {
+    /// Sharp corners, which are beveled when they are longer than the miter limit
+    Miter,
+    /// Rounded corners
+    Round,
+    /// Corners cut off flat
+    Bevel,
+}
+
+/// The shape of the ends of stroked lines and dashes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LineCap {
+    /// The line ends at the end point
+    Butt,
+    /// The line ends with a square that extends past the end point by half the line width
+    Square,
+    /// The line ends with a half circle
+    Round,
+}
+
+/// A drawing command of a DrawPath
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathCommand {
+    /// Start a new subpath at the point
+    MoveTo(Vector),
+    /// A straight line to the point
+    LineTo(Vector),
+    /// A quadratic bezier curve with the control point and the end point
+    QuadTo(Vector, Vector),
+    /// A cubic bezier curve with two control points and the end point
+    CubicTo(Vector, Vector, Vector),
+    /// An elliptical arc to the point, like the SVG arc command. Params: the radii, the rotation of the x axis in
+    /// degrees, the large arc flag, the sweep flag (clockwise on screen) and the end point
+    ArcTo(Vector, f32, bool, bool, Vector),
+    /// A straight line back to the start of the subpath, which is closed
+    Close,
+}
+
+/// A subpath of a DrawPath after curves have been converted to line segments
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polyline {
+    /// The points in order
+    pub points: Vec<Vector>,
+    /// Does the last point connect to the first point?
+    pub closed: bool,
+}
+
+//-- DrawPath -----------------------------------------------------------------------
+
+/// A vector path built from lines, bezier curves and arcs, which can be filled and stroked into a Mesh for the
+/// shapes that DrawShape does not provide. Curves are converted to line segments with the tolerance, and fills
+/// follow the fill rule for overlapping subpaths. Strokes use the joins, caps and optional dash pattern.
+///
+/// ```ignore
+/// let path = DrawPath::new().move_to((0.0, 20.0)).quad_to((20.0, 0.0), (40.0, 20.0)).line_to((20.0, 40.0)).close();
+/// let mesh = path.draw(Some(Color::RED), Some(Color::BLACK), 2.0);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct DrawPath {
+    /// The drawing commands in order
+    pub commands: Vec<PathCommand>,
+    /// Defines which areas are filled
+    pub fill_rule: FillRule,
+    /// The shape of stroked corners
+    pub line_join: LineJoin,
+    /// The shape of stroked line ends
+    pub line_cap: LineCap,
+    /// The limit of the ratio between the miter length and the line width for LineJoin::Miter
+    pub miter_limit: f32,
+    /// Alternating lengths of dashes and gaps. Lines are solid if it is empty.
+    pub dashes: Vec<f32>,
+    /// The distance into the dash pattern where the stroke starts
+    pub dash_offset: f32,
+    /// The maximum distance between a curve and its line segments
+    pub tolerance: f32,
+}
+
+impl Default for DrawPath {
+    fn default() -> Self {
+        DrawPath::new()
+    }
+}
 
 impl DrawPath {
+    /// Constructor for an empty path
+    pub fn new() -> Self {
+        DrawPath {
+            commands: Vec::new(),
+            fill_rule: FillRule::NonZero,
+            line_join: LineJoin::Miter,
+            line_cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+            tolerance: PATH_TOLERANCE,
+        }
+    }
+
+    /// Builder method to set the fill rule
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    /// Builder method to set the join and cap of strokes
+    pub fn with_line_style(mut self, join: LineJoin, cap: LineCap) -> Self {
+        self.line_join = join;
+        self.line_cap = cap;
+        self
+    }
+
+    /// Builder method to set the miter limit
+    pub fn with_miter_limit(mut self, limit: f32) -> Self {
+        self.miter_limit = limit.max(1.0);
+        self
+    }
+
+    /// Builder method to set the dash pattern of alternating dash and gap lengths, and the offset into the pattern.
+    /// Patterns with an odd number of lengths are repeated to make an even number, like in SVG.
+    pub fn with_dashes(mut self, dashes: &[f32], offset: f32) -> Self {
+        self.dashes = dashes.iter().map(|length| length.max(0.0)).collect();
+        if self.dashes.len() % 2 == 1 {
+            let copy = self.dashes.clone();
+            self.dashes.extend(copy);
+        }
+        self.dash_offset = offset;
+        self
+    }
+
+    /// Builder method to set the tolerance for converting curves to line segments
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance.max(0.01);
+        self
+    }
+
+    /// Start a new subpath at the point
+    pub fn move_to(mut self, to: impl Into<Vector>) -> Self {
+        self.commands.push(PathCommand::MoveTo(to.into()));
+        self
+    }
+
+    /// Add a straight line to the point
+    pub fn line_to(mut self, to: impl Into<Vector>) -> Self {
+        self.commands.push(PathCommand::LineTo(to.into()));
+        self
+    }
+
+    /// Add a quadratic bezier curve
+    pub fn quad_to(mut self, ctrl: impl Into<Vector>, to: impl Into<Vector>) -> Self {
+        self.commands.push(PathCommand::QuadTo(ctrl.into(), to.into()));
+        self
+    }
+
+    /// Add a cubic bezier curve
+    pub fn cubic_to(mut self, ctrl1: impl Into<Vector>, ctrl2: impl Into<Vector>, to: impl Into<Vector>) -> Self {
+        self.commands.push(PathCommand::CubicTo(ctrl1.into(), ctrl2.into(), to.into()));
+        self
+    }
+
+    /// Add an elliptical arc to the point with the same parameters as the SVG arc command. The sweep flag draws
+    /// the arc clockwise on screen.
+    pub fn arc_to(
+        mut self,
+        radii: impl Into<Vector>,
+        rotate_degrees: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: impl Into<Vector>,
+    ) -> Self {
+        self.commands.push(PathCommand::ArcTo(radii.into(), rotate_degrees, large_arc, sweep, to.into()));
+        self
+    }
+
+    /// Close the subpath with a line back to its start
+    pub fn close(mut self) -> Self {
+        self.commands.push(PathCommand::Close);
+        self
+    }
+
+    /// Add a closed rectangle with the corner radii in the order: top left, top right, bottom right, bottom left.
+    /// Radii are reduced to fit the rectangle like in DrawShape::rounded_rectangle.
+    pub fn rounded_rect(self, rect: &Rectangle, radii: [f32; 4]) -> Self {
+        let (x1, y1) = (rect.x(), rect.y());
+        let (x2, y2) = (rect.x() + rect.width(), rect.y() + rect.height());
+        let r = super::draw_shape::fit_radii(rect, radii);
+        let mut path = self.move_to((x1 + r[0], y1)).line_to((x2 - r[1], y1));
+        if r[1] > 0.0 {
+            path = path.arc_to((r[1], r[1]), 0.0, false, true, (x2, y1 + r[1]));
+        }
+        path = path.line_to((x2, y2 - r[2]));
+        if r[2] > 0.0 {
+            path = path.arc_to((r[2], r[2]), 0.0, false, true, (x2 - r[2], y2));
+        }
+        path = path.line_to((x1 + r[3], y2));
+        if r[3] > 0.0 {
+            path = path.arc_to((r[3], r[3]), 0.0, false, true, (x1, y2 - r[3]));
+        }
+        path = path.line_to((x1, y1 + r[0]));
+        if r[0] > 0.0 {
+            path = path.arc_to((r[0], r[0]), 0.0, false, true, (x1 + r[0], y1));
+        }
+        path.close()
+    }
+
+    /// Is the path empty?
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// A copy of the path where every point is moved by the offset
+    pub fn translated(&self, offset: Vector) -> DrawPath {
+        self.transformed(|pt| pt + offset)
+    }
+
+    /// A copy of the path where every point is scaled from the origin 0,0. Arcs with a rotation are only exact
+    /// when the scale is the same on both axes.
+    pub fn scaled(&self, scale: Vector) -> DrawPath {
+        let mut path = self.transformed(|pt| Vector::new(pt.x * scale.x, pt.y * scale.y));
+        let mirrored = scale.x * scale.y < 0.0;
+        for command in path.commands.iter_mut() {
+            if let PathCommand::ArcTo(radii, _, _, sweep, _) = command {
+                *radii = Vector::new(radii.x * scale.x.abs(), radii.y * scale.y.abs());
+                // Mirroring reverses the direction of the arc
+                *sweep ^= mirrored;
+            }
+        }
+        path
+    }
+
+    /// A copy of the path with the function applied to every point
+    fn transformed(&self, f: impl Fn(Vector) -> Vector) -> DrawPath {
+        let mut path = self.clone();
+        for command in path.commands.iter_mut() {
+            *command = match *command {
+                PathCommand::MoveTo(to) => PathCommand::MoveTo(f(to)),
+                PathCommand::LineTo(to) => PathCommand::LineTo(f(to)),
+                PathCommand::QuadTo(ctrl, to) => PathCommand::QuadTo(f(ctrl), f(to)),
+                PathCommand::CubicTo(ctrl1, ctrl2, to) => PathCommand::CubicTo(f(ctrl1), f(ctrl2), f(to)),
+                PathCommand::ArcTo(radii, rotate, large_arc, sweep, to) => {
+                    PathCommand::ArcTo(radii, rotate, large_arc, sweep, f(to))
+                }
+                PathCommand::Close => PathCommand::Close,
+            };
+        }
+        path
+    }
+
+    /// The smallest rectangle that contains every point of the flattened path
+    pub fn bounds(&self) -> Rectangle {
+        let polylines = self.flatten();
+        let mut points = polylines.iter().flat_map(|polyline| polyline.points.iter());
+        let first = match points.next() {
+            Some(pt) => *pt,
+            None => return Rectangle::new_sized((0.0, 0.0)),
+        };
+        let (min, max) = points.fold((first, first), |(min, max), pt| {
+            (Vector::new(min.x.min(pt.x), min.y.min(pt.y)), Vector::new(max.x.max(pt.x), max.y.max(pt.y)))
+        });
+        Rectangle::new(min, max - min)
+    }
+
+    /// Convert the path to polylines by replacing curves with line segments within the tolerance
+    pub fn flatten(&self) -> Vec<Polyline> {
+        let mut polylines: Vec<Polyline> = Vec::new();
+        let mut points: Vec<Vector> = Vec::new();
+        let mut start = Vector::ZERO;
+        let mut current = Vector::ZERO;
+
+        let finish = |points: &mut Vec<Vector>, closed: bool, polylines: &mut Vec<Polyline>| {
+            if points.len() > 1 {
+                polylines.push(Polyline { points: points.clone(), closed });
+            }
+            points.clear();
+        };
+
+        for command in &self.commands {
+            match *command {
+                PathCommand::MoveTo(to) => {
+                    finish(&mut points, false, &mut polylines);
+                    start = to;
+                    current = to;
+                    points.push(to);
+                    continue;
+                }
+                PathCommand::Close => {
+                    finish(&mut points, true, &mut polylines);
+                    current = start;
+                    continue;
+                }
+                _ => (),
+            }
+            // A subpath without move_to starts at the current point
+            if points.is_empty() {
+                start = current;
+                points.push(current);
+            }
+            match *command {
+                PathCommand::LineTo(to) => {
+                    points.push(to);
+                    current = to;
+                }
+                PathCommand::QuadTo(ctrl, to) => {
+                    let dd = (current - ctrl * 2.0 + to).len();
+                    let segments = self.segment_count((dd / (8.0 * self.tolerance)).sqrt());
+                    for i in 1..=segments {
+                        let t = i as f32 / segments as f32;
+                        let mt = 1.0 - t;
+                        points.push(current * (mt * mt) + ctrl * (2.0 * mt * t) + to * (t * t));
+                    }
+                    current = to;
+                }
+                PathCommand::CubicTo(ctrl1, ctrl2, to) => {
+                    let dd = (current - ctrl1 * 2.0 + ctrl2).len().max((ctrl1 - ctrl2 * 2.0 + to).len());
+                    let segments = self.segment_count((3.0 * dd / (4.0 * self.tolerance)).sqrt());
+                    for i in 1..=segments {
+                        let t = i as f32 / segments as f32;
+                        let mt = 1.0 - t;
+                        points.push(
+                            current * (mt * mt * mt)
+                                + ctrl1 * (3.0 * mt * mt * t)
+                                + ctrl2 * (3.0 * mt * t * t)
+                                + to * (t * t * t),
+                        );
+                    }
+                    current = to;
+                }
+                PathCommand::ArcTo(radii, rotate, large_arc, sweep, to) => {
+                    self.flatten_arc(current, radii, rotate, large_arc, sweep, to, &mut points);
+                    current = to;
+                }
+                _ => (),
+            }
+        }
+        finish(&mut points, false, &mut polylines);
+        polylines
+    }
+
+    /// The number of line segments for a curve, within limits
+    fn segment_count(&self, estimate: f32) -> usize {
+        if estimate.is_finite() {
+            (estimate.ceil() as usize).max(1).min(MAX_SEGMENTS)
+        } else {
+            1
+        }
+    }
+
+    /// Add the points of an elliptical arc, using the endpoint to center conversion from the SVG specification
+    #[allow(clippy::too_many_arguments)]
+    fn flatten_arc(
+        &self,
+        from: Vector,
+        radii: Vector,
+        rotate_degrees: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Vector,
+        points: &mut Vec<Vector>,
+    ) {
+        let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+        if rx < PATH_EPSILON || ry < PATH_EPSILON || (to - from).len() < PATH_EPSILON {
+            points.push(to);
+            return;
+        }
+        let (sin, cos) = rotate_degrees.to_radians().sin_cos();
+        let half = (from - to) * 0.5;
+        let x1 = cos * half.x + sin * half.y;
+        let y1 = -sin * half.x + cos * half.y;
+
+        // Scale up radii that are too small to reach the end point
+        let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+        if lambda > 1.0 {
+            rx *= lambda.sqrt();
+            ry *= lambda.sqrt();
+        }
+        let numerator = (rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1).max(0.0);
+        let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+        let mut coef = if denominator > 0.0 { (numerator / denominator).sqrt() } else { 0.0 };
+        if large_arc == sweep {
+            coef = -coef;
+        }
+        let cx1 = coef * rx * y1 / ry;
+        let cy1 = -coef * ry * x1 / rx;
+        let mid = (from + to) * 0.5;
+        let center = Vector::new(cos * cx1 - sin * cy1 + mid.x, sin * cx1 + cos * cy1 + mid.y);
+
+        let angle = |ux: f32, uy: f32, vx: f32, vy: f32| {
+            let dot = ux * vx + uy * vy;
+            let len = (ux * ux + uy * uy).sqrt() * (vx * vx + vy * vy).sqrt();
+            let value = (dot / len).max(-1.0).min(1.0).acos();
+            if ux * vy - uy * vx < 0.0 {
+                -value
+            } else {
+                value
+            }
+        };
+        let start_angle = angle(1.0, 0.0, (x1 - cx1) / rx, (y1 - cy1) / ry);
+        let mut sweep_angle = angle((x1 - cx1) / rx, (y1 - cy1) / ry, (-x1 - cx1) / rx, (-y1 - cy1) / ry);
+        let full = std::f32::consts::PI * 2.0;
+        if !sweep && sweep_angle > 0.0 {
+            sweep_angle -= full;
+        } else if sweep && sweep_angle < 0.0 {
+            sweep_angle += full;
+        }
+
+        let radius = rx.max(ry);
+        let step = 2.0 * (1.0 - (self.tolerance / radius).min(1.0)).acos();
+        let segments = self.segment_count(if step > 0.0 { sweep_angle.abs() / step } else { 1.0 });
+        for i in 1..segments {
+            let theta = start_angle + sweep_angle * i as f32 / segments as f32;
+            let (x, y) = (rx * theta.cos(), ry * theta.sin());
+            points.push(Vector::new(cos * x - sin * y + center.x, sin * x + cos * y + center.y));
+        }
+        points.push(to);
+    }
+
+    //-- Output -----------------------------------------------------------------------
+
+    /// Draw the path with optional fill and line colors, like the DrawShape methods
+    pub fn draw(&self, fill: Option<Color>, line: Option<Color>, line_width: f32) -> Mesh {
+        let mut mesh = Mesh::new();
+        if let Some(color) = fill {
+            let mut fill_mesh = self.fill(color);
+            DrawPath::append_mesh(&mut mesh, &mut fill_mesh);
+        }
+        if let Some(color) = line {
+            if line_width > 0.0 {
+                let mut stroke_mesh = self.stroke(color, line_width);
+                DrawPath::append_mesh(&mut mesh, &mut stroke_mesh);
+            }
+        }
+        mesh
+    }
+
+    /// Draw the path into a MeshTask
+    pub fn draw_task(&self, fill: Option<Color>, line: Option<Color>, line_width: f32) -> MeshTask {
+        let mut task = MeshTask::new(0);
+        let mut mesh = self.draw(fill, line, line_width);
+        task.append(&mut mesh);
+        task
+    }
+
+    /// Fill the inside of the path using the fill rule. Open subpaths are closed for filling.
+    pub fn fill(&self, color: Color) -> Mesh {
+        let mut mesh = Mesh::new();
+        for (top, bottom) in fill_trapezoids(&self.flatten(), self.fill_rule) {
+            let offset = mesh.vertices.len() as u32;
+            mesh.vertices.push(Vertex::new(Vector::new(top.0, top.2), None, Col(color)));
+            mesh.vertices.push(Vertex::new(Vector::new(top.1, top.2), None, Col(color)));
+            mesh.vertices.push(Vertex::new(Vector::new(bottom.1, bottom.2), None, Col(color)));
+            mesh.vertices.push(Vertex::new(Vector::new(bottom.0, bottom.2), None, Col(color)));
+            mesh.triangles.push(GpuTriangle::new(offset, [0, 1, 2], 0.0, Col(color)));
+            mesh.triangles.push(GpuTriangle::new(offset, [2, 3, 0], 0.0, Col(color)));
+        }
+        mesh
+    }
+
+    /// Stroke the outline of the path with the joins, caps and dash pattern
+    pub fn stroke(&self, color: Color, line_width: f32) -> Mesh {
+        let mut mesh = Mesh::new();
+        let join = match self.line_join {
+            LineJoin::Miter => LyonJoin::Miter,
+            LineJoin::Round => LyonJoin::Round,
+            LineJoin::Bevel => LyonJoin::Bevel,
+        };
+        let cap = match self.line_cap {
+            LineCap::Butt => LyonCap::Butt,
+            LineCap::Square => LyonCap::Square,
+            LineCap::Round => LyonCap::Round,
+        };
+        let options = StrokeOptions::tolerance(0.01)
+            .with_line_width(line_width)
+            .with_line_join(join)
+            .with_line_cap(cap)
+            .with_miter_limit(self.miter_limit);
+
+        let mut polylines = self.flatten();
+        if self.dashes.iter().any(|length| *length > 0.0) {
+            polylines = polylines.iter().flat_map(|polyline| self.dash_polyline(polyline)).collect();
+        }
+        let mut renderer = ShapeRenderer::new(&mut mesh, color);
+        for polyline in polylines {
+            let points = polyline.points.iter().map(|pt| point(pt.x, pt.y));
+            let _ = stroke_polyline(points, polyline.closed, &options, &mut renderer);
+        }
+        mesh
+    }
+
+    /// Split the polyline into open polylines for the dashes of the dash pattern
+    fn dash_polyline(&self, polyline: &Polyline) -> Vec<Polyline> {
+        let mut dashes: Vec<Polyline> = Vec::new();
+        if polyline.points.len() < 2 {
+            return dashes;
+        }
+        let pattern_length: f32 = self.dashes.iter().sum();
+
+        // Find the dash at the offset into the pattern
+        let mut index = 0;
+        let mut remaining = self.dashes[0];
+        let mut offset = self.dash_offset.rem_euclid(pattern_length);
+        while offset > 0.0 {
+            if offset < remaining {
+                remaining -= offset;
+                break;
+            }
+            offset -= remaining;
+            index = (index + 1) % self.dashes.len();
+            remaining = self.dashes[index];
+        }
+
+        let mut points = polyline.points.clone();
+        if polyline.closed {
+            points.push(polyline.points[0]);
+        }
+        let mut current: Vec<Vector> = if index % 2 == 0 { vec![points[0]] } else { Vec::new() };
+        for pair in points.windows(2) {
+            let (mut from, to) = (pair[0], pair[1]);
+            let mut length = (to - from).len();
+            while length > PATH_EPSILON {
+                let step = remaining.min(length);
+                let pt = from + (to - from) * (step / length);
+                if index % 2 == 0 {
+                    current.push(pt);
+                }
+                length -= step;
+                remaining -= step;
+                from = pt;
+                if remaining <= PATH_EPSILON {
+                    if index % 2 == 0 && current.len() > 1 {
+                        dashes.push(Polyline { points: current.clone(), closed: false });
+                    }
+                    current.clear();
+                    index = (index + 1) % self.dashes.len();
+                    remaining = self.dashes[index];
+                    if index % 2 == 0 {
+                        current.push(from);
+                    }
+                }
+            }
+        }
+        if current.len() > 1 {
+            dashes.push(Polyline { points: current, closed: false });
+        }
+        dashes
+    }
+
+    fn append_mesh(mesh: &mut Mesh, other: &mut Mesh) {
+        let offset = mesh.vertices.len() as u32;
+        mesh.vertices.append(&mut other.vertices);
+        for mut triangle in other.triangles.drain(..) {
+            triangle.indices =
+                [triangle.indices[0] + offset, triangle.indices[1] + offset, triangle.indices[2] + offset];
+            mesh.triangles.push(triangle);
+        }
+    }
+}
+
+//-- Fill -----------------------------------------------------------------------
+
+/// The left and right x values and the y value of the top or bottom edge of a trapezoid
+type TrapezoidEdge = (f32, f32, f32);
+
+/// Fill the closed polylines with the fill rule by dividing them into horizontal bands at every vertex and
+/// crossing, and returning the trapezoids between the edges in each band that are inside.
+fn fill_trapezoids(polylines: &[Polyline], fill_rule: FillRule) -> Vec<(TrapezoidEdge, TrapezoidEdge)> {
+    // Edges are stored top to bottom with the winding direction
+    let mut edges: Vec<(Vector, Vector, i32)> = Vec::new();
+    for polyline in polylines {
+        let count = polyline.points.len();
+        for i in 0..count {
+            let (a, b) = (polyline.points[i], polyline.points[(i + 1) % count]);
+            if (a.y - b.y).abs() < PATH_EPSILON {
+                continue;
+            }
+            if a.y < b.y {
+                edges.push((a, b, 1));
+            } else {
+                edges.push((b, a, -1));
+            }
+        }
+    }
+
+    let mut ys: Vec<f32> = edges.iter().flat_map(|edge| vec![edge.0.y, edge.1.y]).collect();
+    for (i, first) in edges.iter().enumerate() {
+        for second in &edges[i + 1..] {
+            if let Some(y) = edge_crossing(first, second) {
+                ys.push(y);
+            }
+        }
+    }
+    ys.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    ys.dedup_by(|a, b| (*a - *b).abs() < PATH_EPSILON);
+
+    let x_at = |edge: &(Vector, Vector, i32), y: f32| {
+        let t = (y - edge.0.y) / (edge.1.y - edge.0.y);
+        edge.0.x + (edge.1.x - edge.0.x) * t
+    };
+    let mut trapezoids = Vec::new();
+    for band in ys.windows(2) {
+        let (top, bottom) = (band[0], band[1]);
+        let mid = (top + bottom) / 2.0;
+        let mut active: Vec<&(Vector, Vector, i32)> =
+            edges.iter().filter(|edge| edge.0.y <= mid && edge.1.y > mid).collect();
+        active.sort_by(|a, b| x_at(a, mid).partial_cmp(&x_at(b, mid)).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut winding = 0;
+        let mut left: Option<&(Vector, Vector, i32)> = None;
+        for edge in active {
+            let was_inside = is_inside(winding, fill_rule);
+            winding += edge.2;
+            let inside = is_inside(winding, fill_rule);
+            if !was_inside && inside {
+                left = Some(edge);
+            } else if was_inside && !inside {
+                if let Some(left) = left.take() {
+                    trapezoids.push((
+                        (x_at(left, top), x_at(edge, top), top),
+                        (x_at(left, bottom), x_at(edge, bottom), bottom),
+                    ));
+                }
+            }
+        }
+    }
+    trapezoids
+}
+
+fn is_inside(winding: i32, fill_rule: FillRule) -> bool {
+    match fill_rule {
+        FillRule::NonZero => winding != 0,
+        FillRule::EvenOdd => winding % 2 != 0,
+    }
+}
+
+/// The y value where two edges cross, if they cross inside both of their y ranges
+fn edge_crossing(a: &(Vector, Vector, i32), b: &(Vector, Vector, i32)) -> Option<f32> {
+    let top = a.0.y.max(b.0.y);
+    let bottom = a.1.y.min(b.1.y);
+    if bottom - top < PATH_EPSILON {
+        return None;
+    }
+    let (da, db) = (a.1 - a.0, b.1 - b.0);
+    let denominator = da.x * db.y - da.y * db.x;
+    if denominator.abs() < PATH_EPSILON {
+        return None;
+    }
+    let diff = b.0 - a.0;
+    let t = (diff.x * db.y - diff.y * db.x) / denominator;
+    let y = a.0.y + da.y * t;
+    if y > top + PATH_EPSILON && y < bottom - PATH_EPSILON {
+        Some(y)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(points: &[(f32, f32)]) -> Polyline {
+        Polyline { points: points.iter().map(|pt| Vector::new(pt.0, pt.1)).collect(), closed: false }
+    }
+
+    /// The dashes of the polyline with the points rounded to 0.01
+    fn dashes(path: &DrawPath, polyline: &Polyline) -> Vec<Vec<(f32, f32)>> {
+        let round = |value: f32| (value * 100.0).round() / 100.0;
+        path.dash_polyline(polyline)
+            .iter()
+            .map(|dash| dash.points.iter().map(|pt| (round(pt.x), round(pt.y))).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_dash_pattern() {
+        let polyline = line(&[(0.0, 0.0), (30.0, 0.0)]);
+        let path = DrawPath::new().with_dashes(&[10.0, 5.0], 0.0);
+        assert_eq!(dashes(&path, &polyline), vec![vec![(0.0, 0.0), (10.0, 0.0)], vec![(15.0, 0.0), (25.0, 0.0)]]);
+
+        // Dashes continue around corners
+        let polyline = line(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        let path = DrawPath::new().with_dashes(&[15.0, 5.0], 0.0);
+        assert_eq!(dashes(&path, &polyline), vec![vec![(0.0, 0.0), (10.0, 0.0), (10.0, 5.0)]]);
+
+        // A closed polyline is dashed back to the first point
+        let mut polyline = line(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+        polyline.closed = true;
+        let path = DrawPath::new().with_dashes(&[5.0, 5.0], 0.0);
+        let result = dashes(&path, &polyline);
+        assert_eq!(result.len(), 4);
+        assert_eq!(result[3], vec![(0.0, 10.0), (0.0, 5.0)]);
+    }
+
+    #[test]
+    fn test_dash_offset() {
+        let polyline = line(&[(0.0, 0.0), (30.0, 0.0)]);
+        let path = DrawPath::new().with_dashes(&[10.0, 5.0], 5.0);
+        assert_eq!(
+            dashes(&path, &polyline),
+            vec![vec![(0.0, 0.0), (5.0, 0.0)], vec![(10.0, 0.0), (20.0, 0.0)], vec![(25.0, 0.0), (30.0, 0.0)]]
+        );
+
+        // An offset that lands in a gap starts with the rest of the gap
+        let path = DrawPath::new().with_dashes(&[10.0, 5.0], 12.0);
+        assert_eq!(dashes(&path, &polyline), vec![vec![(3.0, 0.0), (13.0, 0.0)], vec![(18.0, 0.0), (28.0, 0.0)]]);
+
+        // Negative offsets and offsets longer than the pattern wrap around
+        let path = DrawPath::new().with_dashes(&[10.0, 5.0], -5.0);
+        assert_eq!(dashes(&path, &polyline), vec![vec![(5.0, 0.0), (15.0, 0.0)], vec![(20.0, 0.0), (30.0, 0.0)]]);
+        let path = DrawPath::new().with_dashes(&[10.0, 5.0], 35.0);
+        assert_eq!(
+            dashes(&path, &polyline),
+            vec![vec![(0.0, 0.0), (5.0, 0.0)], vec![(10.0, 0.0), (20.0, 0.0)], vec![(25.0, 0.0), (30.0, 0.0)]]
+        );
+    }
+
+    #[test]
+    fn test_dash_odd_pattern() {
+        let path = DrawPath::new().with_dashes(&[10.0], 0.0);
+        assert_eq!(path.dashes, vec![10.0, 10.0]);
+        let polyline = line(&[(0.0, 0.0), (45.0, 0.0)]);
+        assert_eq!(
+            dashes(&path, &polyline),
+            vec![vec![(0.0, 0.0), (10.0, 0.0)], vec![(20.0, 0.0), (30.0, 0.0)], vec![(40.0, 0.0), (45.0, 0.0)]]
+        );
 
+        // The repeated pattern swaps dashes and gaps
+        let path = DrawPath::new().with_dashes(&[5.0, 10.0, 15.0], 0.0);
+        let polyline = line(&[(0.0, 0.0), (60.0, 0.0)]);
+        assert_eq!(
+            dashes(&path, &polyline),
+            vec![vec![(0.0, 0.0), (5.0, 0.0)], vec![(15.0, 0.0), (30.0, 0.0)], vec![(35.0, 0.0), (45.0, 0.0)]]
+        );
+    }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_dash_short_polyline() {
+        let path = DrawPath::new().with_dashes(&[10.0, 5.0], 3.0);
+        assert!(path.dash_polyline(&line(&[])).is_empty());
+        assert!(path.dash_polyline(&line(&[(5.0, 5.0)])).is_empty());
+        let mut polyline = line(&[(5.0, 5.0)]);
+        polyline.closed = true;
+        assert!(path.dash_polyline(&polyline).is_empty());
+    }
+}
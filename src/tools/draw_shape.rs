@@ -3,7 +3,7 @@
 ///
 use quicksilver::{
     geom::{Rectangle, Shape, Vector},
-    graphics::{Color, Mesh, ShapeRenderer},
+    graphics::{Background::Col, Color, GpuTriangle, Mesh, ShapeRenderer, Vertex},
     lyon::{
         geom::math::*,
         tessellation::{
@@ -13,6 +13,69 @@ use quicksilver::{
     },
 };
 
+use std::cmp::Ordering;
+use std::f32::consts::PI;
+
+/// The number of rings per gradient length in radial gradients
+const RADIAL_RINGS: f32 = 24.0;
+/// The number of sectors in radial gradients
+const RADIAL_SECTORS: usize = 48;
+
+//-- Support -----------------------------------------------------------------------
+
+/// A color at an offset along a gradient, where 0.0 is the start and 1.0 is the end
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GradientStop {
+    /// The position along the gradient
+    pub offset: f32,
+    /// The color at the offset
+    pub color: Color,
+}
+
+impl GradientStop {
+    /// Constructor
+    pub fn new(offset: f32, color: Color) -> Self {
+        GradientStop { offset, color }
+    }
+
+    /// Create stops for the colors at even intervals from 0.0 to 1.0
+    pub fn evenly_spaced(colors: &[Color]) -> Vec<GradientStop> {
+        let last = (colors.len().max(2) - 1) as f32;
+        colors.iter().enumerate().map(|(i, color)| GradientStop::new(i as f32 / last, *color)).collect()
+    }
+
+    /// Get the color at the offset by interpolating between the stops, which must be in order of offset. Offsets
+    /// outside the stops get the color of the first or last stop.
+    pub fn color_at(stops: &[GradientStop], offset: f32) -> Color {
+        let first = match stops.first() {
+            Some(stop) => stop,
+            None => return Color { a: 0.0, ..Color::WHITE },
+        };
+        if offset <= first.offset {
+            return first.color;
+        }
+        for pair in stops.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if offset <= b.offset {
+                let span = b.offset - a.offset;
+                if span <= 0.0 {
+                    return b.color;
+                }
+                let t = (offset - a.offset) / span;
+                return Color {
+                    r: a.color.r + (b.color.r - a.color.r) * t,
+                    g: a.color.g + (b.color.g - a.color.g) * t,
+                    b: a.color.b + (b.color.b - a.color.b) * t,
+                    a: a.color.a + (b.color.a - a.color.a) * t,
+                };
+            }
+        }
+        stops[stops.len() - 1].color
+    }
+}
+
+//-- Main -----------------------------------------------------------------------
+
 /// A utility struct for creating Quicksilver Mesh objects for a variety of shapes using Lyon
 pub struct DrawShape {}
 
@@ -46,6 +109,36 @@ impl DrawShape {
         mesh
     }
 
+    /// Draws the outline of an arc around the center point. Angles are in degrees, where 0.0 is at 12 o'clock and
+    /// positive sweep_degrees go clockwise.
+    pub fn arc(
+        center: &Vector,
+        radius: f32,
+        start_degrees: f32,
+        sweep_degrees: f32,
+        color: Color,
+        line_width: f32,
+    ) -> Mesh {
+        let mut mesh = Mesh::new();
+        if sweep_degrees.abs() <= std::f32::EPSILON || radius <= 0.0 {
+            return mesh;
+        }
+        // Use one segment for every 5 degrees, which is smooth enough at the sizes of controls
+        let segments = ((sweep_degrees.abs() / 5.0).ceil() as usize).max(1);
+        let points: Vec<Point> = (0..=segments)
+            .map(|i| {
+                let degrees = start_degrees + sweep_degrees * i as f32 / segments as f32;
+                let radians = (degrees - 90.0).to_radians();
+                point(center.x + radius * radians.cos(), center.y + radius * radians.sin())
+            })
+            .collect();
+
+        let mut renderer = ShapeRenderer::new(&mut mesh, color);
+        let options = StrokeOptions::tolerance(0.01).with_line_width(line_width);
+        stroke_polyline(points.into_iter(), false, &options, &mut renderer).unwrap();
+        mesh
+    }
+
     /// Draw an ellipse bounded by the specified Rectangle which defines the center point and 2 radii for the ellipse.
     /// Furthermore, specify the optional fill and line colors. And lastly, define the rotation in degrees.
     /// Technically, you can draw a circle with this method, but the options are different. Internally within the Lyon
@@ -117,42 +210,227 @@ impl DrawShape {
         line_width: f32,
         corner_radius: f32,
     ) -> Mesh {
+        DrawShape::rounded_rectangle(frame, fill, line, line_width, [corner_radius; 4])
+    }
+
+    /// Draws a Rectangle with optional line and fill colors and a radius for each corner in the order: top left,
+    /// top right, bottom right, bottom left. Radii are reduced to fit the rectangle and the line is centered on the
+    /// edge like in DrawShape::rectangle.
+    pub fn rounded_rectangle(
+        frame: &Rectangle,
+        fill: Option<Color>,
+        line: Option<Color>,
+        line_width: f32,
+        radii: [f32; 4],
+    ) -> Mesh {
+        let mut mesh = Mesh::new();
+        let radii = fit_radii(frame, radii);
+        let half = line_width.max(0.0) / 2.0;
+        let segments = if max_radius(radii) > 0.0 { corner_segments(max_radius(radii) + half) } else { 0 };
+        if let Some(color) = fill {
+            let points = rounded_rect_contour(frame, radii, segments);
+            fill_convex(&mut mesh, &points, |_| color);
+        }
+        if let Some(color) = line {
+            if line_width > 0.0 {
+                // Both edges of the line share the centers of the corners, and sharp corners stay sharp
+                let outer = rounded_rect_contour(&inset_rect(frame, -half), offset_radii(radii, half, true), segments);
+                let inner = rounded_rect_contour(&inset_rect(frame, half), offset_radii(radii, -half, true), segments);
+                let colors = vec![color; outer.len()];
+                fill_strip(&mut mesh, &outer, &colors, &inner, &colors);
+            }
+        }
+        mesh
+    }
+
+    /// Fill a rectangle with a linear gradient. The angle in degrees sets the direction, where 0.0 is left to right
+    /// and 90.0 is top to bottom, and the gradient spans the rectangle in that direction. The corner radius rounds
+    /// the corners like in DrawShape::rounded_rectangle.
+    pub fn linear_gradient(frame: &Rectangle, stops: &[GradientStop], angle: f32, radii: [f32; 4]) -> Mesh {
         let mut mesh = Mesh::new();
-        let rect = rect(frame.x(), frame.y(), frame.width(), frame.height());
+        let stops = sorted_stops(stops);
+        let shape = rounded_rect_points(frame, radii);
+        if stops.is_empty() || shape.len() < 3 {
+            return mesh;
+        }
+        let offset_at = linear_offset(frame, angle);
 
-        if corner_radius >= 0.0 {
-            let radii = BorderRadii::new_all_same(corner_radius);
-            if let Some(color) = fill {
-                let mut renderer = ShapeRenderer::new(&mut mesh, color);
-                let options = FillOptions::tolerance(0.01);
-                fill_rounded_rectangle(&rect, &radii, &options, &mut renderer).unwrap();
+        // The color is linear between two stops, so each band between stops is exact with vertex colors
+        let mut breaks: Vec<f32> = stops.iter().map(|stop| stop.offset).collect();
+        breaks.dedup();
+        let mut lower: Option<f32> = None;
+        for i in 0..=breaks.len() {
+            let upper = breaks.get(i).cloned();
+            let mut band = shape.clone();
+            if let Some(lower) = lower {
+                band = clip_polygon(&band, |pt| offset_at(pt) - lower);
+            }
+            if let Some(upper) = upper {
+                band = clip_polygon(&band, |pt| upper - offset_at(pt));
             }
+            fill_convex(&mut mesh, &band, |pt| GradientStop::color_at(&stops, offset_at(pt)));
+            lower = upper;
+        }
+        mesh
+    }
 
-            if let Some(color) = line {
-                if line_width > 0.0 {
-                    let mut renderer = ShapeRenderer::new(&mut mesh, color);
-                    let options = StrokeOptions::tolerance(0.01).with_line_width(line_width);
-                    stroke_rounded_rectangle(&rect, &radii, &options, &mut renderer).unwrap();
-                }
+    /// Fill a rectangle with a radial gradient. The center is a fraction of the frame size, where (0.5, 0.5) is the
+    /// middle, and the gradient is a circle that reaches the farthest corner. The corner radius rounds the corners
+    /// like in DrawShape::rounded_rectangle.
+    pub fn radial_gradient(frame: &Rectangle, stops: &[GradientStop], center: Vector, radii: [f32; 4]) -> Mesh {
+        let mut mesh = Mesh::new();
+        let stops = sorted_stops(stops);
+        let shape = rounded_rect_points(frame, radii);
+        if stops.is_empty() || shape.len() < 3 {
+            return mesh;
+        }
+        let center = Vector::new(frame.x() + frame.width() * center.x, frame.y() + frame.height() * center.y);
+        let distance = |pt: Vector| ((pt.x - center.x).powi(2) + (pt.y - center.y).powi(2)).sqrt();
+        let radius = rect_corners(frame).iter().map(|pt| distance(*pt)).fold(0.0, f32::max).max(std::f32::EPSILON);
+        let offset_at = |pt: Vector| distance(pt) / radius;
+
+        // Rings break at every stop and are thin enough for the color to look continuous
+        let mut rings: Vec<f32> = vec![0.0];
+        let mut breaks: Vec<f32> = stops.iter().map(|stop| stop.offset).filter(|offset| *offset > 0.0).collect();
+        breaks.retain(|offset| *offset < 1.0);
+        breaks.push(1.0);
+        for end in breaks {
+            let start = rings[rings.len() - 1];
+            if end <= start {
+                continue;
             }
-        } else {
-            if let Some(color) = fill {
-                let mut renderer = ShapeRenderer::new(&mut mesh, color);
-                let options = FillOptions::tolerance(0.01);
-                fill_rectangle(&rect, &options, &mut renderer).unwrap();
+            let count = ((end - start) * RADIAL_RINGS).ceil().max(1.0) as usize;
+            for i in 1..=count {
+                rings.push(start + (end - start) * i as f32 / count as f32);
             }
+        }
+        // The outer ring of chords must surround the circle to cover the farthest corner
+        let step = 2.0 * PI / RADIAL_SECTORS as f32;
+        if let Some(outer) = rings.last_mut() {
+            *outer /= (step / 2.0).cos();
+        }
 
-            if let Some(color) = line {
-                if line_width > 0.0 {
-                    let mut renderer = ShapeRenderer::new(&mut mesh, color);
-                    let options = StrokeOptions::tolerance(0.01).with_line_width(line_width);
-                    stroke_rectangle(&rect, &options, &mut renderer).unwrap();
+        let point_at = |ring: f32, sector: usize| {
+            let (sin, cos) = (step * sector as f32).sin_cos();
+            Vector::new(center.x + cos * ring * radius, center.y + sin * ring * radius)
+        };
+        for pair in rings.windows(2) {
+            for sector in 0..RADIAL_SECTORS {
+                let mut cell = vec![point_at(pair[1], sector), point_at(pair[1], sector + 1)];
+                if pair[0] > 0.0 {
+                    cell.push(point_at(pair[0], sector + 1));
+                    cell.push(point_at(pair[0], sector));
+                } else {
+                    cell.push(center);
+                }
+                if !overlaps(&cell, frame) {
+                    continue;
+                }
+                // Cells are convex and clockwise on screen, so the inside is to the right of every edge
+                let mut piece = shape.clone();
+                for i in 0..cell.len() {
+                    let (a, b) = (cell[i], cell[(i + 1) % cell.len()]);
+                    piece = clip_polygon(&piece, |pt| (b.x - a.x) * (pt.y - a.y) - (b.y - a.y) * (pt.x - a.x));
                 }
+                fill_convex(&mut mesh, &piece, |pt| GradientStop::color_at(&stops, offset_at(pt)));
             }
         }
         mesh
     }
 
+    /// Fill an area outside of the frame with the colors at the nearest edge of a linear gradient that spans the
+    /// frame, as if the gradient continued past the frame with the colors at its edges. This masks content that
+    /// overflows a gradient background.
+    pub fn linear_gradient_pad(frame: &Rectangle, area: &Rectangle, stops: &[GradientStop], angle: f32) -> Mesh {
+        let stops = sorted_stops(stops);
+        let offset_at = linear_offset(frame, angle);
+        fill_pad(frame, area, |pt| GradientStop::color_at(&stops, offset_at(pt)))
+    }
+
+    /// Fill an area outside of the frame with the colors at the nearest edge of a radial gradient, like
+    /// DrawShape::linear_gradient_pad.
+    pub fn radial_gradient_pad(frame: &Rectangle, area: &Rectangle, stops: &[GradientStop], center: Vector) -> Mesh {
+        let stops = sorted_stops(stops);
+        let offset_at = radial_offset(frame, center);
+        fill_pad(frame, area, |pt| GradientStop::color_at(&stops, offset_at(pt)))
+    }
+
+    /// Draw a shadow or glow shaped like a rounded rectangle. The color fades from full at half the softness inside
+    /// the edge to transparent at half the softness outside of it, and shapes smaller than the softness are fainter.
+    pub fn shadow(frame: &Rectangle, radii: [f32; 4], color: Color, softness: f32) -> Mesh {
+        let mut mesh = Mesh::new();
+        let half = softness.max(0.0) / 2.0;
+        let limit = (frame.width().min(frame.height()) / 2.0).max(0.0);
+        let radii = fit_radii(frame, radii);
+        let shrink = half.min(limit);
+        let center_color = match half > 0.0 {
+            true => Color { a: color.a * (half + shrink) / (half * 2.0), ..color },
+            false => color,
+        };
+        let segments = corner_segments(max_radius(radii) + half);
+        // The corners of both contours share a center, so the fade is even all around
+        let inner = rounded_rect_contour(&inset_rect(frame, shrink), offset_radii(radii, -shrink, false), segments);
+        fill_convex(&mut mesh, &inner, |_| center_color);
+        if half > 0.0 {
+            let outer = rounded_rect_contour(&inset_rect(frame, -half), offset_radii(radii, half, false), segments);
+            let inner_colors = vec![center_color; inner.len()];
+            let outer_colors = vec![Color { a: 0.0, ..color }; outer.len()];
+            fill_strip(&mut mesh, &outer, &outer_colors, &inner, &inner_colors);
+        }
+        mesh
+    }
+
+    /// Draw a shadow inside a rounded rectangle, around a hole where the shadow fades out. The color fades from full
+    /// at half the softness outside the edge of the hole to transparent at half the softness inside of it. Any part
+    /// of the hole outside of the frame is clamped to the frame.
+    pub fn inner_shadow(
+        frame: &Rectangle,
+        radii: [f32; 4],
+        hole: &Rectangle,
+        hole_radii: [f32; 4],
+        color: Color,
+        softness: f32,
+    ) -> Mesh {
+        let mut mesh = Mesh::new();
+        let half = softness.max(0.0) / 2.0;
+        let radii = fit_radii(frame, radii);
+        let hole_radii = fit_radii(hole, hole_radii);
+        let shrink = half.min((hole.width().min(hole.height()) / 2.0).max(0.0));
+        let segments = corner_segments(max_radius(radii).max(max_radius(hole_radii) + half));
+
+        let edge = rounded_rect_contour(frame, radii, segments);
+        let inner = rounded_rect_contour(&inset_rect(hole, shrink), offset_radii(hole_radii, -shrink, false), segments);
+        let outer = rounded_rect_contour(&inset_rect(hole, -half), offset_radii(hole_radii, half, false), segments);
+        let inner_alpha = match half > 0.0 {
+            true => color.a * (half - shrink) / (half * 2.0),
+            false => 0.0,
+        };
+        let clamp = |pt: &Vector| clamp_to_rounded_rect(*pt, frame, radii);
+        // A clamped point on the outer contour is part of the way through the fade
+        let outer_colors: Vec<Color> = outer
+            .iter()
+            .zip(inner.iter())
+            .map(|(pt, inner_pt)| {
+                let full = distance(*pt, *inner_pt);
+                let fraction = match full > 0.0 {
+                    true => distance(clamp(pt), *inner_pt) / full,
+                    false => 1.0,
+                };
+                Color { a: inner_alpha + (color.a - inner_alpha) * fraction.min(1.0), ..color }
+            })
+            .collect();
+        let inner_colors = vec![Color { a: inner_alpha, ..color }; inner.len()];
+        let edge_colors = vec![color; edge.len()];
+        let outer: Vec<Vector> = outer.iter().map(clamp).collect();
+        let inner: Vec<Vector> = inner.iter().map(clamp).collect();
+        fill_strip(&mut mesh, &edge, &edge_colors, &outer, &outer_colors);
+        fill_strip(&mut mesh, &outer, &outer_colors, &inner, &inner_colors);
+        if inner_alpha > 0.0 {
+            fill_convex(&mut mesh, &inner, |_| Color { a: inner_alpha, ..color });
+        }
+        mesh
+    }
+
     /// Draw a four-sided polygon using the specified points.
     pub fn quad(points: &[&Vector; 4], fill: Option<Color>, line: Option<Color>, line_width: f32) -> Mesh {
         let mut mesh = Mesh::new();
@@ -177,3 +455,266 @@ impl DrawShape {
         mesh
     }
 }
+
+//-- Shadows -----------------------------------------------------------------------
+
+/// Grow or shrink a rectangle by the amount on each side, keeping the center
+fn inset_rect(frame: &Rectangle, amount: f32) -> Rectangle {
+    let amount = amount.min(frame.width() / 2.0).min(frame.height() / 2.0);
+    Rectangle::new(
+        (frame.x() + amount, frame.y() + amount),
+        (frame.width() - amount * 2.0, frame.height() - amount * 2.0),
+    )
+}
+
+/// Move a point that is outside of a rounded rectangle to the nearest point on its edge
+fn clamp_to_rounded_rect(pt: Vector, frame: &Rectangle, radii: [f32; 4]) -> Vector {
+    let (x1, y1) = (frame.x(), frame.y());
+    let (x2, y2) = (frame.x() + frame.width(), frame.y() + frame.height());
+    let pt = Vector::new(pt.x.max(x1).min(x2), pt.y.max(y1).min(y2));
+    match corner_center(pt, frame, radii) {
+        Some((center, radius)) => {
+            let (dx, dy) = (pt.x - center.x, pt.y - center.y);
+            let distance = (dx * dx + dy * dy).sqrt();
+            if distance > radius {
+                Vector::new(center.x + dx * radius / distance, center.y + dy * radius / distance)
+            } else {
+                pt
+            }
+        }
+        None => pt,
+    }
+}
+
+/// Is the point inside of the rounded rectangle? The radii are in the order: top left, top right, bottom right,
+/// bottom left.
+pub(crate) fn rounded_rect_contains(frame: &Rectangle, radii: [f32; 4], pt: &Vector) -> bool {
+    if !pt.overlaps_rectangle(frame) {
+        return false;
+    }
+    match corner_center(*pt, frame, fit_radii(frame, radii)) {
+        Some((center, radius)) => distance(*pt, center) <= radius,
+        None => true,
+    }
+}
+
+/// The center and radius of the rounded corner that the point is in, or None if the point is not in the square
+/// of a rounded corner
+fn corner_center(pt: Vector, frame: &Rectangle, radii: [f32; 4]) -> Option<(Vector, f32)> {
+    let (x1, y1) = (frame.x(), frame.y());
+    let (x2, y2) = (frame.x() + frame.width(), frame.y() + frame.height());
+    let left = pt.x < x1 + frame.width() / 2.0;
+    let top = pt.y < y1 + frame.height() / 2.0;
+    let (index, cx, cy) = match (left, top) {
+        (true, true) => (0, x1 + radii[0], y1 + radii[0]),
+        (false, true) => (1, x2 - radii[1], y1 + radii[1]),
+        (false, false) => (2, x2 - radii[2], y2 - radii[2]),
+        (true, false) => (3, x1 + radii[3], y2 - radii[3]),
+    };
+    let outside_x = if left { pt.x < cx } else { pt.x > cx };
+    let outside_y = if top { pt.y < cy } else { pt.y > cy };
+    if radii[index] > 0.0 && outside_x && outside_y {
+        Some((Vector::new(cx, cy), radii[index]))
+    } else {
+        None
+    }
+}
+
+fn distance(a: Vector, b: Vector) -> f32 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+//-- Gradients -----------------------------------------------------------------------
+
+fn sorted_stops(stops: &[GradientStop]) -> Vec<GradientStop> {
+    let mut stops = stops.to_vec();
+    stops.sort_by(|a, b| a.offset.partial_cmp(&b.offset).unwrap_or(Ordering::Equal));
+    stops
+}
+
+fn rect_corners(frame: &Rectangle) -> [Vector; 4] {
+    let (x1, y1) = (frame.x(), frame.y());
+    let (x2, y2) = (frame.x() + frame.width(), frame.y() + frame.height());
+    [Vector::new(x1, y1), Vector::new(x2, y1), Vector::new(x2, y2), Vector::new(x1, y2)]
+}
+
+/// Get the function for the gradient offset of a point in a linear gradient that spans the frame
+fn linear_offset(frame: &Rectangle, angle: f32) -> impl Fn(Vector) -> f32 {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let project = move |pt: Vector| pt.x * cos + pt.y * sin;
+    let corners = rect_corners(frame);
+    let min = corners.iter().map(|pt| project(*pt)).fold(std::f32::MAX, f32::min);
+    let max = corners.iter().map(|pt| project(*pt)).fold(std::f32::MIN, f32::max);
+    let length = (max - min).max(std::f32::EPSILON);
+    move |pt: Vector| (project(pt) - min) / length
+}
+
+/// Get the function for the gradient offset of a point in a radial gradient that reaches the farthest corner
+fn radial_offset(frame: &Rectangle, center: Vector) -> impl Fn(Vector) -> f32 {
+    let center = Vector::new(frame.x() + frame.width() * center.x, frame.y() + frame.height() * center.y);
+    let radius = rect_corners(frame).iter().map(|pt| distance(*pt, center)).fold(0.0, f32::max);
+    let radius = radius.max(std::f32::EPSILON);
+    move |pt: Vector| distance(pt, center) / radius
+}
+
+/// Fill the area in slices along its longer side, with the color of each vertex from the nearest point in the frame
+fn fill_pad(frame: &Rectangle, area: &Rectangle, color_at: impl Fn(Vector) -> Color) -> Mesh {
+    let mut mesh = Mesh::new();
+    let nearest = |pt: Vector| {
+        Vector::new(
+            pt.x.max(frame.x()).min(frame.x() + frame.width()),
+            pt.y.max(frame.y()).min(frame.y() + frame.height()),
+        )
+    };
+    let horizontal = area.width() >= area.height();
+    for i in 0..RADIAL_SECTORS {
+        let (a, b) = (i as f32 / RADIAL_SECTORS as f32, (i + 1) as f32 / RADIAL_SECTORS as f32);
+        let slice = match horizontal {
+            true => Rectangle::new((area.x() + area.width() * a, area.y()), (area.width() * (b - a), area.height())),
+            false => Rectangle::new((area.x(), area.y() + area.height() * a), (area.width(), area.height() * (b - a))),
+        };
+        fill_convex(&mut mesh, &rect_corners(&slice), |pt| color_at(nearest(pt)));
+    }
+    mesh
+}
+
+/// The points of a rounded rectangle in clockwise order on screen, with the corner radii in the order: top left,
+/// top right, bottom right, bottom left
+pub(crate) fn rounded_rect_points(frame: &Rectangle, radii: [f32; 4]) -> Vec<Vector> {
+    let radii = fit_radii(frame, radii);
+    if max_radius(radii) <= 0.0 {
+        return rect_corners(frame).to_vec();
+    }
+    rounded_rect_contour(frame, radii, corner_segments(max_radius(radii)))
+}
+
+/// The number of segments for each rounded corner
+fn corner_segments(radius: f32) -> usize {
+    ((radius / 2.0).ceil() as usize).max(2).min(16)
+}
+
+/// Reduce the corner radii to fit the rectangle. Like CSS, all radii are scaled by the same factor so that the
+/// two radii on each side fit the length of the side.
+pub(crate) fn fit_radii(frame: &Rectangle, radii: [f32; 4]) -> [f32; 4] {
+    let mut result = radii;
+    for radius in result.iter_mut() {
+        *radius = radius.max(0.0);
+    }
+    let (width, height) = (frame.width().max(0.0), frame.height().max(0.0));
+    // The sides in the order: top, right, bottom, left
+    let sides = [(width, 0, 1), (height, 1, 2), (width, 2, 3), (height, 3, 0)];
+    let mut scale: f32 = 1.0;
+    for (length, a, b) in sides.iter() {
+        let sum = result[*a] + result[*b];
+        if sum > 0.0 {
+            scale = scale.min(length / sum);
+        }
+    }
+    if scale < 1.0 {
+        for radius in result.iter_mut() {
+            *radius *= scale;
+        }
+    }
+    result
+}
+
+/// Add the amount to each corner radius. Sharp corners can stay sharp, like the outside of a border on a square.
+fn offset_radii(radii: [f32; 4], amount: f32, keep_sharp: bool) -> [f32; 4] {
+    let mut result = [0.0; 4];
+    for (radius, original) in result.iter_mut().zip(radii.iter()) {
+        if *original > 0.0 || !keep_sharp {
+            *radius = (original + amount).max(0.0);
+        }
+    }
+    result
+}
+
+fn max_radius(radii: [f32; 4]) -> f32 {
+    radii.iter().cloned().fold(0.0, f32::max)
+}
+
+/// The points of a rounded rectangle in clockwise order on screen, with the same number of points for any radii so
+/// that the contours of different rectangles line up
+fn rounded_rect_contour(frame: &Rectangle, radii: [f32; 4], segments: usize) -> Vec<Vector> {
+    let radii = fit_radii(frame, radii);
+    let (x1, y1) = (frame.x(), frame.y());
+    let (x2, y2) = (frame.x() + frame.width(), frame.y() + frame.height());
+    let corners = [
+        (x2 - radii[1], y1 + radii[1], radii[1]),
+        (x2 - radii[2], y2 - radii[2], radii[2]),
+        (x1 + radii[3], y2 - radii[3], radii[3]),
+        (x1 + radii[0], y1 + radii[0], radii[0]),
+    ];
+    let mut points = Vec::new();
+    for (i, (cx, cy, radius)) in corners.iter().enumerate() {
+        let start = (i as f32 - 1.0) * PI / 2.0;
+        for j in 0..=segments {
+            let angle = if segments > 0 { start + PI / 2.0 * j as f32 / segments as f32 } else { start };
+            let (sin, cos) = angle.sin_cos();
+            points.push(Vector::new(cx + cos * radius, cy + sin * radius));
+        }
+    }
+    points
+}
+
+/// Add the ring between two contours with the same number of points to the mesh, with a color for each point
+fn fill_strip(mesh: &mut Mesh, outer: &[Vector], outer_colors: &[Color], inner: &[Vector], inner_colors: &[Color]) {
+    let count = outer.len().min(inner.len()) as u32;
+    if count < 2 {
+        return;
+    }
+    let offset = mesh.vertices.len() as u32;
+    let outer = outer.iter().zip(outer_colors.iter()).take(count as usize);
+    let inner = inner.iter().zip(inner_colors.iter()).take(count as usize);
+    for (pt, color) in outer.chain(inner) {
+        mesh.vertices.push(Vertex::new(*pt, None, Col(*color)));
+    }
+    for i in 0..count {
+        let j = (i + 1) % count;
+        mesh.triangles.push(GpuTriangle::new(offset, [i, j, count + j], 0.0, Col(Color::WHITE)));
+        mesh.triangles.push(GpuTriangle::new(offset, [i, count + j, count + i], 0.0, Col(Color::WHITE)));
+    }
+}
+
+/// Does the bounding box of the points overlap the rectangle?
+fn overlaps(points: &[Vector], frame: &Rectangle) -> bool {
+    let min_x = points.iter().map(|pt| pt.x).fold(std::f32::MAX, f32::min);
+    let max_x = points.iter().map(|pt| pt.x).fold(std::f32::MIN, f32::max);
+    let min_y = points.iter().map(|pt| pt.y).fold(std::f32::MAX, f32::min);
+    let max_y = points.iter().map(|pt| pt.y).fold(std::f32::MIN, f32::max);
+    max_x >= frame.x()
+        && min_x <= frame.x() + frame.width()
+        && max_y >= frame.y()
+        && min_y <= frame.y() + frame.height()
+}
+
+/// Clip a convex polygon to the side of a line where the linear function of the points is >= 0
+fn clip_polygon(points: &[Vector], side: impl Fn(Vector) -> f32) -> Vec<Vector> {
+    let mut result = Vec::with_capacity(points.len() + 1);
+    for i in 0..points.len() {
+        let (a, b) = (points[i], points[(i + 1) % points.len()]);
+        let (side_a, side_b) = (side(a), side(b));
+        if side_a >= 0.0 {
+            result.push(a);
+        }
+        if (side_a >= 0.0) != (side_b >= 0.0) {
+            let t = side_a / (side_a - side_b);
+            result.push(Vector::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t));
+        }
+    }
+    result
+}
+
+/// Add a convex polygon to the mesh as a triangle fan, with the color of each vertex from the function
+fn fill_convex(mesh: &mut Mesh, points: &[Vector], color_at: impl Fn(Vector) -> Color) {
+    if points.len() < 3 {
+        return;
+    }
+    let offset = mesh.vertices.len() as u32;
+    for pt in points {
+        mesh.vertices.push(Vertex::new(*pt, None, Col(color_at(*pt))));
+    }
+    for i in 1..points.len() as u32 - 1 {
+        mesh.triangles.push(GpuTriangle::new(offset, [0, i, i + 1], 0.0, Col(Color::WHITE)));
+    }
+}
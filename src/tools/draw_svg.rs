@@ -1,21 +1,1287 @@
-/// A tool for drawing various kinds of SVG
+/// A tool for drawing SVG documents
 ///
+use super::*;
 
 use quicksilver::{
-    geom::{Rectangle, Shape, Vector},
-    graphics::{Color, Mesh, ShapeRenderer},
-    lyon::{
-        geom::math::*,
-        tessellation::{
-            basic_shapes::*,
-            {FillOptions, StrokeOptions},
-        },
-    },
+    geom::{Rectangle, Vector},
+    graphics::{Color, MeshTask},
 };
 
-pub struct DrawSvg {}
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::fmt;
+
+/// The distance of bezier control points from the corner of a quarter ellipse with radius 1
+const CURVE_KAPPA: f32 = 0.552_284_75;
+/// The size of documents without width, height or viewBox
+const DEFAULT_SVG_SIZE: f32 = 100.0;
+
+//-- Support -----------------------------------------------------------------------
+
+/// The reason an SVG document could not be loaded
+#[derive(Clone, Debug, PartialEq)]
+pub enum SvgError {
+    /// The text is not valid UTF-8
+    Encoding,
+    /// The XML is malformed at the byte offset
+    Syntax(usize),
+    /// There is no svg element
+    MissingRoot,
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgError::Encoding => write!(f, "SVG text is not valid UTF-8"),
+            SvgError::Syntax(offset) => write!(f, "Malformed SVG at byte {}", offset),
+            SvgError::MissingRoot => write!(f, "No svg element found"),
+        }
+    }
+}
+
+/// A 2D affine transform with the same parameters as the SVG matrix() function
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Affine {
+    a: f32,
+    b: f32,
+    c: f32,
+    d: f32,
+    e: f32,
+    f: f32,
+}
+
+impl Affine {
+    const IDENTITY: Affine = Affine { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 };
+
+    fn translate(x: f32, y: f32) -> Self {
+        Affine { e: x, f: y, ..Affine::IDENTITY }
+    }
+
+    fn scale(x: f32, y: f32) -> Self {
+        Affine { a: x, d: y, ..Affine::IDENTITY }
+    }
+
+    fn rotate(degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Affine { a: cos, b: sin, c: -sin, d: cos, e: 0.0, f: 0.0 }
+    }
+
+    /// The transform that applies the other transform first and then this one
+    fn then(&self, other: &Affine) -> Affine {
+        Affine {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+
+    fn apply(&self, pt: Vector) -> Vector {
+        Vector::new(self.a * pt.x + self.c * pt.y + self.e, self.b * pt.x + self.d * pt.y + self.f)
+    }
+
+    /// The average scale, which is applied to line widths
+    fn line_scale(&self) -> f32 {
+        (self.a * self.d - self.b * self.c).abs().sqrt()
+    }
+
+    /// Parse a transform attribute such as "translate(10 20) rotate(45)". Unknown functions are ignored.
+    fn parse(text: &str) -> Affine {
+        let mut result = Affine::IDENTITY;
+        let mut rest = text;
+        while let Some(open) = rest.find('(') {
+            let close = match rest[open..].find(')') {
+                Some(close) => open + close,
+                None => break,
+            };
+            let name = rest[..open].trim_matches(|ch: char| ch.is_whitespace() || ch == ',');
+            let args = parse_numbers(&rest[open + 1..close]);
+            let arg = |i: usize| args.get(i).cloned().unwrap_or(0.0);
+            let transform = match (name, args.len()) {
+                ("matrix", 6) => Affine { a: arg(0), b: arg(1), c: arg(2), d: arg(3), e: arg(4), f: arg(5) },
+                ("translate", 1) => Affine::translate(arg(0), 0.0),
+                ("translate", 2) => Affine::translate(arg(0), arg(1)),
+                ("scale", 1) => Affine::scale(arg(0), arg(0)),
+                ("scale", 2) => Affine::scale(arg(0), arg(1)),
+                ("rotate", 1) => Affine::rotate(arg(0)),
+                ("rotate", 3) => Affine::translate(arg(1), arg(2))
+                    .then(&Affine::rotate(arg(0)))
+                    .then(&Affine::translate(-arg(1), -arg(2))),
+                ("skewX", 1) => Affine { c: arg(0).to_radians().tan(), ..Affine::IDENTITY },
+                ("skewY", 1) => Affine { b: arg(0).to_radians().tan(), ..Affine::IDENTITY },
+                _ => Affine::IDENTITY,
+            };
+            result = result.then(&transform);
+            rest = &rest[close + 1..];
+        }
+        result
+    }
+}
+
+/// A fill or stroke color, which may be taken from the color property
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Paint {
+    None,
+    Color(Color),
+    CurrentColor,
+}
+
+/// The inherited presentation properties of an element
+#[derive(Clone, Debug)]
+struct SvgStyle {
+    fill: Paint,
+    stroke: Paint,
+    color: Color,
+    stroke_width: f32,
+    fill_opacity: f32,
+    stroke_opacity: f32,
+    /// The product of the opacity of the element and its ancestors
+    opacity: f32,
+    fill_rule: FillRule,
+    line_join: LineJoin,
+    line_cap: LineCap,
+    miter_limit: f32,
+    dashes: Vec<f32>,
+    dash_offset: f32,
+    hidden: bool,
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        SvgStyle {
+            fill: Paint::Color(Color::BLACK),
+            stroke: Paint::None,
+            color: Color::BLACK,
+            stroke_width: 1.0,
+            fill_opacity: 1.0,
+            stroke_opacity: 1.0,
+            opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            line_join: LineJoin::Miter,
+            line_cap: LineCap::Butt,
+            miter_limit: 4.0,
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+            hidden: false,
+        }
+    }
+}
+
+impl SvgStyle {
+    /// Apply one presentation attribute or CSS property. Invalid values are ignored.
+    fn apply(&mut self, name: &str, value: &str) {
+        let value = value.trim();
+        match name {
+            "fill" => self.fill = parse_paint(value).unwrap_or(self.fill),
+            "stroke" => self.stroke = parse_paint(value).unwrap_or(self.stroke),
+            "color" => self.color = parse_color(value).unwrap_or(self.color),
+            "stroke-width" => self.stroke_width = parse_length(value).unwrap_or(self.stroke_width).max(0.0),
+            "fill-opacity" => self.fill_opacity = parse_opacity(value).unwrap_or(self.fill_opacity),
+            "stroke-opacity" => self.stroke_opacity = parse_opacity(value).unwrap_or(self.stroke_opacity),
+            "opacity" => self.opacity *= parse_opacity(value).unwrap_or(1.0),
+            "fill-rule" => match value {
+                "evenodd" => self.fill_rule = FillRule::EvenOdd,
+                "nonzero" => self.fill_rule = FillRule::NonZero,
+                _ => (),
+            },
+            "stroke-linejoin" => match value {
+                "miter" => self.line_join = LineJoin::Miter,
+                "round" => self.line_join = LineJoin::Round,
+                "bevel" => self.line_join = LineJoin::Bevel,
+                _ => (),
+            },
+            "stroke-linecap" => match value {
+                "butt" => self.line_cap = LineCap::Butt,
+                "round" => self.line_cap = LineCap::Round,
+                "square" => self.line_cap = LineCap::Square,
+                _ => (),
+            },
+            "stroke-miterlimit" => self.miter_limit = parse_length(value).unwrap_or(self.miter_limit),
+            "stroke-dasharray" => self.dashes = if value == "none" { Vec::new() } else { parse_numbers(value) },
+            "stroke-dashoffset" => self.dash_offset = parse_length(value).unwrap_or(self.dash_offset),
+            "display" => self.hidden |= value == "none",
+            "visibility" => self.hidden = value == "hidden" || value == "collapse",
+            _ => (),
+        }
+    }
+
+    fn resolve(&self, paint: Paint, opacity: f32) -> Option<Color> {
+        let color = match paint {
+            Paint::None => return None,
+            Paint::Color(color) => color,
+            Paint::CurrentColor => self.color,
+        };
+        Some(Color { a: color.a * opacity * self.opacity, ..color })
+    }
+}
+
+/// A filled and/or stroked path in document coordinates
+#[derive(Clone, Debug)]
+struct SvgShape {
+    path: DrawPath,
+    fill: Option<Color>,
+    stroke: Option<Color>,
+    stroke_width: f32,
+}
+
+//-- Main -----------------------------------------------------------------------
+
+/// An SVG document that is converted to DrawPath shapes and drawn as a MeshTask at any scale, for
+/// resolution-independent icons. It supports paths, basic shapes, groups, transforms, solid fills and strokes,
+/// opacity, and simple CSS class and type rules in style elements. Gradients, patterns, text, clip paths, masks
+/// and use elements are skipped. Group opacity is applied to each shape in the group.
+///
+/// ```ignore
+/// let mut svg = DrawSvg::parse(include_str!("../../static/icons/svg/ios-star.svg"))?;
+/// let task = svg.draw(&Rectangle::new((10.0, 10.0), (32.0, 32.0)));
+/// ```
+#[derive(Clone)]
+pub struct DrawSvg {
+    /// The area of the document coordinates that is displayed
+    view_box: Rectangle,
+    /// The intrinsic size from the width and height attributes
+    size: Vector,
+    shapes: Vec<SvgShape>,
+    /// The last mesh and the scale it was drawn at
+    cache: Option<(Vector, MeshTask)>,
+}
 
 impl DrawSvg {
+    /// Parse the text of an SVG document
+    pub fn parse(text: &str) -> Result<Self, SvgError> {
+        let tokens = tokenize_xml(text)?;
+        let rules = collect_css_rules(&tokens);
+        let mut root: Option<(Option<Rectangle>, Option<f32>, Option<f32>)> = None;
+        let mut shapes: Vec<SvgShape> = Vec::new();
+        // The style, transform and skipped state of every open element
+        let mut stack: Vec<(SvgStyle, Affine, bool)> = Vec::new();
+
+        for token in &tokens {
+            match token {
+                XmlToken::Open(tag) => {
+                    let (parent_style, parent_transform, parent_skipped) = match stack.last() {
+                        Some(node) => node.clone(),
+                        None => (SvgStyle::default(), Affine::IDENTITY, false),
+                    };
+                    let mut style = parent_style;
+                    for (name, value) in element_properties(tag, &rules) {
+                        style.apply(&name, &value);
+                    }
+                    let mut transform = parent_transform;
+                    if let Some(value) = tag.attrs.get("transform") {
+                        transform = transform.then(&Affine::parse(value));
+                    }
+                    let skipped = parent_skipped || style.hidden || is_skipped_element(&tag.name);
+                    if tag.name == "svg" {
+                        if root.is_none() {
+                            let view_box = tag.attrs.get("viewBox").and_then(|value| {
+                                let values = parse_numbers(value);
+                                if values.len() == 4 && values[2] > 0.0 && values[3] > 0.0 {
+                                    Some(Rectangle::new((values[0], values[1]), (values[2], values[3])))
+                                } else {
+                                    None
+                                }
+                            });
+                            let width = tag.attrs.get("width").and_then(|value| parse_length(value));
+                            let height = tag.attrs.get("height").and_then(|value| parse_length(value));
+                            root = Some((view_box, width, height));
+                        } else {
+                            // Nested documents are treated as groups at their position
+                            let x = tag.attrs.get("x").and_then(|value| parse_length(value)).unwrap_or(0.0);
+                            let y = tag.attrs.get("y").and_then(|value| parse_length(value)).unwrap_or(0.0);
+                            transform = transform.then(&Affine::translate(x, y));
+                        }
+                    }
+                    if !skipped && root.is_some() {
+                        if let Some(path) = element_path(tag) {
+                            if let Some(shape) = DrawSvg::build_shape(path, &style, &transform) {
+                                shapes.push(shape);
+                            }
+                        }
+                    }
+                    if !tag.self_closing {
+                        stack.push((style, transform, skipped));
+                    }
+                }
+                XmlToken::Close(_) => {
+                    stack.pop();
+                }
+                XmlToken::Text(_) => (),
+            }
+        }
+
+        let (view_box, width, height) = root.ok_or(SvgError::MissingRoot)?;
+        let view_box = view_box.unwrap_or_else(|| {
+            let size = Vector::new(width.unwrap_or(DEFAULT_SVG_SIZE), height.unwrap_or(DEFAULT_SVG_SIZE));
+            Rectangle::new_sized(size)
+        });
+        let size = match (width, height) {
+            (Some(width), Some(height)) => Vector::new(width, height),
+            // Keep the aspect ratio of the viewBox when one dimension is missing
+            (Some(width), None) => Vector::new(width, width * view_box.height() / view_box.width()),
+            (None, Some(height)) => Vector::new(height * view_box.width() / view_box.height(), height),
+            (None, None) => view_box.size,
+        };
+        Ok(DrawSvg { view_box, size, shapes, cache: None })
+    }
+
+    /// Parse the bytes of an SVG file
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SvgError> {
+        let text = std::str::from_utf8(bytes).map_err(|_| SvgError::Encoding)?;
+        DrawSvg::parse(text)
+    }
+
+    /// The intrinsic size of the document
+    pub fn size(&self) -> Vector {
+        self.size
+    }
+
+    /// The area of the document coordinates that is displayed
+    pub fn view_box(&self) -> Rectangle {
+        self.view_box
+    }
+
+    /// Is there nothing to draw?
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    /// Draw the document stretched to fill the rectangle
+    pub fn draw(&mut self, rect: &Rectangle) -> MeshTask {
+        let scale = Vector::new(rect.width() / self.view_box.width(), rect.height() / self.view_box.height());
+        let mut task = self.mesh_task(scale);
+        for vertex in task.vertices.iter_mut() {
+            vertex.pos = vertex.pos + rect.pos;
+        }
+        task
+    }
+
+    /// Get the mesh of the document with the scale applied to the viewBox, where the top left of the viewBox is
+    /// at 0,0. The mesh is cached until the scale changes, so callers can move it by translating the vertices.
+    pub fn mesh_task(&mut self, scale: Vector) -> MeshTask {
+        if let Some((cached_scale, task)) = &self.cache {
+            if *cached_scale == scale {
+                return task.clone();
+            }
+        }
+        let mut task = MeshTask::new(0);
+        let line_scale = (scale.x * scale.y).abs().sqrt();
+        for shape in &self.shapes {
+            let mut path = shape.path.translated(-self.view_box.pos).scaled(scale);
+            let dashes: Vec<f32> = path.dashes.iter().map(|length| length * line_scale).collect();
+            path = path.with_dashes(&dashes, shape.path.dash_offset * line_scale);
+            let mut mesh = path.draw(shape.fill, shape.stroke, shape.stroke_width * line_scale);
+            task.append(&mut mesh);
+        }
+        self.cache = Some((scale, task.clone()));
+        task
+    }
+
+    /// Apply the style and transform to the path of an element
+    fn build_shape(path: DrawPath, style: &SvgStyle, transform: &Affine) -> Option<SvgShape> {
+        let fill = style.resolve(style.fill, style.fill_opacity);
+        let stroke = style.resolve(style.stroke, style.stroke_opacity).filter(|_| style.stroke_width > 0.0);
+        if path.is_empty() || (fill.is_none() && stroke.is_none()) {
+            return None;
+        }
+        let line_scale = transform.line_scale();
+        let dashes: Vec<f32> = style.dashes.iter().map(|length| length * line_scale).collect();
+        let mut path = path
+            .with_fill_rule(style.fill_rule)
+            .with_line_style(style.line_join, style.line_cap)
+            .with_miter_limit(style.miter_limit)
+            .with_dashes(&dashes, style.dash_offset * line_scale);
+        for command in path.commands.iter_mut() {
+            *command = match *command {
+                PathCommand::MoveTo(to) => PathCommand::MoveTo(transform.apply(to)),
+                PathCommand::LineTo(to) => PathCommand::LineTo(transform.apply(to)),
+                PathCommand::QuadTo(ctrl, to) => PathCommand::QuadTo(transform.apply(ctrl), transform.apply(to)),
+                PathCommand::CubicTo(ctrl1, ctrl2, to) => {
+                    PathCommand::CubicTo(transform.apply(ctrl1), transform.apply(ctrl2), transform.apply(to))
+                }
+                // Arcs are converted to cubic curves when parsing, because they cannot be skewed
+                command => command,
+            };
+        }
+        Some(SvgShape { path, fill, stroke, stroke_width: style.stroke_width * line_scale })
+    }
+}
+
+//-- Elements -----------------------------------------------------------------------
+
+/// Elements whose content is not drawn directly
+fn is_skipped_element(name: &str) -> bool {
+    match name {
+        "defs" | "clipPath" | "mask" | "marker" | "pattern" | "symbol" | "linearGradient" | "radialGradient"
+        | "text" | "title" | "desc" | "metadata" | "style" | "script" => true,
+        _ => false,
+    }
+}
+
+/// The presentation properties of an element in order of priority: attributes, CSS type rules, CSS class rules
+/// and the style attribute
+fn element_properties(tag: &XmlTag, rules: &[(String, Vec<(String, String)>)]) -> Vec<(String, String)> {
+    let mut properties: Vec<(String, String)> =
+        tag.attrs.iter().filter(|(name, _)| *name != "style").map(|(k, v)| (k.clone(), v.clone())).collect();
+    let classes: Vec<&str> = tag.attrs.get("class").map(|value| value.split_whitespace().collect()).unwrap_or_default();
+    for (_, declarations) in rules.iter().filter(|(selector, _)| *selector == tag.name) {
+        properties.extend(declarations.iter().cloned());
+    }
+    for (_, declarations) in rules
+        .iter()
+        .filter(|(selector, _)| selector.starts_with('.') && classes.iter().any(|class| selector[1..] == **class))
+    {
+        properties.extend(declarations.iter().cloned());
+    }
+    if let Some(value) = tag.attrs.get("style") {
+        properties.extend(parse_declarations(value));
+    }
+    // Keep only the last value of each property
+    let mut result: Vec<(String, String)> = Vec::new();
+    for (name, value) in properties {
+        result.retain(|(existing, _)| *existing != name);
+        result.push((name, value));
+    }
+    result
+}
+
+/// Get the path of a shape element in its own coordinates
+fn element_path(tag: &XmlTag) -> Option<DrawPath> {
+    let attr = |name: &str| tag.attrs.get(name).and_then(|value| parse_length(value));
+    let value = |name: &str| attr(name).unwrap_or(0.0);
+    let path = match tag.name.as_str() {
+        "path" => parse_path_data(tag.attrs.get("d")?),
+        "rect" => {
+            let (width, height) = (value("width"), value("height"));
+            if width <= 0.0 || height <= 0.0 {
+                return None;
+            }
+            let (rx, ry) = match (attr("rx"), attr("ry")) {
+                (Some(rx), Some(ry)) => (rx, ry),
+                (Some(r), None) | (None, Some(r)) => (r, r),
+                (None, None) => (0.0, 0.0),
+            };
+            let radii = Vector::new(rx.max(0.0).min(width / 2.0), ry.max(0.0).min(height / 2.0));
+            rect_path(&Rectangle::new((value("x"), value("y")), (width, height)), radii)
+        }
+        "circle" => {
+            let r = value("r");
+            ellipse_path(Vector::new(value("cx"), value("cy")), Vector::new(r, r))?
+        }
+        "ellipse" => ellipse_path(Vector::new(value("cx"), value("cy")), Vector::new(value("rx"), value("ry")))?,
+        "line" => DrawPath::new().move_to((value("x1"), value("y1"))).line_to((value("x2"), value("y2"))),
+        "polyline" | "polygon" => {
+            let numbers = parse_numbers(tag.attrs.get("points")?);
+            let mut points = numbers.chunks(2).filter(|pair| pair.len() == 2).map(|pair| (pair[0], pair[1]));
+            let mut path = DrawPath::new().move_to(points.next()?);
+            for pt in points {
+                path = path.line_to(pt);
+            }
+            if tag.name == "polygon" {
+                path = path.close();
+            }
+            path
+        }
+        _ => return None,
+    };
+    Some(path)
+}
+
+/// A closed rectangle path with elliptical corners
+fn rect_path(rect: &Rectangle, radii: Vector) -> DrawPath {
+    let (x1, y1) = (rect.x(), rect.y());
+    let (x2, y2) = (rect.x() + rect.width(), rect.y() + rect.height());
+    let (rx, ry) = (radii.x, radii.y);
+    if rx <= 0.0 || ry <= 0.0 {
+        return DrawPath::new().move_to((x1, y1)).line_to((x2, y1)).line_to((x2, y2)).line_to((x1, y2)).close();
+    }
+    let (kx, ky) = (rx * CURVE_KAPPA, ry * CURVE_KAPPA);
+    DrawPath::new()
+        .move_to((x1 + rx, y1))
+        .line_to((x2 - rx, y1))
+        .cubic_to((x2 - rx + kx, y1), (x2, y1 + ry - ky), (x2, y1 + ry))
+        .line_to((x2, y2 - ry))
+        .cubic_to((x2, y2 - ry + ky), (x2 - rx + kx, y2), (x2 - rx, y2))
+        .line_to((x1 + rx, y2))
+        .cubic_to((x1 + rx - kx, y2), (x1, y2 - ry + ky), (x1, y2 - ry))
+        .line_to((x1, y1 + ry))
+        .cubic_to((x1, y1 + ry - ky), (x1 + rx - kx, y1), (x1 + rx, y1))
+        .close()
+}
+
+/// A closed ellipse path made of four cubic curves
+fn ellipse_path(center: Vector, radii: Vector) -> Option<DrawPath> {
+    if radii.x <= 0.0 || radii.y <= 0.0 {
+        return None;
+    }
+    let (cx, cy, rx, ry) = (center.x, center.y, radii.x, radii.y);
+    let (kx, ky) = (rx * CURVE_KAPPA, ry * CURVE_KAPPA);
+    let path = DrawPath::new()
+        .move_to((cx + rx, cy))
+        .cubic_to((cx + rx, cy + ky), (cx + kx, cy + ry), (cx, cy + ry))
+        .cubic_to((cx - kx, cy + ry), (cx - rx, cy + ky), (cx - rx, cy))
+        .cubic_to((cx - rx, cy - ky), (cx - kx, cy - ry), (cx, cy - ry))
+        .cubic_to((cx + kx, cy - ry), (cx + rx, cy - ky), (cx + rx, cy))
+        .close();
+    Some(path)
+}
+
+//-- Path data -----------------------------------------------------------------------
+
+/// Reads numbers and flags from path data and other number lists, where separators are optional
+struct NumberReader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> NumberReader<'a> {
+    fn new(text: &'a str) -> Self {
+        NumberReader { bytes: text.as_bytes(), pos: 0 }
+    }
+
+    fn skip_separators(&mut self) {
+        while self.pos < self.bytes.len()
+            && (self.bytes[self.pos].is_ascii_whitespace() || self.bytes[self.pos] == b',')
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_separators();
+        self.bytes.get(self.pos).cloned()
+    }
+
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let bytes = self.bytes;
+        let is_digit = |i: usize| i < bytes.len() && bytes[i].is_ascii_digit();
+        let start = self.pos;
+        let mut i = start;
+        if i < bytes.len() && (bytes[i] == b'+' || bytes[i] == b'-') {
+            i += 1;
+        }
+        let mut has_digits = false;
+        while is_digit(i) {
+            i += 1;
+            has_digits = true;
+        }
+        if i < bytes.len() && bytes[i] == b'.' {
+            i += 1;
+            while is_digit(i) {
+                i += 1;
+                has_digits = true;
+            }
+        }
+        if !has_digits {
+            return None;
+        }
+        if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+            let mut j = i + 1;
+            if j < bytes.len() && (bytes[j] == b'+' || bytes[j] == b'-') {
+                j += 1;
+            }
+            if is_digit(j) {
+                while is_digit(j) {
+                    j += 1;
+                }
+                i = j;
+            }
+        }
+        self.pos = i;
+        std::str::from_utf8(&bytes[start..i]).ok()?.parse().ok()
+    }
+
+    fn point(&mut self) -> Option<Vector> {
+        let x = self.number()?;
+        let y = self.number()?;
+        Some(Vector::new(x, y))
+    }
+
+    /// Arc flags are a single 0 or 1, which may be followed by the next number without a separator
+    fn flag(&mut self) -> Option<bool> {
+        match self.peek()? {
+            b'0' => {
+                self.pos += 1;
+                Some(false)
+            }
+            b'1' => {
+                self.pos += 1;
+                Some(true)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parse a list of numbers separated by commas and/or whitespace
+fn parse_numbers(text: &str) -> Vec<f32> {
+    let mut reader = NumberReader::new(text);
+    let mut numbers = Vec::new();
+    while let Some(number) = reader.number() {
+        numbers.push(number);
+    }
+    numbers
+}
+
+/// Parse the d attribute of a path. Like browsers, the path is drawn up to the first error.
+fn parse_path_data(data: &str) -> DrawPath {
+    let mut path = DrawPath::new();
+    let _ = read_path_data(data, &mut path);
+    path
+}
+
+fn read_path_data(data: &str, path: &mut DrawPath) -> Option<()> {
+    let mut reader = NumberReader::new(data);
+    let mut current = Vector::ZERO;
+    let mut start = Vector::ZERO;
+    // The last control points for the smooth curve commands
+    let mut last_cubic: Option<Vector> = None;
+    let mut last_quad: Option<Vector> = None;
+    let mut command = b' ';
+    loop {
+        let next = reader.peek()?;
+        if next.is_ascii_alphabetic() {
+            command = next;
+            reader.pos += 1;
+        } else {
+            // Numbers after a command repeat it, and a move is followed by lines
+            match command {
+                b'M' => command = b'L',
+                b'm' => command = b'l',
+                b'Z' | b'z' | b' ' => return None,
+                _ => (),
+            }
+        }
+        let relative = command.is_ascii_lowercase();
+        let base = if relative { current } else { Vector::ZERO };
+        let (mut cubic, mut quad) = (None, None);
+        match command.to_ascii_uppercase() {
+            b'M' => {
+                current = reader.point()? + base;
+                start = current;
+                path.commands.push(PathCommand::MoveTo(current));
+            }
+            b'L' => {
+                current = reader.point()? + base;
+                path.commands.push(PathCommand::LineTo(current));
+            }
+            b'H' => {
+                current.x = reader.number()? + base.x;
+                path.commands.push(PathCommand::LineTo(current));
+            }
+            b'V' => {
+                current.y = reader.number()? + base.y;
+                path.commands.push(PathCommand::LineTo(current));
+            }
+            b'C' | b'S' => {
+                let ctrl1 = if command.to_ascii_uppercase() == b'C' {
+                    reader.point()? + base
+                } else {
+                    last_cubic.map_or(current, |ctrl| current * 2.0 - ctrl)
+                };
+                let ctrl2 = reader.point()? + base;
+                current = reader.point()? + base;
+                path.commands.push(PathCommand::CubicTo(ctrl1, ctrl2, current));
+                cubic = Some(ctrl2);
+            }
+            b'Q' | b'T' => {
+                let ctrl = if command.to_ascii_uppercase() == b'Q' {
+                    reader.point()? + base
+                } else {
+                    last_quad.map_or(current, |ctrl| current * 2.0 - ctrl)
+                };
+                current = reader.point()? + base;
+                path.commands.push(PathCommand::QuadTo(ctrl, current));
+                quad = Some(ctrl);
+            }
+            b'A' => {
+                let radii = reader.point()?;
+                let rotate = reader.number()?;
+                let large_arc = reader.flag()?;
+                let sweep = reader.flag()?;
+                let to = reader.point()? + base;
+                push_arc(path, current, radii, rotate, large_arc, sweep, to);
+                current = to;
+            }
+            b'Z' => {
+                path.commands.push(PathCommand::Close);
+                current = start;
+            }
+            _ => return None,
+        }
+        last_cubic = cubic;
+        last_quad = quad;
+    }
+}
+
+/// Add an SVG arc as cubic curves of up to 90 degrees, using the endpoint to center conversion from the SVG
+/// specification
+fn push_arc(path: &mut DrawPath, from: Vector, radii: Vector, rotate: f32, large_arc: bool, sweep: bool, to: Vector) {
+    let (mut rx, mut ry) = (radii.x.abs(), radii.y.abs());
+    if from == to {
+        return;
+    }
+    if rx == 0.0 || ry == 0.0 {
+        path.commands.push(PathCommand::LineTo(to));
+        return;
+    }
+    let (sin, cos) = rotate.to_radians().sin_cos();
+    let half = (from - to) / 2.0;
+    let x1 = cos * half.x + sin * half.y;
+    let y1 = -sin * half.x + cos * half.y;
+    // Scale up radii that are too small to reach the end point
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coef = sign * (numerator / denominator).max(0.0).sqrt();
+    let (cx1, cy1) = (coef * rx * y1 / ry, -coef * ry * x1 / rx);
+    let mid = (from + to) / 2.0;
+    let center = Vector::new(cos * cx1 - sin * cy1 + mid.x, sin * cx1 + cos * cy1 + mid.y);
+
+    let start_angle = ((y1 - cy1) / ry).atan2((x1 - cx1) / rx);
+    let end_angle = ((-y1 - cy1) / ry).atan2((-x1 - cx1) / rx);
+    let mut sweep_angle = end_angle - start_angle;
+    if sweep && sweep_angle < 0.0 {
+        sweep_angle += 2.0 * PI;
+    } else if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= 2.0 * PI;
+    }
+
+    let point_at = |angle: f32| {
+        let (x, y) = (rx * angle.cos(), ry * angle.sin());
+        Vector::new(cos * x - sin * y + center.x, sin * x + cos * y + center.y)
+    };
+    let tangent_at = |angle: f32| {
+        let (x, y) = (-rx * angle.sin(), ry * angle.cos());
+        Vector::new(cos * x - sin * y, sin * x + cos * y)
+    };
+    let segments = (sweep_angle.abs() / (PI / 2.0)).ceil().max(1.0) as usize;
+    let step = sweep_angle / segments as f32;
+    let handle = 4.0 / 3.0 * (step / 4.0).tan();
+    for i in 0..segments {
+        let a1 = start_angle + step * i as f32;
+        let a2 = a1 + step;
+        let p1 = point_at(a1);
+        let p2 = if i == segments - 1 { to } else { point_at(a2) };
+        path.commands.push(PathCommand::CubicTo(p1 + tangent_at(a1) * handle, p2 - tangent_at(a2) * handle, p2));
+    }
+}
+
+//-- Values -----------------------------------------------------------------------
+
+/// Parse a length in user units. Pixel units are accepted and other units are not supported.
+fn parse_length(text: &str) -> Option<f32> {
+    let text = text.trim();
+    let text = if text.ends_with("px") { &text[..text.len() - 2] } else { text };
+    text.trim().parse::<f32>().ok()
+}
+
+/// Parse an opacity as a number or a percentage
+fn parse_opacity(text: &str) -> Option<f32> {
+    let text = text.trim();
+    let value =
+        if text.ends_with('%') { text[..text.len() - 1].parse::<f32>().ok()? / 100.0 } else { text.parse().ok()? };
+    Some(value.max(0.0).min(1.0))
+}
+
+fn parse_paint(text: &str) -> Option<Paint> {
+    let text = text.trim();
+    match text {
+        "none" | "transparent" => Some(Paint::None),
+        "currentColor" => Some(Paint::CurrentColor),
+        _ if text.starts_with("url(") => {
+            // Gradients and patterns are not supported, so use the fallback color if there is one
+            let fallback = text.find(')').map(|i| text[i + 1..].trim()).unwrap_or("");
+            if fallback.is_empty() {
+                Some(Paint::None)
+            } else {
+                parse_paint(fallback)
+            }
+        }
+        _ => parse_color(text).map(Paint::Color),
+    }
+}
+
+/// Parse a hex, rgb(), rgba() or basic named color
+fn parse_color(text: &str) -> Option<Color> {
+    let text = text.trim();
+    if text.starts_with('#') {
+        let hex = &text[1..];
+        let digits: Vec<u32> = hex.chars().map(|ch| ch.to_digit(16)).collect::<Option<Vec<u32>>>()?;
+        let channel = |i: usize, len: usize| {
+            let value = if len == 1 { digits[i] * 17 } else { digits[i] * 16 + digits[i + 1] };
+            value as f32 / 255.0
+        };
+        return match digits.len() {
+            3 => Some(Color { r: channel(0, 1), g: channel(1, 1), b: channel(2, 1), a: 1.0 }),
+            6 => Some(Color { r: channel(0, 2), g: channel(2, 2), b: channel(4, 2), a: 1.0 }),
+            _ => None,
+        };
+    }
+    if text.starts_with("rgb") {
+        let open = text.find('(')?;
+        let close = text.find(')')?;
+        let parts: Vec<&str> = text[open + 1..close].split(|ch: char| ch == ',' || ch == '/' || ch == ' ').collect();
+        let parts: Vec<&str> = parts.into_iter().map(|part| part.trim()).filter(|part| !part.is_empty()).collect();
+        if parts.len() < 3 {
+            return None;
+        }
+        let channel = |part: &str| -> Option<f32> {
+            if part.ends_with('%') {
+                Some(part[..part.len() - 1].parse::<f32>().ok()? / 100.0)
+            } else {
+                Some(part.parse::<f32>().ok()? / 255.0)
+            }
+        };
+        let alpha = match parts.get(3) {
+            Some(part) => parse_opacity(part)?,
+            None => 1.0,
+        };
+        let (r, g, b) = (channel(parts[0])?, channel(parts[1])?, channel(parts[2])?);
+        return Some(Color { r: r.min(1.0), g: g.min(1.0), b: b.min(1.0), a: alpha });
+    }
+    let hex = match text.to_lowercase().as_str() {
+        "black" => "#000000",
+        "white" => "#FFFFFF",
+        "red" => "#FF0000",
+        "green" => "#008000",
+        "lime" => "#00FF00",
+        "blue" => "#0000FF",
+        "yellow" => "#FFFF00",
+        "cyan" | "aqua" => "#00FFFF",
+        "magenta" | "fuchsia" => "#FF00FF",
+        "gray" | "grey" => "#808080",
+        "silver" => "#C0C0C0",
+        "maroon" => "#800000",
+        "olive" => "#808000",
+        "navy" => "#000080",
+        "purple" => "#800080",
+        "teal" => "#008080",
+        "orange" => "#FFA500",
+        _ => return None,
+    };
+    parse_color(hex)
+}
+
+//-- CSS -----------------------------------------------------------------------
+
+/// Parse CSS declarations like "fill:#fff; stroke-width:2"
+fn parse_declarations(text: &str) -> Vec<(String, String)> {
+    text.split(';')
+        .filter_map(|declaration| {
+            let colon = declaration.find(':')?;
+            let name = declaration[..colon].trim();
+            let value = declaration[colon + 1..].trim().trim_end_matches("!important").trim();
+            if name.is_empty() || value.is_empty() {
+                None
+            } else {
+                Some((name.to_string(), value.to_string()))
+            }
+        })
+        .collect()
+}
+
+/// Collect the rules of style elements with class or type selectors. Other selectors are ignored.
+fn collect_css_rules(tokens: &[XmlToken]) -> Vec<(String, Vec<(String, String)>)> {
+    let mut css = String::new();
+    let mut in_style = false;
+    for token in tokens {
+        match token {
+            XmlToken::Open(tag) if tag.name == "style" => in_style = !tag.self_closing,
+            XmlToken::Close(name) if name == "style" => in_style = false,
+            XmlToken::Text(text) if in_style => css.push_str(text),
+            _ => (),
+        }
+    }
+    // Remove comments
+    while let Some(start) = css.find("/*") {
+        let end = css[start..].find("*/").map_or(css.len(), |end| start + end + 2);
+        css.replace_range(start..end, "");
+    }
+    let mut rules = Vec::new();
+    for block in css.split('}') {
+        let open = match block.find('{') {
+            Some(open) => open,
+            None => continue,
+        };
+        let declarations = parse_declarations(&block[open + 1..]);
+        for selector in block[..open].split(',').map(|selector| selector.trim()) {
+            let name = selector.trim_start_matches('.');
+            if !name.is_empty() && name.chars().all(|ch| ch.is_alphanumeric() || ch == '-' || ch == '_') {
+                rules.push((selector.to_string(), declarations.clone()));
+            }
+        }
+    }
+    rules
+}
+
+//-- XML -----------------------------------------------------------------------
+
+/// A start tag with its attributes
+#[derive(Clone, Debug)]
+struct XmlTag {
+    name: String,
+    attrs: HashMap<String, String>,
+    self_closing: bool,
+}
+
+#[derive(Clone, Debug)]
+enum XmlToken {
+    Open(XmlTag),
+    Close(String),
+    Text(String),
+}
+
+/// Split XML into tags and text. Comments, processing instructions and the doctype are skipped. The nesting of
+/// tags is not validated.
+fn tokenize_xml(text: &str) -> Result<Vec<XmlToken>, SvgError> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < text.len() {
+        let rest = &text[pos..];
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(XmlToken::Text(decode_entities(&rest[..end])));
+            pos += end;
+            continue;
+        }
+        let offset = pos;
+        let find = |pattern: &str| rest.find(pattern).ok_or(SvgError::Syntax(offset));
+        if rest.starts_with("<!--") {
+            pos += find("-->")? + 3;
+        } else if rest.starts_with("<![CDATA[") {
+            let end = find("]]>")?;
+            tokens.push(XmlToken::Text(rest[9..end].to_string()));
+            pos += end + 3;
+        } else if rest.starts_with("<?") {
+            pos += find("?>")? + 2;
+        } else if rest.starts_with("<!") {
+            // A doctype may have an internal subset in brackets
+            let end = find(">")?;
+            match rest[..end].find('[') {
+                Some(_) => pos += find("]>")? + 2,
+                None => pos += end + 1,
+            }
+        } else if rest.starts_with("</") {
+            let end = find(">")?;
+            tokens.push(XmlToken::Close(rest[2..end].trim().to_string()));
+            pos += end + 1;
+        } else {
+            let (tag, len) = parse_tag(rest).ok_or(SvgError::Syntax(pos))?;
+            tokens.push(XmlToken::Open(tag));
+            pos += len;
+        }
+    }
+    Ok(tokens)
+}
+
+/// Parse a start tag at the beginning of the text, and return it with its length
+fn parse_tag(text: &str) -> Option<(XmlTag, usize)> {
+    let bytes = text.as_bytes();
+    let is_name_end = |b: u8| b.is_ascii_whitespace() || b == b'/' || b == b'>' || b == b'=';
+    let mut pos = 1;
+    while pos < bytes.len() && !is_name_end(bytes[pos]) {
+        pos += 1;
+    }
+    let name = text[1..pos].to_string();
+    if name.is_empty() {
+        return None;
+    }
+    let mut attrs = HashMap::new();
+    loop {
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        match bytes.get(pos)? {
+            b'>' => return Some((XmlTag { name, attrs, self_closing: false }, pos + 1)),
+            b'/' => {
+                if bytes.get(pos + 1)? != &b'>' {
+                    return None;
+                }
+                return Some((XmlTag { name, attrs, self_closing: true }, pos + 2));
+            }
+            _ => (),
+        }
+        let start = pos;
+        while pos < bytes.len() && !is_name_end(bytes[pos]) {
+            pos += 1;
+        }
+        let attr_name = text[start..pos].to_string();
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        if attr_name.is_empty() || bytes.get(pos)? != &b'=' {
+            return None;
+        }
+        pos += 1;
+        while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+            pos += 1;
+        }
+        let quote = *bytes.get(pos)?;
+        if quote != b'"' && quote != b'\'' {
+            return None;
+        }
+        let end = pos + 1 + text[pos + 1..].find(quote as char)?;
+        attrs.insert(attr_name, decode_entities(&text[pos + 1..end]));
+        pos = end + 1;
+    }
+}
+
+/// Replace the predefined and numeric character entities
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        result.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').and_then(|semi| {
+            let entity = &rest[1..semi];
+            let ch = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ if entity.starts_with("#x") => {
+                    u32::from_str_radix(&entity[2..], 16).ok().and_then(std::char::from_u32)
+                }
+                _ if entity.starts_with('#') => entity[1..].parse().ok().and_then(std::char::from_u32),
+                _ => None,
+            };
+            ch.map(|ch| (ch, semi + 1))
+        });
+        match decoded {
+            Some((ch, len)) => {
+                result.push(ch);
+                rest = &rest[len..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: Vector, expected: (f32, f32)) {
+        assert!(
+            (actual.x - expected.0).abs() < 0.01 && (actual.y - expected.1).abs() < 0.01,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    /// The end point of every command except Close
+    fn end_points(path: &DrawPath) -> Vec<Vector> {
+        path.commands
+            .iter()
+            .filter_map(|command| match *command {
+                PathCommand::MoveTo(to) | PathCommand::LineTo(to) => Some(to),
+                PathCommand::QuadTo(_, to) | PathCommand::CubicTo(_, _, to) => Some(to),
+                PathCommand::ArcTo(_, _, _, _, to) => Some(to),
+                PathCommand::Close => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_path_lines() {
+        let path = parse_path_data("M10 20 l5 5 H30 v-10 h-5 z m2,2 L 1,2 3,4");
+        let v = |x: f32, y: f32| Vector::new(x, y);
+        assert_eq!(
+            path.commands,
+            vec![
+                PathCommand::MoveTo(v(10.0, 20.0)),
+                PathCommand::LineTo(v(15.0, 25.0)),
+                PathCommand::LineTo(v(30.0, 25.0)),
+                PathCommand::LineTo(v(30.0, 15.0)),
+                PathCommand::LineTo(v(25.0, 15.0)),
+                PathCommand::Close,
+                // A move after close is relative to the start of the closed subpath
+                PathCommand::MoveTo(v(12.0, 22.0)),
+                PathCommand::LineTo(v(1.0, 2.0)),
+                PathCommand::LineTo(v(3.0, 4.0)),
+            ]
+        );
+
+        // Numbers without separators and extra numbers after a move
+        let path = parse_path_data("m1-2.5.5 1e1-1");
+        assert_eq!(path.commands, vec![PathCommand::MoveTo(v(1.0, -2.5)), PathCommand::LineTo(v(1.5, 7.5))]);
+        let path = parse_path_data("M1 1");
+        assert_eq!(path.commands, vec![PathCommand::MoveTo(v(1.0, 1.0))]);
+
+        // The path is drawn up to the first error
+        let path = parse_path_data("M0 0 L10 10 L20");
+        assert_eq!(path.commands.len(), 2);
+        assert_eq!(parse_path_data("L10 10").commands.len(), 1);
+        assert!(parse_path_data("10 10").is_empty());
+    }
+
+    #[test]
+    fn test_path_curves() {
+        let v = |x: f32, y: f32| Vector::new(x, y);
+        let path = parse_path_data("M0 0 C1 1 2 2 3 3 s1 1 2 2 S6 6 7 7");
+        assert_eq!(path.commands[1], PathCommand::CubicTo(v(1.0, 1.0), v(2.0, 2.0), v(3.0, 3.0)));
+        // The first control point of a smooth curve reflects the previous second control point
+        assert_eq!(path.commands[2], PathCommand::CubicTo(v(4.0, 4.0), v(4.0, 4.0), v(5.0, 5.0)));
+        assert_eq!(path.commands[3], PathCommand::CubicTo(v(6.0, 6.0), v(6.0, 6.0), v(7.0, 7.0)));
+
+        let path = parse_path_data("M0 0 Q1 1 2 0 T4 0 t2 0");
+        assert_eq!(path.commands[1], PathCommand::QuadTo(v(1.0, 1.0), v(2.0, 0.0)));
+        assert_eq!(path.commands[2], PathCommand::QuadTo(v(3.0, -1.0), v(4.0, 0.0)));
+        assert_eq!(path.commands[3], PathCommand::QuadTo(v(5.0, 1.0), v(6.0, 0.0)));
+
+        // Without a previous curve the control point is the current point
+        let path = parse_path_data("M2 2 T4 0");
+        assert_eq!(path.commands[1], PathCommand::QuadTo(v(2.0, 2.0), v(4.0, 0.0)));
+    }
+
+    #[test]
+    fn test_path_arcs() {
+        // A half circle that sweeps clockwise on screen passes above the center
+        let path = parse_path_data("M0 0 A10 10 0 0 1 20 0");
+        let points = end_points(&path);
+        assert_eq!(points.len(), 3);
+        assert_near(points[1], (10.0, -10.0));
+        assert_eq!(points[2], Vector::new(20.0, 0.0));
+
+        // Relative end point with the sweep flag off, and flags without separators
+        let path = parse_path_data("M10 10 a10,10 0 0020,0");
+        let points = end_points(&path);
+        assert_near(points[1], (20.0, 20.0));
+        assert_eq!(points[2], Vector::new(30.0, 10.0));
+
+        // The large arc goes the long way around
+        let path = parse_path_data("M0 0 A10 10 0 1 1 10 10");
+        assert_eq!(end_points(&path).len(), 4);
+        assert_near(end_points(&path)[1], (10.0, -10.0));
+
+        // Radii that are too small are scaled up to reach the end point
+        let path = parse_path_data("M0 0 A1 1 0 0 1 20 0");
+        assert_near(end_points(&path)[1], (10.0, -10.0));
+
+        // A zero radius is a straight line, and an arc to the current point is skipped
+        let path = parse_path_data("M0 0 A0 5 0 0 1 20 0 A5 5 0 0 1 20 0");
+        assert_eq!(path.commands, vec![PathCommand::MoveTo(Vector::ZERO), PathCommand::LineTo(Vector::new(20.0, 0.0))]);
+
+        // Invalid flags stop the path
+        assert_eq!(parse_path_data("M0 0 A10 10 0 2 1 20 0").commands.len(), 1);
+    }
+
+    #[test]
+    fn test_transform_list() {
+        let pt = |text: &str, x: f32, y: f32| Affine::parse(text).apply(Vector::new(x, y));
+        // Transforms apply right to left
+        assert_near(pt("translate(10 20) scale(2)", 1.0, 1.0), (12.0, 22.0));
+        assert_near(pt("scale(2),translate(10,20)", 1.0, 1.0), (22.0, 42.0));
+        assert_near(pt("translate(5)", 1.0, 1.0), (6.0, 1.0));
+        assert_near(pt("scale(2 3)", 1.0, 1.0), (2.0, 3.0));
+        assert_near(pt("rotate(90)", 1.0, 0.0), (0.0, 1.0));
+        assert_near(pt("rotate(90 10 10)", 20.0, 10.0), (10.0, 20.0));
+        assert_near(pt("matrix(1 0 0 1 5 6)", 1.0, 1.0), (6.0, 7.0));
+        assert_near(pt("skewX(45)", 0.0, 1.0), (1.0, 1.0));
+        assert_near(pt("skewY(45)", 1.0, 0.0), (1.0, 1.0));
+        // Unknown functions and wrong argument counts are ignored
+        assert_near(pt("wobble(3) translate(1 1) matrix(1 2)", 0.0, 0.0), (1.0, 1.0));
+        assert_eq!(Affine::parse("scale(2 4)").line_scale(), 8.0f32.sqrt());
+
+        // Element transforms apply inside the transforms of the groups
+        let svg = DrawSvg::parse(
+            r#"<svg viewBox="0 0 10 10"><g transform="translate(5 0)"><rect x="1" y="1" width="2" height="2" transform="scale(2)" stroke-width="2"/></g></svg>"#,
+        )
+        .unwrap();
+        assert_eq!(svg.shapes.len(), 1);
+        assert_near(end_points(&svg.shapes[0].path)[0], (7.0, 2.0));
+        assert_near(end_points(&svg.shapes[0].path)[2], (11.0, 6.0));
+        assert_eq!(svg.shapes[0].stroke_width, 4.0);
+    }
+
+    #[test]
+    fn test_colors() {
+        let rgb = |r: f32, g: f32, b: f32, a: f32| Some(Color { r: r / 255.0, g: g / 255.0, b: b / 255.0, a });
+        assert_eq!(parse_color("#fff"), rgb(255.0, 255.0, 255.0, 1.0));
+        assert_eq!(parse_color("#2329D6"), rgb(35.0, 41.0, 214.0, 1.0));
+        assert_eq!(parse_color(" #0a0B0c "), rgb(10.0, 11.0, 12.0, 1.0));
+        assert_eq!(parse_color("rgb(255, 0, 51)"), rgb(255.0, 0.0, 51.0, 1.0));
+        assert_eq!(parse_color("rgb(100%,50%,0%)"), Some(Color { r: 1.0, g: 0.5, b: 0.0, a: 1.0 }));
+        assert_eq!(parse_color("rgba(0,0,255,0.5)"), rgb(0.0, 0.0, 255.0, 0.5));
+        assert_eq!(parse_color("rgb(0 0 255 / 25%)"), rgb(0.0, 0.0, 255.0, 0.25));
+        assert_eq!(parse_color("rgb(300,0,0)"), rgb(255.0, 0.0, 0.0, 1.0));
+        assert_eq!(parse_color("Navy"), rgb(0.0, 0.0, 128.0, 1.0));
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("#ggg"), None);
+        assert_eq!(parse_color("rgb(1,2)"), None);
+        assert_eq!(parse_color("chartreuse"), None);
+
+        assert_eq!(parse_paint("none"), Some(Paint::None));
+        assert_eq!(parse_paint("currentColor"), Some(Paint::CurrentColor));
+        assert_eq!(parse_paint("url(#grad) red"), Some(Paint::Color(Color { r: 1.0, g: 0.0, b: 0.0, a: 1.0 })));
+        assert_eq!(parse_paint("url(#grad)"), Some(Paint::None));
+    }
+
+    #[test]
+    fn test_view_box() {
+        let svg = DrawSvg::parse(r#"<svg width="40" height="20" viewBox="-5 5 24 12"/>"#).unwrap();
+        assert_eq!(svg.view_box(), Rectangle::new((-5.0, 5.0), (24.0, 12.0)));
+        assert_eq!(svg.size(), Vector::new(40.0, 20.0));
+
+        // A malformed viewBox is ignored and the width and height are used
+        let svg = DrawSvg::parse(r#"<svg width="40px" height="20" viewBox="0 0 0 24"/>"#).unwrap();
+        assert_eq!(svg.view_box(), Rectangle::new((0.0, 0.0), (40.0, 20.0)));
+        let svg = DrawSvg::parse(r#"<svg width="30" viewBox="0 0 24"/>"#).unwrap();
+        assert_eq!(svg.view_box(), Rectangle::new((0.0, 0.0), (30.0, DEFAULT_SVG_SIZE)));
+        let svg = DrawSvg::parse(r#"<svg viewBox="a b c d"/>"#).unwrap();
+        assert_eq!(svg.size(), Vector::new(DEFAULT_SVG_SIZE, DEFAULT_SVG_SIZE));
+
+        // One missing dimension keeps the aspect ratio of the viewBox
+        let svg = DrawSvg::parse(r#"<svg height="10" viewBox="0 0 40 20"/>"#).unwrap();
+        assert_eq!(svg.size(), Vector::new(20.0, 10.0));
+
+        assert_eq!(DrawSvg::parse("<g/>").err(), Some(SvgError::MissingRoot));
+        assert_eq!(DrawSvg::parse(r#"<svg><path d="M0 0"</svg>"#).err(), Some(SvgError::Syntax(5)));
+        assert_eq!(DrawSvg::from_bytes(&[b'<', 0xFF]).err(), Some(SvgError::Encoding));
+    }
 
+    #[test]
+    fn test_static_files() {
+        let svg = DrawSvg::parse(include_str!("../../static/circle.svg")).unwrap();
+        assert_eq!(svg.size(), Vector::new(40.0, 40.0));
+        assert_eq!(svg.view_box(), Rectangle::new((0.0, 0.0), (24.0, 24.0)));
+        // The title and desc are skipped and the circle is only stroked
+        assert_eq!(svg.shapes.len(), 1);
+        let shape = &svg.shapes[0];
+        assert_eq!(shape.fill, None);
+        assert_eq!(shape.stroke, parse_color("#2329D6"));
+        assert_eq!(shape.stroke_width, 1.2);
+        assert_eq!(shape.path.line_cap, LineCap::Square);
+        assert_eq!(end_points(&shape.path)[0], Vector::new(20.0, 12.0));
 
-}
\ No newline at end of file
+        let svg = DrawSvg::parse(include_str!("../../static/svg/logo-MK.svg")).unwrap();
+        assert_eq!(svg.view_box(), Rectangle::new((0.0, 0.0), (428.54, 323.29)));
+        assert_eq!(svg.size(), svg.view_box().size);
+        // Every path and polygon is filled with the color of its CSS class
+        assert_eq!(svg.shapes.len(), 18);
+        assert!(svg.shapes.iter().all(|shape| shape.fill.is_some() && shape.stroke.is_none()));
+        assert_eq!(svg.shapes[0].fill, parse_color("#fff"));
+        // The first path is moved by its transform
+        assert_near(end_points(&svg.shapes[0].path)[0], (6.11, 268.5));
+    }
+}
@@ -2,6 +2,7 @@
 /// https://docs.rs/glyph_brush/0.4.1/glyph_brush/
 // #[allow(unused_imports)]
 use super::*;
+use crate::events::TextInputEvent;
 
 use glyph_brush::rusttype::{self, GlyphId, Scale};
 use glyph_brush::{self, GlyphCruncher, Layout, Section, SectionText, VariedSection};
@@ -46,6 +47,16 @@ pub enum InsertMode {
     AppendPreviousLine,
 }
 
+/// The uncommitted text of an IME composition, also known as preedit text. It is not part of the
+/// editor string until it is committed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Preedit {
+    /// The composition text
+    pub text: String,
+    /// The cursor position within the composition text as a char index
+    pub cursor: usize,
+}
+
 //-- Main -----------------------------------------------------------------------
 
 /// Base struct for TextEditor that holds much of the state information
@@ -84,6 +95,8 @@ pub struct EditorContext {
     baselines: Vec<f32>,
     /// Holds the currently visible range of chars
     visible_range: Range<usize>,
+    /// Uncommitted IME composition text which is displayed at the cursor position
+    preedit: Option<Preedit>,
 }
 
 impl Default for EditorContext {
@@ -108,6 +121,7 @@ impl Default for EditorContext {
             metrics: Vec::new(),
             baselines: Vec::new(),
             visible_range: 0..1,
+            preedit: None,
         };
         ctx
     }
@@ -156,6 +170,121 @@ impl EditorContext {
         self.string = text.to_owned();
     }
 
+    /// Replace the text content while editing and move the cursor to the end. This is used when the
+    /// text is reformatted as the user types, such as with an InputMask.
+    pub fn replace_text(&mut self, text: &str) {
+        self.string = text.to_owned();
+        self.cursor_pos = self.char_count();
+        self.has_changed = true;
+        self.update_metrics();
+    }
+
+    /// The number of chars in the text. The cursor_pos and metrics are indexed by char, not by byte.
+    pub fn char_count(&self) -> usize {
+        self.string.chars().count()
+    }
+
+    /// Convert a char index to a byte offset in the string
+    fn byte_offset(&self, char_pos: usize) -> usize {
+        self.string.char_indices().nth(char_pos).map(|(i, _)| i).unwrap_or(self.string.len())
+    }
+
+    /// Insert a string at the cursor position and move the cursor to the end of it
+    pub fn insert_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        self.has_changed = true;
+        let offset = self.byte_offset(self.cursor_pos.min(self.char_count()));
+        self.string.insert_str(offset, text);
+        self.cursor_pos = self.cursor_pos.min(self.char_count()) + text.chars().count();
+        self.update_metrics();
+    }
+
+    // *****************************************************************************************************
+    // IME composition
+    // *****************************************************************************************************
+
+    /// Apply a TextInputEvent from the backend. Returns true if the editor state changed.
+    pub fn handle_text_input(&mut self, event: &TextInputEvent) -> bool {
+        match event {
+            TextInputEvent::CompositionStart => {
+                self.set_composition("", 0);
+                true
+            }
+            TextInputEvent::CompositionUpdate { text, cursor } => {
+                self.set_composition(text, *cursor);
+                true
+            }
+            TextInputEvent::CompositionCommit(text) => {
+                self.commit_composition(text);
+                true
+            }
+            TextInputEvent::CompositionCancel => {
+                let composing = self.is_composing();
+                self.cancel_composition();
+                composing
+            }
+        }
+    }
+
+    /// Set or replace the preedit text. The cursor is clamped to the length of the text.
+    pub fn set_composition(&mut self, text: &str, cursor: usize) {
+        let cursor = cursor.min(text.chars().count());
+        self.preedit = Some(Preedit { text: text.to_owned(), cursor });
+        self.has_changed = true;
+        self.draw_font.cached_mesh = None;
+    }
+
+    /// Discard the preedit text and insert the committed text at the cursor
+    pub fn commit_composition(&mut self, text: &str) {
+        self.preedit = None;
+        self.draw_font.cached_mesh = None;
+        self.has_changed = true;
+        self.insert_text(text);
+    }
+
+    /// Discard the preedit text without changing the editor string
+    pub fn cancel_composition(&mut self) {
+        if self.preedit.take().is_some() {
+            self.has_changed = true;
+            self.draw_font.cached_mesh = None;
+        }
+    }
+
+    /// True if there is uncommitted preedit text
+    pub fn is_composing(&self) -> bool {
+        self.preedit.is_some()
+    }
+
+    /// Get the current preedit state
+    pub fn composition(&self) -> Option<&Preedit> {
+        self.preedit.as_ref()
+    }
+
+    /// The text to display while editing, which is the editor string with any preedit text inserted
+    /// at the cursor position. Also returns the char range of the preedit text.
+    pub fn display_text(&self) -> (String, Option<Range<usize>>) {
+        if let Some(preedit) = &self.preedit {
+            let pos = self.cursor_pos.min(self.char_count());
+            let offset = self.byte_offset(pos);
+            let mut text = self.string.clone();
+            text.insert_str(offset, &preedit.text);
+            (text, Some(pos..pos + preedit.text.chars().count()))
+        } else {
+            (self.string.clone(), None)
+        }
+    }
+
+    /// The distance in pixels that single-line text is scrolled to the left to keep the cursor in view
+    pub fn scroll_x(&self) -> f32 {
+        let start = self.visible_range.start;
+        match self.metrics.get(start) {
+            Some(data) if start > 0 => data.x - (self.text_origin.0 - self.frame.min.x),
+            _ => 0.0,
+        }
+    }
+
     /// This is called whenever self.metrics needs to be updated.
     /// – When initializing a text input view
     /// – When the user has inserted or deleted characters (edit mode)
@@ -298,8 +427,9 @@ impl EditorContext {
         // }
     }
 
-    /// Switch read mode
+    /// Switch read mode. Any uncommitted preedit text is discarded.
     pub fn stop_editing(&mut self) {
+        self.preedit = None;
         self.has_changed = false;
     }
 
@@ -307,31 +437,34 @@ impl EditorContext {
     pub fn insert_char(&mut self, c: char) {
         self.has_changed = true;
         log::trace!("Insert char={:?}", c);
-        if self.cursor_pos == self.string.len() {
+        if self.cursor_pos == self.char_count() {
             // cursor is at the end. add there.
             self.string.push(c);
-            self.cursor_pos = self.string.len();
-        } else if self.cursor_pos < self.string.len() {
+            self.cursor_pos = self.char_count();
+        } else if self.cursor_pos < self.char_count() {
             // insert at cursor
-            self.string.insert(self.cursor_pos, c);
+            let offset = self.byte_offset(self.cursor_pos);
+            self.string.insert(offset, c);
             self.cursor_pos += 1;
         }
         self.update_metrics();
     }
 
-    /// Handle delete button
+    /// Handle the backspace key by deleting the char left of the cursor, as described in the TextField docs.
+    /// Deleting within the text used to remove the char at the cursor instead.
     pub fn delete_char(&mut self) {
         self.has_changed = true;
-        if self.string.len() == 0 || self.cursor_pos == 0 {
+        if self.char_count() == 0 || self.cursor_pos == 0 {
             return;
         }
-        if self.cursor_pos == self.string.len() {
+        if self.cursor_pos == self.char_count() {
             // cursor is at the end. add there.
             self.string.pop();
-            self.cursor_pos = self.string.len();
+            self.cursor_pos = self.char_count();
         } else {
-            // insert at cursor
-            self.string.remove(self.cursor_pos);
+            // remove the char before the cursor
+            let offset = self.byte_offset(self.cursor_pos - 1);
+            self.string.remove(offset);
             self.cursor_pos -= 1;
         }
         self.update_metrics();
@@ -342,11 +475,11 @@ impl EditorContext {
     pub fn move_cursor(&mut self, shift: i32) {
         self.has_changed = true;
         if shift > 0 {
-            if self.string.len() > 0 && self.cursor_pos < self.string.len() {
+            if self.char_count() > 0 && self.cursor_pos < self.char_count() {
                 self.cursor_pos += 1;
             }
         } else {
-            if self.string.len() > 0 && self.cursor_pos > 0 {
+            if self.char_count() > 0 && self.cursor_pos > 0 {
                 self.cursor_pos -= 1;
             }
         }
@@ -417,9 +550,9 @@ impl TextFieldEditor {
 
         if self.ctx.text_size.0 as f32 <= self.ctx.frame.width() {
             // Text size is less than frame width
-            if self.ctx.string.len() > 0 {
-                self.ctx.visible_range = 0..self.ctx.string.len();
-                if self.ctx.cursor_pos == self.ctx.string.len() {
+            if self.ctx.char_count() > 0 {
+                self.ctx.visible_range = 0..self.ctx.char_count();
+                if self.ctx.cursor_pos == self.ctx.char_count() {
                     self.ctx.insert_mode = InsertMode::End(false);
                     // Since text_size does not account for trailing spaces, append a space.
                     let text_size = self.ctx.measure_text(&self.ctx.string);
@@ -445,7 +578,7 @@ impl TextFieldEditor {
                         self.ctx.cursor_pos,
                         self.ctx.metrics
                     );
-                    self.ctx.visible_range = 0..self.ctx.string.len();
+                    self.ctx.visible_range = 0..self.ctx.char_count();
                 }
             } else {
                 // The field is being edited.
@@ -458,14 +591,14 @@ impl TextFieldEditor {
                     .rev()
                     .position(|m| m.letter != SPACE && m.x < self.ctx.text_size.0 as f32 - self.ctx.frame.width())
                 {
-                    let start = self.ctx.string.len() - rev_index - 1;
-                    self.ctx.visible_range = start..self.ctx.string.len();
+                    let start = self.ctx.char_count() - rev_index - 1;
+                    self.ctx.visible_range = start..self.ctx.char_count();
                     // Determine offset width for range of text left of the view
                     let offset = self.ctx.metrics[start].x;
                     let string_w = self.ctx.text_size.0 as f32 - offset;
                     self.ctx.text_origin.0 = self.ctx.frame.max.x - string_w as f32;
 
-                    if self.ctx.cursor_pos == self.ctx.string.len() {
+                    if self.ctx.cursor_pos == self.ctx.char_count() {
                         // Cursor is at the end
                         self.ctx.insert_mode = InsertMode::End(true);
                         self.ctx.cursor_origin.0 = self.ctx.frame.max.x;
@@ -489,7 +622,7 @@ impl TextFieldEditor {
                         self.ctx.cursor_pos,
                         self.ctx.metrics
                     );
-                    self.ctx.visible_range = 0..self.ctx.string.len();
+                    self.ctx.visible_range = 0..self.ctx.char_count();
                 }
             }
         }
@@ -500,9 +633,10 @@ impl TextFieldEditor {
 
     /// Method to determine the text that is visible in the TextField
     pub fn get_visible_text(&self, _scroll_x: f32) -> Option<String> {
-        let chunk = &self.ctx.string[self.ctx.visible_range.clone()];
+        let range = self.ctx.visible_range.clone();
+        let chunk: String = self.ctx.string.chars().skip(range.start).take(range.end - range.start).collect();
         log::debug!("visible text={:?}", chunk);
-        Some(chunk.to_string())
+        Some(chunk)
     }
 
     /// Locate the closest cursor insertion point based on the mouse position
@@ -654,3 +788,160 @@ impl TextAreaEditor {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tools::{CompositionSource, SyntheticComposition};
+
+    /// An editor whose font is not uploaded to the GPU
+    fn editor(text: &str) -> EditorContext {
+        let draw_font = DrawFont::from_bytes(ROBOTO_REGULAR.to_vec(), Some(&0));
+        let rect = rusttype::Rect { min: rusttype::point(0.0, 0.0), max: rusttype::point(200.0, 20.0) };
+        let mut ctx = EditorContext {
+            draw_font,
+            is_multiline: false,
+            string: String::default(),
+            font_size: 14.0,
+            cursor_pos: 0,
+            cursor_origin: (0.0, 0.0),
+            text_origin: (0.0, 0.0),
+            text_size: (0, 0),
+            has_changed: true,
+            frame: rect,
+            glyph_db: HashMap::new(),
+            char_db: HashMap::new(),
+            insert_mode: InsertMode::Start(false),
+            debug: false,
+            metrics: Vec::new(),
+            baselines: Vec::new(),
+            visible_range: 0..1,
+            preedit: None,
+        };
+        ctx.set_text(text);
+        ctx.cursor_pos = ctx.char_count();
+        ctx
+    }
+
+    #[test]
+    fn test_insert_multibyte() {
+        let mut ctx = editor("día");
+        assert_eq!(ctx.char_count(), 3);
+        ctx.insert_char('s');
+        assert_eq!(ctx.get_text(), "días");
+        assert_eq!(ctx.cursor_pos, 4);
+
+        ctx.cursor_pos = 2;
+        ctx.insert_char('ñ');
+        assert_eq!(ctx.get_text(), "díñas");
+        assert_eq!(ctx.cursor_pos, 3);
+
+        ctx.cursor_pos = 0;
+        ctx.insert_text("日本");
+        assert_eq!(ctx.get_text(), "日本díñas");
+        assert_eq!(ctx.cursor_pos, 2);
+    }
+
+    #[test]
+    fn test_delete_multibyte() {
+        let mut ctx = editor("añö");
+        ctx.delete_char();
+        assert_eq!(ctx.get_text(), "añ");
+        assert_eq!(ctx.cursor_pos, 2);
+
+        // Backspace removes the char before the cursor
+        let mut ctx = editor("日本語");
+        ctx.cursor_pos = 2;
+        ctx.delete_char();
+        assert_eq!(ctx.get_text(), "日語");
+        assert_eq!(ctx.cursor_pos, 1);
+
+        ctx.cursor_pos = 0;
+        ctx.delete_char();
+        assert_eq!(ctx.get_text(), "日語");
+    }
+
+    /// The TextField docs used to list this text as a known crash, because byte offsets were used as char indexes
+    #[test]
+    fn test_special_chars() {
+        let text = "čćdđe ёєжзѕиіїйјк";
+        let mut ctx = editor(text);
+        ctx.update_metrics();
+        assert_eq!(ctx.char_count(), 17);
+        ctx.cursor_pos = 6;
+        ctx.insert_char('ж');
+        assert_eq!(ctx.get_text(), "čćdđe жёєжзѕиіїйјк");
+        ctx.delete_char();
+        ctx.delete_char();
+        assert_eq!(ctx.get_text(), "čćdđeёєжзѕиіїйјк");
+        assert_eq!(ctx.cursor_pos, 5);
+        for _ in 0..20 {
+            ctx.move_cursor(1);
+        }
+        assert_eq!(ctx.cursor_pos, ctx.char_count());
+        ctx.delete_char();
+        assert_eq!(ctx.get_text(), "čćdđeёєжзѕиіїйј");
+    }
+
+    #[test]
+    fn test_move_cursor() {
+        let mut ctx = editor("ü€");
+        ctx.move_cursor(1);
+        assert_eq!(ctx.cursor_pos, 2);
+        ctx.move_cursor(-1);
+        ctx.move_cursor(-1);
+        assert_eq!(ctx.cursor_pos, 0);
+        ctx.move_cursor(-1);
+        assert_eq!(ctx.cursor_pos, 0);
+        ctx.move_cursor(1);
+        ctx.insert_char('x');
+        assert_eq!(ctx.get_text(), "üx€");
+    }
+
+    #[test]
+    fn test_replace_text() {
+        let mut ctx = editor("abc");
+        ctx.cursor_pos = 1;
+        ctx.replace_text("ÄÖÜ-");
+        assert_eq!(ctx.cursor_pos, 4);
+    }
+
+    #[test]
+    fn test_composition_sequence() {
+        let mut ctx = editor("東");
+        let mut source = SyntheticComposition::new().compose(&["k", "き", "きょう"], "京");
+        assert_eq!(source.len(), 5);
+
+        assert!(ctx.handle_text_input(&source.poll_event().unwrap()));
+        assert!(ctx.is_composing());
+        ctx.handle_text_input(&source.poll_event().unwrap());
+        ctx.handle_text_input(&source.poll_event().unwrap());
+        ctx.handle_text_input(&source.poll_event().unwrap());
+        let (text, range) = ctx.display_text();
+        assert_eq!(text, "東きょう");
+        assert_eq!(range, Some(1..4));
+        assert_eq!(ctx.composition().map(|p| p.cursor), Some(3));
+        // The editor string is unchanged until the commit
+        assert_eq!(ctx.get_text(), "東");
+
+        ctx.handle_text_input(&source.poll_event().unwrap());
+        assert!(source.is_empty());
+        assert!(!ctx.is_composing());
+        assert_eq!(ctx.get_text(), "東京");
+        assert_eq!(ctx.cursor_pos, 2);
+    }
+
+    #[test]
+    fn test_composition_inside_text() {
+        let mut ctx = editor("ac");
+        ctx.cursor_pos = 1;
+        let mut source = SyntheticComposition::new().dead_key('´', 'e').start().update("ü", 1).cancel();
+        while let Some(event) = source.poll_event() {
+            ctx.handle_text_input(&event);
+        }
+        assert_eq!(ctx.get_text(), "aéc");
+        assert_eq!(ctx.cursor_pos, 2);
+        assert!(!ctx.is_composing());
+        assert_eq!(ctx.display_text(), ("aéc".to_string(), None));
+    }
+}
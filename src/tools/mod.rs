@@ -2,20 +2,32 @@
 //! Quicksilver only, so this module/files may get renamed or moved in the future.
 
 pub use self::app_delegate::*;
+pub use self::atlas::*;
 pub use self::command::*;
+pub use self::composition::*;
 pub use self::draw_font::*;
 pub use self::draw_image::*;
+pub use self::draw_path::*;
 pub use self::draw_shape::*;
+pub use self::draw_svg::*;
 pub use self::editor::*;
+pub use self::sprite_sheet::*;
 pub use self::ui::*;
+pub use self::validator::*;
 
 mod app_delegate;
+mod atlas;
 mod command;
+mod composition;
 mod draw_font;
 mod draw_image;
+mod draw_path;
 mod draw_shape;
+mod draw_svg;
 mod editor;
+mod sprite_sheet;
 mod ui;
+mod validator;
 
 /// Used for checking equality between floats
 /// TODO: Use EPSILON? https://doc.rust-lang.org/beta/std/f32/constant.EPSILON.html
@@ -0,0 +1,492 @@
+/// Sprite sheets describe the named frames that are packed into a single texture
+///
+use super::*;
+
+use quicksilver::geom::{Rectangle, Vector};
+
+use std::fmt;
+
+//-- Support -----------------------------------------------------------------------
+
+/// The reason a sprite sheet descriptor could not be loaded
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpriteSheetError {
+    /// The text is not valid UTF-8
+    Encoding,
+    /// The JSON is malformed at the byte offset
+    Syntax(usize),
+    /// There is no frames object or array
+    MissingFrames,
+    /// The frame with this name has no frame rectangle
+    InvalidFrame(String),
+}
+
+impl fmt::Display for SpriteSheetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpriteSheetError::Encoding => write!(f, "Sprite sheet text is not valid UTF-8"),
+            SpriteSheetError::Syntax(offset) => write!(f, "Malformed JSON at byte {}", offset),
+            SpriteSheetError::MissingFrames => write!(f, "No frames found"),
+            SpriteSheetError::InvalidFrame(name) => write!(f, "Frame {} has no frame rectangle", name),
+        }
+    }
+}
+
+/// A single image in a sprite sheet
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpriteFrame {
+    /// The name of the frame. Grid frames are named by their index.
+    pub name: String,
+    /// The region of the texture in pixels. If rotated, this is the size before rotation.
+    pub region: Rectangle,
+    /// True if the frame is stored rotated 90 degrees clockwise in the texture
+    pub rotated: bool,
+    /// The position of the trimmed region inside the original image
+    pub offset: Vector,
+    /// The size of the original image before trimming
+    pub source_size: Vector,
+}
+
+impl SpriteFrame {
+    /// Constructor for an untrimmed and unrotated frame
+    pub fn new(name: &str, region: Rectangle) -> Self {
+        SpriteFrame { name: name.to_string(), region, rotated: false, offset: Vector::ZERO, source_size: region.size }
+    }
+
+    /// The 0..1 texture coordinates of the corners in the order top left, top right, bottom right, bottom left
+    pub fn tex_quad(&self, texture_size: Vector) -> [Vector; 4] {
+        if !self.rotated {
+            return DrawImage::normalize_tex_quad(texture_size, self.region);
+        }
+        // The texture holds the frame turned clockwise, so the top left of the frame is the top right of the region
+        let region = Rectangle::new(self.region.pos, (self.region.height(), self.region.width()));
+        let quad = DrawImage::normalize_tex_quad(texture_size, region);
+        [quad[1], quad[2], quad[3], quad[0]]
+    }
+
+    /// The rectangle to draw the frame in so that the original image fills the given frame
+    pub fn draw_rect(&self, frame: &Rectangle) -> Rectangle {
+        if self.source_size.x <= 0.0 || self.source_size.y <= 0.0 {
+            return *frame;
+        }
+        let scale = Vector::new(frame.width() / self.source_size.x, frame.height() / self.source_size.y);
+        Rectangle::new(frame.pos + self.offset.times(scale), self.region.size.times(scale))
+    }
+}
+
+//-- Main -----------------------------------------------------------------------
+
+/// A list of frames in a texture. Create one from a grid of equal cells or load a TexturePacker JSON file
+/// (hash or array format).
+#[derive(Clone, Debug)]
+pub struct SpriteSheet {
+    /// The size of the texture in pixels
+    pub size: Vector,
+    frames: Vec<SpriteFrame>,
+}
+
+impl SpriteSheet {
+    /// Constructor for an empty sheet with the texture size
+    pub fn new(size: Vector) -> Self {
+        SpriteSheet { size, frames: Vec::new() }
+    }
+
+    /// Divide the texture into a grid of equal cells, ordered left to right and top to bottom
+    pub fn grid(size: Vector, columns: u32, rows: u32) -> Self {
+        let mut sheet = SpriteSheet::new(size);
+        if columns == 0 || rows == 0 {
+            return sheet;
+        }
+        let cell = Vector::new(size.x / columns as f32, size.y / rows as f32);
+        for row in 0..rows {
+            for col in 0..columns {
+                let index = row * columns + col;
+                let pos = Vector::new(col as f32 * cell.x, row as f32 * cell.y);
+                sheet.add_frame(SpriteFrame::new(&index.to_string(), Rectangle::new(pos, cell)));
+            }
+        }
+        sheet
+    }
+
+    /// Builder method to drop the empty cells at the end of a grid
+    pub fn with_frame_count(mut self, count: usize) -> Self {
+        self.frames.truncate(count);
+        self
+    }
+
+    /// Parse the text of a TexturePacker JSON file
+    pub fn parse(text: &str) -> Result<Self, SpriteSheetError> {
+        let json = JsonParser::new(text).parse_document()?;
+        let size =
+            json.get("meta").and_then(|meta| meta.get("size")).and_then(|size| size.to_size()).unwrap_or(Vector::ZERO);
+        let mut sheet = SpriteSheet::new(size);
+        match json.get("frames") {
+            Some(JsonValue::Object(entries)) => {
+                for (name, entry) in entries {
+                    sheet.add_frame(SpriteSheet::parse_frame(name, entry)?);
+                }
+            }
+            Some(JsonValue::Array(entries)) => {
+                for entry in entries {
+                    let name = entry.get("filename").and_then(|name| name.as_str()).unwrap_or_default();
+                    sheet.add_frame(SpriteSheet::parse_frame(name, entry)?);
+                }
+            }
+            _ => return Err(SpriteSheetError::MissingFrames),
+        }
+        Ok(sheet)
+    }
+
+    /// Parse the bytes of a TexturePacker JSON file
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SpriteSheetError> {
+        let text = std::str::from_utf8(bytes).map_err(|_| SpriteSheetError::Encoding)?;
+        SpriteSheet::parse(text)
+    }
+
+    fn parse_frame(name: &str, entry: &JsonValue) -> Result<SpriteFrame, SpriteSheetError> {
+        let region = entry
+            .get("frame")
+            .and_then(|frame| frame.to_rect())
+            .ok_or_else(|| SpriteSheetError::InvalidFrame(name.to_string()))?;
+        let mut frame = SpriteFrame::new(name, region);
+        frame.rotated = entry.get("rotated").and_then(|rotated| rotated.as_bool()).unwrap_or(false);
+        if let Some(trim) = entry.get("spriteSourceSize").and_then(|trim| trim.to_rect()) {
+            frame.offset = trim.pos;
+        }
+        if let Some(size) = entry.get("sourceSize").and_then(|size| size.to_size()) {
+            frame.source_size = size;
+        }
+        Ok(frame)
+    }
+
+    /// Add a frame to the end of the list
+    pub fn add_frame(&mut self, frame: SpriteFrame) {
+        self.frames.push(frame);
+    }
+
+    /// All frames in the order they were added
+    pub fn frames(&self) -> &[SpriteFrame] {
+        &self.frames
+    }
+
+    /// Get the frame at the index
+    pub fn get(&self, index: usize) -> Option<&SpriteFrame> {
+        self.frames.get(index)
+    }
+
+    /// Get the index of the frame with the name
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.frames.iter().position(|frame| frame.name == name)
+    }
+
+    /// Get the indexes of the frames whose names start with the prefix, sorted by name.
+    /// For example, "walk" selects walk_01.png, walk_02.png and so on.
+    pub fn sequence(&self, prefix: &str) -> Vec<usize> {
+        let mut indexes: Vec<usize> =
+            (0..self.frames.len()).filter(|index| self.frames[*index].name.starts_with(prefix)).collect();
+        indexes.sort_by(|a, b| self.frames[*a].name.cmp(&self.frames[*b].name));
+        indexes
+    }
+
+    /// The number of frames
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// True if there are no frames
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+// ************************************************************************************
+// JSON
+// ************************************************************************************
+
+/// The subset of JSON needed for sprite sheet descriptors
+#[derive(Clone, Debug, PartialEq)]
+enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f32),
+    Text(String),
+    Array(Vec<JsonValue>),
+    /// Keys are kept in file order, which is the frame order in TexturePacker files
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(entries) => entries.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    fn as_f32(&self) -> Option<f32> {
+        match self {
+            JsonValue::Number(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::Text(value) => Some(value.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Read an object with w and h
+    fn to_size(&self) -> Option<Vector> {
+        Some(Vector::new(self.get("w")?.as_f32()?, self.get("h")?.as_f32()?))
+    }
+
+    /// Read an object with x, y, w and h
+    fn to_rect(&self) -> Option<Rectangle> {
+        let pos = Vector::new(self.get("x")?.as_f32()?, self.get("y")?.as_f32()?);
+        Some(Rectangle::new(pos, self.to_size()?))
+    }
+}
+
+struct JsonParser<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(text: &'a str) -> Self {
+        JsonParser { text, pos: 0 }
+    }
+
+    fn parse_document(&mut self) -> Result<JsonValue, SpriteSheetError> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        if self.pos < self.text.len() {
+            return Err(SpriteSheetError::Syntax(self.pos));
+        }
+        Ok(value)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), SpriteSheetError> {
+        self.skip_whitespace();
+        if self.peek() != Some(expected) {
+            return Err(SpriteSheetError::Syntax(self.pos));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<JsonValue, SpriteSheetError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => Ok(JsonValue::Text(self.parse_string()?)),
+            Some('t') => self.parse_literal("true", JsonValue::Bool(true)),
+            Some('f') => self.parse_literal("false", JsonValue::Bool(false)),
+            Some('n') => self.parse_literal("null", JsonValue::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            _ => Err(SpriteSheetError::Syntax(self.pos)),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<JsonValue, SpriteSheetError> {
+        self.expect('{')?;
+        let mut entries: Vec<(String, JsonValue)> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(JsonValue::Object(entries));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(':')?;
+            entries.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Object(entries));
+                }
+                _ => return Err(SpriteSheetError::Syntax(self.pos)),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<JsonValue, SpriteSheetError> {
+        self.expect('[')?;
+        let mut items: Vec<JsonValue> = Vec::new();
+        self.skip_whitespace();
+        if self.peek() == Some(']') {
+            self.pos += 1;
+            return Ok(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                _ => return Err(SpriteSheetError::Syntax(self.pos)),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, SpriteSheetError> {
+        if self.peek() != Some('"') {
+            return Err(SpriteSheetError::Syntax(self.pos));
+        }
+        self.pos += 1;
+        let mut result = String::new();
+        let mut chars = self.text[self.pos..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += offset + 1;
+                    return Ok(result);
+                }
+                '\\' => match chars.next() {
+                    Some((_, 'n')) => result.push('\n'),
+                    Some((_, 't')) => result.push('\t'),
+                    Some((_, 'r')) => result.push('\r'),
+                    Some((_, 'b')) => result.push('\u{8}'),
+                    Some((_, 'f')) => result.push('\u{c}'),
+                    Some((at, 'u')) => {
+                        let start = self.pos + at + 1;
+                        let code = self
+                            .text
+                            .get(start..start + 4)
+                            .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                            .ok_or(SpriteSheetError::Syntax(start))?;
+                        result.push(std::char::from_u32(code).unwrap_or('\u{fffd}'));
+                        for _ in 0..4 {
+                            chars.next();
+                        }
+                    }
+                    Some((_, escaped)) => result.push(escaped),
+                    None => break,
+                },
+                _ => result.push(c),
+            }
+        }
+        Err(SpriteSheetError::Syntax(self.text.len()))
+    }
+
+    fn parse_number(&mut self) -> Result<JsonValue, SpriteSheetError> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if !(c.is_ascii_digit() || c == '-' || c == '+' || c == '.' || c == 'e' || c == 'E') {
+                break;
+            }
+            self.pos += 1;
+        }
+        self.text[start..self.pos].parse::<f32>().map(JsonValue::Number).map_err(|_| SpriteSheetError::Syntax(start))
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, SpriteSheetError> {
+        if !self.text[self.pos..].starts_with(literal) {
+            return Err(SpriteSheetError::Syntax(self.pos));
+        }
+        self.pos += literal.len();
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH_JSON: &str = r#"{
+        "frames": {
+            "walk_02.png": {
+                "frame": {"x": 32, "y": 0, "w": 32, "h": 48},
+                "rotated": true,
+                "trimmed": true,
+                "spriteSourceSize": {"x": 4, "y": 2, "w": 32, "h": 48},
+                "sourceSize": {"w": 40, "h": 52}
+            },
+            "walk_01.png": {
+                "frame": {"x": 0, "y": 0, "w": 32, "h": 48},
+                "rotated": false,
+                "sourceSize": {"w": 32, "h": 48}
+            }
+        },
+        "meta": {"image": "walk.png", "size": {"w": 128, "h": 64}, "scale": "1"}
+    }"#;
+
+    #[test]
+    fn test_parse_hash() {
+        let sheet = SpriteSheet::parse(HASH_JSON).unwrap();
+        assert_eq!(sheet.size, Vector::new(128.0, 64.0));
+        assert_eq!(sheet.len(), 2);
+        // Frames keep the file order, while a sequence is sorted by name
+        assert_eq!(sheet.find("walk_02.png"), Some(0));
+        assert_eq!(sheet.sequence("walk"), vec![1, 0]);
+        let frame = sheet.get(0).unwrap();
+        assert_eq!(frame.region, Rectangle::new((32.0, 0.0), (32.0, 48.0)));
+        assert!(frame.rotated);
+        assert_eq!(frame.offset, Vector::new(4.0, 2.0));
+        assert_eq!(frame.source_size, Vector::new(40.0, 52.0));
+        let frame = sheet.get(1).unwrap();
+        assert!(!frame.rotated);
+        assert_eq!(frame.offset, Vector::ZERO);
+    }
+
+    #[test]
+    fn test_parse_array() {
+        let text = r#"{"frames": [
+            {"filename": "idle", "frame": {"x": 0, "y": 16, "w": 16, "h": 16}},
+            {"filename": "jump \u0031", "frame": {"x": 16.5, "y": 16, "w": 16, "h": 16}, "rotated": false}
+        ], "meta": {"size": {"w": 64, "h": 32}}}"#;
+        let sheet = SpriteSheet::from_bytes(text.as_bytes()).unwrap();
+        assert_eq!(sheet.size, Vector::new(64.0, 32.0));
+        let names: Vec<&str> = sheet.frames().iter().map(|frame| frame.name.as_str()).collect();
+        assert_eq!(names, vec!["idle", "jump 1"]);
+        assert_eq!(sheet.get(1).unwrap().region, Rectangle::new((16.5, 16.0), (16.0, 16.0)));
+        assert_eq!(sheet.get(0).unwrap().source_size, Vector::new(16.0, 16.0));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let missing = r#"{"frames": {"idle": {"rotated": false, "sourceSize": {"w": 16, "h": 16}}}}"#;
+        assert_eq!(SpriteSheet::parse(missing).unwrap_err(), SpriteSheetError::InvalidFrame("idle".to_string()));
+        let incomplete = r#"{"frames": [{"filename": "idle", "frame": {"x": 0, "y": 0, "w": 16}}]}"#;
+        assert_eq!(SpriteSheet::parse(incomplete).unwrap_err(), SpriteSheetError::InvalidFrame("idle".to_string()));
+        assert_eq!(SpriteSheet::parse(r#"{"meta": {}}"#).unwrap_err(), SpriteSheetError::MissingFrames);
+
+        // Truncated input fails at the end of the text
+        let truncated = &HASH_JSON[..HASH_JSON.find("walk_01").unwrap()];
+        assert_eq!(SpriteSheet::parse(truncated).unwrap_err(), SpriteSheetError::Syntax(truncated.len()));
+        let unclosed = r#"{"frames": {"idle": {"frame": {"x": 0,"#;
+        assert_eq!(SpriteSheet::parse(unclosed).unwrap_err(), SpriteSheetError::Syntax(unclosed.len()));
+        assert_eq!(SpriteSheet::parse(r#"{"frames": [] "#).unwrap_err(), SpriteSheetError::Syntax(14));
+        assert_eq!(SpriteSheet::parse(r#"{"frames": []} x"#).unwrap_err(), SpriteSheetError::Syntax(15));
+        assert_eq!(SpriteSheet::from_bytes(&[0x7b, 0xff]).unwrap_err(), SpriteSheetError::Encoding);
+    }
+}
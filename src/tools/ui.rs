@@ -5,6 +5,7 @@ use super::*;
 use quicksilver::{
     geom::{Line, Rectangle, Vector},
     graphics::{Background::Col, Color, GpuTriangle, MeshTask, Vertex},
+    input::Key,
 };
 
 use std::any::TypeId;
@@ -93,6 +94,48 @@ impl UITools {
         None
     }
 
+    // *****************************************************************************************************
+    // Row navigation
+    // *****************************************************************************************************
+
+    /// The number of rows to move for PageUp and PageDown
+    pub fn page_size(view_height: f32, row_height: f32) -> usize {
+        if row_height <= 0.0 {
+            return 1;
+        }
+        ((view_height / row_height).floor() as usize).max(1)
+    }
+
+    /// The scroll offset that brings a row from top to bottom into view with the least movement
+    pub fn scroll_to_span(offset: f32, top: f32, bottom: f32, view_height: f32) -> f32 {
+        if top < offset {
+            top
+        } else if bottom > offset + view_height {
+            (bottom - view_height).max(0.0)
+        } else {
+            offset
+        }
+    }
+
+    /// The row to select for the Up, Down, Home, End, PageUp and PageDown keys in a list of rows where
+    /// focus is the current row. Returns None for other keys or an empty list.
+    pub fn row_for_key(key: &Key, focus: Option<usize>, count: usize, page: usize) -> Option<usize> {
+        if count == 0 {
+            return None;
+        }
+        let last = count - 1;
+        let row = match key {
+            Key::Up => focus.map_or(0, |row| row.saturating_sub(1)),
+            Key::Down => focus.map_or(0, |row| (row + 1).min(last)),
+            Key::Home => 0,
+            Key::End => last,
+            Key::PageUp => focus.map_or(0, |row| row.saturating_sub(page)),
+            Key::PageDown => focus.map_or(0, |row| (row + page).min(last)),
+            _ => return None,
+        };
+        Some(row.min(last))
+    }
+
     /// Given a rectangle, generate an array of Lines for all 4 sides
     pub fn make_border_lines(rect: &Rectangle, width: f32) -> Vec<Line> {
         let mut lines: Vec<Line> = Vec::new();
@@ -269,6 +312,72 @@ impl UITools {
             }
         }
     }
+
+    /// Clip the triangles in a MeshTask to a rounded rectangle, with the corner radii in the order: top left,
+    /// top right, bottom right, bottom left. Unlike clip_mesh, this works for any triangles. The colors and tex
+    /// coordinates are interpolated where triangles are cut.
+    pub fn clip_rounded(mesh: &mut MeshTask, frame: &Rectangle, radii: [f32; 4]) {
+        let contour = rounded_rect_points(frame, radii);
+        let mut vertices: Vec<Vertex> = Vec::with_capacity(mesh.vertices.len());
+        let mut triangles: Vec<GpuTriangle> = Vec::with_capacity(mesh.triangles.len());
+        for triangle in &mesh.triangles {
+            let mut polygon: Vec<Vertex> = triangle.indices.iter().map(|i| mesh.vertices[*i as usize]).collect();
+            for i in 0..contour.len() {
+                let (a, b) = (contour[i], contour[(i + 1) % contour.len()]);
+                if a == b {
+                    continue;
+                }
+                let side = |pt: Vector| (b.x - a.x) * (pt.y - a.y) - (b.y - a.y) * (pt.x - a.x);
+                if polygon.iter().all(|vertex| side(vertex.pos) >= 0.0) {
+                    continue;
+                }
+                polygon = UITools::clip_vertices(&polygon, side);
+                if polygon.len() < 3 {
+                    break;
+                }
+            }
+            if polygon.len() < 3 {
+                continue;
+            }
+            let offset = vertices.len() as u32;
+            for i in 1..polygon.len() as u32 - 1 {
+                let mut triangle = triangle.clone();
+                triangle.indices = [offset, offset + i, offset + i + 1];
+                triangles.push(triangle);
+            }
+            vertices.append(&mut polygon);
+        }
+        mesh.vertices = vertices;
+        mesh.triangles = triangles;
+    }
+
+    /// Clip a convex polygon of vertices to the side of a line where the function of the position is >= 0
+    fn clip_vertices(polygon: &[Vertex], side: impl Fn(Vector) -> f32) -> Vec<Vertex> {
+        let mut result = Vec::with_capacity(polygon.len() + 1);
+        for i in 0..polygon.len() {
+            let (a, b) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            let (side_a, side_b) = (side(a.pos), side(b.pos));
+            if side_a >= 0.0 {
+                result.push(a);
+            }
+            if (side_a >= 0.0) != (side_b >= 0.0) {
+                let t = side_a / (side_a - side_b);
+                let lerp = |from: f32, to: f32| from + (to - from) * t;
+                let tex_pos = match (a.tex_pos, b.tex_pos) {
+                    (Some(from), Some(to)) => Some(Vector::new(lerp(from.x, to.x), lerp(from.y, to.y))),
+                    _ => a.tex_pos,
+                };
+                let col = Color {
+                    r: lerp(a.col.r, b.col.r),
+                    g: lerp(a.col.g, b.col.g),
+                    b: lerp(a.col.b, b.col.b),
+                    a: lerp(a.col.a, b.col.a),
+                };
+                result.push(Vertex { pos: Vector::new(lerp(a.pos.x, b.pos.x), lerp(a.pos.y, b.pos.y)), tex_pos, col });
+            }
+        }
+        result
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -293,3 +402,67 @@ impl Container for Rectangle {
         (xpos, ypos)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_row_for_key() {
+        assert_eq!(UITools::row_for_key(&Key::Down, None, 5, 2), Some(0));
+        assert_eq!(UITools::row_for_key(&Key::Down, Some(4), 5, 2), Some(4));
+        assert_eq!(UITools::row_for_key(&Key::Up, Some(0), 5, 2), Some(0));
+        assert_eq!(UITools::row_for_key(&Key::End, Some(1), 5, 2), Some(4));
+        assert_eq!(UITools::row_for_key(&Key::PageDown, Some(3), 5, 2), Some(4));
+        assert_eq!(UITools::row_for_key(&Key::PageUp, Some(3), 5, 2), Some(1));
+        assert_eq!(UITools::row_for_key(&Key::Home, Some(9), 5, 2), Some(0));
+        assert_eq!(UITools::row_for_key(&Key::Down, Some(1), 0, 2), None);
+        assert_eq!(UITools::row_for_key(&Key::Return, Some(1), 5, 2), None);
+    }
+
+    #[test]
+    fn test_fit_radii() {
+        let frame = Rectangle::new((0.0, 0.0), (100.0, 30.0));
+        // A single large corner is only reduced to fit the sides it touches
+        assert_eq!(fit_radii(&frame, [40.0, 0.0, 0.0, 0.0]), [30.0, 0.0, 0.0, 0.0]);
+        assert_eq!(fit_radii(&frame, [10.0, 20.0, 5.0, 0.0]), [10.0, 20.0, 5.0, 0.0]);
+        // All radii are scaled by the same factor to keep their proportions
+        assert_eq!(fit_radii(&frame, [60.0, 0.0, 0.0, 20.0]), [22.5, 0.0, 0.0, 7.5]);
+        assert_eq!(fit_radii(&frame, [60.0; 4]), [15.0; 4]);
+        let square = Rectangle::new((0.0, 0.0), (100.0, 100.0));
+        assert_eq!(fit_radii(&square, [80.0, 80.0, 0.0, 0.0]), [50.0, 50.0, 0.0, 0.0]);
+        assert_eq!(fit_radii(&square, [-5.0, 10.0, 0.0, 0.0]), [0.0, 10.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_clip_rounded() {
+        // Clip a square of two triangles to a rounded frame with one large corner
+        let mut task = UITools::draw_rectangles(vec![Rectangle::new((0.0, 0.0), (100.0, 30.0))], Color::WHITE);
+        let frame = Rectangle::new((0.0, 0.0), (100.0, 30.0));
+        UITools::clip_rounded(&mut task, &frame, [40.0, 0.0, 0.0, 0.0]);
+        assert!(task
+            .triangles
+            .iter()
+            .all(|triangle| triangle.indices.iter().all(|i| (*i as usize) < task.vertices.len())));
+        let contains =
+            |x: f32, y: f32| task.vertices.iter().any(|v| (v.pos.x - x).abs() < 0.01 && (v.pos.y - y).abs() < 0.01);
+        // The corner is cut with the reduced radius of 30, which reaches the bottom left corner
+        assert!(!contains(0.0, 0.0));
+        assert!(contains(30.0, 0.0));
+        assert!(contains(0.0, 30.0));
+        assert!(contains(100.0, 0.0));
+        assert!(task.vertices.iter().all(|v| {
+            let (dx, dy) = (v.pos.x - 30.0, v.pos.y - 30.0);
+            v.pos.x >= 30.0 || v.pos.y >= 30.0 || dx * dx + dy * dy <= 30.0 * 30.0 + 0.1
+        }));
+    }
+
+    #[test]
+    fn test_scroll_to_span() {
+        assert_eq!(UITools::scroll_to_span(40.0, 20.0, 40.0, 100.0), 20.0);
+        assert_eq!(UITools::scroll_to_span(0.0, 100.0, 120.0, 100.0), 20.0);
+        assert_eq!(UITools::scroll_to_span(10.0, 40.0, 60.0, 100.0), 10.0);
+        assert_eq!(UITools::page_size(100.0, 30.0), 3);
+        assert_eq!(UITools::page_size(10.0, 30.0), 1);
+    }
+}
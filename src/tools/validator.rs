@@ -0,0 +1,472 @@
+/// Validation and formatting helpers for text input fields.
+/// A FieldValidator holds the list of rules for a TextField and the InputMask provides live formatting
+/// of fixed-pattern input like phone numbers.
+///
+use crate::events::ValidationError;
+
+use regex::Regex;
+
+//-- Support -----------------------------------------------------------------------
+
+/// Enum to define a single validation rule.
+pub enum ValidationRule {
+    /// The field must not be empty
+    Required,
+    /// Minimum number of chars
+    MinLength(usize),
+    /// Maximum number of chars
+    MaxLength(usize),
+    /// The full text must match the regex
+    Pattern(Regex),
+    /// A closure that returns true if the text is valid
+    Custom(Box<dyn Fn(&str) -> bool + 'static>),
+}
+
+impl ValidationRule {
+    /// Evaluate the rule against the text. Empty text is only checked by the Required rule, so that
+    /// optional fields do not show errors before anything has been entered.
+    pub fn check(&self, text: &str) -> Result<(), ValidationError> {
+        let count = text.chars().count();
+        match self {
+            ValidationRule::Required => {
+                if text.trim().is_empty() {
+                    return Err(ValidationError::Required);
+                }
+            }
+            ValidationRule::MinLength(min) => {
+                if count > 0 && count < *min {
+                    return Err(ValidationError::TooShort(*min));
+                }
+            }
+            ValidationRule::MaxLength(max) => {
+                if count > *max {
+                    return Err(ValidationError::TooLong(*max));
+                }
+            }
+            ValidationRule::Pattern(regex) => {
+                if count > 0 && !regex.is_match(text) {
+                    return Err(ValidationError::PatternMismatch);
+                }
+            }
+            ValidationRule::Custom(cb) => {
+                if count > 0 && !cb(text) {
+                    return Err(ValidationError::Custom);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+//-- Main -----------------------------------------------------------------------
+
+/// A collection of ValidationRules that are evaluated in order. The first failure is returned.
+#[derive(Default)]
+pub struct FieldValidator {
+    /// The rules to check
+    pub rules: Vec<ValidationRule>,
+}
+
+impl FieldValidator {
+    /// Constructor
+    pub fn new() -> Self {
+        FieldValidator { rules: Vec::new() }
+    }
+
+    /// Builder method to add a rule
+    pub fn rule(mut self, rule: ValidationRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Builder method to add a regex rule. An invalid regex is logged and ignored.
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        match Regex::new(pattern) {
+            Ok(regex) => self.rules.push(ValidationRule::Pattern(regex)),
+            Err(err) => log::error!("Invalid regex pattern {:?}: {:?}", pattern, err),
+        }
+        self
+    }
+
+    /// Builder method to add a closure rule
+    pub fn custom<C>(mut self, cb: C) -> Self
+    where
+        C: Fn(&str) -> bool + 'static,
+    {
+        self.rules.push(ValidationRule::Custom(Box::new(cb)));
+        self
+    }
+
+    /// Run all rules and return the first error
+    pub fn validate(&self, text: &str) -> Result<(), ValidationError> {
+        for rule in &self.rules {
+            rule.check(text)?;
+        }
+        Ok(())
+    }
+}
+
+/// Simple checks for common input formats. These are intentionally lenient and do not try to cover
+/// every edge case of the relevant RFCs.
+pub struct Validators {}
+
+impl Validators {
+    /// Check for a decimal number like -12.5
+    pub fn is_number(text: &str) -> bool {
+        let text = text.trim();
+        !text.is_empty() && text.parse::<f64>().is_ok() && !text.contains(|c: char| c.is_alphabetic())
+    }
+
+    /// Check for a whole number like -12
+    pub fn is_integer(text: &str) -> bool {
+        text.trim().parse::<i64>().is_ok()
+    }
+
+    /// Check for an email address in the form name@domain.tld
+    pub fn is_email(text: &str) -> bool {
+        if text.contains(char::is_whitespace) {
+            return false;
+        }
+        let parts: Vec<&str> = text.split('@').collect();
+        if parts.len() != 2 || parts[0].is_empty() {
+            return false;
+        }
+        let domain = parts[1];
+        if let Some(dot) = domain.rfind('.') {
+            dot > 0 && dot < domain.len() - 1 && !domain.starts_with('.') && !domain.contains("..")
+        } else {
+            false
+        }
+    }
+
+    /// Check for an http or https URL with a host name
+    pub fn is_url(text: &str) -> bool {
+        if text.contains(char::is_whitespace) {
+            return false;
+        }
+        let rest = {
+            if text.starts_with("https://") {
+                &text[8..]
+            } else if text.starts_with("http://") {
+                &text[7..]
+            } else {
+                return false;
+            }
+        };
+        let host = rest.split(|c| c == '/' || c == '?' || c == '#').next().unwrap_or("");
+        let host = host.split(':').next().unwrap_or("");
+        host == "localhost" || (host.contains('.') && !host.starts_with('.') && !host.ends_with('.'))
+    }
+
+    /// Check for a date in YYYY-MM-DD format, including days in month and leap years
+    pub fn is_date(text: &str) -> bool {
+        Validators::parse_date(text).is_some()
+    }
+
+    /// Parse a date in YYYY-MM-DD format into (year, month, day)
+    pub fn parse_date(text: &str) -> Option<(i32, u32, u32)> {
+        let parts: Vec<&str> = text.trim().split('-').collect();
+        if parts.len() != 3 || parts[0].len() != 4 || parts[1].len() != 2 || parts[2].len() != 2 {
+            return None;
+        }
+        let year = parts[0].parse::<i32>().ok()?;
+        let month = parts[1].parse::<u32>().ok()?;
+        let day = parts[2].parse::<u32>().ok()?;
+        if month < 1 || month > 12 || day < 1 || day > Validators::days_in_month(year, month) {
+            return None;
+        }
+        Some((year, month, day))
+    }
+
+    /// Number of days in the specified month, where month is 1..=12
+    pub fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 => {
+                if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 {
+                    29
+                } else {
+                    28
+                }
+            }
+            _ => 0,
+        }
+    }
+}
+
+// ************************************************************************************
+// InputMask
+// ************************************************************************************
+
+/// A single position in an InputMask pattern
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MaskSlot {
+    /// Accepts 0-9. Pattern char: #
+    Digit,
+    /// Accepts a-z and A-Z. Pattern char: A
+    Letter,
+    /// Accepts a digit or letter. Pattern char: *
+    AlphaNumeric,
+    /// A fixed character that is inserted automatically
+    Literal(char),
+}
+
+impl MaskSlot {
+    /// Does this slot accept user input?
+    pub fn is_input(&self) -> bool {
+        match self {
+            MaskSlot::Literal(_) => false,
+            _ => true,
+        }
+    }
+
+    /// Check if the char is allowed in this slot
+    pub fn accepts(&self, c: char) -> bool {
+        match self {
+            MaskSlot::Digit => c.is_ascii_digit(),
+            MaskSlot::Letter => c.is_ascii_alphabetic(),
+            MaskSlot::AlphaNumeric => c.is_ascii_alphanumeric(),
+            MaskSlot::Literal(_) => false,
+        }
+    }
+}
+
+/// A live input mask for fixed-format text. For example, the pattern "(###) ###-####" formats
+/// typed digits as a US phone number. Literal chars are inserted as the user types.
+/// Use a backslash to escape a pattern char, e.g. "\#".
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputMask {
+    slots: Vec<MaskSlot>,
+}
+
+impl InputMask {
+    /// Constructor which parses the pattern string
+    pub fn new(pattern: &str) -> Self {
+        let mut slots: Vec<MaskSlot> = Vec::new();
+        let mut escaped = false;
+        for c in pattern.chars() {
+            if escaped {
+                slots.push(MaskSlot::Literal(c));
+                escaped = false;
+                continue;
+            }
+            let slot = match c {
+                '\\' => {
+                    escaped = true;
+                    continue;
+                }
+                '#' => MaskSlot::Digit,
+                'A' => MaskSlot::Letter,
+                '*' => MaskSlot::AlphaNumeric,
+                _ => MaskSlot::Literal(c),
+            };
+            slots.push(slot);
+        }
+        InputMask { slots }
+    }
+
+    /// The number of chars a user can enter
+    pub fn capacity(&self) -> usize {
+        self.slots.iter().filter(|s| s.is_input()).count()
+    }
+
+    /// Extract the user-entered chars from text that was formatted with apply()
+    pub fn strip(&self, text: &str) -> String {
+        self.slots.iter().zip(text.chars()).filter(|(slot, _)| slot.is_input()).map(|(_, c)| c).collect()
+    }
+
+    /// Check if the char can be appended to the raw input
+    pub fn accepts(&self, raw: &str, c: char) -> bool {
+        let index = raw.chars().count();
+        if let Some(slot) = self.slots.iter().filter(|s| s.is_input()).nth(index) {
+            slot.accepts(c)
+        } else {
+            false
+        }
+    }
+
+    /// Format the raw input using the pattern. Literals are inserted up to the last entered char, and
+    /// leading literals are shown as soon as there is any input.
+    pub fn apply(&self, raw: &str) -> String {
+        let mut result = String::new();
+        let mut chars = raw.chars().peekable();
+        for slot in &self.slots {
+            if chars.peek().is_none() {
+                break;
+            }
+            match slot {
+                MaskSlot::Literal(c) => result.push(*c),
+                _ => {
+                    if let Some(c) = chars.next() {
+                        result.push(c);
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// True if every input slot has been filled
+    pub fn is_complete(&self, raw: &str) -> bool {
+        raw.chars().count() >= self.capacity()
+    }
+
+    /// True if every char of the raw input is accepted by its slot and there are no extra chars
+    pub fn fits(&self, raw: &str) -> bool {
+        let mut slots = self.slots.iter().filter(|s| s.is_input());
+        raw.chars().all(|c| slots.next().map_or(false, |slot| slot.accepts(c)))
+    }
+
+    /// Convert a char position in the formatted text to the number of raw chars before it
+    pub fn raw_index(&self, formatted_pos: usize) -> usize {
+        self.slots.iter().take(formatted_pos).filter(|s| s.is_input()).count()
+    }
+
+    /// Convert a number of raw chars to the char position in the formatted text just after them
+    pub fn formatted_index(&self, raw_index: usize) -> usize {
+        if raw_index == 0 {
+            return 0;
+        }
+        let mut count = 0;
+        for (pos, slot) in self.slots.iter().enumerate() {
+            if slot.is_input() {
+                count += 1;
+                if count == raw_index {
+                    return pos + 1;
+                }
+            }
+        }
+        self.slots.len()
+    }
+}
+
+// ************************************************************************************
+// Tests
+// ************************************************************************************
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask_apply_and_strip() {
+        let mask = InputMask::new("(###) ###-####");
+        assert_eq!(mask.capacity(), 10);
+        assert_eq!(mask.apply(""), "");
+        assert_eq!(mask.apply("5"), "(5");
+        assert_eq!(mask.apply("5551"), "(555) 1");
+        assert_eq!(mask.apply("5551234567"), "(555) 123-4567");
+        assert_eq!(mask.strip("(555) 123-4567"), "5551234567");
+        assert_eq!(mask.strip("(555) 1"), "5551");
+        assert!(mask.is_complete("5551234567"));
+        assert!(!mask.is_complete("555123"));
+    }
+
+    #[test]
+    fn test_mask_accepts() {
+        let mask = InputMask::new("AA-##");
+        assert!(mask.accepts("", 'x'));
+        assert!(!mask.accepts("", '1'));
+        assert!(mask.accepts("xy", '1'));
+        assert!(!mask.accepts("xy", 'z'));
+        // Full
+        assert!(!mask.accepts("xy12", '3'));
+        assert!(mask.fits("xy1"));
+        assert!(!mask.fits("x1"));
+        assert!(!mask.fits("xy123"));
+        // Multibyte letters are not ASCII and are rejected
+        assert!(!mask.accepts("", 'é'));
+    }
+
+    #[test]
+    fn test_mask_escape_and_positions() {
+        let mask = InputMask::new(r"\##-*");
+        assert_eq!(mask.apply("1a"), "#1-a");
+        assert_eq!(mask.strip("#1-a"), "1a");
+        let mask = InputMask::new("(###) ###");
+        assert_eq!(mask.raw_index(0), 0);
+        assert_eq!(mask.raw_index(1), 0);
+        assert_eq!(mask.raw_index(4), 3);
+        assert_eq!(mask.raw_index(6), 3);
+        assert_eq!(mask.raw_index(7), 4);
+        assert_eq!(mask.formatted_index(0), 0);
+        assert_eq!(mask.formatted_index(1), 2);
+        assert_eq!(mask.formatted_index(3), 4);
+        assert_eq!(mask.formatted_index(4), 7);
+    }
+
+    #[test]
+    fn test_email() {
+        assert!(Validators::is_email("name@example.com"));
+        assert!(Validators::is_email("first.last@mail.example.co"));
+        assert!(!Validators::is_email(""));
+        assert!(!Validators::is_email("name@example"));
+        assert!(!Validators::is_email("@example.com"));
+        assert!(!Validators::is_email("name@@example.com"));
+        assert!(!Validators::is_email("name@.example.com"));
+        assert!(!Validators::is_email("name@example..com"));
+        assert!(!Validators::is_email("name@example."));
+        assert!(!Validators::is_email("na me@example.com"));
+    }
+
+    #[test]
+    fn test_url() {
+        assert!(Validators::is_url("https://example.com"));
+        assert!(Validators::is_url("http://example.com:8080/path?q=1#top"));
+        assert!(Validators::is_url("http://localhost:3000"));
+        assert!(!Validators::is_url("example.com"));
+        assert!(!Validators::is_url("ftp://example.com"));
+        assert!(!Validators::is_url("https://"));
+        assert!(!Validators::is_url("https://example"));
+        assert!(!Validators::is_url("https://.example.com"));
+        assert!(!Validators::is_url("https://exa mple.com"));
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(Validators::parse_date("2024-02-29"), Some((2024, 2, 29)));
+        assert_eq!(Validators::parse_date("2000-02-29"), Some((2000, 2, 29)));
+        assert_eq!(Validators::parse_date("2023-02-29"), None);
+        assert_eq!(Validators::parse_date("1900-02-29"), None);
+        assert_eq!(Validators::parse_date("2023-04-31"), None);
+        assert_eq!(Validators::parse_date("2023-12-31"), Some((2023, 12, 31)));
+        assert_eq!(Validators::parse_date("2023-13-01"), None);
+        assert_eq!(Validators::parse_date("2023-00-10"), None);
+        assert_eq!(Validators::parse_date("2023-1-10"), None);
+        assert_eq!(Validators::parse_date("23-01-10"), None);
+        assert_eq!(Validators::parse_date("2023/01/10"), None);
+        assert_eq!(Validators::parse_date("abcd-ef-gh"), None);
+        assert!(Validators::is_date(" 2023-01-10 "));
+        assert_eq!(Validators::days_in_month(2023, 2), 28);
+        assert_eq!(Validators::days_in_month(2023, 13), 0);
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert!(Validators::is_number("-12.5"));
+        assert!(!Validators::is_number("inf"));
+        assert!(!Validators::is_number("NaN"));
+        assert!(!Validators::is_number(""));
+        assert!(Validators::is_integer("42"));
+        assert!(!Validators::is_integer("4.2"));
+    }
+
+    #[test]
+    fn test_rules() {
+        let validator = FieldValidator::new()
+            .rule(ValidationRule::Required)
+            .rule(ValidationRule::MinLength(3))
+            .rule(ValidationRule::MaxLength(5))
+            .pattern("^[a-zé]+$");
+        assert_eq!(validator.validate(""), Err(ValidationError::Required));
+        assert_eq!(validator.validate("ab"), Err(ValidationError::TooShort(3)));
+        // Lengths count chars, not bytes
+        assert_eq!(validator.validate("éééé"), Ok(()));
+        assert_eq!(validator.validate("abcdef"), Err(ValidationError::TooLong(5)));
+        assert_eq!(validator.validate("ab1"), Err(ValidationError::PatternMismatch));
+        let custom = FieldValidator::new().custom(|text| text != "no");
+        assert_eq!(custom.validate("no"), Err(ValidationError::Custom));
+        assert_eq!(custom.validate(""), Ok(()));
+    }
+}